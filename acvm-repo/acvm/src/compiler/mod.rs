@@ -95,7 +95,7 @@ pub fn compile<F: AcirField>(
 ) -> (Circuit<F>, AcirTransformationMap) {
     let acir_opcode_positions = (0..acir.opcodes.len()).collect::<Vec<_>>();
 
-    let (acir, acir_opcode_positions) = optimize_internal(acir, acir_opcode_positions);
+    let (acir, acir_opcode_positions) = optimize_internal(acir, acir_opcode_positions, false);
 
     let (mut acir, acir_opcode_positions) =
         transform_internal(acir, expression_width, acir_opcode_positions);
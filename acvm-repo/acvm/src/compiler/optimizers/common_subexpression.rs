@@ -0,0 +1,384 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use acir::{
+    AcirField,
+    circuit::{
+        Circuit, ExpressionOrMemory, Opcode,
+        brillig::{BrilligInputs, BrilligOutputs},
+    },
+    native_types::{Expression, Witness},
+};
+
+use crate::compiler::CircuitSimulator;
+
+use super::GeneralOptimizer;
+
+/// `CommonSubexpressionOptimizer` removes redundant [`Opcode::AssertZero`] constraints.
+///
+/// Expressions are put in a canonical form, with sorted terms and a leading coefficient of one,
+/// so that constraints which are identical or scalar multiples of each other compare equal.
+/// The opcodes are then processed in order:
+/// - A constraint whose canonical form was already seen is removed.
+/// - A constraint defining an intermediate witness as an expression which already defines
+///   another witness is removed, and the duplicate witness is replaced by the other one
+///   in the following opcodes.
+///
+/// For instance, where `_{value}` refers to a witness:
+/// ```text
+/// EXPR [ (1, _0, _1) (-1, _2) 0 ]
+/// EXPR [ (2, _0, _1) (-2, _3) 0 ]
+/// EXPR [ (1, _2) (1, _3) (-1, _4) 0 ]
+/// ```
+/// becomes:
+/// ```text
+/// EXPR [ (1, _0, _1) (-1, _2) 0 ]
+/// EXPR [ (2, _2) (-1, _4) 0 ]
+/// ```
+///
+/// A witness is only replaced if it is an intermediate witness which is first used by the opcode
+/// defining it, and only used by [`Opcode::AssertZero`] constraints. In particular, the witnesses
+/// of assertion payloads are kept, as they are read when reporting a failing assertion.
+pub(crate) struct CommonSubexpressionOptimizer<F: AcirField> {
+    circuit: Circuit<F>,
+    /// Witnesses which cannot be replaced by another witness.
+    fixed_witnesses: HashSet<Witness>,
+    /// The index of the first opcode using each witness.
+    first_use: HashMap<Witness, usize>,
+}
+
+impl<F: AcirField> CommonSubexpressionOptimizer<F> {
+    /// Creates a new `CommonSubexpressionOptimizer` by collecting the witnesses of the `Circuit`
+    /// which must be kept, and where each witness is first used.
+    pub(crate) fn new(circuit: Circuit<F>) -> Self {
+        let mut fixed_witnesses: HashSet<Witness> =
+            circuit.circuit_arguments().union(&circuit.public_inputs().0).cloned().collect();
+        for (_, assertion_payload) in &circuit.assert_messages {
+            for item in &assertion_payload.payload {
+                if let ExpressionOrMemory::Expression(expr) = item {
+                    fixed_witnesses.extend(CircuitSimulator::expr_wit(expr));
+                }
+            }
+        }
+        let mut first_use = HashMap::new();
+
+        for (idx, opcode) in circuit.opcodes.iter().enumerate() {
+            let witnesses = opcode_witnesses(opcode);
+            if !matches!(opcode, Opcode::AssertZero(_)) {
+                fixed_witnesses.extend(witnesses.iter().copied());
+            }
+            for witness in witnesses {
+                first_use.entry(witness).or_insert(idx);
+            }
+        }
+
+        Self { circuit, fixed_witnesses, first_use }
+    }
+
+    /// Returns a `Circuit` where duplicate [`Opcode::AssertZero`] constraints are removed
+    /// and duplicate intermediate witnesses are merged.
+    pub(crate) fn eliminate_common_subexpressions(
+        self,
+        order_list: Vec<usize>,
+    ) -> (Circuit<F>, Vec<usize>) {
+        // The canonical forms of the constraints kept so far
+        let mut constraints: HashSet<Expression<F>> = HashSet::new();
+        // The expressions defining a witness, in canonical form
+        let mut definitions: HashMap<Expression<F>, Witness> = HashMap::new();
+        // The witnesses which were merged into another witness
+        let mut substitutions: HashMap<Witness, Witness> = HashMap::new();
+
+        let mut new_order_list = Vec::with_capacity(order_list.len());
+        let mut optimized_opcodes = Vec::with_capacity(self.circuit.opcodes.len());
+        for (idx, opcode) in self.circuit.opcodes.into_iter().enumerate() {
+            let Opcode::AssertZero(expr) = opcode else {
+                new_order_list.push(order_list[idx]);
+                optimized_opcodes.push(opcode);
+                continue;
+            };
+
+            let expr = substitute(expr, &substitutions);
+            if expr.is_zero() {
+                continue;
+            }
+            let Some(canonical) = canonicalize(&expr) else {
+                // Constant expressions are kept, so that a failing constraint still fails.
+                new_order_list.push(order_list[idx]);
+                optimized_opcodes.push(Opcode::AssertZero(expr));
+                continue;
+            };
+            if !constraints.insert(canonical.clone()) {
+                continue;
+            }
+
+            if let Some((witness, definition)) = definition(&canonical) {
+                match definitions.get(&definition) {
+                    Some(existing)
+                        if *existing != witness
+                            && !self.fixed_witnesses.contains(&witness)
+                            && self.first_use.get(&witness) == Some(&idx) =>
+                    {
+                        substitutions.insert(witness, *existing);
+                        continue;
+                    }
+                    Some(_) => (),
+                    None => {
+                        definitions.insert(definition, witness);
+                    }
+                }
+            }
+
+            new_order_list.push(order_list[idx]);
+            optimized_opcodes.push(Opcode::AssertZero(expr));
+        }
+
+        (Circuit { opcodes: optimized_opcodes, ..self.circuit }, new_order_list)
+    }
+}
+
+/// Replace the merged witnesses of the expression and simplify the result.
+fn substitute<F: AcirField>(
+    mut expr: Expression<F>,
+    substitutions: &HashMap<Witness, Witness>,
+) -> Expression<F> {
+    if substitutions.is_empty() {
+        return expr;
+    }
+    let replace = |witness: &mut Witness| {
+        if let Some(replacement) = substitutions.get(witness) {
+            *witness = *replacement;
+        }
+    };
+    for (_, lhs, rhs) in &mut expr.mul_terms {
+        replace(lhs);
+        replace(rhs);
+    }
+    for (_, witness) in &mut expr.linear_combinations {
+        replace(witness);
+    }
+    let mut expr = GeneralOptimizer::optimize(expr);
+    // Merging the terms may cancel some quadratic terms out
+    expr.mul_terms.retain(|(scale, _, _)| !scale.is_zero());
+    expr
+}
+
+/// Sort the terms of the expression and scale it so that its leading coefficient is one.
+///
+/// Returns `None` for constant expressions.
+fn canonicalize<F: AcirField>(expr: &Expression<F>) -> Option<Expression<F>> {
+    let mut expr = expr.clone();
+    expr.sort();
+    let leading = expr
+        .mul_terms
+        .first()
+        .map(|(scale, _, _)| *scale)
+        .or_else(|| expr.linear_combinations.first().map(|(scale, _)| *scale))?;
+    Some(&expr * leading.inverse())
+}
+
+/// Split a canonical expression into a witness and the canonical expression it is equal to.
+///
+/// The witness is the one with the highest index among the linear terms,
+/// which does not appear in any quadratic term.
+fn definition<F: AcirField>(canonical: &Expression<F>) -> Option<(Witness, Expression<F>)> {
+    let in_mul_terms = |witness: &Witness| {
+        canonical.mul_terms.iter().any(|(_, lhs, rhs)| lhs == witness || rhs == witness)
+    };
+    let (position, (scale, witness)) = canonical
+        .linear_combinations
+        .iter()
+        .enumerate()
+        .rev()
+        .find(|(_, (_, witness))| !in_mul_terms(witness))?;
+
+    let mut rest = canonical.clone();
+    rest.linear_combinations.remove(position);
+    let definition = &rest * (-scale.inverse());
+    Some((*witness, definition))
+}
+
+/// Returns the witnesses used by the opcode.
+fn opcode_witnesses<F: AcirField>(opcode: &Opcode<F>) -> BTreeSet<Witness> {
+    match opcode {
+        Opcode::AssertZero(expr) => CircuitSimulator::expr_wit(expr),
+        Opcode::BlackBoxFuncCall(bb_func) => {
+            let mut witnesses = bb_func.get_input_witnesses();
+            witnesses.extend(bb_func.get_outputs_vec());
+            witnesses
+        }
+        Opcode::MemoryOp { block_id: _, op, predicate } => {
+            let mut witnesses = CircuitSimulator::expr_wit(&op.index);
+            witnesses.extend(CircuitSimulator::expr_wit(&op.value));
+            if let Some(p) = predicate {
+                witnesses.extend(CircuitSimulator::expr_wit(p));
+            }
+            witnesses
+        }
+        Opcode::MemoryInit { block_id: _, init, block_type: _ } => init.iter().cloned().collect(),
+        Opcode::BrilligCall { inputs, outputs, predicate, .. } => {
+            let mut witnesses = BTreeSet::new();
+            for input in inputs {
+                match input {
+                    BrilligInputs::Single(expr) => {
+                        witnesses.extend(CircuitSimulator::expr_wit(expr));
+                    }
+                    BrilligInputs::Array(exprs) => {
+                        for expr in exprs {
+                            witnesses.extend(CircuitSimulator::expr_wit(expr));
+                        }
+                    }
+                    // The witnesses of memory blocks are used by their `MemoryInit` opcode
+                    BrilligInputs::MemoryArray(_) => (),
+                }
+            }
+            for output in outputs {
+                match output {
+                    BrilligOutputs::Simple(witness) => {
+                        witnesses.insert(*witness);
+                    }
+                    BrilligOutputs::Array(outputs) => witnesses.extend(outputs),
+                }
+            }
+            if let Some(p) = predicate {
+                witnesses.extend(CircuitSimulator::expr_wit(p));
+            }
+            witnesses
+        }
+        Opcode::Call { id: _, inputs, outputs, predicate } => {
+            let mut witnesses: BTreeSet<Witness> = inputs.iter().copied().collect();
+            witnesses.extend(outputs);
+            if let Some(p) = predicate {
+                witnesses.extend(CircuitSimulator::expr_wit(p));
+            }
+            witnesses
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use acir::{
+        FieldElement,
+        acir_field::AcirField,
+        circuit::{
+            AssertionPayload, Circuit, ExpressionOrMemory, ExpressionWidth, Opcode, OpcodeLocation,
+            PublicInputs,
+            brillig::{BrilligFunctionId, BrilligInputs, BrilligOutputs},
+        },
+        native_types::{Expression, Witness},
+    };
+
+    use crate::compiler::{CircuitSimulator, optimizers::CommonSubexpressionOptimizer};
+
+    fn expr(
+        mul_terms: &[(i128, u32, u32)],
+        linear_combinations: &[(i128, u32)],
+    ) -> Expression<FieldElement> {
+        Expression {
+            mul_terms: mul_terms
+                .iter()
+                .map(|(scale, lhs, rhs)| (FieldElement::from(*scale), Witness(*lhs), Witness(*rhs)))
+                .collect(),
+            linear_combinations: linear_combinations
+                .iter()
+                .map(|(scale, witness)| (FieldElement::from(*scale), Witness(*witness)))
+                .collect(),
+            q_c: FieldElement::zero(),
+        }
+    }
+
+    fn circuit(opcodes: Vec<Opcode<FieldElement>>, return_values: &[u32]) -> Circuit<FieldElement> {
+        Circuit {
+            current_witness_index: 5,
+            expression_width: ExpressionWidth::Unbounded,
+            opcodes,
+            private_parameters: BTreeSet::from([Witness(0), Witness(1)]),
+            public_parameters: PublicInputs::default(),
+            return_values: PublicInputs(return_values.iter().map(|w| Witness(*w)).collect()),
+            assert_messages: Default::default(),
+        }
+    }
+
+    fn optimize(circuit: Circuit<FieldElement>) -> (Circuit<FieldElement>, Vec<usize>) {
+        assert!(CircuitSimulator::default().check_circuit(&circuit));
+        let acir_opcode_positions = (0..circuit.opcodes.len()).collect();
+        let optimizer = CommonSubexpressionOptimizer::new(circuit);
+        let (optimized_circuit, positions) =
+            optimizer.eliminate_common_subexpressions(acir_opcode_positions);
+        // check that the circuit is still valid after optimization
+        assert!(CircuitSimulator::default().check_circuit(&optimized_circuit));
+        (optimized_circuit, positions)
+    }
+
+    #[test]
+    fn removes_scalar_multiple_constraints() {
+        let opcodes = vec![
+            Opcode::AssertZero(expr(&[], &[(2, 0), (3, 1), (-1, 2)])),
+            Opcode::AssertZero(expr(&[], &[(4, 0), (6, 1), (-2, 2)])),
+            Opcode::AssertZero(expr(&[], &[(1, 2), (-1, 3)])),
+        ];
+        let (optimized_circuit, positions) = optimize(circuit(opcodes.clone(), &[3]));
+
+        assert_eq!(optimized_circuit.opcodes, vec![opcodes[0].clone(), opcodes[2].clone()]);
+        assert_eq!(positions, vec![0, 2]);
+    }
+
+    #[test]
+    fn merges_duplicate_intermediate_witnesses() {
+        let opcodes = vec![
+            Opcode::AssertZero(expr(&[(1, 0, 1)], &[(-1, 2)])),
+            Opcode::AssertZero(expr(&[(2, 0, 1)], &[(-2, 3)])),
+            Opcode::AssertZero(expr(&[], &[(1, 2), (1, 3), (-1, 4)])),
+        ];
+        let (optimized_circuit, positions) = optimize(circuit(opcodes.clone(), &[4]));
+
+        assert_eq!(
+            optimized_circuit.opcodes,
+            vec![opcodes[0].clone(), Opcode::AssertZero(expr(&[], &[(2, 2), (-1, 4)]))]
+        );
+        assert_eq!(positions, vec![0, 2]);
+    }
+
+    #[test]
+    fn does_not_merge_witnesses_used_outside_of_constraints() {
+        let opcodes = vec![
+            Opcode::AssertZero(expr(&[(1, 0, 1)], &[(-1, 2)])),
+            Opcode::AssertZero(expr(&[(1, 0, 1)], &[(-1, 3)])),
+            Opcode::BrilligCall {
+                id: BrilligFunctionId::default(),
+                inputs: vec![BrilligInputs::Single(expr(&[], &[(1, 3)]))],
+                outputs: vec![BrilligOutputs::Simple(Witness(4))],
+                predicate: None,
+            },
+            Opcode::AssertZero(expr(&[(1, 0, 1)], &[(-1, 5)])),
+        ];
+        // `_3` is used by a Brillig call and `_5` is returned by the circuit
+        let (optimized_circuit, positions) = optimize(circuit(opcodes.clone(), &[5]));
+
+        assert_eq!(optimized_circuit.opcodes, opcodes);
+        assert_eq!(positions, vec![0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn does_not_merge_witnesses_used_by_assertion_payloads() {
+        let opcodes = vec![
+            Opcode::AssertZero(expr(&[(1, 0, 1)], &[(-1, 2)])),
+            Opcode::AssertZero(expr(&[(1, 0, 1)], &[(-1, 3)])),
+            Opcode::AssertZero(expr(&[], &[(1, 2), (1, 3), (-1, 4)])),
+        ];
+        let mut circuit = circuit(opcodes.clone(), &[4]);
+        // The failure of the last constraint reports the value of `_3`
+        circuit.assert_messages = vec![(
+            OpcodeLocation::Acir(2),
+            AssertionPayload {
+                error_selector: 0,
+                payload: vec![ExpressionOrMemory::Expression(expr(&[], &[(1, 3)]))],
+            },
+        )];
+        let (optimized_circuit, positions) = optimize(circuit);
+
+        assert_eq!(optimized_circuit.opcodes, opcodes);
+        assert_eq!(positions, vec![0, 1, 2]);
+    }
+}
@@ -3,11 +3,13 @@ use acir::{
     circuit::{Circuit, Opcode},
 };
 
+mod common_subexpression;
 mod general;
 mod merge_expressions;
 mod redundant_range;
 mod unused_memory;
 
+pub(crate) use common_subexpression::CommonSubexpressionOptimizer;
 pub(crate) use general::GeneralOptimizer;
 pub(crate) use merge_expressions::MergeExpressionsOptimizer;
 pub(crate) use redundant_range::RangeOptimizer;
@@ -18,12 +20,18 @@ use self::unused_memory::UnusedMemoryOptimizer;
 use super::{AcirTransformationMap, transform_assert_messages};
 
 /// Applies backend independent optimizations to a [`Circuit`].
-pub fn optimize<F: AcirField>(acir: Circuit<F>) -> (Circuit<F>, AcirTransformationMap) {
+///
+/// The elimination of common subexpressions is opt-in, as it changes the ACIR of existing programs.
+pub fn optimize<F: AcirField>(
+    acir: Circuit<F>,
+    eliminate_common_subexpressions: bool,
+) -> (Circuit<F>, AcirTransformationMap) {
     // Track original acir opcode positions throughout the transformation passes of the compilation
     // by applying the modifications done to the circuit opcodes and also to the opcode_positions (delete and insert)
     let acir_opcode_positions = (0..acir.opcodes.len()).collect();
 
-    let (mut acir, new_opcode_positions) = optimize_internal(acir, acir_opcode_positions);
+    let (mut acir, new_opcode_positions) =
+        optimize_internal(acir, acir_opcode_positions, eliminate_common_subexpressions);
 
     let transformation_map = AcirTransformationMap::new(&new_opcode_positions);
 
@@ -39,6 +47,7 @@ pub fn optimize<F: AcirField>(acir: Circuit<F>) -> (Circuit<F>, AcirTransformati
 pub(super) fn optimize_internal<F: AcirField>(
     acir: Circuit<F>,
     acir_opcode_positions: Vec<usize>,
+    eliminate_common_subexpressions: bool,
 ) -> (Circuit<F>, Vec<usize>) {
     if acir.opcodes.len() == 1 && matches!(acir.opcodes[0], Opcode::BrilligCall { .. }) {
         info!("Program is fully unconstrained, skipping optimization pass");
@@ -61,6 +70,14 @@ pub(super) fn optimize_internal<F: AcirField>(
         .collect();
     let acir = Circuit { opcodes, ..acir };
 
+    // Common subexpression elimination pass
+    let (acir, acir_opcode_positions) = if eliminate_common_subexpressions {
+        let cse_optimizer = CommonSubexpressionOptimizer::new(acir);
+        cse_optimizer.eliminate_common_subexpressions(acir_opcode_positions)
+    } else {
+        (acir, acir_opcode_positions)
+    };
+
     // Unused memory optimization pass
     let memory_optimizer = UnusedMemoryOptimizer::new(acir);
     let (acir, acir_opcode_positions) =
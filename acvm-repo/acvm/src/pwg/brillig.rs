@@ -19,8 +19,8 @@ use serde::{Deserialize, Serialize};
 use crate::{OpcodeResolutionError, pwg::OpcodeNotSolvable};
 
 use super::{
-    ErrorSelector, RawAssertionPayload, ResolvedAssertionPayload, get_value, insert_value,
-    memory_op::MemoryOpSolver,
+    ErrorSelector, ExecutionBudget, RawAssertionPayload, ResolvedAssertionPayload, get_value,
+    insert_value, memory_op::MemoryOpSolver,
 };
 
 #[derive(Debug)]
@@ -177,6 +177,21 @@ impl<'b, B: BlackBoxFunctionSolver<F>, F: AcirField> BrilligSolver<'b, F, B> {
         self.handle_vm_status(status)
     }
 
+    /// Like [Self::solve], but counts each executed opcode against the budget
+    /// and stops with an error as soon as it's exhausted.
+    pub(crate) fn solve_with_budget(
+        &mut self,
+        budget: &ExecutionBudget,
+    ) -> Result<BrilligSolverStatus<F>, OpcodeResolutionError<F>> {
+        loop {
+            budget.consume_step()?;
+            let status = self.vm.process_opcode();
+            if !matches!(status, VMStatus::InProgress) {
+                return self.handle_vm_status(status);
+            }
+        }
+    }
+
     pub fn step(&mut self) -> Result<BrilligSolverStatus<F>, OpcodeResolutionError<F>> {
         let status = self.vm.process_opcode();
         self.handle_vm_status(status)
@@ -250,6 +265,12 @@ impl<'b, B: BlackBoxFunctionSolver<F>, F: AcirField> BrilligSolver<'b, F, B> {
         Ok(self.vm.take_profiling_samples())
     }
 
+    /// Takes the profiling samples of the opcodes executed so far, for example
+    /// to keep them when the execution fails.
+    pub(crate) fn take_profiling_samples(&mut self) -> BrilligProfilingSamples {
+        self.vm.take_profiling_samples()
+    }
+
     /// Finalize the VM execution and write the outputs to the provided witness map.
     fn finalize_inner(
         &self,
@@ -0,0 +1,84 @@
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+use super::{ErrorLocation, OpcodeResolutionError};
+
+/// Limits the amount of work done while executing a program.
+///
+/// Each executed ACIR opcode and Brillig instruction counts as one step. A budget is
+/// shared by reference between the ACVMs executing each function of a program, and
+/// can be interrupted from another thread, for example once a timeout has elapsed.
+#[derive(Debug, Default)]
+pub struct ExecutionBudget {
+    step_limit: Option<usize>,
+    executed_steps: AtomicUsize,
+    interrupted: AtomicBool,
+}
+
+impl ExecutionBudget {
+    pub fn new(step_limit: Option<usize>) -> Self {
+        ExecutionBudget { step_limit, ..Default::default() }
+    }
+
+    /// Makes the execution fail with [OpcodeResolutionError::ExecutionInterrupted] at its next step.
+    pub fn interrupt(&self) {
+        self.interrupted.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_interrupted(&self) -> bool {
+        self.interrupted.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of steps executed so far.
+    pub fn executed_steps(&self) -> usize {
+        self.executed_steps.load(Ordering::Relaxed)
+    }
+
+    /// Counts one more step, returning an error if the execution can't take it.
+    pub(crate) fn consume_step<F>(&self) -> Result<(), OpcodeResolutionError<F>> {
+        if self.is_interrupted() {
+            return Err(OpcodeResolutionError::ExecutionInterrupted {
+                opcode_location: ErrorLocation::Unresolved,
+            });
+        }
+        let executed_steps = self.executed_steps.fetch_add(1, Ordering::Relaxed);
+        match self.step_limit {
+            Some(step_limit) if executed_steps >= step_limit => {
+                Err(OpcodeResolutionError::StepLimitExceeded {
+                    opcode_location: ErrorLocation::Unresolved,
+                    step_limit,
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use acir::FieldElement;
+
+    use super::*;
+
+    #[test]
+    fn fails_once_the_step_limit_is_reached() {
+        let budget = ExecutionBudget::new(Some(2));
+        assert!(budget.consume_step::<FieldElement>().is_ok());
+        assert!(budget.consume_step::<FieldElement>().is_ok());
+        assert!(matches!(
+            budget.consume_step::<FieldElement>(),
+            Err(OpcodeResolutionError::StepLimitExceeded { step_limit: 2, .. })
+        ));
+    }
+
+    #[test]
+    fn fails_once_interrupted() {
+        let budget = ExecutionBudget::new(None);
+        assert!(budget.consume_step::<FieldElement>().is_ok());
+        budget.interrupt();
+        assert!(matches!(
+            budget.consume_step::<FieldElement>(),
+            Err(OpcodeResolutionError::ExecutionInterrupted { .. })
+        ));
+        assert_eq!(budget.executed_steps(), 1);
+    }
+}
@@ -15,7 +15,7 @@ use acir::{
     native_types::{Expression, Witness, WitnessMap},
 };
 use acvm_blackbox_solver::BlackBoxResolutionError;
-use brillig_vm::BranchToFeatureMap;
+use brillig_vm::{BranchToFeatureMap, BrilligProfilingSamples};
 
 use self::{
     arithmetic::ExpressionSolver, blackbox::bigint::AcvmBigIntSolver, memory_op::MemoryOpSolver,
@@ -30,9 +30,11 @@ pub(crate) mod arithmetic;
 pub(crate) mod brillig;
 // black box functions
 pub(crate) mod blackbox;
+mod budget;
 mod memory_op;
 
 pub use self::brillig::{BrilligSolver, BrilligSolverStatus};
+pub use self::budget::ExecutionBudget;
 pub use brillig::ForeignCallWaitInfo;
 use serde::{Deserialize, Serialize};
 
@@ -170,6 +172,10 @@ pub enum OpcodeResolutionError<F> {
     AcirCallOutputsMismatch { opcode_location: ErrorLocation, results_size: u32, outputs_size: u32 },
     #[error("(--pedantic): Predicates are expected to be 0 or 1, but found: {pred_value}")]
     PredicateLargerThanOne { opcode_location: ErrorLocation, pred_value: F },
+    #[error("Execution exceeded the limit of {step_limit} steps")]
+    StepLimitExceeded { opcode_location: ErrorLocation, step_limit: usize },
+    #[error("Execution was interrupted")]
+    ExecutionInterrupted { opcode_location: ErrorLocation },
 }
 
 impl<F> From<BlackBoxResolutionError> for OpcodeResolutionError<F> {
@@ -249,6 +255,9 @@ pub struct ACVM<'a, F: AcirField, B: BlackBoxFunctionSolver<F>> {
     brillig_branch_to_feature_map: Option<&'a BranchToFeatureMap>,
 
     brillig_fuzzing_trace: Option<Vec<u32>>,
+
+    // Limits on the number of steps executed, shared with the ACVMs of the other functions
+    budget: Option<&'a ExecutionBudget>,
 }
 
 impl<'a, F: AcirField, B: BlackBoxFunctionSolver<F>> ACVM<'a, F, B> {
@@ -279,6 +288,7 @@ impl<'a, F: AcirField, B: BlackBoxFunctionSolver<F>> ACVM<'a, F, B> {
             brillig_fuzzing_active: false,
             brillig_branch_to_feature_map: None,
             brillig_fuzzing_trace: None,
+            budget: None,
         }
     }
 
@@ -296,6 +306,11 @@ impl<'a, F: AcirField, B: BlackBoxFunctionSolver<F>> ACVM<'a, F, B> {
         self.brillig_branch_to_feature_map = brillig_branch_to_feature_map;
     }
 
+    // Enable execution limits
+    pub fn with_budget(&mut self, budget: Option<&'a ExecutionBudget>) {
+        self.budget = budget;
+    }
+
     pub fn get_brillig_fuzzing_trace(&self) -> Option<Vec<u32>> {
         self.brillig_fuzzing_trace.clone()
     }
@@ -423,6 +438,9 @@ impl<'a, F: AcirField, B: BlackBoxFunctionSolver<F>> ACVM<'a, F, B> {
     /// or a custom implementation to execute the foreign call.
     /// Then it will resume execution of the current ACVM with the results of the call.
     pub fn solve_opcode(&mut self) -> ACVMStatus<F> {
+        if let Some(Err(error)) = self.budget.map(ExecutionBudget::consume_step) {
+            return self.handle_opcode_resolution(Err(error));
+        }
         let opcode = &self.opcodes[self.instruction_pointer];
         let resolution = match opcode {
             Opcode::AssertZero(expr) => ExpressionSolver::solve(&mut self.witness_map, expr),
@@ -496,6 +514,13 @@ impl<'a, F: AcirField, B: BlackBoxFunctionSolver<F>> ACVM<'a, F, B> {
                     OpcodeResolutionError::InvalidInputBitSize {
                         opcode_location: opcode_index,
                         ..
+                    }
+                    | OpcodeResolutionError::StepLimitExceeded {
+                        opcode_location: opcode_index,
+                        ..
+                    }
+                    | OpcodeResolutionError::ExecutionInterrupted {
+                        opcode_location: opcode_index,
                     } => {
                         let location = OpcodeLocation::Acir(self.instruction_pointer());
                         *opcode_index = ErrorLocation::Resolved(location);
@@ -582,10 +607,23 @@ impl<'a, F: AcirField, B: BlackBoxFunctionSolver<F>> ACVM<'a, F, B> {
         };
 
         // If we're fuzzing, we need to get the fuzzing trace on an error
-        let result = solver.solve().inspect_err(|_| {
+        let result = match self.budget {
+            Some(budget) => solver.solve_with_budget(budget),
+            None => solver.solve(),
+        };
+        let result = result.inspect_err(|_| {
             if self.brillig_fuzzing_active {
                 self.brillig_fuzzing_trace = Some(solver.get_fuzzing_trace());
             };
+            // Keep the samples of the opcodes executed up to the failure
+            if self.profiling_active {
+                let samples = solver.take_profiling_samples();
+                self.profiling_samples.extend(brillig_profiling_samples(
+                    self.instruction_pointer,
+                    *id,
+                    samples,
+                ));
+            }
         })?;
 
         match result {
@@ -605,21 +643,11 @@ impl<'a, F: AcirField, B: BlackBoxFunctionSolver<F>> ACVM<'a, F, B> {
                 if self.profiling_active {
                     let profiling_info =
                         solver.finalize_with_profiling(&mut self.witness_map, outputs)?;
-                    profiling_info.into_iter().for_each(|sample| {
-                        let mapped =
-                            sample.call_stack.into_iter().map(|loc| OpcodeLocation::Brillig {
-                                acir_index: self.instruction_pointer,
-                                brillig_index: loc,
-                            });
-                        self.profiling_samples.push(ProfilingSample {
-                            call_stack: std::iter::once(OpcodeLocation::Acir(
-                                self.instruction_pointer,
-                            ))
-                            .chain(mapped)
-                            .collect(),
-                            brillig_function_id: Some(*id),
-                        });
-                    });
+                    self.profiling_samples.extend(brillig_profiling_samples(
+                        self.instruction_pointer,
+                        *id,
+                        profiling_info,
+                    ));
                 } else {
                     solver.finalize(&mut self.witness_map, outputs)?;
                 }
@@ -864,6 +892,24 @@ pub(crate) fn is_predicate_false<F: AcirField>(
     }
 }
 
+/// Maps the samples of a Brillig call made from the given ACIR opcode to full call stacks.
+fn brillig_profiling_samples(
+    acir_index: usize,
+    brillig_function_id: BrilligFunctionId,
+    samples: BrilligProfilingSamples,
+) -> impl Iterator<Item = ProfilingSample> {
+    samples.into_iter().map(move |sample| {
+        let mapped = sample
+            .call_stack
+            .into_iter()
+            .map(|brillig_index| OpcodeLocation::Brillig { acir_index, brillig_index });
+        ProfilingSample {
+            call_stack: std::iter::once(OpcodeLocation::Acir(acir_index)).chain(mapped).collect(),
+            brillig_function_id: Some(brillig_function_id),
+        }
+    })
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct AcirCallWaitInfo<F> {
     /// Index in the list of ACIR function's that should be called
@@ -2,12 +2,15 @@
 #![warn(unused_crate_dependencies, unused_extern_crates)]
 
 use abi_gen::{abi_type_from_hir_type, value_from_hir_expression};
+use acvm::FieldElement;
 use acvm::acir::circuit::ExpressionWidth;
 use acvm::compiler::MIN_EXPRESSION_WIDTH;
 use clap::Args;
 use fm::{FileId, FileManager};
 use iter_extended::vecmap;
-use noirc_abi::{AbiParameter, AbiType, AbiValue};
+use noirc_abi::input_parser::Format;
+use noirc_abi::{Abi, AbiParameter, AbiType, AbiValue};
+use noirc_errors::call_stack::CallStack;
 use noirc_errors::{CustomDiagnostic, DiagnosticKind};
 use noirc_evaluator::brillig::BrilligOptions;
 use noirc_evaluator::create_program;
@@ -24,7 +27,7 @@ use noirc_frontend::monomorphization::{
 };
 use noirc_frontend::node_interner::{FuncId, GlobalId, TypeId};
 use noirc_frontend::token::SecondaryAttributeKind;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::path::{Path, PathBuf};
 use tracing::info;
 
@@ -70,6 +73,11 @@ pub struct CompileOptions {
     #[arg(long = "force")]
     pub force_compile: bool,
 
+    /// Do not reuse, or save, cached front-end results of packages which haven't changed
+    /// since they were last compiled.
+    #[arg(long)]
+    pub no_cache: bool,
+
     /// Emit debug information for the intermediate SSA IR to stdout
     #[arg(long, hide = true)]
     pub show_ssa: bool,
@@ -92,6 +100,12 @@ pub struct CompileOptions {
     #[arg(long, hide = true)]
     pub skip_ssa_pass: Vec<String>,
 
+    /// Interpret the SSA after every pass with the inputs in the given TOML file,
+    /// and fail at the first pass which changes the return values, the printed
+    /// output or whether the execution fails.
+    #[arg(long, hide = true)]
+    pub verify_ssa_passes: Option<PathBuf>,
+
     /// Emit the unoptimized SSA IR to file.
     /// The IR will be dumped into the workspace target directory,
     /// under `[compiled-package].ssa.json`.
@@ -185,6 +199,57 @@ pub struct CompileOptions {
     #[arg(long, hide = true, allow_hyphen_values = true)]
     pub max_bytecode_increase_percent: Option<i32>,
 
+    /// Setting the maximum number of times the body of a Brillig loop is repeated
+    /// by partial unrolling. Values of 0 and 1 disable partial unrolling.
+    #[arg(long, hide = true, default_value_t = 1)]
+    pub brillig_unroll_factor: u32,
+
+    /// Unswitch Brillig loops which branch on a loop invariant condition,
+    /// trading a larger program for fewer executed branches.
+    #[arg(long, hide = true)]
+    pub brillig_unswitch_loops: bool,
+
+    /// Setting the maximum number of SSA instructions a Brillig loop can have after
+    /// being partially unrolled or unswitched.
+    #[arg(long, hide = true, default_value_t = 256)]
+    pub brillig_loop_size_limit: usize,
+
+    /// Setting the maximum number of copies of a non-inlined function which are
+    /// specialized on the constant arguments it is called with. 0 disables specialization.
+    #[arg(long, hide = true, default_value_t = 0)]
+    pub max_function_specializations: usize,
+
+    /// Remove range checks and truncations of values which are already known
+    /// to fit in the checked bit size, based on a value range analysis of the SSA.
+    #[arg(long, hide = true)]
+    pub enable_value_range_analysis: bool,
+
+    /// Eliminate redundant computations across the blocks of Brillig functions
+    /// with a dominator-based global value numbering pass.
+    #[arg(long, hide = true)]
+    pub enable_brillig_global_value_numbering: bool,
+
+    /// Assign the stack slots of the variables of Brillig functions with an interference graph,
+    /// letting variables which are never alive at the same time share a slot.
+    #[arg(long, hide = true)]
+    pub enable_brillig_interference_graph_allocation: bool,
+
+    /// Run the peephole optimizer over the linked bytecode of Brillig entry points.
+    #[arg(long, hide = true)]
+    pub enable_brillig_peephole_optimizer: bool,
+
+    /// Remove duplicate ACIR constraints and merge witnesses defined by the same expression.
+    #[arg(long, hide = true)]
+    pub enable_acir_common_subexpression_elimination: bool,
+
+    /// Replace the default SSA pipeline with a custom one, given as the list of passes to run.
+    #[arg(long, hide = true, conflicts_with = "ssa_pipeline_file")]
+    pub ssa_pipeline: Option<String>,
+
+    /// Replace the default SSA pipeline with a custom one, read from the given file.
+    #[arg(long, hide = true)]
+    pub ssa_pipeline_file: Option<PathBuf>,
+
     /// Use pedantic ACVM solving, i.e. double-check some black-box function
     /// assumptions when solving.
     /// This is disabled by default.
@@ -226,6 +291,9 @@ impl CompileOptions {
                 enable_debug_trace: self.show_brillig,
                 enable_debug_assertions: self.enable_brillig_debug_assertions,
                 enable_array_copy_counter: self.count_array_copies,
+                enable_interference_graph_allocation: self
+                    .enable_brillig_interference_graph_allocation,
+                enable_peephole_optimizer: self.enable_brillig_peephole_optimizer,
             },
             print_codegen_timings: self.benchmark_codegen,
             expression_width: if self.bounded_codegen {
@@ -241,7 +309,17 @@ impl CompileOptions {
                 && self.skip_brillig_constraints_check,
             inliner_aggressiveness: self.inliner_aggressiveness,
             max_bytecode_increase_percent: self.max_bytecode_increase_percent,
+            brillig_unroll_factor: self.brillig_unroll_factor,
+            brillig_unswitch_loops: self.brillig_unswitch_loops,
+            brillig_loop_size_limit: self.brillig_loop_size_limit,
+            max_function_specializations: self.max_function_specializations,
+            enable_value_range_analysis: self.enable_value_range_analysis,
+            enable_brillig_global_value_numbering: self.enable_brillig_global_value_numbering,
+            enable_acir_common_subexpression_elimination: self
+                .enable_acir_common_subexpression_elimination,
+            ssa_pipeline: self.ssa_pipeline.clone(),
             skip_passes: self.skip_ssa_pass.clone(),
+            verify_passes_with_inputs: None,
         }
     }
 }
@@ -277,6 +355,8 @@ impl CompileOptions {
 pub enum CompileError {
     MonomorphizationError(MonomorphizationError),
     RuntimeError(RuntimeError),
+    /// The inputs to verify the SSA passes with couldn't be read
+    SsaVerificationInputs(String),
 }
 
 impl From<MonomorphizationError> for CompileError {
@@ -296,6 +376,9 @@ impl From<CompileError> for CustomDiagnostic {
         match error {
             CompileError::RuntimeError(err) => err.into(),
             CompileError::MonomorphizationError(err) => err.into(),
+            CompileError::SsaVerificationInputs(message) => {
+                CustomDiagnostic::from_message(&message, FileId::default())
+            }
         }
     }
 }
@@ -783,7 +866,9 @@ pub fn compile_no_check(
         || options.show_ssa
         || !options.show_ssa_pass.is_empty()
         || options.emit_ssa
-        || options.minimal_ssa;
+        || options.minimal_ssa
+        || options.verify_ssa_passes.is_some()
+        || options.ssa_pipeline_file.is_some();
 
     // Hash the AST program, which is going to be used to fingerprint the compilation artifact.
     let hash = fxhash::hash64(&program);
@@ -796,7 +881,22 @@ pub fn compile_no_check(
     }
 
     let return_visibility = program.return_visibility();
-    let ssa_evaluator_options = options.as_ssa_options(context.package_build_path.clone());
+    let mut ssa_evaluator_options = options.as_ssa_options(context.package_build_path.clone());
+    if let Some(inputs_file) = &options.verify_ssa_passes {
+        let abi = gen_abi(context, &main_function, return_visibility, BTreeMap::default());
+        let inputs = read_ssa_verification_inputs(inputs_file, &abi)
+            .map_err(CompileError::SsaVerificationInputs)?;
+        ssa_evaluator_options.verify_passes_with_inputs = Some(inputs);
+    }
+    if let Some(pipeline_file) = &options.ssa_pipeline_file {
+        let pipeline = std::fs::read_to_string(pipeline_file).map_err(|error| {
+            RuntimeError::InvalidSsaPipeline {
+                reason: format!("cannot read {}: {error}", pipeline_file.display()),
+                call_stack: CallStack::default(),
+            }
+        })?;
+        ssa_evaluator_options.ssa_pipeline = Some(pipeline);
+    }
 
     let SsaProgramArtifact { program, debug, warnings, names, brillig_names, error_types, .. } =
         if options.minimal_ssa {
@@ -829,6 +929,20 @@ pub fn compile_no_check(
     })
 }
 
+/// Reads the inputs of `main` from a TOML file, as the fields they are ABI encoded into.
+fn read_ssa_verification_inputs(path: &Path, abi: &Abi) -> Result<Vec<FieldElement>, String> {
+    let display = path.display();
+    let input_string =
+        std::fs::read_to_string(path).map_err(|error| format!("cannot read {display}: {error}"))?;
+    let input_map = Format::Toml
+        .parse(&input_string, abi)
+        .map_err(|error| format!("cannot parse {display}: {error}"))?;
+    let witness_map = abi
+        .encode(&input_map, None)
+        .map_err(|error| format!("cannot encode the inputs in {display}: {error}"))?;
+    Ok(witness_map.into_iter().map(|(_, value)| value).collect())
+}
+
 /// Specifies a contract function and extra metadata that
 /// one can use when processing a contract function.
 ///
@@ -0,0 +1,108 @@
+use std::path::Path;
+
+use acvm::{FieldElement, acir::circuit::Program};
+use noirc_driver::{CompileOptions, compile_main, file_manager_with_stdlib, prepare_crate};
+use noirc_frontend::hir::{Context, def_map::parse_file};
+
+/// Compiles a program and returns its ACIR.
+fn compile_program(source: &str) -> Program<FieldElement> {
+    let root = Path::new("");
+    let file_name = Path::new("main.nr");
+    let mut file_manager = file_manager_with_stdlib(root);
+    file_manager.add_file_with_source(file_name, source.to_owned()).expect(
+        "Adding source buffer to file manager should never fail when file manager is empty",
+    );
+    let parsed_files = file_manager
+        .as_file_map()
+        .all_file_ids()
+        .map(|&file_id| (file_id, parse_file(&file_manager, file_id)))
+        .collect();
+
+    let mut context = Context::new(file_manager, parsed_files);
+    let root_crate_id = prepare_crate(&mut context, file_name);
+
+    let (compiled_program, _) =
+        compile_main(&mut context, root_crate_id, &CompileOptions::default(), None)
+            .expect("program should compile");
+    compiled_program.program
+}
+
+#[test]
+fn early_return_compiles_to_the_same_acir_as_nested_if() {
+    let early_return = "
+fn main(x: u32, y: u32) {
+    if x == 0 {
+        return;
+    }
+    assert(y != x);
+}";
+    let nested_if = "
+fn main(x: u32, y: u32) {
+    if x != 0 {
+        assert(y != x);
+    }
+}";
+
+    assert_eq!(compile_program(early_return), compile_program(nested_if));
+}
+
+#[test]
+fn early_return_of_a_value_compiles_to_the_same_acir_as_if_else() {
+    let early_return = "
+fn main(c: bool, a: u32, b: u32) -> pub u32 {
+    if c {
+        return a;
+    }
+    b
+}";
+    let if_else = "
+fn main(c: bool, a: u32, b: u32) -> pub u32 {
+    if c {
+        a
+    } else {
+        b
+    }
+}";
+
+    assert_eq!(compile_program(early_return), compile_program(if_else));
+}
+
+#[test]
+fn early_return_in_loop_compiles_to_the_same_acir_as_a_returned_flag() {
+    let early_return = "
+fn main(values: [u32; 4], threshold: u32) -> pub u32 {
+    for i in 0..4 {
+        if values[i] > threshold {
+            return i;
+        }
+    }
+    4
+}";
+    let returned_flag = "
+fn main(values: [u32; 4], threshold: u32) -> pub u32 {
+    let mut returned = false;
+    let mut return_value = 0;
+    let result = {
+        for i in 0..4 {
+            if !returned {
+                if values[i] > threshold {
+                    return_value = i;
+                    returned = true;
+                }
+            }
+        }
+        if !returned {
+            4
+        } else {
+            0
+        }
+    };
+    if returned {
+        return_value
+    } else {
+        result
+    }
+}";
+
+    assert_eq!(compile_program(early_return), compile_program(returned_flag));
+}
@@ -6,6 +6,7 @@ pub(crate) mod brillig_fn;
 pub(crate) mod brillig_globals;
 pub(crate) mod brillig_slice_ops;
 pub(crate) mod constant_allocation;
+mod register_allocation;
 mod variable_liveness;
 
 use acvm::FieldElement;
@@ -88,5 +89,6 @@ pub(crate) fn gen_brillig_for(
         }
     }
     // Generate the final bytecode
-    Ok(entry_point.finish())
+    let artifact = entry_point.finish();
+    if options.enable_peephole_optimizer { Ok(artifact.optimize()) } else { Ok(artifact) }
 }
@@ -88,11 +88,14 @@ impl<'block, Registers: RegisterAllocator> BrilligBlock<'block, Registers> {
 
         let variables = BlockVariables::new(live_in_no_globals);
 
+        // The stack slots assigned to the variables of the function stay allocated in every block,
+        // so temporaries and call frames are placed above them.
         brillig_context.set_allocated_registers(
             variables
                 .get_available_variables(function_context)
                 .into_iter()
                 .map(|variable| variable.extract_register())
+                .chain(function_context.register_assignment.registers())
                 .collect(),
         );
         let last_uses = function_context.liveness.get_last_uses(&block_id).clone();
@@ -264,12 +267,30 @@ impl<'block, Registers: RegisterAllocator> BrilligBlock<'block, Registers> {
                 call_stack: _,
             } => {
                 let target_block = &dfg[*destination_block];
-                for (src, dest) in arguments.iter().zip(target_block.parameters()) {
-                    // Destinations are block parameters so they should have been allocated previously.
-                    let destination = self.variables.get_allocation(self.function_context, *dest);
-                    let source = self.convert_ssa_value(*src, dfg);
-                    self.brillig_context
-                        .mov_instruction(destination.extract_register(), source.extract_register());
+                if self.function_context.register_assignment.is_empty() {
+                    for (src, dest) in arguments.iter().zip(target_block.parameters()) {
+                        // Destinations are block parameters so they should have been allocated previously.
+                        let destination =
+                            self.variables.get_allocation(self.function_context, *dest);
+                        let source = self.convert_ssa_value(*src, dfg);
+                        self.brillig_context.mov_instruction(
+                            destination.extract_register(),
+                            source.extract_register(),
+                        );
+                    }
+                } else {
+                    let mut sources = Vec::with_capacity(arguments.len());
+                    let mut destinations = Vec::with_capacity(arguments.len());
+                    for (src, dest) in arguments.iter().zip(target_block.parameters()) {
+                        let destination =
+                            self.variables.get_allocation(self.function_context, *dest);
+                        let source = self.convert_ssa_value(*src, dfg);
+                        sources.push(source.extract_register());
+                        destinations.push(destination.extract_register());
+                    }
+                    // Block parameters may share stack slots with the arguments passed to them,
+                    // so they are written as a parallel move.
+                    self.brillig_context.codegen_mov_registers_to_registers(sources, destinations);
                 }
                 self.brillig_context.jump_instruction(
                     self.create_block_label_for_current_function(*destination_block),
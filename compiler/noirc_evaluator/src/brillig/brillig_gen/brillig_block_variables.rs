@@ -10,7 +10,7 @@
 //! - Allocated when first defined in a block (if not already global or hoisted to the global space).
 //! - Cached for reuse to avoid redundant register allocation.
 //! - Deallocated explicitly when no longer needed (as determined by SSA liveness).
-use acvm::FieldElement;
+use acvm::{FieldElement, acir::brillig::MemoryAddress};
 use fxhash::FxHashSet as HashSet;
 
 use crate::{
@@ -76,7 +76,11 @@ impl BlockVariables {
         value_id: ValueId,
         dfg: &DataFlowGraph,
     ) -> BrilligVariable {
-        let variable = allocate_value(value_id, brillig_context, dfg);
+        // Variables assigned a stack slot ahead of time are placed there, the rest get a free register.
+        let variable = match function_context.register_assignment.register_of(value_id) {
+            Some(register) => variable_with_register(dfg.type_of_value(value_id), register),
+            None => allocate_value(value_id, brillig_context, dfg),
+        };
 
         if function_context.ssa_value_allocations.insert(value_id, variable).is_some() {
             unreachable!("ICE: ValueId {value_id:?} was already in cache");
@@ -111,7 +115,10 @@ impl BlockVariables {
             .ssa_value_allocations
             .get(value_id)
             .expect("ICE: Variable allocation not found");
-        brillig_context.deallocate_register(variable.extract_register());
+        // Assigned stack slots stay reserved for the whole function
+        if function_context.register_assignment.register_of(*value_id).is_none() {
+            brillig_context.deallocate_register(variable.extract_register());
+        }
     }
 
     /// Checks if a variable is allocated.
@@ -158,19 +165,22 @@ pub(crate) fn allocate_value_with_type<F, Registers: RegisterAllocator>(
     brillig_context: &mut BrilligContext<F, Registers>,
     typ: Type,
 ) -> BrilligVariable {
+    variable_with_register(typ, brillig_context.allocate_register())
+}
+
+/// Creates the variable of the given type held in the given register.
+fn variable_with_register(typ: Type, register: MemoryAddress) -> BrilligVariable {
     match typ {
         Type::Numeric(_) | Type::Reference(_) | Type::Function => {
             BrilligVariable::SingleAddr(SingleAddrVariable {
-                address: brillig_context.allocate_register(),
+                address: register,
                 bit_size: get_bit_size_from_ssa_type(&typ),
             })
         }
         Type::Array(item_typ, elem_count) => BrilligVariable::BrilligArray(BrilligArray {
-            pointer: brillig_context.allocate_register(),
+            pointer: register,
             size: compute_array_length(&item_typ, elem_count as usize),
         }),
-        Type::Slice(_) => BrilligVariable::BrilligVector(BrilligVector {
-            pointer: brillig_context.allocate_register(),
-        }),
+        Type::Slice(_) => BrilligVariable::BrilligVector(BrilligVector { pointer: register }),
     }
 }
@@ -2,9 +2,12 @@
 use iter_extended::vecmap;
 
 use crate::{
-    brillig::brillig_ir::{
-        artifact::BrilligParameter,
-        brillig_variable::{BrilligVariable, get_bit_size_from_ssa_type},
+    brillig::{
+        BrilligOptions,
+        brillig_ir::{
+            artifact::BrilligParameter,
+            brillig_variable::{BrilligVariable, get_bit_size_from_ssa_type},
+        },
     },
     ssa::ir::{
         basic_block::BasicBlockId,
@@ -16,7 +19,10 @@ use crate::{
 };
 use fxhash::FxHashMap as HashMap;
 
-use super::{constant_allocation::ConstantAllocation, variable_liveness::VariableLiveness};
+use super::{
+    constant_allocation::ConstantAllocation, register_allocation::RegisterAssignment,
+    variable_liveness::VariableLiveness,
+};
 
 /// Information required to compile an SSA [Function] into Brillig bytecode.
 ///
@@ -34,6 +40,8 @@ pub(crate) struct FunctionContext {
     pub(crate) blocks: Vec<BasicBlockId>,
     /// Liveness information for each variable in the function.
     pub(crate) liveness: VariableLiveness,
+    /// The stack slots assigned to the block parameters and instruction results of the function.
+    pub(crate) register_assignment: RegisterAssignment,
     /// Information on where to allocate constants
     pub(crate) constant_allocation: ConstantAllocation,
     /// True if this function is a brillig entry point
@@ -42,7 +50,7 @@ pub(crate) struct FunctionContext {
 
 impl FunctionContext {
     /// Creates a new function context. It will allocate parameters for all blocks and compute the liveness of every variable.
-    pub(crate) fn new(function: &Function, is_entry_point: bool) -> Self {
+    pub(crate) fn new(function: &Function, is_entry_point: bool, options: &BrilligOptions) -> Self {
         let id = function.id();
 
         let mut reverse_post_order = Vec::new();
@@ -51,12 +59,18 @@ impl FunctionContext {
 
         let constants = ConstantAllocation::from_function(function);
         let liveness = VariableLiveness::from_function(function, &constants);
+        let register_assignment = if options.enable_interference_graph_allocation {
+            RegisterAssignment::from_function(function, &reverse_post_order)
+        } else {
+            RegisterAssignment::default()
+        };
 
         Self {
             function_id: Some(id),
             ssa_value_allocations: HashMap::default(),
             blocks: reverse_post_order,
             liveness,
+            register_assignment,
             is_entry_point,
             constant_allocation: constants,
         }
@@ -164,7 +164,6 @@ mod tests {
     use fxhash::FxHashMap as HashMap;
     use noirc_frontend::monomorphization::ast::InlineType;
 
-    use crate::brillig::ValueId;
     use crate::brillig::brillig_gen::brillig_block::BrilligBlock;
     use crate::brillig::brillig_gen::brillig_block_variables::BlockVariables;
     use crate::brillig::brillig_gen::brillig_fn::FunctionContext;
@@ -177,6 +176,7 @@ mod tests {
         create_and_run_vm, create_context, create_entry_point_bytecode,
     };
     use crate::brillig::brillig_ir::{BRILLIG_MEMORY_ADDRESSING_BIT_SIZE, BrilligContext};
+    use crate::brillig::{BrilligOptions, ValueId};
     use crate::ssa::function_builder::FunctionBuilder;
     use crate::ssa::ir::function::RuntimeType;
     use crate::ssa::ir::map::Id;
@@ -191,7 +191,7 @@ mod tests {
         let mut brillig_context = create_context(ssa.main_id);
         brillig_context.enter_context(Label::block(ssa.main_id, Id::test_new(0)));
 
-        let function_context = FunctionContext::new(ssa.main(), true);
+        let function_context = FunctionContext::new(ssa.main(), true, &BrilligOptions::default());
         (ssa, function_context, brillig_context)
     }
 
@@ -337,6 +337,69 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_slice_push_back_nested_vector() {
+        // Items of a slice of slices are the pointers to the inner vectors
+        let outer = vec![FieldElement::from(100_usize), FieldElement::from(200_usize)];
+        let inner_pointer = FieldElement::from(300_usize);
+
+        let arguments = vec![
+            BrilligParameter::Slice(
+                vec![BrilligParameter::SingleAddr(BRILLIG_MEMORY_ADDRESSING_BIT_SIZE)],
+                outer.len(),
+            ),
+            BrilligParameter::SingleAddr(BRILLIG_MEMORY_ADDRESSING_BIT_SIZE),
+        ];
+        let result_length = outer.len() + 1;
+        let result_length_with_metadata = result_length + 2; // Leading length and capacity
+        let returns = vec![BrilligParameter::Array(
+            vec![BrilligParameter::SingleAddr(BRILLIG_MEMORY_ADDRESSING_BIT_SIZE)],
+            result_length_with_metadata,
+        )];
+
+        let (_, mut function_context, mut context) = create_test_environment();
+
+        // Allocate the parameters
+        let source_vector = BrilligVector { pointer: context.allocate_register() };
+        let inner_vector = BrilligVector { pointer: context.allocate_register() };
+
+        // Allocate the results
+        let target_vector = BrilligVector { pointer: context.allocate_register() };
+
+        let brillig_globals = HashMap::default();
+        let hoisted_globals = HashMap::default();
+        let mut block = create_brillig_block(
+            &mut function_context,
+            &mut context,
+            &brillig_globals,
+            &hoisted_globals,
+        );
+
+        block.slice_push_back_operation(
+            target_vector,
+            source_vector,
+            &[BrilligVariable::BrilligVector(inner_vector)],
+        );
+
+        context.codegen_return(&[target_vector.pointer]);
+
+        let bytecode = create_entry_point_bytecode(context, arguments, returns).byte_code;
+        let calldata = outer.iter().copied().chain(vec![inner_pointer]).collect();
+        let (vm, return_data_offset, return_data_size) = create_and_run_vm(calldata, &bytecode);
+        assert_eq!(return_data_size, result_length_with_metadata);
+        let mut returned_vector: Vec<FieldElement> = vm.get_memory()
+            [return_data_offset..(return_data_offset + result_length_with_metadata)]
+            .iter()
+            .map(|mem_val| mem_val.to_field())
+            .collect();
+        let returned_size = returned_vector.remove(0);
+        assert_eq!(returned_size, result_length.into());
+        let _returned_capacity = returned_vector.remove(0);
+
+        let expected_return: Vec<_> = outer.into_iter().chain(vec![inner_pointer]).collect();
+        assert_eq!(returned_vector, expected_return);
+    }
+
     #[test]
     fn test_slice_pop_back_operation() {
         fn test_case_pop(
@@ -0,0 +1,381 @@
+//! This module assigns stack slots to the variables of a Brillig function ahead of code generation.
+//!
+//! Block parameters and instruction results are the variables which live across instructions
+//! and blocks. Instead of handing out a fresh register for each of them as they are defined,
+//! we build an interference graph from their liveness and color it greedily, so that variables
+//! which are never alive at the same time share a slot.
+//!
+//! Coloring prefers, when possible, the slot of values a variable is moved to or from:
+//! - A value passed to a block parameter takes the slot of the parameter, so the move at the jump disappears.
+//! - A returned value takes the slot it is returned in, so the move before the return disappears.
+//!
+//! Function parameters are precolored to the first slots of the frame, as that is where callers pass them.
+use acvm::acir::brillig::MemoryAddress;
+use fxhash::{FxHashMap as HashMap, FxHashSet as HashSet};
+
+use crate::{
+    brillig::brillig_ir::registers::{RegisterAllocator, Stack},
+    ssa::ir::{
+        basic_block::BasicBlockId,
+        dfg::DataFlowGraph,
+        function::Function,
+        instruction::{Binary, BinaryOp, Instruction, TerminatorInstruction},
+        value::{Value, ValueId},
+    },
+};
+
+/// The stack slots assigned to the variables of a function.
+#[derive(Debug, Default)]
+pub(crate) struct RegisterAssignment {
+    slots: HashMap<ValueId, usize>,
+    num_slots: usize,
+}
+
+impl RegisterAssignment {
+    /// Assigns a slot to each variable of the function. `blocks` are the reachable blocks in reverse post order.
+    pub(crate) fn from_function(func: &Function, blocks: &[BasicBlockId]) -> Self {
+        let dfg = &func.dfg;
+        let interference = InterferenceGraph::from_function(func, blocks);
+
+        // Values which are moved into each other when jumping to a block.
+        let mut move_related: HashMap<ValueId, Vec<ValueId>> = HashMap::default();
+        // The slot each returned value is moved to before returning.
+        let mut return_slots: HashMap<ValueId, usize> = HashMap::default();
+        for block in blocks {
+            match dfg[*block].terminator() {
+                Some(TerminatorInstruction::Jmp { destination, arguments, .. }) => {
+                    for (argument, parameter) in
+                        arguments.iter().zip(dfg.block_parameters(*destination))
+                    {
+                        if is_variable(dfg, *argument) {
+                            move_related.entry(*argument).or_default().push(*parameter);
+                            move_related.entry(*parameter).or_default().push(*argument);
+                        }
+                    }
+                }
+                Some(TerminatorInstruction::Return { return_values, .. }) => {
+                    for (slot, value) in return_values.iter().enumerate() {
+                        return_slots.entry(*value).or_insert(slot);
+                    }
+                }
+                _ => (),
+            }
+        }
+
+        let mut assignment = Self::default();
+        for (slot, parameter) in func.parameters().iter().enumerate() {
+            assignment.assign(*parameter, slot);
+        }
+
+        for block in blocks {
+            let definitions = dfg.block_parameters(*block).iter().chain(
+                dfg[*block]
+                    .instructions()
+                    .iter()
+                    .flat_map(|instruction| dfg.instruction_results(*instruction)),
+            );
+            for value in definitions {
+                if assignment.slots.contains_key(value) || !is_variable(dfg, *value) {
+                    continue;
+                }
+                let taken: HashSet<usize> = interference
+                    .neighbors(*value)
+                    .filter_map(|neighbor| assignment.slots.get(&neighbor).copied())
+                    .collect();
+                let preferred = move_related
+                    .get(value)
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|related| assignment.slots.get(related).copied())
+                    .chain(return_slots.get(value).copied())
+                    .find(|slot| !taken.contains(slot));
+                let slot =
+                    preferred.unwrap_or_else(|| (0..).find(|slot| !taken.contains(slot)).unwrap());
+                assignment.assign(*value, slot);
+            }
+        }
+
+        assignment
+    }
+
+    fn assign(&mut self, value: ValueId, slot: usize) {
+        self.slots.insert(value, slot);
+        self.num_slots = self.num_slots.max(slot + 1);
+    }
+
+    /// The register assigned to a variable, if it has been assigned one.
+    pub(crate) fn register_of(&self, value: ValueId) -> Option<MemoryAddress> {
+        self.slots.get(&value).map(|slot| Self::register_of_slot(*slot))
+    }
+
+    /// True if no variable was assigned a slot, e.g. because the assignment is disabled.
+    pub(crate) fn is_empty(&self) -> bool {
+        self.slots.is_empty()
+    }
+
+    /// All the registers reserved for the variables of the function.
+    pub(crate) fn registers(&self) -> impl Iterator<Item = MemoryAddress> {
+        (0..self.num_slots).map(Self::register_of_slot)
+    }
+
+    fn register_of_slot(slot: usize) -> MemoryAddress {
+        MemoryAddress::relative(Stack::start() + slot)
+    }
+}
+
+/// Block parameters and instruction results are the values that get a slot.
+/// Constants are allocated on demand by the block codegen and globals live in global memory.
+fn is_variable(dfg: &DataFlowGraph, value: ValueId) -> bool {
+    matches!(dfg[value], Value::Param { .. } | Value::Instruction { .. })
+}
+
+/// Whether the instruction is compiled into a single opcode, which reads all of its operands
+/// before writing its result. Only then can the result reuse the slot of an operand which dies in it.
+fn reads_operands_before_writing(instruction: &Instruction, dfg: &DataFlowGraph) -> bool {
+    match instruction {
+        Instruction::Binary(Binary { lhs, operator, .. }) => {
+            !dfg.type_of_value(*lhs).is_signed()
+                && matches!(
+                    operator,
+                    BinaryOp::Add { unchecked: true }
+                        | BinaryOp::Sub { unchecked: true }
+                        | BinaryOp::Mul { unchecked: true }
+                        | BinaryOp::Eq
+                        | BinaryOp::And
+                        | BinaryOp::Or
+                        | BinaryOp::Xor
+                )
+        }
+        Instruction::Not(_) => true,
+        _ => false,
+    }
+}
+
+/// Undirected graph of the variables which cannot share a slot.
+#[derive(Default)]
+struct InterferenceGraph {
+    edges: HashMap<ValueId, HashSet<ValueId>>,
+}
+
+impl InterferenceGraph {
+    fn from_function(func: &Function, blocks: &[BasicBlockId]) -> Self {
+        let dfg = &func.dfg;
+        let live_in = compute_live_in(dfg, blocks);
+        let mut graph = Self::default();
+
+        for block in blocks {
+            let mut live: HashSet<ValueId> = dfg[*block]
+                .successors()
+                .flat_map(|successor| live_in[&successor].iter().copied())
+                .collect();
+            if let Some(terminator) = dfg[*block].terminator() {
+                terminator.for_each_value(|value| {
+                    if is_variable(dfg, value) {
+                        live.insert(value);
+                    }
+                });
+            }
+
+            for instruction_id in dfg[*block].instructions().iter().rev() {
+                let instruction = &dfg[*instruction_id];
+                let results = dfg.instruction_results(*instruction_id);
+                let mut operands = Vec::new();
+                instruction.for_each_value(|value| {
+                    if is_variable(dfg, value) {
+                        operands.push(value);
+                    }
+                });
+
+                for result in results {
+                    live.remove(result);
+                }
+                // Results are written while the values live after the instruction, and usually
+                // its operands, are still needed.
+                let conflicts_with_operands = !reads_operands_before_writing(instruction, dfg);
+                for result in results {
+                    for other in live.iter().chain(results) {
+                        graph.add_edge(*result, *other);
+                    }
+                    if conflicts_with_operands {
+                        for operand in &operands {
+                            graph.add_edge(*result, *operand);
+                        }
+                    }
+                }
+                live.extend(operands);
+            }
+
+            // Block parameters are written together by the jumps into the block.
+            let parameters = dfg.block_parameters(*block);
+            for parameter in parameters {
+                live.remove(parameter);
+            }
+            for parameter in parameters {
+                for other in live.iter().chain(parameters) {
+                    graph.add_edge(*parameter, *other);
+                }
+            }
+        }
+
+        graph
+    }
+
+    fn add_edge(&mut self, a: ValueId, b: ValueId) {
+        if a != b {
+            self.edges.entry(a).or_default().insert(b);
+            self.edges.entry(b).or_default().insert(a);
+        }
+    }
+
+    fn neighbors(&self, value: ValueId) -> impl Iterator<Item = ValueId> + '_ {
+        self.edges.get(&value).into_iter().flatten().copied()
+    }
+}
+
+/// Computes the variables alive when each block starts executing.
+///
+/// Unlike [super::variable_liveness::VariableLiveness], which keeps block parameters alive from
+/// their definition point in the immediate dominator, block parameters are considered to be
+/// defined when their block starts and jump arguments to be used by the jump passing them.
+fn compute_live_in(
+    dfg: &DataFlowGraph,
+    blocks: &[BasicBlockId],
+) -> HashMap<BasicBlockId, HashSet<ValueId>> {
+    // The variables each block uses before defining them, and the ones it defines
+    let mut used_before_def: HashMap<BasicBlockId, HashSet<ValueId>> = HashMap::default();
+    let mut defined: HashMap<BasicBlockId, HashSet<ValueId>> = HashMap::default();
+    for block in blocks {
+        let mut block_defined: HashSet<ValueId> =
+            dfg.block_parameters(*block).iter().copied().collect();
+        let mut block_used = HashSet::default();
+        for instruction_id in dfg[*block].instructions() {
+            dfg[*instruction_id].for_each_value(|value| {
+                if is_variable(dfg, value) && !block_defined.contains(&value) {
+                    block_used.insert(value);
+                }
+            });
+            block_defined.extend(dfg.instruction_results(*instruction_id));
+        }
+        if let Some(terminator) = dfg[*block].terminator() {
+            terminator.for_each_value(|value| {
+                if is_variable(dfg, value) && !block_defined.contains(&value) {
+                    block_used.insert(value);
+                }
+            });
+        }
+        used_before_def.insert(*block, block_used);
+        defined.insert(*block, block_defined);
+    }
+
+    let mut live_in: HashMap<BasicBlockId, HashSet<ValueId>> =
+        blocks.iter().map(|block| (*block, HashSet::default())).collect();
+    let mut changed = true;
+    while changed {
+        changed = false;
+        // Going in post order so that most successors are processed before their predecessors
+        for block in blocks.iter().rev() {
+            let mut live: HashSet<ValueId> = dfg[*block]
+                .successors()
+                .flat_map(|successor| live_in[&successor].iter().copied())
+                .filter(|value| !defined[block].contains(value))
+                .collect();
+            live.extend(&used_before_def[block]);
+            if live != live_in[block] {
+                live_in.insert(*block, live);
+                changed = true;
+            }
+        }
+    }
+
+    live_in
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ssa::{
+        ir::{
+            function::Function, instruction::TerminatorInstruction, post_order::PostOrder,
+            value::ValueId,
+        },
+        ssa_gen::Ssa,
+    };
+
+    use super::RegisterAssignment;
+
+    fn assign_registers(function: &Function) -> RegisterAssignment {
+        let mut blocks = PostOrder::with_function(function).as_slice().to_vec();
+        blocks.reverse();
+        RegisterAssignment::from_function(function, &blocks)
+    }
+
+    fn slot(assignment: &RegisterAssignment, value: ValueId) -> usize {
+        assignment.slots[&value]
+    }
+
+    #[test]
+    fn coalesces_loop_variable_with_its_increment() {
+        let src = "
+        brillig(inline) fn main f0 {
+          b0(v0: u32):
+            jmp b1(u32 0)
+          b1(v1: u32):
+            v3 = lt v1, v0
+            jmpif v3 then: b2, else: b3
+          b2():
+            v5 = unchecked_add v1, u32 1
+            jmp b1(v5)
+          b3():
+            return v1
+        }
+        ";
+        let ssa = Ssa::from_str(src).unwrap();
+        let function = ssa.main();
+        let dfg = &function.dfg;
+        let assignment = assign_registers(function);
+
+        let Some(TerminatorInstruction::Jmp { destination: header, .. }) =
+            dfg[function.entry_block()].terminator()
+        else {
+            panic!("Expected the entry block to jump to the loop header");
+        };
+        let loop_variable = dfg.block_parameters(*header)[0];
+        let Some(TerminatorInstruction::JmpIf { then_destination: body, .. }) =
+            dfg[*header].terminator()
+        else {
+            panic!("Expected the loop header to end with a conditional jump");
+        };
+        let increment = dfg.instruction_results(dfg[*body].instructions()[0])[0];
+
+        let parameter = function.parameters()[0];
+        assert_eq!(slot(&assignment, parameter), 0);
+        assert_ne!(slot(&assignment, loop_variable), slot(&assignment, parameter));
+        assert_eq!(slot(&assignment, increment), slot(&assignment, loop_variable));
+        assert_eq!(assignment.num_slots, 3);
+    }
+
+    #[test]
+    fn reuses_slots_of_dead_values() {
+        let src = "
+        brillig(inline) fn main f0 {
+          b0(v0: u32, v1: u32):
+            v2 = mul v0, v1
+            v4 = unchecked_add v2, u32 1
+            return v4
+        }
+        ";
+        let ssa = Ssa::from_str(src).unwrap();
+        let function = ssa.main();
+        let dfg = &function.dfg;
+        let assignment = assign_registers(function);
+
+        let instructions = dfg[function.entry_block()].instructions();
+        let product = dfg.instruction_results(instructions[0])[0];
+        let sum = dfg.instruction_results(instructions[1])[0];
+
+        // The checked multiplication reads its operands after writing its result
+        assert_eq!(slot(&assignment, product), 2);
+        // The sum is written directly in the slot it is returned in
+        assert_eq!(slot(&assignment, sum), 0);
+        assert_eq!(assignment.num_slots, 3);
+    }
+}
@@ -23,6 +23,7 @@ mod codegen_memory;
 mod codegen_stack;
 mod entry_point;
 mod instructions;
+mod peephole;
 
 use artifact::Label;
 use brillig_variable::SingleAddrVariable;
@@ -368,6 +369,8 @@ pub(crate) mod tests {
             enable_debug_trace: true,
             enable_debug_assertions: true,
             enable_array_copy_counter: false,
+            enable_interference_graph_allocation: false,
+            enable_peephole_optimizer: false,
         };
         let mut context = BrilligContext::new(&options);
         context.enter_context(Label::function(id));
@@ -383,6 +386,8 @@ pub(crate) mod tests {
             enable_debug_trace: false,
             enable_debug_assertions: context.enable_debug_assertions,
             enable_array_copy_counter: context.count_arrays_copied,
+            enable_interference_graph_allocation: false,
+            enable_peephole_optimizer: false,
         };
         let artifact = context.artifact();
         let mut entry_point_artifact = BrilligContext::new_entry_point_artifact(
@@ -437,6 +442,8 @@ pub(crate) mod tests {
             enable_debug_trace: true,
             enable_debug_assertions: true,
             enable_array_copy_counter: false,
+            enable_interference_graph_allocation: false,
+            enable_peephole_optimizer: false,
         };
         let mut context = BrilligContext::new(&options);
         let r_stack = ReservedRegisters::free_memory_pointer();
@@ -211,6 +211,8 @@ mod tests {
             enable_debug_trace: true,
             enable_debug_assertions: true,
             enable_array_copy_counter: false,
+            enable_interference_graph_allocation: false,
+            enable_peephole_optimizer: false,
         };
         let mut context = BrilligContext::new(&options);
         context.enter_context(Label::function(FunctionId::test_new(0)));
@@ -0,0 +1,426 @@
+//! Peephole optimizations over linked Brillig bytecode.
+//!
+//! Brillig codegen emits opcodes one SSA instruction at a time and links functions and procedures
+//! together afterwards, which leaves behind patterns only visible on the final bytecode:
+//! - Jumps to jumps, and jumps to the next opcode.
+//! - Constants written into registers already holding them, and moves between registers holding the same value.
+//! - Constants and moves overwritten before being read.
+//! - Code that can't be reached, such as procedures no longer called.
+//!
+//! The optimizations run after jumps have been resolved, so removing an opcode relocates the jump and call
+//! targets, the debug locations and the procedure ranges of the bytecode.
+//! Registers are only tracked within straight-line code: knowledge is dropped at jump targets,
+//! after control flow opcodes and at any opcode that may access memory through a pointer.
+use std::collections::{HashMap, HashSet};
+
+use acvm::acir::brillig::{BitSize, Label, MemoryAddress, Opcode as BrilligOpcode};
+
+use super::artifact::{GeneratedBrillig, OpcodeLocation};
+
+/// What is known about the value held by a register.
+#[derive(Debug, Clone)]
+enum KnownValue<F> {
+    Constant(BitSize, F),
+    /// The register holds the same value as another register.
+    Copy(MemoryAddress),
+}
+
+impl<F: Clone + PartialEq> GeneratedBrillig<F> {
+    /// Applies the peephole optimizations until none of them changes the bytecode.
+    pub(crate) fn optimize(mut self) -> Self {
+        loop {
+            let mut changed = self.thread_jumps();
+
+            let unreachable = self.unreachable_opcodes();
+            changed |= self.remove_opcodes(&unreachable);
+            let redundant = self.redundant_writes();
+            changed |= self.remove_opcodes(&redundant);
+            let dead = self.dead_stores();
+            changed |= self.remove_opcodes(&dead);
+            let jumps_to_next = self.jumps_to_next_opcode();
+            changed |= self.remove_opcodes(&jumps_to_next);
+
+            if !changed {
+                return self;
+            }
+        }
+    }
+
+    /// Retargets jumps and calls which land on an unconditional jump to the final destination of that jump.
+    fn thread_jumps(&mut self) -> bool {
+        let mut changed = false;
+        for index in 0..self.byte_code.len() {
+            let Some(location) = jump_target(&self.byte_code[index]) else {
+                continue;
+            };
+            let destination = self.final_destination(location);
+            if destination != location {
+                *jump_target_mut(&mut self.byte_code[index]).expect("opcode should be a jump") =
+                    destination;
+                changed = true;
+            }
+        }
+        changed
+    }
+
+    /// Follows the unconditional jumps starting at the given location.
+    fn final_destination(&self, mut location: Label) -> Label {
+        // Bounded by the bytecode length so that cycles of jumps don't loop forever
+        for _ in 0..self.byte_code.len() {
+            match self.byte_code.get(location) {
+                Some(BrilligOpcode::Jump { location: next }) => location = *next,
+                _ => break,
+            }
+        }
+        location
+    }
+
+    /// Opcodes which can't be reached from the start of the bytecode.
+    fn unreachable_opcodes(&self) -> Vec<bool> {
+        let mut unreachable = vec![true; self.byte_code.len()];
+        let mut to_visit = vec![0];
+        while let Some(index) = to_visit.pop() {
+            if index >= self.byte_code.len() || !unreachable[index] {
+                continue;
+            }
+            unreachable[index] = false;
+
+            let opcode = &self.byte_code[index];
+            to_visit.extend(jump_target(opcode));
+            // Calls continue at the next opcode once the callee returns
+            if !matches!(
+                opcode,
+                BrilligOpcode::Jump { .. }
+                    | BrilligOpcode::Return
+                    | BrilligOpcode::Stop { .. }
+                    | BrilligOpcode::Trap { .. }
+            ) {
+                to_visit.push(index + 1);
+            }
+        }
+        unreachable
+    }
+
+    /// Constants and moves writing a value the destination register already holds.
+    fn redundant_writes(&self) -> Vec<bool> {
+        let block_starts = self.block_starts();
+        let mut redundant = vec![false; self.byte_code.len()];
+        let mut known: HashMap<MemoryAddress, KnownValue<F>> = HashMap::new();
+
+        for (index, opcode) in self.byte_code.iter().enumerate() {
+            if block_starts.contains(&index) {
+                known.clear();
+            }
+            match opcode {
+                BrilligOpcode::Const { destination, bit_size, value } => {
+                    if let Some(KnownValue::Constant(known_bit_size, known_value)) =
+                        known.get(destination)
+                    {
+                        if known_bit_size == bit_size && known_value == value {
+                            redundant[index] = true;
+                            continue;
+                        }
+                    }
+                    forget(&mut known, *destination);
+                    known.insert(*destination, KnownValue::Constant(*bit_size, value.clone()));
+                }
+                BrilligOpcode::Mov { destination, source } => {
+                    if hold_same_value(&known, *destination, *source) {
+                        redundant[index] = true;
+                        continue;
+                    }
+                    let value = known.get(source).cloned().unwrap_or(KnownValue::Copy(*source));
+                    forget(&mut known, *destination);
+                    known.insert(*destination, value);
+                }
+                _ => match register_accesses(opcode) {
+                    Some((_, destination)) => forget(&mut known, destination),
+                    None => known.clear(),
+                },
+            }
+        }
+        redundant
+    }
+
+    /// Constants and moves into registers which are overwritten before being read.
+    fn dead_stores(&self) -> Vec<bool> {
+        let block_starts = self.block_starts();
+        let mut dead = vec![false; self.byte_code.len()];
+        // The relative registers written by a constant or a move which haven't been read since.
+        let mut unread: HashMap<MemoryAddress, OpcodeLocation> = HashMap::new();
+
+        for (index, opcode) in self.byte_code.iter().enumerate() {
+            if block_starts.contains(&index) {
+                unread.clear();
+            }
+            let Some((reads, destination)) = register_accesses(opcode) else {
+                unread.clear();
+                continue;
+            };
+            for read in reads {
+                if read.is_relative() {
+                    unread.remove(&read);
+                } else {
+                    unread.clear();
+                }
+            }
+            if !destination.is_relative() {
+                unread.clear();
+                continue;
+            }
+            if let Some(previous) = unread.remove(&destination) {
+                dead[previous] = true;
+            }
+            if matches!(opcode, BrilligOpcode::Const { .. } | BrilligOpcode::Mov { .. }) {
+                unread.insert(destination, index);
+            }
+        }
+        dead
+    }
+
+    /// Jumps to the opcode right after them.
+    fn jumps_to_next_opcode(&self) -> Vec<bool> {
+        self.byte_code
+            .iter()
+            .enumerate()
+            .map(|(index, opcode)| {
+                matches!(
+                    opcode,
+                    BrilligOpcode::Jump { .. }
+                        | BrilligOpcode::JumpIf { .. }
+                        | BrilligOpcode::JumpIfNot { .. }
+                ) && jump_target(opcode) == Some(index + 1)
+            })
+            .collect()
+    }
+
+    /// The locations where straight-line code starts: jump and call targets, and the opcodes after control flow opcodes.
+    fn block_starts(&self) -> HashSet<OpcodeLocation> {
+        let mut block_starts = HashSet::new();
+        for (index, opcode) in self.byte_code.iter().enumerate() {
+            if let Some(location) = jump_target(opcode) {
+                block_starts.insert(location);
+                block_starts.insert(index + 1);
+            }
+            if matches!(
+                opcode,
+                BrilligOpcode::Return | BrilligOpcode::Stop { .. } | BrilligOpcode::Trap { .. }
+            ) {
+                block_starts.insert(index + 1);
+            }
+        }
+        block_starts
+    }
+
+    /// Removes the given opcodes, relocating everything that refers to opcode locations.
+    /// Jumps to a removed opcode land on the next opcode kept.
+    fn remove_opcodes(&mut self, removed: &[bool]) -> bool {
+        if !removed.contains(&true) {
+            return false;
+        }
+
+        let mut new_locations = Vec::with_capacity(removed.len() + 1);
+        let mut kept = 0;
+        for is_removed in removed {
+            new_locations.push(kept);
+            if !is_removed {
+                kept += 1;
+            }
+        }
+        new_locations.push(kept);
+
+        let byte_code = std::mem::take(&mut self.byte_code);
+        self.byte_code = byte_code
+            .into_iter()
+            .zip(removed)
+            .filter(|(_, is_removed)| !**is_removed)
+            .map(|(mut opcode, _)| {
+                if let Some(location) = jump_target_mut(&mut opcode) {
+                    *location = new_locations[*location];
+                }
+                opcode
+            })
+            .collect();
+
+        self.locations = std::mem::take(&mut self.locations)
+            .into_iter()
+            .filter(|(location, _)| !removed[*location])
+            .map(|(location, call_stack)| (new_locations[location], call_stack))
+            .collect();
+
+        // Procedures whose opcodes were all removed are dropped
+        self.procedure_locations = std::mem::take(&mut self.procedure_locations)
+            .into_iter()
+            .filter_map(|(procedure_id, (start, end))| {
+                let (start, end) = (new_locations[start], new_locations[end + 1]);
+                (start < end).then_some((procedure_id, (start, end - 1)))
+            })
+            .collect();
+
+        true
+    }
+}
+
+/// The location an opcode may jump or call to.
+fn jump_target<F>(opcode: &BrilligOpcode<F>) -> Option<Label> {
+    match opcode {
+        BrilligOpcode::Jump { location }
+        | BrilligOpcode::JumpIf { location, .. }
+        | BrilligOpcode::JumpIfNot { location, .. }
+        | BrilligOpcode::Call { location } => Some(*location),
+        _ => None,
+    }
+}
+
+fn jump_target_mut<F>(opcode: &mut BrilligOpcode<F>) -> Option<&mut Label> {
+    match opcode {
+        BrilligOpcode::Jump { location }
+        | BrilligOpcode::JumpIf { location, .. }
+        | BrilligOpcode::JumpIfNot { location, .. }
+        | BrilligOpcode::Call { location } => Some(location),
+        _ => None,
+    }
+}
+
+/// The registers read and the register written by an opcode which doesn't access any other memory.
+/// Returns `None` for all other opcodes.
+fn register_accesses<F>(opcode: &BrilligOpcode<F>) -> Option<(Vec<MemoryAddress>, MemoryAddress)> {
+    match opcode {
+        BrilligOpcode::Const { destination, .. } => Some((Vec::new(), *destination)),
+        BrilligOpcode::Mov { destination, source }
+        | BrilligOpcode::Cast { destination, source, .. }
+        | BrilligOpcode::Not { destination, source, .. } => Some((vec![*source], *destination)),
+        BrilligOpcode::BinaryFieldOp { destination, lhs, rhs, .. }
+        | BrilligOpcode::BinaryIntOp { destination, lhs, rhs, .. } => {
+            Some((vec![*lhs, *rhs], *destination))
+        }
+        BrilligOpcode::ConditionalMov { destination, source_a, source_b, condition } => {
+            Some((vec![*source_a, *source_b, *condition], *destination))
+        }
+        _ => None,
+    }
+}
+
+fn hold_same_value<F: PartialEq>(
+    known: &HashMap<MemoryAddress, KnownValue<F>>,
+    a: MemoryAddress,
+    b: MemoryAddress,
+) -> bool {
+    if a == b {
+        return true;
+    }
+    match (known.get(&a), known.get(&b)) {
+        (Some(KnownValue::Copy(copied)), _) if *copied == b => true,
+        (_, Some(KnownValue::Copy(copied))) if *copied == a => true,
+        (Some(KnownValue::Copy(a_copied)), Some(KnownValue::Copy(b_copied))) => {
+            a_copied == b_copied
+        }
+        (
+            Some(KnownValue::Constant(a_bit_size, a_value)),
+            Some(KnownValue::Constant(b_bit_size, b_value)),
+        ) => a_bit_size == b_bit_size && a_value == b_value,
+        _ => false,
+    }
+}
+
+/// Forgets what is known about a register that is being written, and about the registers copied from it.
+fn forget<F>(known: &mut HashMap<MemoryAddress, KnownValue<F>>, written: MemoryAddress) {
+    // Direct addresses may alias relative ones, and writing the stack pointer moves all of them
+    if !written.is_relative() {
+        known.clear();
+        return;
+    }
+    known.retain(|address, value| {
+        *address != written
+            && address.is_relative()
+            && !matches!(value, KnownValue::Copy(copied) if *copied == written || !copied.is_relative())
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use acvm::{
+        FieldElement,
+        acir::brillig::{BitSize, IntegerBitSize, MemoryAddress, Opcode as BrilligOpcode},
+    };
+    use noirc_errors::call_stack::CallStackId;
+
+    use crate::brillig::brillig_ir::artifact::GeneratedBrillig;
+
+    fn optimize(byte_code: Vec<BrilligOpcode<FieldElement>>) -> Vec<BrilligOpcode<FieldElement>> {
+        GeneratedBrillig { byte_code, ..Default::default() }.optimize().byte_code
+    }
+
+    fn constant(register: usize, value: u128) -> BrilligOpcode<FieldElement> {
+        BrilligOpcode::Const {
+            destination: MemoryAddress::relative(register),
+            bit_size: BitSize::Integer(IntegerBitSize::U32),
+            value: FieldElement::from(value),
+        }
+    }
+
+    fn mov(destination: usize, source: usize) -> BrilligOpcode<FieldElement> {
+        BrilligOpcode::Mov {
+            destination: MemoryAddress::relative(destination),
+            source: MemoryAddress::relative(source),
+        }
+    }
+
+    #[test]
+    fn threads_jumps_and_relocates_locations() {
+        let condition = MemoryAddress::relative(1);
+        let generated = GeneratedBrillig {
+            byte_code: vec![
+                BrilligOpcode::JumpIf { condition, location: 2 },
+                BrilligOpcode::Jump { location: 3 },
+                BrilligOpcode::Jump { location: 4 },
+                constant(2, 1),
+                BrilligOpcode::Return,
+            ],
+            locations: [(3, CallStackId::new(1))].into_iter().collect(),
+            ..Default::default()
+        };
+        let optimized = generated.optimize();
+
+        // The jump to a jump is threaded, which leaves the second jump unreachable
+        // and the first one jumping to the next opcode.
+        assert_eq!(
+            optimized.byte_code,
+            vec![
+                BrilligOpcode::JumpIf { condition, location: 2 },
+                constant(2, 1),
+                BrilligOpcode::Return
+            ]
+        );
+        assert_eq!(
+            optimized.locations.into_iter().collect::<Vec<_>>(),
+            vec![(1, CallStackId::new(1))]
+        );
+    }
+
+    #[test]
+    fn removes_redundant_constants_and_moves() {
+        let byte_code = vec![
+            constant(1, 5),
+            mov(2, 1),
+            constant(1, 5),
+            mov(1, 2),
+            mov(3, 3),
+            BrilligOpcode::Return,
+        ];
+        assert_eq!(optimize(byte_code), vec![constant(1, 5), mov(2, 1), BrilligOpcode::Return]);
+    }
+
+    #[test]
+    fn keeps_constants_written_again_after_a_jump_target() {
+        let byte_code =
+            vec![constant(1, 5), mov(2, 1), constant(1, 5), BrilligOpcode::Jump { location: 2 }];
+        assert_eq!(optimize(byte_code.clone()), byte_code);
+    }
+
+    #[test]
+    fn removes_overwritten_stores() {
+        let byte_code = vec![constant(1, 1), constant(1, 2), mov(2, 1), BrilligOpcode::Return];
+        assert_eq!(optimize(byte_code), vec![constant(1, 2), mov(2, 1), BrilligOpcode::Return]);
+    }
+}
@@ -276,6 +276,7 @@ impl DeallocationListAllocator {
                 }
             },
         );
+        let preallocated_registers: BTreeSet<usize> = preallocated_registers.into_iter().collect();
         let mut deallocated_registers = BTreeSet::new();
         for i in start..next_free_register_index {
             if !preallocated_registers.contains(&i) {
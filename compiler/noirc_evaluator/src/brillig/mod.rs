@@ -41,6 +41,11 @@ pub struct BrilligOptions {
     pub enable_debug_trace: bool,
     pub enable_debug_assertions: bool,
     pub enable_array_copy_counter: bool,
+    /// Assign the stack slots of the variables of each function ahead of code generation,
+    /// letting variables which are never alive at the same time share a slot.
+    pub enable_interference_graph_allocation: bool,
+    /// Run the peephole optimizer over the linked bytecode of each entry point.
+    pub enable_peephole_optimizer: bool,
 }
 
 /// Context structure for the brillig pass.
@@ -104,7 +109,7 @@ impl Brillig {
     ) -> BrilligArtifact<FieldElement> {
         let mut brillig_context = BrilligContext::new(options);
 
-        let mut function_context = FunctionContext::new(func, is_entry_point);
+        let mut function_context = FunctionContext::new(func, is_entry_point, options);
 
         brillig_context.enter_context(Label::function(func.id()));
 
@@ -87,6 +87,12 @@ pub enum RuntimeError {
         "Only constant indices are supported when indexing an array containing reference values"
     )]
     DynamicIndexingWithReference { call_stack: CallStack },
+    #[error("Cannot verify SSA passes with the given inputs: {reason}")]
+    InvalidSsaVerificationInputs { reason: String, call_stack: CallStack },
+    #[error("SSA pass `{pass}` changed the behavior of the program: {difference}")]
+    SsaPassChangedBehavior { pass: String, difference: String, call_stack: CallStack },
+    #[error("Invalid SSA pipeline: {reason}")]
+    InvalidSsaPipeline { reason: String, call_stack: CallStack },
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Hash)]
@@ -205,6 +211,9 @@ impl RuntimeError {
             | RuntimeError::ReturnedFunctionFromDynamicIf { call_stack }
             | RuntimeError::BreakOrContinue { call_stack }
             | RuntimeError::DynamicIndexingWithReference { call_stack }
+            | RuntimeError::InvalidSsaVerificationInputs { call_stack, .. }
+            | RuntimeError::SsaPassChangedBehavior { call_stack, .. }
+            | RuntimeError::InvalidSsaPipeline { call_stack, .. }
             | RuntimeError::UnknownReference { call_stack }
             | RuntimeError::RecursionLimit { call_stack, .. } => call_stack,
         }
@@ -230,6 +239,12 @@ impl RuntimeError {
                     Location::dummy(),
                 )
             }
+            // These aren't caused by a particular piece of code, so they have no location
+            RuntimeError::InvalidSsaVerificationInputs { .. }
+            | RuntimeError::SsaPassChangedBehavior { .. }
+            | RuntimeError::InvalidSsaPipeline { .. } => {
+                CustomDiagnostic::simple_error(self.to_string(), String::new(), Location::dummy())
+            }
             RuntimeError::UnknownLoopBound { .. } => {
                 let primary_message = self.to_string();
                 let location =
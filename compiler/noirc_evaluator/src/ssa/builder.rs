@@ -3,11 +3,13 @@ use std::io::Write;
 use std::path::Path;
 use std::{collections::HashMap, path::PathBuf};
 
+use acvm::FieldElement;
 use noirc_frontend::monomorphization::ast::Program;
 
 use crate::errors::RuntimeError;
 
 use super::ssa_gen::generate_ssa;
+use super::translation_validation::TranslationValidator;
 use super::{Ssa, SsaLogging};
 
 type SsaPassResult = Result<Ssa, RuntimeError>;
@@ -83,6 +85,8 @@ pub struct SsaBuilder<'local> {
     passed: HashMap<String, usize>,
     /// List of SSA pass message fragments that we want to skip, for testing purposes.
     skip_passes: Vec<String>,
+    /// Checks that each pass preserves the behavior of the program, if enabled.
+    validator: Option<TranslationValidator>,
 
     /// Providing a file manager is optional - if provided it can be used to print source
     /// locations along with each ssa instructions when debugging.
@@ -123,6 +127,7 @@ impl<'local> SsaBuilder<'local> {
             files,
             passed: Default::default(),
             skip_passes: Default::default(),
+            validator: None,
         }
     }
 
@@ -140,6 +145,16 @@ impl<'local> SsaBuilder<'local> {
         self
     }
 
+    /// Interpret the SSA with the given inputs after each pass, failing at the first
+    /// pass which changes the behavior of the program.
+    pub fn with_translation_validation(
+        mut self,
+        inputs: Vec<FieldElement>,
+    ) -> Result<Self, RuntimeError> {
+        self.validator = Some(TranslationValidator::new(&self.ssa, inputs)?);
+        Ok(self)
+    }
+
     pub fn finish(self) -> Ssa {
         self.ssa.generate_entry_point_index()
     }
@@ -177,6 +192,9 @@ impl<'local> SsaBuilder<'local> {
 
         if !skip {
             self.ssa = time(&msg, self.print_codegen_timings, || pass(self.ssa))?;
+            if let Some(validator) = &mut self.validator {
+                validator.validate(&self.ssa, &msg)?;
+            }
             Ok(self.print(&msg))
         } else {
             Ok(self)
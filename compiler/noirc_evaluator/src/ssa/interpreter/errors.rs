@@ -46,6 +46,8 @@ pub enum InterpreterError {
     /// interpret it since we lack the context of what the external function is.
     #[error("Call to unknown foreign function {name}")]
     UnknownForeignFunctionCall { name: String },
+    #[error("Foreign call to {name} failed: {reason}")]
+    ForeignCallFailed { name: String, reason: String },
     #[error("Division by zero: `div {lhs_id}, {rhs_id}`  ({lhs} / {rhs})")]
     DivisionByZero { lhs_id: ValueId, lhs: String, rhs_id: ValueId, rhs: String },
     #[error("Underflow in dec_rc when decrementing reference count of `{value_id} = {value}`")]
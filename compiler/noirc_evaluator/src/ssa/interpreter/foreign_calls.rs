@@ -0,0 +1,197 @@
+//! Resolution of calls to foreign functions (oracles) other than `print`.
+//!
+//! Arguments and results are passed the same way Brillig passes them to the
+//! foreign call executor: numeric values as single fields, and arrays and slices
+//! flattened into a single array of fields.
+use std::sync::Arc;
+
+use acvm::{
+    FieldElement,
+    acir::brillig::{ForeignCallParam, ForeignCallResult},
+    pwg::ForeignCallWaitInfo,
+};
+use iter_extended::try_vecmap;
+
+use crate::ssa::ir::{
+    types::{NumericType, Type},
+    value::ValueId,
+};
+
+use super::{
+    IResult, IResults, Interpreter, InterpreterError,
+    errors::InternalError,
+    value::{NumericValue, Value},
+};
+
+/// Resolves the foreign calls made by the interpreted program.
+///
+/// This mirrors the `ForeignCallExecutor` trait of `nargo`, which can't be used
+/// directly here as `nargo` depends on this crate.
+pub trait ForeignCallExecutor {
+    /// Returns the results of the call, or a description of why it failed.
+    fn execute(
+        &mut self,
+        foreign_call: &ForeignCallWaitInfo<FieldElement>,
+    ) -> Result<ForeignCallResult<FieldElement>, String>;
+}
+
+impl<W: std::io::Write> Interpreter<'_, W> {
+    pub(super) fn call_foreign_function(
+        &mut self,
+        name: String,
+        arguments: Vec<Value>,
+        results: &[ValueId],
+    ) -> IResults {
+        let Some(executor) = self.foreign_call_executor.as_mut() else {
+            return Err(InterpreterError::UnknownForeignFunctionCall { name });
+        };
+
+        let inputs = try_vecmap(&arguments, value_to_foreign_call_param)?;
+        let foreign_call = ForeignCallWaitInfo { function: name, inputs };
+        let result = executor.execute(&foreign_call).map_err(|reason| {
+            InterpreterError::ForeignCallFailed { name: foreign_call.function.clone(), reason }
+        })?;
+
+        let name = foreign_call.function;
+        if result.values.len() != results.len() {
+            let reason = format!(
+                "expected {} return value(s) but got {}",
+                results.len(),
+                result.values.len()
+            );
+            return Err(InterpreterError::ForeignCallFailed { name, reason });
+        }
+
+        let mut values = Vec::with_capacity(results.len());
+        for (index, (result, param)) in results.iter().zip(result.values).enumerate() {
+            let typ = self.dfg().type_of_value(*result);
+            let value = foreign_call_param_to_value(&typ, param).map_err(|reason| {
+                InterpreterError::ForeignCallFailed { name: name.clone(), reason }
+            })?;
+
+            // Like in Brillig, the length of a returned slice is derived from the
+            // returned elements rather than from the value returned for it.
+            if let (Type::Slice(_), Some(length)) = (&typ, index.checked_sub(1)) {
+                let Value::ArrayOrSlice(slice) = &value else {
+                    unreachable!("slices are always converted into array values")
+                };
+                let slice_length = slice.elements.borrow().len() / typ.element_size();
+                values[length] = Value::Numeric(NumericValue::U32(slice_length as u32));
+            }
+            values.push(value);
+        }
+        Ok(values)
+    }
+}
+
+fn value_to_foreign_call_param(value: &Value) -> IResult<ForeignCallParam<FieldElement>> {
+    fn flatten(value: &Value, fields: &mut Vec<FieldElement>) -> IResult<()> {
+        match value {
+            Value::Numeric(numeric) => fields.push(numeric.convert_to_field()),
+            Value::ArrayOrSlice(array) => {
+                for element in array.elements.borrow().iter() {
+                    flatten(element, fields)?;
+                }
+            }
+            other => {
+                return Err(InterpreterError::Internal(InternalError::UnexpectedInput {
+                    name: "foreign call argument",
+                    expected_type: "numeric, array or slice",
+                    value: other.to_string(),
+                }));
+            }
+        }
+        Ok(())
+    }
+
+    match value {
+        Value::Numeric(numeric) => Ok(ForeignCallParam::Single(numeric.convert_to_field())),
+        other => {
+            let mut fields = Vec::new();
+            flatten(other, &mut fields)?;
+            Ok(ForeignCallParam::Array(fields))
+        }
+    }
+}
+
+/// Converts a value returned by a foreign call into a value of the expected type.
+fn foreign_call_param_to_value(
+    typ: &Type,
+    param: ForeignCallParam<FieldElement>,
+) -> Result<Value, String> {
+    match (typ, param) {
+        (Type::Numeric(numeric_type), ForeignCallParam::Single(field)) => {
+            numeric_value(field, *numeric_type)
+        }
+        (Type::Array(element_types, length), ForeignCallParam::Array(fields)) => {
+            let expected = flattened_size(element_types) * *length as usize;
+            if fields.len() != expected {
+                return Err(format!(
+                    "expected an array of {expected} fields for `{typ}` but got {}",
+                    fields.len()
+                ));
+            }
+            let elements = unflatten(element_types, *length as usize, &mut fields.into_iter())?;
+            Ok(Value::array(elements, element_types.to_vec()))
+        }
+        (Type::Slice(element_types), ForeignCallParam::Array(fields)) => {
+            let element_size = flattened_size(element_types);
+            if element_size == 0 || fields.len() % element_size != 0 {
+                return Err(format!(
+                    "expected a multiple of {element_size} fields for `{typ}` but got {}",
+                    fields.len()
+                ));
+            }
+            let length = fields.len() / element_size;
+            let elements = unflatten(element_types, length, &mut fields.into_iter())?;
+            Ok(Value::slice(elements, element_types.clone()))
+        }
+        (typ, ForeignCallParam::Single(_)) => {
+            Err(format!("expected an array for `{typ}` but got a single field"))
+        }
+        (typ, ForeignCallParam::Array(_)) => {
+            Err(format!("expected a single field for `{typ}` but got an array"))
+        }
+    }
+}
+
+/// Reads `length` elements of the given types from the flattened fields.
+pub(super) fn unflatten(
+    element_types: &Arc<Vec<Type>>,
+    length: usize,
+    fields: &mut impl Iterator<Item = FieldElement>,
+) -> Result<Vec<Value>, String> {
+    let mut elements = Vec::with_capacity(length * element_types.len());
+    for _ in 0..length {
+        for element_type in element_types.iter() {
+            let element = match element_type {
+                Type::Numeric(numeric_type) => {
+                    let field = fields.next().expect("the number of fields was checked");
+                    numeric_value(field, *numeric_type)?
+                }
+                Type::Array(nested_types, nested_length) => {
+                    let nested = unflatten(nested_types, *nested_length as usize, fields)?;
+                    Value::array(nested, nested_types.to_vec())
+                }
+                other => return Err(format!("`{other}` values can't be read from fields")),
+            };
+            elements.push(element);
+        }
+    }
+    Ok(elements)
+}
+
+/// The number of fields a value of each of these types is flattened into.
+pub(super) fn flattened_size(types: &[Type]) -> usize {
+    types
+        .iter()
+        .map(|typ| match typ {
+            Type::Array(element_types, length) => flattened_size(element_types) * *length as usize,
+            _ => 1,
+        })
+        .sum()
+}
+
+fn numeric_value(field: FieldElement, typ: NumericType) -> Result<Value, String> {
+    Value::from_constant(field, typ).map_err(|_| format!("`{field}` does not fit in `{typ}`"))
+}
@@ -1,4 +1,4 @@
-use std::{cmp::Ordering, io::Write};
+use std::{cmp::Ordering, io::Write, sync::Arc};
 
 use super::{
     Ssa,
@@ -21,10 +21,12 @@ use noirc_frontend::Shared;
 use value::{ArrayValue, NumericValue, ReferenceValue};
 
 pub mod errors;
+mod foreign_calls;
 mod intrinsics;
 pub(crate) mod tests;
 pub mod value;
 
+pub use foreign_calls::ForeignCallExecutor;
 use value::Value;
 
 struct Interpreter<'ssa, W> {
@@ -43,6 +45,8 @@ struct Interpreter<'ssa, W> {
     options: InterpreterOptions,
     /// Print output.
     output: W,
+    /// Resolves calls to foreign functions other than `print`, if any.
+    foreign_call_executor: Option<&'ssa mut dyn ForeignCallExecutor>,
 }
 
 #[derive(Debug, Clone, Copy, Default)]
@@ -85,26 +89,60 @@ impl Ssa {
         options: InterpreterOptions,
         output: W,
     ) -> IResults {
-        self.interpret_function(self.main_id, args, options, output)
+        self.interpret_function(self.main_id, args, options, output, None)
     }
 
-    fn interpret_function<W: Write>(
+    /// Interprets `main`, resolving calls to foreign functions other than `print`
+    /// with the given executor.
+    pub fn interpret_with_foreign_calls<'a, W: Write>(
+        &'a self,
+        args: Vec<Value>,
+        options: InterpreterOptions,
+        output: W,
+        foreign_call_executor: &'a mut dyn ForeignCallExecutor,
+    ) -> IResults {
+        self.interpret_function(self.main_id, args, options, output, Some(foreign_call_executor))
+    }
+
+    /// Decodes the arguments of `main` from the fields its ABI encoded inputs are
+    /// flattened into, in the order of its parameters.
+    pub(crate) fn main_arguments_from_fields(
         &self,
+        fields: &[FieldElement],
+    ) -> Result<Vec<Value>, String> {
+        let main = &self.functions[&self.main_id];
+        let parameter_types =
+            vecmap(main.parameters(), |parameter| main.dfg.type_of_value(*parameter));
+        let expected = foreign_calls::flattened_size(&parameter_types);
+        if fields.len() != expected {
+            return Err(format!("`main` expects {expected} input fields but got {}", fields.len()));
+        }
+        foreign_calls::unflatten(&Arc::new(parameter_types), 1, &mut fields.iter().copied())
+    }
+
+    fn interpret_function<'a, W: Write>(
+        &'a self,
         function: FunctionId,
         args: Vec<Value>,
         options: InterpreterOptions,
         output: W,
+        foreign_call_executor: Option<&'a mut dyn ForeignCallExecutor>,
     ) -> IResults {
-        let mut interpreter = Interpreter::new(self, options, output);
+        let mut interpreter = Interpreter::new(self, options, output, foreign_call_executor);
         interpreter.interpret_globals()?;
         interpreter.call_function(function, args)
     }
 }
 
 impl<'ssa, W: Write> Interpreter<'ssa, W> {
-    fn new(ssa: &'ssa Ssa, options: InterpreterOptions, output: W) -> Self {
+    fn new(
+        ssa: &'ssa Ssa,
+        options: InterpreterOptions,
+        output: W,
+        foreign_call_executor: Option<&'ssa mut dyn ForeignCallExecutor>,
+    ) -> Self {
         let call_stack = vec![CallContext::global_context()];
-        Self { ssa, call_stack, side_effects_enabled: true, options, output }
+        Self { ssa, call_stack, side_effects_enabled: true, options, output, foreign_call_executor }
     }
 
     fn call_context(&self) -> &CallContext {
@@ -756,7 +794,7 @@ impl<'ssa, W: Write> Interpreter<'ssa, W> {
                 }
                 Value::ForeignFunction(name) if name == "print" => self.call_print(arguments)?,
                 Value::ForeignFunction(name) => {
-                    return Err(InterpreterError::UnknownForeignFunctionCall { name });
+                    self.call_foreign_function(name, arguments, results)?
                 }
                 other => {
                     return Err(internal(InternalError::CalledNonFunction {
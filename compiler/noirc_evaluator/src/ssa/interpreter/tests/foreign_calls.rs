@@ -0,0 +1,151 @@
+use std::sync::Arc;
+
+use acvm::{
+    FieldElement,
+    acir::brillig::{ForeignCallParam, ForeignCallResult},
+    pwg::ForeignCallWaitInfo,
+};
+
+use crate::ssa::{
+    Ssa,
+    function_builder::FunctionBuilder,
+    interpreter::{ForeignCallExecutor, InterpreterError, tests::from_constant, value::Value},
+    ir::{
+        map::Id,
+        types::{NumericType, Type},
+    },
+};
+
+/// Records the calls it receives and answers each of them with the same result.
+struct MockExecutor {
+    calls: Vec<ForeignCallWaitInfo<FieldElement>>,
+    result: Result<ForeignCallResult<FieldElement>, String>,
+}
+
+impl ForeignCallExecutor for MockExecutor {
+    fn execute(
+        &mut self,
+        foreign_call: &ForeignCallWaitInfo<FieldElement>,
+    ) -> Result<ForeignCallResult<FieldElement>, String> {
+        self.calls.push(foreign_call.clone());
+        self.result.clone()
+    }
+}
+
+/// Builds a `main` function passing `Field 2` and `[u8 1, u8 2]` to the `get_values`
+/// oracle, which returns a `[u8]` slice (with its length) and a `Field`.
+fn oracle_program() -> Ssa {
+    let main_id = Id::test_new(0);
+    let mut builder = FunctionBuilder::new("main".into(), main_id);
+    let oracle = builder.import_foreign_function("get_values");
+    let field = builder.field_constant(2_u128);
+    let one = builder.numeric_constant(1_u128, NumericType::unsigned(8));
+    let two = builder.numeric_constant(2_u128, NumericType::unsigned(8));
+    let array_type = Type::Array(Arc::new(vec![Type::unsigned(8)]), 2);
+    let array = builder.insert_make_array(im::vector![one, two], array_type);
+    let slice_type = Type::Slice(Arc::new(vec![Type::unsigned(8)]));
+    let results = builder
+        .insert_call(
+            oracle,
+            vec![field, array],
+            vec![Type::length_type(), slice_type, Type::field()],
+        )
+        .to_vec();
+    builder.terminate_with_return(results);
+    builder.finish()
+}
+
+#[test]
+fn passes_values_to_foreign_call_executor() {
+    let mut executor = MockExecutor {
+        calls: Vec::new(),
+        result: Ok(ForeignCallResult {
+            values: vec![
+                ForeignCallParam::Single(FieldElement::from(0_u128)),
+                ForeignCallParam::Array(vec![3_u128.into(), 4_u128.into(), 5_u128.into()]),
+                ForeignCallParam::Single(FieldElement::from(6_u128)),
+            ],
+        }),
+    };
+    let ssa = oracle_program();
+    let results = ssa
+        .interpret_with_foreign_calls(
+            Vec::new(),
+            Default::default(),
+            std::io::empty(),
+            &mut executor,
+        )
+        .unwrap();
+
+    assert_eq!(executor.calls.len(), 1);
+    assert_eq!(executor.calls[0].function, "get_values");
+    assert_eq!(
+        executor.calls[0].inputs,
+        vec![
+            ForeignCallParam::Single(FieldElement::from(2_u128)),
+            ForeignCallParam::Array(vec![1_u128.into(), 2_u128.into()]),
+        ]
+    );
+
+    // The slice length is derived from the returned elements
+    let u8_type = NumericType::unsigned(8);
+    let elements = vec![
+        from_constant(3_u128.into(), u8_type),
+        from_constant(4_u128.into(), u8_type),
+        from_constant(5_u128.into(), u8_type),
+    ];
+    let expected = vec![
+        from_constant(3_u128.into(), NumericType::length_type()),
+        Value::slice(elements, Arc::new(vec![Type::unsigned(8)])),
+        from_constant(6_u128.into(), NumericType::NativeField),
+    ];
+    assert_eq!(results, expected);
+}
+
+#[test]
+fn fails_on_unexpected_foreign_call_results() {
+    let mut executor = MockExecutor {
+        calls: Vec::new(),
+        result: Ok(ForeignCallResult {
+            values: vec![ForeignCallParam::Single(FieldElement::from(0_u128))],
+        }),
+    };
+    let ssa = oracle_program();
+    let error = ssa
+        .interpret_with_foreign_calls(
+            Vec::new(),
+            Default::default(),
+            std::io::empty(),
+            &mut executor,
+        )
+        .unwrap_err();
+    assert!(matches!(error, InterpreterError::ForeignCallFailed { .. }));
+}
+
+#[test]
+fn fails_on_foreign_call_errors() {
+    let mut executor =
+        MockExecutor { calls: Vec::new(), result: Err("no handler for get_values".to_string()) };
+    let ssa = oracle_program();
+    let error = ssa
+        .interpret_with_foreign_calls(
+            Vec::new(),
+            Default::default(),
+            std::io::empty(),
+            &mut executor,
+        )
+        .unwrap_err();
+    let expected = InterpreterError::ForeignCallFailed {
+        name: "get_values".to_string(),
+        reason: "no handler for get_values".to_string(),
+    };
+    assert_eq!(error, expected);
+}
+
+#[test]
+fn fails_on_foreign_calls_without_executor() {
+    let ssa = oracle_program();
+    let error = ssa.interpret(Vec::new()).unwrap_err();
+    let expected = InterpreterError::UnknownForeignFunctionCall { name: "get_values".to_string() };
+    assert_eq!(error, expected);
+}
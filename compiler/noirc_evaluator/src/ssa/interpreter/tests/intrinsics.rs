@@ -52,6 +52,25 @@ fn as_witness() {
     assert_eq!(value, Value::Numeric(NumericValue::Field(1_u128.into())));
 }
 
+#[test]
+fn slice_push_back_nested_slice() {
+    let value = expect_value(
+        "
+        brillig(inline) fn main f0 {
+          b0():
+            v0 = make_array [Field 1, Field 2] : [Field]
+            v1 = make_array [u32 2, v0] : [(u32, [Field])]
+            v2 = make_array [Field 3] : [Field]
+            v3, v4 = call slice_push_back(u32 1, v1, u32 1, v2) -> (u32, [(u32, [Field])])
+            v5 = array_get v4, index u32 3 -> [Field]
+            v6 = array_get v5, index u32 0 -> Field
+            return v6
+        }
+    ",
+    );
+    assert_eq!(value, Value::Numeric(NumericValue::Field(3_u128.into())));
+}
+
 #[test]
 fn print() {
     let src = r#"
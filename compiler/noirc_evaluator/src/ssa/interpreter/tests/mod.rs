@@ -13,6 +13,7 @@ use crate::ssa::{
 use super::{InterpreterError, Ssa, Value};
 
 mod black_box;
+mod foreign_calls;
 mod instructions;
 mod intrinsics;
 
@@ -26,7 +26,7 @@ use acvm::{
 
 use ir::instruction::ErrorType;
 use noirc_errors::{
-    call_stack::CallStackId,
+    call_stack::{CallStack, CallStackId},
     debug_info::{DebugFunctions, DebugInfo, DebugTypes, DebugVariables},
 };
 
@@ -49,7 +49,9 @@ pub mod interpreter;
 pub mod ir;
 pub(crate) mod opt;
 pub mod parser;
+pub mod pipeline;
 pub mod ssa_gen;
+mod translation_validation;
 pub(crate) mod validation;
 
 #[derive(Debug, Clone)]
@@ -95,8 +97,43 @@ pub struct SsaEvaluatorOptions {
     /// instruction count is accepted.
     pub max_bytecode_increase_percent: Option<i32>,
 
+    /// Maximum number of times the body of a Brillig loop is repeated by partial unrolling.
+    /// Values of 0 and 1 disable partial unrolling.
+    pub brillig_unroll_factor: u32,
+
+    /// Unswitch Brillig loops which branch on a loop invariant condition.
+    pub brillig_unswitch_loops: bool,
+
+    /// Maximum number of SSA instructions a Brillig loop can have after
+    /// being partially unrolled or unswitched.
+    pub brillig_loop_size_limit: usize,
+
+    /// Maximum number of copies of a non-inlined function specialized on constant arguments.
+    /// A value of 0 disables function specialization.
+    pub max_function_specializations: usize,
+
+    /// Remove range checks and truncations of values which are already known
+    /// to fit in the checked bit size, based on a value range analysis.
+    pub enable_value_range_analysis: bool,
+
+    /// Eliminate redundant computations across the blocks of Brillig functions
+    /// with a dominator-based global value numbering pass.
+    pub enable_brillig_global_value_numbering: bool,
+
+    /// Remove duplicate ACIR constraints and merge witnesses defined by the same expression.
+    pub enable_acir_common_subexpression_elimination: bool,
+
+    /// A description of the SSA passes to run instead of the [primary_passes].
+    /// See the [pipeline] module for the format.
+    pub ssa_pipeline: Option<String>,
+
     /// A list of SSA pass messages to skip, for testing purposes.
     pub skip_passes: Vec<String>,
+
+    /// Interpret the SSA after every pass with these inputs, failing at the first pass
+    /// which changes the behavior of the program. The inputs are the fields which the
+    /// ABI encoded arguments of `main` are flattened into.
+    pub verify_passes_with_inputs: Option<Vec<FieldElement>>,
 }
 
 pub struct ArtifactsAndWarnings(pub Artifacts, pub Vec<SsaReport>);
@@ -133,6 +170,11 @@ pub fn primary_passes(options: &SsaEvaluatorOptions) -> Vec<SsaPass> {
             Ssa::evaluate_static_assert_and_assert_constant,
             "`static_assert` and `assert_constant`",
         ),
+        SsaPass::new(
+            move |ssa| ssa.specialize_functions(options.max_function_specializations),
+            "Function Specialization",
+        )
+        .and_then(Ssa::remove_unreachable_functions),
         SsaPass::new(Ssa::purity_analysis, "Purity Analysis"),
         SsaPass::new(Ssa::loop_invariant_code_motion, "Loop Invariant Code Motion"),
         SsaPass::new_try(
@@ -156,6 +198,16 @@ pub fn primary_passes(options: &SsaEvaluatorOptions) -> Vec<SsaPass> {
         SsaPass::new_try(Ssa::remove_if_else, "Remove IfElse"),
         SsaPass::new(Ssa::purity_analysis, "Purity Analysis"),
         SsaPass::new(Ssa::fold_constants, "Constant Folding"),
+        SsaPass::new(
+            move |ssa| {
+                if options.enable_brillig_global_value_numbering {
+                    ssa.brillig_global_value_numbering()
+                } else {
+                    ssa
+                }
+            },
+            "Global Value Numbering for Brillig",
+        ),
         SsaPass::new(Ssa::flatten_basic_conditionals, "Simplify conditionals for unconstrained"),
         SsaPass::new(Ssa::remove_enable_side_effects, "EnableSideEffectsIf removal"),
         SsaPass::new(Ssa::fold_constants_using_constraints, "Constraint Folding using constraints"),
@@ -163,6 +215,25 @@ pub fn primary_passes(options: &SsaEvaluatorOptions) -> Vec<SsaPass> {
             move |ssa| ssa.unroll_loops_iteratively(options.max_bytecode_increase_percent),
             "Unrolling",
         ),
+        SsaPass::new(
+            move |ssa| {
+                if options.brillig_unswitch_loops {
+                    ssa.unswitch_loops(options.brillig_loop_size_limit)
+                } else {
+                    ssa
+                }
+            },
+            "Loop Unswitching",
+        ),
+        SsaPass::new(
+            move |ssa| {
+                ssa.unroll_loops_partially(
+                    options.brillig_unroll_factor,
+                    options.brillig_loop_size_limit,
+                )
+            },
+            "Partial Unrolling",
+        ),
         SsaPass::new(Ssa::make_constrain_not_equal_instructions, "Adding constrain not equal"),
         SsaPass::new(Ssa::check_u128_mul_overflow, "Check u128 mul overflow"),
         // Simplifying the CFG can have a positive effect on mem2reg: every time we unify with a
@@ -181,6 +252,12 @@ pub fn primary_passes(options: &SsaEvaluatorOptions) -> Vec<SsaPass> {
             // Remove any potentially unnecessary duplication from the Brillig entry point analysis.
             .and_then(Ssa::remove_unreachable_functions),
         SsaPass::new(Ssa::remove_truncate_after_range_check, "Removing Truncate after RangeCheck"),
+        SsaPass::new(
+            move |ssa| {
+                if options.enable_value_range_analysis { ssa.value_range_analysis() } else { ssa }
+            },
+            "Value Range Analysis",
+        ),
         // This pass makes transformations specific to Brillig generation.
         // It must be the last pass to either alter or add new instructions before Brillig generation,
         // as other semantics in the compiler can potentially break (e.g. inserting instructions).
@@ -265,7 +342,11 @@ where
 {
     let ssa_gen_span = span!(Level::TRACE, "ssa_generation");
     let ssa_gen_span_guard = ssa_gen_span.enter();
-    let builder = builder.with_skip_passes(options.skip_passes.clone()).run_passes(primary)?;
+    let mut builder = builder.with_skip_passes(options.skip_passes.clone());
+    if let Some(inputs) = &options.verify_passes_with_inputs {
+        builder = builder.with_translation_validation(inputs.clone())?;
+    }
+    let builder = builder.run_passes(primary)?;
 
     drop(ssa_gen_span_guard);
 
@@ -350,7 +431,24 @@ pub fn create_program(
     options: &SsaEvaluatorOptions,
     files: Option<&fm::FileManager>,
 ) -> Result<SsaProgramArtifact, RuntimeError> {
-    create_program_with_passes(program, options, &primary_passes(options), secondary_passes, files)
+    create_program_with_passes(
+        program,
+        options,
+        &configured_passes(options)?,
+        secondary_passes,
+        files,
+    )
+}
+
+/// The passes to run in the primary pipeline: the custom pipeline given in the options,
+/// or the [primary_passes] if there is none.
+pub fn configured_passes(options: &SsaEvaluatorOptions) -> Result<Vec<SsaPass>, RuntimeError> {
+    match &options.ssa_pipeline {
+        Some(description) => pipeline::custom_passes(description, options).map_err(|reason| {
+            RuntimeError::InvalidSsaPipeline { reason, call_stack: CallStack::default() }
+        }),
+        None => Ok(primary_passes(options)),
+    }
 }
 
 /// Compiles the [`Program`] into [`ACIR`][acvm::acir::circuit::Program] using the minimum amount of SSA passes.
@@ -401,6 +499,7 @@ where
         debug_variables,
         debug_functions,
         debug_types,
+        options.enable_acir_common_subexpression_elimination,
     ))
 }
 
@@ -410,6 +509,7 @@ pub fn combine_artifacts(
     debug_variables: DebugVariables,
     debug_functions: DebugFunctions,
     debug_types: DebugTypes,
+    eliminate_common_subexpressions: bool,
 ) -> SsaProgramArtifact {
     let ArtifactsAndWarnings(
         (generated_acirs, generated_brillig, brillig_function_names, error_types),
@@ -432,6 +532,7 @@ pub fn combine_artifacts(
                 debug_variables.clone(),
                 debug_functions.clone(),
                 debug_types.clone(),
+                eliminate_common_subexpressions,
             )
         })
         .collect();
@@ -464,6 +565,7 @@ pub fn convert_generated_acir_into_circuit(
     debug_variables: DebugVariables,
     debug_functions: DebugFunctions,
     debug_types: DebugTypes,
+    eliminate_common_subexpressions: bool,
 ) -> SsaCircuitArtifact {
     let opcodes = generated_acir.take_opcodes();
     let current_witness_index = generated_acir.current_witness_index().0;
@@ -513,7 +615,8 @@ pub fn convert_generated_acir_into_circuit(
     );
 
     // Perform any ACIR-level optimizations
-    let (optimized_circuit, transformation_map) = acvm::compiler::optimize(circuit);
+    let (optimized_circuit, transformation_map) =
+        acvm::compiler::optimize(circuit, eliminate_common_subexpressions);
     debug_info.update_acir(transformation_map);
 
     SsaCircuitArtifact {
@@ -0,0 +1,208 @@
+//! Function specialization clones functions which are not inlined, such as Brillig functions
+//! and `#[fold]` ACIR functions, for the constant arguments they are called with.
+//!
+//! For example if `foo(x, 3)` is called from several places, a copy of `foo` is created
+//! in which the second parameter is replaced by `3` everywhere, and the call sites are
+//! updated to call the copy. The signature of the copy is unchanged, so the arguments
+//! are still passed, but later passes such as constant folding and loop unrolling can
+//! simplify the body of the copy using the known values.
+//!
+//! To limit the growth of the program, each function is specialized at most a given number
+//! of times, preferring the combinations of constant arguments with the most call sites.
+//! Functions left without callers are expected to be removed by a later pass.
+use std::collections::BTreeMap;
+
+use acvm::FieldElement;
+
+use crate::ssa::{
+    Ssa,
+    ir::{
+        function::{Function, FunctionId},
+        instruction::{Instruction, InstructionId},
+        types::NumericType,
+        value::{Value, ValueMapping},
+    },
+};
+
+/// The constant value of each argument of a call, if it has one.
+type ConstantArguments = Vec<Option<(FieldElement, NumericType)>>;
+
+/// A call instruction in a given function.
+struct CallSite {
+    caller: FunctionId,
+    instruction: InstructionId,
+}
+
+impl Ssa {
+    /// Specialize non-inlined functions on the constant arguments they are called with,
+    /// creating at most `max_specializations` copies of each function.
+    #[tracing::instrument(level = "trace", skip(self))]
+    pub(crate) fn specialize_functions(mut self, max_specializations: usize) -> Ssa {
+        if max_specializations == 0 {
+            return self;
+        }
+
+        for (callee, mut candidates) in self.collect_specialization_candidates() {
+            // The sort is stable, so candidates with the same number of call sites
+            // keep the order in which they were found.
+            candidates.sort_by_key(|(_, call_sites)| std::cmp::Reverse(call_sites.len()));
+
+            for (arguments, call_sites) in candidates.into_iter().take(max_specializations) {
+                let function = self.functions[&callee].clone();
+                let specialized_id =
+                    self.add_fn(|id| specialize_function(id, &function, &arguments));
+
+                for CallSite { caller, instruction } in call_sites {
+                    let caller = self.functions.get_mut(&caller).expect("ICE: unknown caller");
+                    let Instruction::Call { arguments, .. } = caller.dfg[instruction].clone()
+                    else {
+                        unreachable!("ICE: expected a call instruction");
+                    };
+                    let func = caller.dfg.import_function(specialized_id);
+                    caller.dfg[instruction] = Instruction::Call { func, arguments };
+                }
+            }
+        }
+
+        self
+    }
+
+    /// Group the calls to non-inlined functions with at least one constant argument
+    /// by the callee and the constant arguments.
+    fn collect_specialization_candidates(
+        &self,
+    ) -> BTreeMap<FunctionId, Vec<(ConstantArguments, Vec<CallSite>)>> {
+        let mut candidates: BTreeMap<FunctionId, Vec<(ConstantArguments, Vec<CallSite>)>> =
+            BTreeMap::new();
+
+        for (caller_id, caller) in &self.functions {
+            for block in caller.reachable_blocks() {
+                for instruction in caller.dfg[block].instructions() {
+                    let Instruction::Call { func, arguments } = &caller.dfg[*instruction] else {
+                        continue;
+                    };
+                    let Value::Function(callee) = &caller.dfg[*func] else {
+                        continue;
+                    };
+                    if callee == caller_id
+                        || *callee == self.main_id
+                        || !self.functions[callee].runtime().is_entry_point()
+                    {
+                        continue;
+                    }
+
+                    let constants = arguments
+                        .iter()
+                        .map(|argument| caller.dfg.get_numeric_constant_with_type(*argument))
+                        .collect::<ConstantArguments>();
+                    if constants.iter().all(Option::is_none) {
+                        continue;
+                    }
+
+                    let call_site = CallSite { caller: *caller_id, instruction: *instruction };
+                    let per_callee = candidates.entry(*callee).or_default();
+                    match per_callee.iter_mut().find(|(existing, _)| *existing == constants) {
+                        Some((_, call_sites)) => call_sites.push(call_site),
+                        None => per_callee.push((constants, vec![call_site])),
+                    }
+                }
+            }
+        }
+
+        candidates
+    }
+}
+
+/// Copy the function, replacing its parameters by the constant arguments.
+fn specialize_function(
+    id: FunctionId,
+    function: &Function,
+    arguments: &[Option<(FieldElement, NumericType)>],
+) -> Function {
+    let mut specialized = Function::clone_with_id(id, function);
+
+    let mut mapping = ValueMapping::default();
+    for (parameter, argument) in function.parameters().iter().zip(arguments) {
+        if let Some((constant, typ)) = argument {
+            let constant = specialized.dfg.make_constant(*constant, *typ);
+            mapping.insert(*parameter, constant);
+        }
+    }
+
+    for block in specialized.reachable_blocks() {
+        specialized.dfg.replace_values_in_block(block, &mapping);
+    }
+    specialized
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ssa::{opt::assert_normalized_ssa_equals, ssa_gen::Ssa};
+
+    const SRC: &str = "
+        acir(inline) fn main f0 {
+          b0(v0: u32):
+            v3 = call f1(v0, u32 3) -> u32
+            v4 = call f1(v0, u32 3) -> u32
+            v6 = call f1(v0, u32 5) -> u32
+            v7 = add v3, v4
+            v8 = add v7, v6
+            return v8
+        }
+        brillig(inline) fn foo f1 {
+          b0(v0: u32, v1: u32):
+            v2 = mul v0, v1
+            return v2
+        }
+        ";
+
+    #[test]
+    fn specializes_most_frequent_constant_arguments() {
+        let ssa = Ssa::from_str(SRC).unwrap();
+        let ssa = ssa.specialize_functions(1);
+
+        assert_normalized_ssa_equals(
+            ssa,
+            "
+        acir(inline) fn main f0 {
+          b0(v0: u32):
+            v3 = call f2(v0, u32 3) -> u32
+            v4 = call f2(v0, u32 3) -> u32
+            v6 = call f1(v0, u32 5) -> u32
+            v7 = add v3, v4
+            v8 = add v7, v6
+            return v8
+        }
+        brillig(inline) fn foo f1 {
+          b0(v0: u32, v1: u32):
+            v2 = mul v0, v1
+            return v2
+        }
+        brillig(inline) fn foo f2 {
+          b0(v0: u32, v1: u32):
+            v3 = mul v0, u32 3
+            return v3
+        }
+        ",
+        );
+    }
+
+    #[test]
+    fn does_not_specialize_without_constant_arguments() {
+        let src = "
+        acir(inline) fn main f0 {
+          b0(v0: u32, v1: u32):
+            v2 = call f1(v0, v1) -> u32
+            return v2
+        }
+        brillig(inline) fn foo f1 {
+          b0(v0: u32, v1: u32):
+            v2 = mul v0, v1
+            return v2
+        }
+        ";
+        let ssa = Ssa::from_str(src).unwrap();
+        let ssa = ssa.specialize_functions(4);
+        assert_normalized_ssa_equals(ssa, src);
+    }
+}
@@ -0,0 +1,415 @@
+//! Global value numbering for Brillig functions.
+//!
+//! [Constant folding][super::constant_folding] deduplicates instructions, but only reuses
+//! results across blocks in limited cases. This pass walks the blocks of unconstrained
+//! functions in reverse post-order, so that every block is visited after its dominators,
+//! and replaces instructions which were already computed in a dominating block with the
+//! earlier results:
+//! - pure instructions are numbered by the instruction itself and the types of its results,
+//!   and an instruction is available in every block dominated by the block it's in.
+//! - loads are available in a block if it's only reached from its immediate dominator, and
+//!   there were no stores or impure calls since the load was made.
+//!
+//! Before that, instructions which can't fail and which are computed in both branches of
+//! a `jmpif` are hoisted into the block with the `jmpif`, so that they are computed once.
+//!
+//! Instructions creating new arrays aren't deduplicated, as Brillig mutates arrays
+//! it has the only reference to in place.
+use std::collections::HashSet;
+
+use fxhash::FxHashMap as HashMap;
+
+use crate::ssa::{
+    ir::{
+        basic_block::BasicBlockId,
+        cfg::ControlFlowGraph,
+        dom::DominatorTree,
+        function::Function,
+        instruction::{Instruction, InstructionId, TerminatorInstruction},
+        post_order::PostOrder,
+        types::Type,
+        value::{Value, ValueId, ValueMapping},
+    },
+    opt::pure::Purity,
+    ssa_gen::Ssa,
+};
+
+use super::constant_folding::can_be_deduplicated;
+
+/// Identifies the value computed by a pure instruction.
+type Key = (Instruction, Vec<Type>);
+
+impl Ssa {
+    /// Removes instructions of Brillig functions which were already computed in a
+    /// dominating block. See the [`global_value_numbering`][self] module for more information.
+    #[tracing::instrument(level = "trace", skip(self))]
+    pub(crate) fn brillig_global_value_numbering(mut self) -> Ssa {
+        for function in self.functions.values_mut() {
+            if function.runtime().is_brillig() {
+                function.global_value_numbering();
+            }
+        }
+        self
+    }
+}
+
+impl Function {
+    fn global_value_numbering(&mut self) {
+        let cfg = ControlFlowGraph::with_function(self);
+        let post_order = PostOrder::with_cfg(&cfg);
+        let dom = DominatorTree::with_cfg_and_post_order(&cfg, &post_order);
+
+        let mut values_to_replace = ValueMapping::default();
+        self.hoist_common_branch_instructions(&cfg, &mut values_to_replace);
+
+        let mut blocks = post_order.into_vec();
+        blocks.reverse();
+
+        // The numbered instructions and loads available at the end of each block
+        let mut available = HashMap::<BasicBlockId, im::HashMap<Key, Vec<ValueId>>>::default();
+        let mut available_loads = HashMap::<BasicBlockId, im::HashMap<ValueId, ValueId>>::default();
+        for block in blocks {
+            let idom = dom.immediate_dominator(block);
+            let mut numbered = idom.map(|idom| available[&idom].clone()).unwrap_or_default();
+            let mut predecessors = cfg.predecessors(block);
+            let mut loads = match (predecessors.len(), predecessors.next()) {
+                (1, Some(predecessor)) if Some(predecessor) == idom => {
+                    available_loads[&predecessor].clone()
+                }
+                _ => im::HashMap::new(),
+            };
+
+            let instruction_ids = self.dfg[block].take_instructions();
+            for instruction_id in instruction_ids {
+                self.dfg[instruction_id].replace_values(&values_to_replace);
+                let results = self.dfg.instruction_results(instruction_id);
+
+                match &self.dfg[instruction_id] {
+                    Instruction::Load { address } => {
+                        if let Some(value) = loads.get(address) {
+                            values_to_replace.insert(results[0], *value);
+                            continue;
+                        }
+                        loads.insert(*address, results[0]);
+                    }
+                    Instruction::Store { address, value } => {
+                        // The address might alias any other one
+                        loads.clear();
+                        loads.insert(*address, *value);
+                    }
+                    Instruction::Call { func, .. } if !self.is_pure_call(*func) => loads.clear(),
+                    _ => (),
+                }
+
+                if let Some(key) = self.value_number_key(instruction_id) {
+                    if let Some(existing) = numbered.get(&key) {
+                        let results = self.dfg.instruction_results(instruction_id);
+                        for (result, existing) in results.iter().zip(existing) {
+                            values_to_replace.insert(*result, *existing);
+                        }
+                        continue;
+                    }
+                    numbered.insert(key, self.dfg.instruction_results(instruction_id).to_vec());
+                }
+                self.dfg[block].insert_instruction(instruction_id);
+            }
+            self.dfg.replace_values_in_block_terminator(block, &values_to_replace);
+
+            available.insert(block, numbered);
+            available_loads.insert(block, loads);
+        }
+        self.dfg.data_bus.replace_values(&values_to_replace);
+    }
+
+    /// Moves instructions which can't fail and are computed in both branches of a `jmpif`
+    /// into the block with the `jmpif`, from where they dominate both branches.
+    fn hoist_common_branch_instructions(
+        &mut self,
+        cfg: &ControlFlowGraph,
+        values_to_replace: &mut ValueMapping,
+    ) {
+        for block in self.reachable_blocks() {
+            let Some(TerminatorInstruction::JmpIf { then_destination, else_destination, .. }) =
+                self.dfg[block].terminator()
+            else {
+                continue;
+            };
+            let (then_block, else_block) = (*then_destination, *else_destination);
+            if then_block == else_block
+                || cfg.predecessors(then_block).len() != 1
+                || cfg.predecessors(else_block).len() != 1
+            {
+                continue;
+            }
+
+            let mut else_instructions = HashMap::default();
+            for (key, instruction) in self.hoistable_instructions(else_block) {
+                else_instructions.entry(key).or_insert(instruction);
+            }
+            for (key, then_instruction) in self.hoistable_instructions(then_block) {
+                let Some(else_instruction) = else_instructions.remove(&key) else {
+                    continue;
+                };
+                let then_results = self.dfg.instruction_results(then_instruction);
+                let else_results = self.dfg.instruction_results(else_instruction);
+                for (else_result, then_result) in else_results.iter().zip(then_results) {
+                    values_to_replace.insert(*else_result, *then_result);
+                }
+                self.dfg[then_block].instructions_mut().retain(|id| *id != then_instruction);
+                self.dfg[else_block].instructions_mut().retain(|id| *id != else_instruction);
+                self.dfg[block].insert_instruction(then_instruction);
+            }
+        }
+    }
+
+    /// Returns the instructions of a block which can't fail and which only use values
+    /// defined before the block, in order.
+    fn hoistable_instructions(&self, block: BasicBlockId) -> Vec<(Key, InstructionId)> {
+        let instructions = self.dfg[block].instructions();
+        let defined: HashSet<ValueId> = instructions
+            .iter()
+            .flat_map(|instruction| self.dfg.instruction_results(*instruction))
+            .copied()
+            .collect();
+
+        let mut hoistable = Vec::new();
+        for instruction_id in instructions {
+            let instruction = &self.dfg[*instruction_id];
+            if instruction.has_side_effects(&self.dfg) {
+                continue;
+            }
+            let mut uses_block_value = false;
+            instruction.for_each_value(|value| uses_block_value |= defined.contains(&value));
+            if uses_block_value {
+                continue;
+            }
+            if let Some(key) = self.value_number_key(*instruction_id) {
+                hoistable.push((key, *instruction_id));
+            }
+        }
+        hoistable
+    }
+
+    /// Returns the key identifying the value computed by the given instruction,
+    /// if it can be reused for identical instructions.
+    fn value_number_key(&self, instruction_id: InstructionId) -> Option<Key> {
+        let instruction = &self.dfg[instruction_id];
+        let results = self.dfg.instruction_results(instruction_id);
+        let result_types: Vec<Type> =
+            results.iter().map(|result| self.dfg.type_of_value(*result)).collect();
+
+        let creates_arrays = match instruction {
+            Instruction::MakeArray { .. } | Instruction::ArraySet { .. } => true,
+            Instruction::Call { .. } => {
+                result_types.iter().any(|typ| !matches!(typ, Type::Numeric(_)))
+            }
+            _ => false,
+        };
+        // There are no predicates in Brillig, so an instruction dominated by an identical one
+        // fails only if the earlier one would have failed already.
+        if creates_arrays || !can_be_deduplicated(instruction, self, true) {
+            return None;
+        }
+        Some((instruction.clone(), result_types))
+    }
+
+    fn is_pure_call(&self, func: ValueId) -> bool {
+        let purity = match self.dfg[func] {
+            Value::Intrinsic(intrinsic) => Some(intrinsic.purity()),
+            Value::Function(id) => self.dfg.purity_of(id),
+            _ => None,
+        };
+        purity == Some(Purity::Pure)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ssa::{opt::assert_normalized_ssa_equals, ssa_gen::Ssa};
+
+    #[test]
+    fn removes_instructions_computed_in_dominating_blocks() {
+        let src = "
+        brillig(inline) fn main f0 {
+          b0(v0: u32, v1: u32, v2: [u32; 3]):
+            v3 = add v0, v1
+            v4 = array_get v2, index v0 -> u32
+            v5 = eq v0, v1
+            jmpif v5 then: b1, else: b2
+          b1():
+            v6 = add v0, v1
+            v7 = array_get v2, index v0 -> u32
+            v8 = mul v6, v7
+            jmp b3(v8)
+          b2():
+            jmp b3(v3)
+          b3(v9: u32):
+            v10 = add v0, v1
+            v11 = add v10, v9
+            return v11
+        }
+        ";
+        let ssa = Ssa::from_str(src).unwrap();
+        let ssa = ssa.brillig_global_value_numbering();
+
+        let expected = "
+        brillig(inline) fn main f0 {
+          b0(v0: u32, v1: u32, v2: [u32; 3]):
+            v3 = add v0, v1
+            v4 = array_get v2, index v0 -> u32
+            v5 = eq v0, v1
+            jmpif v5 then: b1, else: b2
+          b1():
+            v8 = mul v3, v4
+            jmp b3(v8)
+          b2():
+            jmp b3(v3)
+          b3(v9: u32):
+            v11 = add v3, v9
+            return v11
+        }
+        ";
+        assert_normalized_ssa_equals(ssa, expected);
+    }
+
+    #[test]
+    fn hoists_instructions_computed_in_both_branches() {
+        let src = "
+        brillig(inline) fn main f0 {
+          b0(v0: u32, v1: u32, v2: u1):
+            jmpif v2 then: b1, else: b2
+          b1():
+            v3 = unchecked_mul v0, v1
+            v4 = unchecked_add v3, u32 1
+            jmp b3(v4)
+          b2():
+            v5 = unchecked_mul v0, v1
+            v6 = unchecked_add v5, u32 2
+            jmp b3(v6)
+          b3(v7: u32):
+            v8 = unchecked_mul v0, v1
+            v9 = unchecked_add v7, v8
+            return v9
+        }
+        ";
+        let ssa = Ssa::from_str(src).unwrap();
+        let ssa = ssa.brillig_global_value_numbering();
+
+        let expected = "
+        brillig(inline) fn main f0 {
+          b0(v0: u32, v1: u32, v2: u1):
+            v3 = unchecked_mul v0, v1
+            jmpif v2 then: b1, else: b2
+          b1():
+            v4 = unchecked_add v3, u32 1
+            jmp b3(v4)
+          b2():
+            v6 = unchecked_add v3, u32 2
+            jmp b3(v6)
+          b3(v7: u32):
+            v9 = unchecked_add v7, v3
+            return v9
+        }
+        ";
+        assert_normalized_ssa_equals(ssa, expected);
+    }
+
+    #[test]
+    fn does_not_hoist_instructions_which_can_fail() {
+        let src = "
+        brillig(inline) fn main f0 {
+          b0(v0: u32, v1: u32, v2: u1):
+            jmpif v2 then: b1, else: b2
+          b1():
+            v3 = div v0, v1
+            jmp b3(v3)
+          b2():
+            v4 = div v0, v1
+            jmp b3(v4)
+          b3(v5: u32):
+            return v5
+        }
+        ";
+        let ssa = Ssa::from_str(src).unwrap();
+        let ssa = ssa.brillig_global_value_numbering();
+        assert_normalized_ssa_equals(ssa, src);
+    }
+
+    #[test]
+    fn removes_loads_only_without_stores_in_between() {
+        let src = "
+        brillig(inline) fn main f0 {
+          b0(v0: &mut u32, v1: u1):
+            v2 = load v0 -> u32
+            jmpif v1 then: b1, else: b2
+          b1():
+            v3 = load v0 -> u32
+            store u32 5 at v0
+            v4 = load v0 -> u32
+            v5 = unchecked_add v3, v4
+            jmp b3(v5)
+          b2():
+            jmp b3(v2)
+          b3(v6: u32):
+            v7 = load v0 -> u32
+            v8 = unchecked_add v6, v7
+            return v8
+        }
+        ";
+        let ssa = Ssa::from_str(src).unwrap();
+        let ssa = ssa.brillig_global_value_numbering();
+
+        let expected = "
+        brillig(inline) fn main f0 {
+          b0(v0: &mut u32, v1: u1):
+            v2 = load v0 -> u32
+            jmpif v1 then: b1, else: b2
+          b1():
+            store u32 5 at v0
+            v5 = unchecked_add v2, u32 5
+            jmp b3(v5)
+          b2():
+            jmp b3(v2)
+          b3(v6: u32):
+            v7 = load v0 -> u32
+            v8 = unchecked_add v6, v7
+            return v8
+        }
+        ";
+        assert_normalized_ssa_equals(ssa, expected);
+    }
+
+    #[test]
+    fn does_not_deduplicate_new_arrays() {
+        let src = "
+        brillig(inline) fn main f0 {
+          b0(v0: u32):
+            v1 = make_array [v0, v0] : [u32; 2]
+            v2 = make_array [v0, v0] : [u32; 2]
+            v3 = array_set v1, index u32 0, value u32 1
+            v4 = array_set v1, index u32 0, value u32 1
+            return v2, v3, v4
+        }
+        ";
+        let ssa = Ssa::from_str(src).unwrap();
+        let ssa = ssa.brillig_global_value_numbering();
+        assert_normalized_ssa_equals(ssa, src);
+    }
+
+    #[test]
+    fn leaves_acir_functions_unchanged() {
+        let src = "
+        acir(inline) fn main f0 {
+          b0(v0: u32, v1: u32):
+            jmp b1()
+          b1():
+            v2 = add v0, v1
+            v3 = add v0, v1
+            return v2, v3
+        }
+        ";
+        let ssa = Ssa::from_str(src).unwrap();
+        let ssa = ssa.brillig_global_value_numbering();
+        assert_normalized_ssa_equals(ssa, src);
+    }
+}
@@ -0,0 +1,354 @@
+//! This file contains the loop unswitching pass for Brillig functions.
+//!
+//! A loop which branches on a condition that doesn't change between iterations evaluates
+//! the branch on every iteration. Unswitching moves the branch in front of the loop and
+//! duplicates the loop, so that each version only contains one side of the branch:
+//! ```text
+//!   b0(v0: u1):
+//!     jmp b1(u32 0)
+//!   b1(v1: u32):
+//!     v2 = lt v1, u32 10
+//!     jmpif v2 then: b2, else: b5
+//!   b2():
+//!     jmpif v0 then: b3, else: b4
+//!   ...
+//! ```
+//! becomes:
+//! ```text
+//!   b0(v0: u1):
+//!     jmpif v0 then: b6, else: b7
+//!   b1(v1: u32):
+//!     v2 = lt v1, u32 10
+//!     jmpif v2 then: b2, else: b5
+//!   b2():
+//!     jmp b3()
+//!   ...
+//!   b6():
+//!     jmp b1(u32 0)
+//!   b7():
+//!     jmp b8(u32 0)
+//!   b8(v3: u32):
+//!     v4 = lt v3, u32 10
+//!     jmpif v4 then: b9, else: b5
+//!   b9():
+//!     jmp b11()
+//!   ...
+//! ```
+//! A later CFG simplification merges the blocks left with a single successor and
+//! removes the unreachable sides of the branch.
+//!
+//! As this doubles the size of the loop, a loop is only unswitched if the result doesn't
+//! exceed the configured maximum number of instructions. Loops whose values are used after
+//! the loop are left alone, as they would need to be merged from the two versions.
+use fxhash::FxHashSet as HashSet;
+
+use crate::ssa::{
+    Ssa,
+    ir::{
+        basic_block::BasicBlockId, cfg::ControlFlowGraph, function::Function,
+        function_inserter::FunctionInserter, instruction::TerminatorInstruction,
+        post_order::PostOrder, value::ValueId,
+    },
+};
+
+use super::unrolling::{Loop, Loops, copy_blocks};
+
+impl Ssa {
+    /// Unswitch loops of Brillig functions which branch on a loop invariant condition,
+    /// as long as the duplicated loops do not contain more than `max_loop_size` instructions.
+    #[tracing::instrument(level = "trace", skip(self))]
+    pub(crate) fn unswitch_loops(mut self, max_loop_size: usize) -> Ssa {
+        for function in self.functions.values_mut() {
+            if function.runtime().is_brillig() {
+                function.unswitch_loops(max_loop_size);
+            }
+        }
+        self
+    }
+}
+
+impl Function {
+    fn unswitch_loops(&mut self, max_loop_size: usize) {
+        // Each loop is unswitched at most once, identified by its header.
+        let mut unswitched = HashSet::default();
+
+        // Unswitching changes the blocks of the loop and any loop around it,
+        // so we look for the loops again after each transformation.
+        loop {
+            let loops = Loops::find_all(self);
+            let candidate = loops.yet_to_unroll.iter().find_map(|loop_| {
+                if unswitched.contains(&loop_.header) {
+                    return None;
+                }
+                let branch = loop_.find_invariant_branch(self, &loops.cfg, max_loop_size)?;
+                Some((loop_, branch))
+            });
+            let Some((loop_, branch)) = candidate else {
+                break;
+            };
+            let copied_header = loop_.unswitch(self, branch);
+            unswitched.insert(loop_.header);
+            unswitched.insert(copied_header);
+        }
+    }
+}
+
+/// A branch inside a loop on a condition which is defined outside of it.
+struct InvariantBranch {
+    pre_header: BasicBlockId,
+    block: BasicBlockId,
+    condition: ValueId,
+}
+
+impl Loop {
+    fn find_invariant_branch(
+        &self,
+        function: &Function,
+        cfg: &ControlFlowGraph,
+        max_loop_size: usize,
+    ) -> Option<InvariantBranch> {
+        if cfg.predecessors(self.header).len() != 2 {
+            return None;
+        }
+        let pre_header = self.get_pre_header(function, cfg).ok()?;
+        if !matches!(function.dfg[pre_header].terminator(), Some(TerminatorInstruction::Jmp { .. }))
+        {
+            return None;
+        }
+        if self.count_all_instructions(function).saturating_mul(2) > max_loop_size {
+            return None;
+        }
+
+        let defined_in_loop = self.defined_values(function);
+        if self.is_any_used_after_loop(function, &defined_in_loop) {
+            return None;
+        }
+
+        self.blocks.iter().filter(|block| **block != self.header).find_map(|block| {
+            match function.dfg[*block].terminator()? {
+                TerminatorInstruction::JmpIf {
+                    condition,
+                    then_destination,
+                    else_destination,
+                    ..
+                } if self.blocks.contains(then_destination)
+                    && self.blocks.contains(else_destination)
+                    && !defined_in_loop.contains(condition)
+                    && !function.dfg.is_constant(*condition) =>
+                {
+                    Some(InvariantBranch { pre_header, block: *block, condition: *condition })
+                }
+                _ => None,
+            }
+        })
+    }
+
+    /// Collect the block parameters and instruction results defined in the loop.
+    fn defined_values(&self, function: &Function) -> HashSet<ValueId> {
+        let mut values = HashSet::default();
+        for block in &self.blocks {
+            values.extend(function.dfg.block_parameters(*block).iter().copied());
+            for instruction in function.dfg[*block].instructions() {
+                values.extend(function.dfg.instruction_results(*instruction).iter().copied());
+            }
+        }
+        values
+    }
+
+    fn is_any_used_after_loop(&self, function: &Function, values: &HashSet<ValueId>) -> bool {
+        let mut is_used = false;
+        for block in function.reachable_blocks() {
+            if self.blocks.contains(&block) {
+                continue;
+            }
+            let mut check = |value: ValueId| is_used |= values.contains(&value);
+            for instruction in function.dfg[block].instructions() {
+                function.dfg[*instruction].for_each_value(&mut check);
+            }
+            if let Some(terminator) = function.dfg[block].terminator() {
+                terminator.for_each_value(&mut check);
+            }
+        }
+        is_used
+    }
+
+    /// Duplicate the loop, keeping the `then` side of the branch in the original
+    /// and the `else` side in the copy, and choose between them in the pre-header.
+    ///
+    /// Returns the header of the copy.
+    fn unswitch(&self, function: &mut Function, branch: InvariantBranch) -> BasicBlockId {
+        let post_order = PostOrder::with_function(function);
+        let blocks = post_order
+            .as_slice()
+            .iter()
+            .rev()
+            .filter(|block| self.blocks.contains(block))
+            .copied()
+            .collect::<Vec<_>>();
+
+        let mut inserter = FunctionInserter::new(function);
+        let copies = copy_blocks(&mut inserter, &blocks);
+        let copied_header = copies[&self.header];
+
+        let TerminatorInstruction::JmpIf { then_destination, else_destination, call_stack, .. } =
+            function.dfg[branch.block].unwrap_terminator().clone()
+        else {
+            unreachable!("ICE: expected the invariant branch to be a jmpif");
+        };
+        let jmp = |destination| TerminatorInstruction::Jmp {
+            destination,
+            arguments: Vec::new(),
+            call_stack,
+        };
+        function.dfg.set_block_terminator(branch.block, jmp(then_destination));
+        function.dfg.set_block_terminator(copies[&branch.block], jmp(copies[&else_destination]));
+
+        let TerminatorInstruction::Jmp { arguments, call_stack, .. } =
+            function.dfg[branch.pre_header].unwrap_terminator().clone()
+        else {
+            unreachable!("ICE: expected the loop pre-header to end in a jmp");
+        };
+        let then_entry = function.dfg.make_block();
+        let else_entry = function.dfg.make_block();
+        function.dfg.set_block_terminator(
+            then_entry,
+            TerminatorInstruction::Jmp {
+                destination: self.header,
+                arguments: arguments.clone(),
+                call_stack,
+            },
+        );
+        function.dfg.set_block_terminator(
+            else_entry,
+            TerminatorInstruction::Jmp { destination: copied_header, arguments, call_stack },
+        );
+        function.dfg.set_block_terminator(
+            branch.pre_header,
+            TerminatorInstruction::JmpIf {
+                condition: branch.condition,
+                then_destination: then_entry,
+                else_destination: else_entry,
+                call_stack,
+            },
+        );
+
+        copied_header
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ssa::{opt::assert_normalized_ssa_equals, ssa_gen::Ssa};
+
+    #[test]
+    fn unswitches_loop_invariant_branch() {
+        let src = "
+        brillig(inline) fn main f0 {
+          b0(v0: u1, v1: u32):
+            v2 = allocate -> &mut u32
+            store u32 0 at v2
+            jmp b1(u32 0)
+          b1(v3: u32):
+            v4 = lt v3, v1
+            jmpif v4 then: b2, else: b3
+          b2():
+            jmpif v0 then: b4, else: b5
+          b3():
+            v5 = load v2 -> u32
+            return v5
+          b4():
+            v6 = load v2 -> u32
+            v7 = add v6, v3
+            store v7 at v2
+            jmp b6()
+          b5():
+            v8 = load v2 -> u32
+            v9 = sub v8, v3
+            store v9 at v2
+            jmp b6()
+          b6():
+            v10 = unchecked_add v3, u32 1
+            jmp b1(v10)
+        }
+        ";
+        let ssa = Ssa::from_str(src).unwrap();
+        let ssa = ssa.unswitch_loops(100);
+
+        // The blocks are listed in the order they were created in: the original loop first,
+        // then the copy of the loop, and finally the blocks choosing between the two.
+        assert_normalized_ssa_equals(
+            ssa,
+            "
+        brillig(inline) fn main f0 {
+          b0(v0: u1, v1: u32):
+            v2 = allocate -> &mut u32
+            store u32 0 at v2
+            jmpif v0 then: b10, else: b11
+          b1(v3: u32):
+            v4 = lt v3, v1
+            jmpif v4 then: b2, else: b3
+          b2():
+            jmp b4()
+          b3():
+            v5 = load v2 -> u32
+            return v5
+          b4():
+            v6 = load v2 -> u32
+            v7 = add v6, v3
+            store v7 at v2
+            jmp b5()
+          b5():
+            v8 = unchecked_add v3, u32 1
+            jmp b1(v8)
+          b6(v9: u32):
+            v10 = lt v9, v1
+            jmpif v10 then: b7, else: b3
+          b7():
+            jmp b8()
+          b8():
+            v11 = load v2 -> u32
+            v12 = sub v11, v9
+            store v12 at v2
+            jmp b9()
+          b9():
+            v13 = unchecked_add v9, u32 1
+            jmp b6(v13)
+          b10():
+            jmp b1(u32 0)
+          b11():
+            jmp b6(u32 0)
+        }
+        ",
+        );
+    }
+
+    #[test]
+    fn does_not_unswitch_branch_on_value_defined_in_loop() {
+        let src = "
+        brillig(inline) fn main f0 {
+          b0(v0: u32):
+            v1 = allocate -> &mut u32
+            store u32 0 at v1
+            jmp b1(u32 0)
+          b1(v2: u32):
+            v3 = lt v2, v0
+            jmpif v3 then: b2, else: b3
+          b2():
+            v4 = eq v2, u32 5
+            jmpif v4 then: b4, else: b5
+          b3():
+            v5 = load v1 -> u32
+            return v5
+          b4():
+            store v2 at v1
+            jmp b5()
+          b5():
+            v6 = unchecked_add v2, u32 1
+            jmp b1(v6)
+        }
+        ";
+        let ssa = Ssa::from_str(src).unwrap();
+        let ssa = ssa.unswitch_loops(100);
+        assert_normalized_ssa_equals(ssa, src);
+    }
+}
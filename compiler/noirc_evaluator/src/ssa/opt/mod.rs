@@ -17,13 +17,17 @@ mod defunctionalize;
 mod die;
 mod expand_signed_checks;
 pub(crate) mod flatten_cfg;
+mod function_specialization;
+mod global_value_numbering;
 mod hint;
 mod inline_simple_functions;
 pub(crate) mod inlining;
 mod loop_invariant;
+mod loop_unswitching;
 mod make_constrain_not_equal;
 mod mem2reg;
 mod normalize_value_ids;
+mod partial_unrolling;
 mod preprocess_fns;
 pub(crate) mod pure;
 mod rc;
@@ -36,6 +40,7 @@ mod remove_unreachable_instructions;
 mod simple_optimization;
 mod simplify_cfg;
 mod unrolling;
+mod value_ranges;
 
 /// Asserts that the given SSA, after normalizing its IDs and printing it,
 /// is equal to the expected string. Normalization is done so the IDs don't
@@ -0,0 +1,347 @@
+//! This file contains the partial loop unrolling pass for Brillig functions.
+//!
+//! Brillig loops are only fully unrolled when they are deemed small enough (see the unrolling pass),
+//! so hot loops with many iterations pay for the loop header's comparison and jumps on every iteration.
+//! This pass duplicates the body of such loops a number of times, so that each trip through the header
+//! executes several iterations of the original loop.
+//!
+//! Only innermost loops of the following shape are unrolled:
+//! - The header has a single induction variable which is compared against a constant upper bound.
+//! - The loop is entered with a constant lower bound.
+//! - The back-edge increments the induction variable by one.
+//! - The loop can only be exited from the header, i.e. there are no `break`s.
+//!
+//! The unroll factor is the largest number up to the configured factor which divides the number of
+//! iterations, so the header never has to be checked in the middle of the duplicated bodies,
+//! and for which the unrolled loop does not exceed the configured maximum number of instructions.
+//!
+//! For example unrolling the following loop by a factor of 2:
+//! ```text
+//!   b1(v1: u32):
+//!     v2 = lt v1, u32 4
+//!     jmpif v2 then: b2, else: b3
+//!   b2():
+//!     ... body ...
+//!     v3 = unchecked_add v1, u32 1
+//!     jmp b1(v3)
+//! ```
+//! results in:
+//! ```text
+//!   b1(v1: u32):
+//!     v2 = lt v1, u32 4
+//!     jmpif v2 then: b2, else: b3
+//!   b2():
+//!     ... body ...
+//!     v3 = unchecked_add v1, u32 1
+//!     jmp b4()
+//!   b4():
+//!     ... body ...
+//!     v4 = unchecked_add v3, u32 1
+//!     jmp b1(v4)
+//! ```
+use acvm::acir::AcirField;
+use fxhash::FxHashSet as HashSet;
+
+use crate::ssa::{
+    Ssa,
+    ir::{
+        basic_block::BasicBlockId,
+        cfg::ControlFlowGraph,
+        function::Function,
+        function_inserter::FunctionInserter,
+        instruction::{Binary, BinaryOp, Instruction, TerminatorInstruction},
+        post_order::PostOrder,
+        value::{Value, ValueId},
+    },
+};
+
+use super::unrolling::{Loop, Loops, copy_blocks};
+
+impl Ssa {
+    /// Partially unroll the innermost loops of Brillig functions by up to `unroll_factor`,
+    /// as long as the unrolled loops do not contain more than `max_loop_size` instructions.
+    ///
+    /// An `unroll_factor` of 0 or 1 disables the pass.
+    #[tracing::instrument(level = "trace", skip(self))]
+    pub(crate) fn unroll_loops_partially(
+        mut self,
+        unroll_factor: u32,
+        max_loop_size: usize,
+    ) -> Ssa {
+        if unroll_factor <= 1 {
+            return self;
+        }
+        for function in self.functions.values_mut() {
+            if function.runtime().is_brillig() {
+                function.unroll_loops_partially(unroll_factor as usize, max_loop_size);
+            }
+        }
+        self
+    }
+}
+
+impl Function {
+    fn unroll_loops_partially(&mut self, unroll_factor: usize, max_loop_size: usize) {
+        let loops = Loops::find_all(self);
+        let headers: HashSet<BasicBlockId> =
+            loops.yet_to_unroll.iter().map(|loop_| loop_.header).collect();
+
+        // Innermost loops are disjoint, so unrolling one of them doesn't affect the others.
+        for loop_ in &loops.yet_to_unroll {
+            let is_innermost =
+                loop_.blocks.iter().all(|block| *block == loop_.header || !headers.contains(block));
+            if !is_innermost {
+                continue;
+            }
+            let Some(factor) =
+                loop_.partial_unroll_factor(self, &loops.cfg, unroll_factor, max_loop_size)
+            else {
+                continue;
+            };
+            loop_.unroll_partially(self, factor);
+        }
+    }
+}
+
+impl Loop {
+    /// Decide how many times the body of the loop should be repeated, if it can be unrolled at all.
+    fn partial_unroll_factor(
+        &self,
+        function: &Function,
+        cfg: &ControlFlowGraph,
+        unroll_factor: usize,
+        max_loop_size: usize,
+    ) -> Option<usize> {
+        if cfg.predecessors(self.header).len() != 2 {
+            return None;
+        }
+        let induction_variable = self.single_induction_variable(function)?;
+        let body_entry = self.body_entry(function)?;
+        if !self.increments_by_one(function, induction_variable) {
+            return None;
+        }
+
+        // The duplicated bodies skip the header, so they must not be able to leave the loop.
+        let has_other_exits = self.blocks.iter().any(|block| {
+            *block != self.header
+                && function.dfg[*block]
+                    .successors()
+                    .any(|successor| !self.blocks.contains(&successor))
+        });
+        if has_other_exits || body_entry == self.header {
+            return None;
+        }
+
+        let pre_header = self.get_pre_header(function, cfg).ok()?;
+        let (lower, upper) = self.get_const_bounds(function, pre_header)?;
+        let iterations: usize = upper.reduce(
+            lower,
+            |u, l| u.saturating_sub(l).max(0) as usize,
+            |u, l| u.saturating_sub(l) as usize,
+        )?;
+
+        let loop_size = self.count_all_instructions(function);
+        (2..=unroll_factor).rev().find(|factor| {
+            iterations >= *factor
+                && iterations % factor == 0
+                && loop_size.saturating_mul(*factor) <= max_loop_size
+        })
+    }
+
+    /// Return the induction variable if the header does nothing else but compare it to a constant.
+    fn single_induction_variable(&self, function: &Function) -> Option<ValueId> {
+        let header = &function.dfg[self.header];
+        let [induction_variable] = header.parameters() else {
+            return None;
+        };
+        let [instruction] = header.instructions() else {
+            return None;
+        };
+        match &function.dfg[*instruction] {
+            Instruction::Binary(Binary { lhs, operator: BinaryOp::Lt, rhs })
+                if lhs == induction_variable && function.dfg.is_constant(*rhs) =>
+            {
+                Some(*induction_variable)
+            }
+            _ => None,
+        }
+    }
+
+    /// Return the block the header jumps to when the loop condition holds.
+    fn body_entry(&self, function: &Function) -> Option<BasicBlockId> {
+        let header = &function.dfg[self.header];
+        let condition = function.dfg.instruction_results(header.instructions()[0])[0];
+        match header.terminator()? {
+            TerminatorInstruction::JmpIf {
+                condition: jmp_condition,
+                then_destination,
+                else_destination,
+                ..
+            } if *jmp_condition == condition
+                && self.blocks.contains(then_destination)
+                && !self.blocks.contains(else_destination) =>
+            {
+                Some(*then_destination)
+            }
+            _ => None,
+        }
+    }
+
+    /// Check that the back-edge passes the induction variable incremented by one to the header.
+    fn increments_by_one(&self, function: &Function, induction_variable: ValueId) -> bool {
+        let Some(next) = self.back_edge_argument(function, self.back_edge_start) else {
+            return false;
+        };
+        let Value::Instruction { instruction, .. } = &function.dfg[next] else {
+            return false;
+        };
+        let Instruction::Binary(Binary { lhs, operator: BinaryOp::Add { .. }, rhs }) =
+            &function.dfg[*instruction]
+        else {
+            return false;
+        };
+        let is_one = |value: ValueId| {
+            function.dfg.get_numeric_constant(value).is_some_and(|constant| constant.is_one())
+        };
+        (*lhs == induction_variable && is_one(*rhs)) || (*rhs == induction_variable && is_one(*lhs))
+    }
+
+    /// Chain `factor - 1` copies of the loop body after the original one.
+    fn unroll_partially(&self, function: &mut Function, factor: usize) {
+        let induction_variable = function.dfg.block_parameters(self.header)[0];
+        let post_order = PostOrder::with_function(function);
+        let body = post_order
+            .as_slice()
+            .iter()
+            .rev()
+            .filter(|block| **block != self.header && self.blocks.contains(block))
+            .copied()
+            .collect::<Vec<_>>();
+        let body_entry = body[0];
+
+        let mut back_edge_start = self.back_edge_start;
+        for _ in 1..factor {
+            let next = self
+                .back_edge_argument(function, back_edge_start)
+                .expect("ICE: the back-edge should pass the induction variable");
+
+            // Each copy continues with the induction variable produced by the previous iteration.
+            let mut inserter = FunctionInserter::new(function);
+            inserter.map_value(induction_variable, next);
+            let copies = copy_blocks(&mut inserter, &body);
+
+            let call_stack = function.dfg[back_edge_start].unwrap_terminator().call_stack();
+            let jmp = TerminatorInstruction::Jmp {
+                destination: copies[&body_entry],
+                arguments: Vec::new(),
+                call_stack,
+            };
+            function.dfg.set_block_terminator(back_edge_start, jmp);
+            back_edge_start = copies[&self.back_edge_start];
+        }
+    }
+
+    /// The value a back-edge starting at `block` passes to the header as the next induction variable.
+    fn back_edge_argument(&self, function: &Function, block: BasicBlockId) -> Option<ValueId> {
+        match function.dfg[block].terminator()? {
+            TerminatorInstruction::Jmp { destination, arguments, .. }
+                if *destination == self.header && arguments.len() == 1 =>
+            {
+                Some(arguments[0])
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ssa::{opt::assert_normalized_ssa_equals, ssa_gen::Ssa};
+
+    #[test]
+    fn unrolls_loop_by_factor_dividing_iterations() {
+        let src = "
+        brillig(inline) fn main f0 {
+          b0(v0: u32):
+            v1 = allocate -> &mut u32
+            store u32 0 at v1
+            jmp b1(u32 0)
+          b1(v2: u32):
+            v3 = lt v2, u32 4
+            jmpif v3 then: b2, else: b3
+          b2():
+            v4 = load v1 -> u32
+            v5 = add v4, v0
+            store v5 at v1
+            v6 = unchecked_add v2, u32 1
+            jmp b1(v6)
+          b3():
+            v7 = load v1 -> u32
+            return v7
+        }
+        ";
+        let ssa = Ssa::from_str(src).unwrap();
+        let ssa = ssa.unroll_loops_partially(2, 100);
+
+        assert_normalized_ssa_equals(
+            ssa,
+            "
+        brillig(inline) fn main f0 {
+          b0(v0: u32):
+            v1 = allocate -> &mut u32
+            store u32 0 at v1
+            jmp b1(u32 0)
+          b1(v2: u32):
+            v3 = lt v2, u32 4
+            jmpif v3 then: b2, else: b3
+          b2():
+            v4 = load v1 -> u32
+            v5 = add v4, v0
+            store v5 at v1
+            v6 = unchecked_add v2, u32 1
+            jmp b4()
+          b3():
+            v7 = load v1 -> u32
+            return v7
+          b4():
+            v8 = load v1 -> u32
+            v9 = add v8, v0
+            store v9 at v1
+            v10 = unchecked_add v6, u32 1
+            jmp b1(v10)
+        }
+        ",
+        );
+    }
+
+    #[test]
+    fn does_not_unroll_if_factor_does_not_divide_iterations_or_loop_is_too_large() {
+        let src = "
+        brillig(inline) fn main f0 {
+          b0(v0: u32):
+            v1 = allocate -> &mut u32
+            store u32 0 at v1
+            jmp b1(u32 0)
+          b1(v2: u32):
+            v3 = lt v2, u32 5
+            jmpif v3 then: b2, else: b3
+          b2():
+            v4 = load v1 -> u32
+            v5 = add v4, v0
+            store v5 at v1
+            v6 = unchecked_add v2, u32 1
+            jmp b1(v6)
+          b3():
+            v7 = load v1 -> u32
+            return v7
+        }
+        ";
+        let ssa = Ssa::from_str(src).unwrap();
+        let ssa = ssa.unroll_loops_partially(2, 100);
+        assert_normalized_ssa_equals(ssa, src);
+
+        let ssa = Ssa::from_str(&src.replace("u32 5", "u32 4")).unwrap();
+        let ssa = ssa.unroll_loops_partially(2, 10);
+        assert_normalized_ssa_equals(ssa, &src.replace("u32 5", "u32 4"));
+    }
+}
@@ -136,7 +136,7 @@ pub(super) struct Loop {
 
     /// The start of the back_edge n -> d is the block n at the end of
     /// the loop that jumps back to the header block d which restarts the loop.
-    back_edge_start: BasicBlockId,
+    pub(super) back_edge_start: BasicBlockId,
 
     /// All the blocks contained within the loop, including `header` and `back_edge_start`.
     pub(super) blocks: BTreeSet<BasicBlockId>,
@@ -640,7 +640,7 @@ impl Loop {
     }
 
     /// Count the number of instructions in the loop, including the terminating jumps.
-    fn count_all_instructions(&self, function: &Function) -> usize {
+    pub(super) fn count_all_instructions(&self, function: &Function) -> usize {
         let iter = self.blocks.iter().map(|block| {
             let block = &function.dfg[*block];
             block.instructions().len() + block.terminator().is_some() as usize
@@ -1019,6 +1019,41 @@ impl<'f> LoopIteration<'f> {
     }
 }
 
+/// Copy the given blocks, which must be in reverse post order, into fresh blocks.
+///
+/// Instructions are copied through the inserter, so any value mappings set up beforehand
+/// (for example for the parameters of a block outside the set) are applied to the copies.
+/// Jumps between blocks of the set are redirected to the copies, while jumps to other blocks
+/// are kept as they are.
+///
+/// Returns the mapping from the original blocks to their copies.
+pub(super) fn copy_blocks(
+    inserter: &mut FunctionInserter,
+    blocks: &[BasicBlockId],
+) -> HashMap<BasicBlockId, BasicBlockId> {
+    let mut copies = HashMap::default();
+    for block in blocks {
+        let copy = inserter.function.dfg.make_block_with_parameters_from_block(*block);
+        inserter.remember_block_params_from_block(*block, copy);
+        copies.insert(*block, copy);
+    }
+
+    for block in blocks {
+        let copy = copies[block];
+        let instructions = inserter.function.dfg[*block].instructions().to_vec();
+        for instruction in instructions {
+            inserter.push_instruction(instruction, copy);
+        }
+
+        let mut terminator = inserter.function.dfg[*block].unwrap_terminator().clone();
+        terminator.map_values_mut(|value| inserter.resolve(value));
+        terminator.mutate_blocks(|target| copies.get(&target).copied().unwrap_or(target));
+        inserter.function.dfg.set_block_terminator(copy, terminator);
+    }
+
+    copies
+}
+
 /// Unrolling leaves some duplicate instructions which can potentially be removed.
 fn simplify_between_unrolls(function: &mut Function) {
     // Do a mem2reg after the last unroll to aid simplify_cfg
@@ -0,0 +1,539 @@
+//! Value range analysis.
+//!
+//! This pass computes an interval for every numeric value of a function, that its value is
+//! known to be in, and uses these intervals to:
+//! - remove `range_check` instructions which can't fail,
+//! - remove `truncate` instructions which can't change their value,
+//! - lower the `max_bit_size` of the remaining `truncate` instructions,
+//! - turn checked additions, subtractions and multiplications which can't overflow into
+//!   unchecked ones.
+//!
+//! Intervals flow into block parameters from the arguments of every jump to their block,
+//! and the analysis iterates until a fixed point is reached. To guarantee termination in
+//! loops, the interval of a parameter which keeps growing is widened to the range of its
+//! type, and then to no bound at all.
+use acvm::AcirField;
+use fxhash::FxHashMap as HashMap;
+
+use crate::ssa::{
+    ir::{
+        dfg::DataFlowGraph,
+        function::Function,
+        instruction::{Binary, BinaryOp, Instruction, InstructionId, TerminatorInstruction},
+        post_order::PostOrder,
+        types::{NumericType, Type},
+        value::ValueId,
+    },
+    ssa_gen::Ssa,
+};
+
+/// How many times the interval of a block parameter can grow before it is widened.
+const MAX_PARAMETER_UPDATES: u32 = 3;
+
+impl Ssa {
+    /// Removes range checks, truncations and overflow checks which are proven to be
+    /// unnecessary by a value range analysis, and narrows the `max_bit_size` of truncations.
+    #[tracing::instrument(level = "trace", skip(self))]
+    pub(crate) fn value_range_analysis(mut self) -> Ssa {
+        for function in self.functions.values_mut() {
+            function.value_range_analysis();
+        }
+        self
+    }
+}
+
+impl Function {
+    fn value_range_analysis(&mut self) {
+        let ranges = ValueRanges::compute(self);
+
+        self.simple_reachable_blocks_optimization(|context| {
+            let instruction_id = context.instruction_id;
+            match *context.instruction() {
+                Instruction::RangeCheck { value, max_bit_size, .. } => {
+                    let range = ranges.get(context.dfg, value);
+                    if range.is_some_and(|range| range.fits_in(max_bit_size)) {
+                        context.remove_current_instruction();
+                    }
+                }
+                Instruction::Truncate { value, bit_size, max_bit_size } => {
+                    let Some(range) = ranges.get(context.dfg, value) else {
+                        return;
+                    };
+                    if range.fits_in(bit_size) {
+                        let result = context.dfg.instruction_results(instruction_id)[0];
+                        context.replace_value(result, value);
+                        context.remove_current_instruction();
+                    } else if range.num_bits() < max_bit_size {
+                        let max_bit_size = range.num_bits();
+                        let truncate = Instruction::Truncate { value, bit_size, max_bit_size };
+                        context.replace_current_instruction_with(truncate);
+                    }
+                }
+                Instruction::Binary(binary) => {
+                    let operator = match binary.operator {
+                        BinaryOp::Add { unchecked: false } => BinaryOp::Add { unchecked: true },
+                        BinaryOp::Sub { unchecked: false } => BinaryOp::Sub { unchecked: true },
+                        BinaryOp::Mul { unchecked: false } => BinaryOp::Mul { unchecked: true },
+                        _ => return,
+                    };
+                    if ranges.cannot_overflow(context.dfg, &binary) {
+                        let binary = Binary { operator, ..binary };
+                        context.replace_current_instruction_with(Instruction::Binary(binary));
+                    }
+                }
+                _ => (),
+            }
+        });
+    }
+}
+
+/// An inclusive interval of unsigned values.
+///
+/// Signed values are represented by their two's complement, and values which
+/// aren't known to fit in a `u128` have no interval (`None`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Interval {
+    min: u128,
+    max: u128,
+}
+
+impl Interval {
+    fn new(min: u128, max: u128) -> Self {
+        Self { min, max }
+    }
+
+    fn exact(value: u128) -> Self {
+        Self::new(value, value)
+    }
+
+    fn bool() -> Self {
+        Self::new(0, 1)
+    }
+
+    /// The values a value of the given type can hold.
+    fn of_type(typ: NumericType) -> Option<Self> {
+        match typ {
+            NumericType::NativeField => None,
+            NumericType::Unsigned { bit_size } | NumericType::Signed { bit_size } => {
+                Some(Self::new(0, max_of_bits(bit_size)))
+            }
+        }
+    }
+
+    fn fits_in(self, bit_size: u32) -> bool {
+        self.max <= max_of_bits(bit_size)
+    }
+
+    /// The number of bits needed to represent every value of this interval.
+    fn num_bits(self) -> u32 {
+        u128::BITS - self.max.leading_zeros()
+    }
+
+    fn contains(self, other: Self) -> bool {
+        self.min <= other.min && other.max <= self.max
+    }
+
+    fn union(self, other: Self) -> Self {
+        Self::new(self.min.min(other.min), self.max.max(other.max))
+    }
+
+    /// The interval of the result of an addition, subtraction or multiplication
+    /// of values of these intervals, if it doesn't wrap around.
+    fn arithmetic(operator: BinaryOp, lhs: Self, rhs: Self) -> Option<Self> {
+        match operator {
+            BinaryOp::Add { .. } => {
+                Some(Self::new(lhs.min.checked_add(rhs.min)?, lhs.max.checked_add(rhs.max)?))
+            }
+            BinaryOp::Sub { .. } => {
+                Some(Self::new(lhs.min.checked_sub(rhs.max)?, lhs.max.checked_sub(rhs.min)?))
+            }
+            BinaryOp::Mul { .. } => {
+                Some(Self::new(lhs.min.checked_mul(rhs.min)?, lhs.max.checked_mul(rhs.max)?))
+            }
+            _ => None,
+        }
+    }
+}
+
+fn max_of_bits(bit_size: u32) -> u128 {
+    if bit_size >= u128::BITS { u128::MAX } else { (1 << bit_size) - 1 }
+}
+
+fn union(lhs: Option<Interval>, rhs: Option<Interval>) -> Option<Interval> {
+    Some(lhs?.union(rhs?))
+}
+
+/// The intervals computed for the values of a function.
+struct ValueRanges {
+    ranges: HashMap<ValueId, Option<Interval>>,
+}
+
+impl ValueRanges {
+    fn compute(function: &Function) -> Self {
+        let dfg = &function.dfg;
+        let mut blocks = PostOrder::with_function(function).into_vec();
+        blocks.reverse();
+
+        let mut value_ranges = Self { ranges: HashMap::default() };
+        let mut parameter_updates = HashMap::<ValueId, u32>::default();
+        loop {
+            let mut changed = false;
+            for block in &blocks {
+                for instruction_id in dfg[*block].instructions() {
+                    let [result] = dfg.instruction_results(*instruction_id) else {
+                        continue;
+                    };
+                    if !matches!(dfg.type_of_value(*result), Type::Numeric(_)) {
+                        continue;
+                    }
+                    let range = value_ranges.instruction_range(dfg, *instruction_id, *result);
+                    value_ranges.ranges.insert(*result, range);
+                }
+
+                if let Some(TerminatorInstruction::Jmp { destination, arguments, .. }) =
+                    dfg[*block].terminator()
+                {
+                    let parameters = dfg.block_parameters(*destination);
+                    for (parameter, argument) in parameters.iter().zip(arguments) {
+                        let updates = parameter_updates.entry(*parameter).or_default();
+                        changed |= value_ranges.join_parameter(dfg, *parameter, *argument, updates);
+                    }
+                }
+            }
+            if !changed {
+                return value_ranges;
+            }
+        }
+    }
+
+    /// Returns the interval of the given value, or `None` if it's not bounded.
+    fn get(&self, dfg: &DataFlowGraph, value: ValueId) -> Option<Interval> {
+        let typ = dfg.type_of_value(value).unwrap_numeric();
+        if let Some(constant) = dfg.get_numeric_constant(value) {
+            if !matches!(typ, NumericType::Signed { .. }) {
+                return constant.try_into_u128().map(Interval::exact);
+            }
+        }
+        // Values the analysis knows nothing about, like the parameters of the entry block,
+        // hold values of their type.
+        self.ranges.get(&value).copied().unwrap_or_else(|| Interval::of_type(typ))
+    }
+
+    /// Merges the interval of an argument into the one of the parameter it is passed to,
+    /// returning whether the parameter's interval changed.
+    fn join_parameter(
+        &mut self,
+        dfg: &DataFlowGraph,
+        parameter: ValueId,
+        argument: ValueId,
+        updates: &mut u32,
+    ) -> bool {
+        if !matches!(dfg.type_of_value(parameter), Type::Numeric(_)) {
+            return false;
+        }
+        let argument = self.get(dfg, argument);
+        let Some(current) = self.ranges.get(&parameter).copied() else {
+            self.ranges.insert(parameter, argument);
+            return true;
+        };
+
+        let mut joined = union(current, argument);
+        if joined == current {
+            return false;
+        }
+        *updates += 1;
+        if *updates > MAX_PARAMETER_UPDATES {
+            let type_range = Interval::of_type(dfg.type_of_value(parameter).unwrap_numeric());
+            joined = match (joined, type_range) {
+                (Some(joined), Some(type_range)) if type_range.contains(joined) => Some(type_range),
+                _ => None,
+            };
+        }
+        self.ranges.insert(parameter, joined);
+        true
+    }
+
+    fn instruction_range(
+        &self,
+        dfg: &DataFlowGraph,
+        instruction_id: InstructionId,
+        result: ValueId,
+    ) -> Option<Interval> {
+        let typ = dfg.type_of_value(result).unwrap_numeric();
+        let type_range = Interval::of_type(typ);
+        match &dfg[instruction_id] {
+            Instruction::Binary(binary) => self.binary_range(dfg, binary, typ),
+            // Casts don't change values, which are expected to fit in the target type
+            Instruction::Cast(value, _) => match (self.get(dfg, *value), type_range) {
+                (Some(range), Some(type_range)) if type_range.contains(range) => Some(range),
+                (range, None) => range,
+                (_, type_range) => type_range,
+            },
+            Instruction::Not(value) => match (self.get(dfg, *value), type_range) {
+                (Some(range), Some(type_range)) if type_range.contains(range) => {
+                    if typ.is_unsigned() {
+                        Some(Interval::new(type_range.max - range.max, type_range.max - range.min))
+                    } else {
+                        Some(type_range)
+                    }
+                }
+                _ => None,
+            },
+            Instruction::Truncate { value, bit_size, .. } => match self.get(dfg, *value) {
+                Some(range) if range.fits_in(*bit_size) => Some(range),
+                _ => Some(Interval::new(0, max_of_bits(*bit_size))),
+            },
+            Instruction::IfElse { then_value, else_value, .. } => {
+                union(self.get(dfg, *then_value), self.get(dfg, *else_value))
+            }
+            _ => type_range,
+        }
+    }
+
+    fn binary_range(
+        &self,
+        dfg: &DataFlowGraph,
+        binary: &Binary,
+        typ: NumericType,
+    ) -> Option<Interval> {
+        if matches!(binary.operator, BinaryOp::Eq | BinaryOp::Lt) {
+            return Some(Interval::bool());
+        }
+        // ACIR doesn't wrap around the results of signed arithmetic, so these aren't bounded
+        if matches!(typ, NumericType::Signed { .. }) {
+            return None;
+        }
+
+        let type_range = Interval::of_type(typ);
+        let lhs = self.get(dfg, binary.lhs);
+        let rhs = self.get(dfg, binary.rhs);
+        match binary.operator {
+            BinaryOp::Add { unchecked }
+            | BinaryOp::Sub { unchecked }
+            | BinaryOp::Mul { unchecked } => {
+                let result = lhs
+                    .zip(rhs)
+                    .and_then(|(lhs, rhs)| Interval::arithmetic(binary.operator, lhs, rhs));
+                match (result, type_range) {
+                    (Some(result), Some(type_range)) if type_range.contains(result) => Some(result),
+                    (result, None) => result,
+                    // A checked operation fails unless its result fits in its type
+                    (_, type_range) if !unchecked => type_range,
+                    _ => None,
+                }
+            }
+            BinaryOp::Div if typ.is_unsigned() => {
+                let lhs = lhs?;
+                let divisor = rhs.map_or(1, |rhs| rhs.min.max(1));
+                Some(Interval::new(0, lhs.max / divisor))
+            }
+            BinaryOp::Mod => match (lhs, rhs) {
+                (Some(lhs), Some(rhs)) if rhs.min > 0 => {
+                    Some(Interval::new(0, lhs.max.min(rhs.max - 1)))
+                }
+                (Some(lhs), _) => Some(Interval::new(0, lhs.max)),
+                _ => None,
+            },
+            BinaryOp::And => match (lhs, rhs) {
+                (Some(lhs), Some(rhs)) => Some(Interval::new(0, lhs.max.min(rhs.max))),
+                _ => type_range,
+            },
+            BinaryOp::Or | BinaryOp::Xor => match (lhs, rhs) {
+                (Some(lhs), Some(rhs)) => {
+                    Some(Interval::new(0, max_of_bits(lhs.union(rhs).num_bits())))
+                }
+                _ => type_range,
+            },
+            BinaryOp::Shr => match (lhs, rhs) {
+                (Some(lhs), Some(rhs)) => {
+                    let shift = |amount: u128| u32::try_from(amount).unwrap_or(u32::MAX);
+                    let min = lhs.min.checked_shr(shift(rhs.max)).unwrap_or(0);
+                    let max = lhs.max.checked_shr(shift(rhs.min)).unwrap_or(0);
+                    Some(Interval::new(min, max))
+                }
+                _ => type_range,
+            },
+            BinaryOp::Shl => match (lhs, rhs, type_range) {
+                (Some(lhs), Some(rhs), Some(type_range))
+                    if rhs.max < u128::from(lhs.max.leading_zeros()) =>
+                {
+                    let result = Interval::new(lhs.min << rhs.min, lhs.max << rhs.max);
+                    Some(if type_range.contains(result) { result } else { type_range })
+                }
+                _ => type_range,
+            },
+            BinaryOp::Div | BinaryOp::Eq | BinaryOp::Lt => type_range,
+        }
+    }
+
+    /// Returns whether a checked addition, subtraction or multiplication of unsigned values
+    /// can't overflow.
+    fn cannot_overflow(&self, dfg: &DataFlowGraph, binary: &Binary) -> bool {
+        let typ = dfg.type_of_value(binary.lhs).unwrap_numeric();
+        if !typ.is_unsigned() {
+            return false;
+        }
+        let (Some(lhs), Some(rhs)) = (self.get(dfg, binary.lhs), self.get(dfg, binary.rhs)) else {
+            return false;
+        };
+        let result = Interval::arithmetic(binary.operator, lhs, rhs);
+        result.zip(Interval::of_type(typ)).is_some_and(|(result, typ)| typ.contains(result))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ssa::{opt::assert_normalized_ssa_equals, ssa_gen::Ssa};
+
+    #[test]
+    fn removes_checks_on_values_which_fit() {
+        let src = "
+        acir(inline) fn main f0 {
+          b0(v0: u8, v1: u8):
+            v2 = cast v0 as u32
+            v3 = cast v1 as u32
+            v4 = add v2, v3
+            v5 = cast v4 as Field
+            range_check v5 to 16 bits
+            v6 = truncate v4 to 16 bits, max_bit_size: 32
+            return v6
+        }
+        ";
+        let ssa = Ssa::from_str(src).unwrap();
+        let ssa = ssa.value_range_analysis();
+
+        let expected = "
+        acir(inline) fn main f0 {
+          b0(v0: u8, v1: u8):
+            v2 = cast v0 as u32
+            v3 = cast v1 as u32
+            v4 = unchecked_add v2, v3
+            v5 = cast v4 as Field
+            return v4
+        }
+        ";
+        assert_normalized_ssa_equals(ssa, expected);
+    }
+
+    #[test]
+    fn narrows_max_bit_size_of_truncate() {
+        let src = "
+        acir(inline) fn main f0 {
+          b0(v0: u8, v1: u8):
+            v2 = cast v0 as Field
+            v3 = cast v1 as Field
+            v4 = mul v2, v3
+            v5 = truncate v4 to 8 bits, max_bit_size: 254
+            return v5
+        }
+        ";
+        let ssa = Ssa::from_str(src).unwrap();
+        let ssa = ssa.value_range_analysis();
+
+        let expected = "
+        acir(inline) fn main f0 {
+          b0(v0: u8, v1: u8):
+            v2 = cast v0 as Field
+            v3 = cast v1 as Field
+            v4 = mul v2, v3
+            v5 = truncate v4 to 8 bits, max_bit_size: 16
+            return v5
+        }
+        ";
+        assert_normalized_ssa_equals(ssa, expected);
+    }
+
+    #[test]
+    fn joins_ranges_of_block_arguments() {
+        let src = "
+        brillig(inline) fn main f0 {
+          b0(v0: u1):
+            jmpif v0 then: b1, else: b2
+          b1():
+            jmp b3(u32 3)
+          b2():
+            jmp b3(u32 200)
+          b3(v1: u32):
+            v2 = mul v1, u32 2
+            range_check v2 to 9 bits
+            v3 = sub v2, u32 6
+            v4 = sub v2, u32 7
+            return v3, v4
+        }
+        ";
+        let ssa = Ssa::from_str(src).unwrap();
+        let ssa = ssa.value_range_analysis();
+
+        let expected = "
+        brillig(inline) fn main f0 {
+          b0(v0: u1):
+            jmpif v0 then: b1, else: b2
+          b1():
+            jmp b3(u32 3)
+          b2():
+            jmp b3(u32 200)
+          b3(v1: u32):
+            v2 = unchecked_mul v1, u32 2
+            v3 = unchecked_sub v2, u32 6
+            v4 = sub v2, u32 7
+            return v3, v4
+        }
+        ";
+        assert_normalized_ssa_equals(ssa, expected);
+    }
+
+    #[test]
+    fn widens_ranges_in_loops() {
+        let src = "
+        brillig(inline) fn main f0 {
+          b0():
+            jmp b1(u32 0)
+          b1(v0: u32):
+            v1 = lt v0, u32 10
+            jmpif v1 then: b2, else: b3
+          b2():
+            v2 = add v0, u32 1
+            jmp b1(v2)
+          b3():
+            v3 = truncate v0 to 32 bits, max_bit_size: 33
+            range_check v0 to 16 bits
+            return v3
+        }
+        ";
+        let ssa = Ssa::from_str(src).unwrap();
+        let ssa = ssa.value_range_analysis();
+
+        let expected = "
+        brillig(inline) fn main f0 {
+          b0():
+            jmp b1(u32 0)
+          b1(v0: u32):
+            v1 = lt v0, u32 10
+            jmpif v1 then: b2, else: b3
+          b2():
+            v2 = add v0, u32 1
+            jmp b1(v2)
+          b3():
+            range_check v0 to 16 bits
+            return v0
+        }
+        ";
+        assert_normalized_ssa_equals(ssa, expected);
+    }
+
+    #[test]
+    fn keeps_checks_which_may_fail() {
+        let src = "
+        acir(inline) fn main f0 {
+          b0(v0: u16, v1: Field):
+            range_check v0 to 8 bits
+            v2 = truncate v1 to 8 bits, max_bit_size: 254
+            v3 = add v0, u16 1
+            return v2, v3
+        }
+        ";
+        let ssa = Ssa::from_str(src).unwrap();
+        let ssa = ssa.value_range_analysis();
+        assert_normalized_ssa_equals(ssa, src);
+    }
+}
@@ -0,0 +1,175 @@
+//! Custom SSA pipelines, replacing the ordering of the [primary_passes] with a user supplied one.
+//!
+//! A pipeline is described by listing the passes to run, one per line or separated by commas.
+//! Each pass is referred to by its message in the default pipeline, for example `Mem2Reg`.
+//! - When the default pipeline contains different passes with the same message, such as `Inlining`,
+//!   the first one is used, unless an occurrence is selected with `@`, e.g. `Inlining@2`.
+//! - A pass can be repeated by appending `*` and the number of repetitions, e.g. `Mem2Reg * 2`.
+//! - Lines starting with `#` are comments.
+//!
+//! For example:
+//! ```text
+//! # Only inline and clean up the result
+//! Defunctionalization
+//! Inlining, Mem2Reg * 2
+//! Simplifying
+//! ```
+use super::{SsaEvaluatorOptions, SsaPass, primary_passes};
+
+/// A pass of the default pipeline, identified by its position, to be run a number of times.
+struct PipelineStep {
+    index: usize,
+    repetitions: usize,
+}
+
+/// Build the passes described by the `description` from the passes of the default pipeline.
+///
+/// Returns an error describing the first invalid entry if any pass name is unknown,
+/// or the occurrence or repetition of a pass is invalid.
+pub fn custom_passes<'a>(
+    description: &str,
+    options: &'a SsaEvaluatorOptions,
+) -> Result<Vec<SsaPass<'a>>, String> {
+    let registry = primary_passes(options);
+    let names = registry.iter().map(|pass| pass.msg().to_string()).collect::<Vec<_>>();
+
+    let mut steps = Vec::new();
+    let entries = description
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.starts_with('#'))
+        .flat_map(|line| line.split(','))
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty());
+    for entry in entries {
+        steps.push(parse_step(entry, &names)?);
+    }
+    if steps.is_empty() {
+        return Err("the pipeline does not contain any passes".to_string());
+    }
+
+    // Passes can't be cloned, so we take each one from a fresh copy of the default pipeline.
+    let mut passes = Vec::new();
+    for PipelineStep { index, repetitions } in steps {
+        for _ in 0..repetitions {
+            passes.push(primary_passes(options).swap_remove(index));
+        }
+    }
+    Ok(passes)
+}
+
+/// Parse an entry such as `Inlining@2 * 3` into a step of the pipeline.
+fn parse_step(entry: &str, names: &[String]) -> Result<PipelineStep, String> {
+    let (pass, repetitions) = match entry.rsplit_once('*') {
+        Some((pass, repetitions)) => {
+            let repetitions = repetitions.trim().parse::<usize>().ok().filter(|n| *n > 0);
+            let Some(repetitions) = repetitions else {
+                return Err(format!("invalid number of repetitions in `{entry}`"));
+            };
+            (pass.trim(), repetitions)
+        }
+        None => (entry, 1),
+    };
+
+    let (name, occurrence) = match pass.rsplit_once('@') {
+        Some((name, occurrence)) => {
+            let occurrence = occurrence.trim().parse::<usize>().ok().filter(|n| *n > 0);
+            let Some(occurrence) = occurrence else {
+                return Err(format!("invalid occurrence in `{entry}`"));
+            };
+            (name.trim(), occurrence)
+        }
+        None => (pass, 1),
+    };
+
+    let mut indexes = names.iter().enumerate().filter(|(_, msg)| *msg == name);
+    let count = indexes.clone().count();
+    if count == 0 {
+        let mut available: Vec<&str> = Vec::new();
+        for msg in names {
+            if !available.contains(&msg.as_str()) {
+                available.push(msg);
+            }
+        }
+        return Err(format!(
+            "unknown SSA pass `{name}`; the available passes are: {}",
+            available.join(", ")
+        ));
+    }
+    let Some((index, _)) = indexes.nth(occurrence - 1) else {
+        return Err(format!(
+            "there is no occurrence {occurrence} of SSA pass `{name}`, it occurs {count} time(s)"
+        ));
+    };
+    Ok(PipelineStep { index, repetitions })
+}
+
+#[cfg(test)]
+mod tests {
+    use acvm::acir::circuit::ExpressionWidth;
+
+    use crate::{
+        brillig::BrilligOptions,
+        ssa::{SsaEvaluatorOptions, SsaLogging},
+    };
+
+    use super::custom_passes;
+
+    fn options() -> SsaEvaluatorOptions {
+        SsaEvaluatorOptions {
+            ssa_logging: SsaLogging::None,
+            brillig_options: BrilligOptions::default(),
+            print_codegen_timings: false,
+            expression_width: ExpressionWidth::default(),
+            emit_ssa: None,
+            skip_underconstrained_check: true,
+            enable_brillig_constraints_check_lookback: false,
+            skip_brillig_constraints_check: true,
+            inliner_aggressiveness: 0,
+            max_bytecode_increase_percent: None,
+            brillig_unroll_factor: 0,
+            brillig_unswitch_loops: false,
+            brillig_loop_size_limit: 0,
+            max_function_specializations: 0,
+            enable_value_range_analysis: false,
+            enable_brillig_global_value_numbering: false,
+            enable_acir_common_subexpression_elimination: false,
+            ssa_pipeline: None,
+            skip_passes: Default::default(),
+            verify_passes_with_inputs: None,
+        }
+    }
+
+    #[test]
+    fn parses_pipeline_with_repetitions_and_comments() {
+        let options = options();
+        let description = "
+            # Inline and clean up
+            Defunctionalization
+            Inlining@2, Mem2Reg * 2
+            Simplifying
+        ";
+        let passes = custom_passes(description, &options).unwrap();
+        let names = passes.iter().map(|pass| pass.msg()).collect::<Vec<_>>();
+        assert_eq!(names, ["Defunctionalization", "Inlining", "Mem2Reg", "Mem2Reg", "Simplifying"]);
+    }
+
+    #[test]
+    fn rejects_invalid_entries() {
+        let options = options();
+        let error = custom_passes("Mem2Reg\nNot A Pass", &options).err().unwrap();
+        assert!(error.starts_with("unknown SSA pass `Not A Pass`"));
+
+        let error = custom_passes("Mem2Reg * 0", &options).err().unwrap();
+        assert_eq!(error, "invalid number of repetitions in `Mem2Reg * 0`");
+
+        let error = custom_passes("Defunctionalization@2", &options).err().unwrap();
+        assert_eq!(
+            error,
+            "there is no occurrence 2 of SSA pass `Defunctionalization`, it occurs 1 time(s)"
+        );
+
+        let error = custom_passes("# nothing", &options).err().unwrap();
+        assert_eq!(error, "the pipeline does not contain any passes");
+    }
+}
@@ -2,7 +2,7 @@ use std::collections::BTreeMap;
 use std::sync::{Arc, Mutex, RwLock};
 
 use acvm::{FieldElement, acir::AcirField};
-use iter_extended::vecmap;
+use iter_extended::{try_vecmap, vecmap};
 use noirc_errors::Location;
 use noirc_frontend::ast::BinaryOpKind;
 use noirc_frontend::monomorphization::ast::{
@@ -93,6 +93,23 @@ pub(super) struct Loop {
     /// The loop index will be `Some` for a `for` and `None` for a `loop`
     pub(super) loop_index: Option<ValueId>,
     pub(super) loop_end: BasicBlockId,
+    /// Set for a constrained `for` loop containing a `break` or `continue`. Since the loop
+    /// is fully unrolled, these can't jump out of the loop body and set these flags instead.
+    pub(super) jump_flags: Option<JumpFlags>,
+}
+
+/// The references a `break` or `continue` in a constrained loop sets to skip the code
+/// it would have jumped over.
+#[derive(Copy, Clone)]
+pub(super) struct JumpFlags {
+    /// Set by a `break`, skipping the body of the remaining iterations.
+    pub(super) broken: ValueId,
+    /// Set by both `break` and `continue`, skipping the rest of the current iteration.
+    pub(super) skipped: ValueId,
+    /// The number of `break` and `continue` lowered so far in the loop.
+    ///
+    /// Comparing it before and after codegening an expression tells whether it may have jumped.
+    pub(super) jumps: usize,
 }
 
 /// The queue of functions remaining to compile
@@ -964,6 +981,80 @@ impl<'a> FunctionContext<'a> {
         // The frontend should ensure break/continue are never used outside a loop
         *self.loops.last().expect("current_loop: not in a loop!")
     }
+
+    /// Allocates the flags of a `for` loop with the given body if it is constrained
+    /// and contains a `break` or `continue`.
+    pub(super) fn allocate_jump_flags(&mut self, body: &ast::Expression) -> Option<JumpFlags> {
+        if !self.builder.current_function.runtime().is_acir() || !body.contains_break_or_continue()
+        {
+            return None;
+        }
+
+        let false_value = self.builder.numeric_constant(0u128, NumericType::bool());
+        let broken = self.builder.insert_allocate(Type::bool());
+        self.builder.insert_store(broken, false_value);
+        let skipped = self.builder.insert_allocate(Type::bool());
+        self.builder.insert_store(skipped, false_value);
+        Some(JumpFlags { broken, skipped, jumps: 0 })
+    }
+
+    /// Lowers a `break` or `continue` in a constrained loop by setting the flags skipping
+    /// the code it would have jumped over.
+    pub(super) fn set_jump_flags(&mut self, is_break: bool) {
+        let flags = self
+            .loops
+            .last_mut()
+            .and_then(|loop_| loop_.jump_flags.as_mut())
+            .expect("set_jump_flags: not in a loop with jump flags!");
+        flags.jumps += 1;
+        let flags = *flags;
+
+        let true_value = self.builder.numeric_constant(1u128, NumericType::bool());
+        if is_break {
+            self.builder.insert_store(flags.broken, true_value);
+        }
+        self.builder.insert_store(flags.skipped, true_value);
+    }
+
+    /// The number of `break` and `continue` lowered to flags so far in the current loop.
+    pub(super) fn jumps_lowered(&self) -> usize {
+        self.loops.last().and_then(|loop_| loop_.jump_flags).map_or(0, |flags| flags.jumps)
+    }
+
+    /// Returns zeroed values of the given type, used as the result of code skipped
+    /// by a `break` or `continue` in a constrained loop.
+    pub(super) fn zeroed_values(&mut self, typ: &ast::Type) -> Result<Values, RuntimeError> {
+        let types = Self::convert_type(typ);
+        let values = try_vecmap(types.clone().flatten(), |typ| self.zeroed_value(&typ))?;
+        let mut values = values.into_iter();
+        Ok(types.map(|_| values.next().expect("Expected a value for each type").into()))
+    }
+
+    pub(super) fn zeroed_value(&mut self, typ: &Type) -> Result<ValueId, RuntimeError> {
+        match typ {
+            Type::Numeric(numeric_type) => Ok(self.builder.numeric_constant(0u128, *numeric_type)),
+            Type::Array(element_types, length) => {
+                let mut elements = im::Vector::new();
+                for _ in 0..*length {
+                    for element_type in element_types.iter() {
+                        elements.push_back(self.zeroed_value(element_type)?);
+                    }
+                }
+                Ok(self.builder.insert_make_array(elements, typ.clone()))
+            }
+            Type::Slice(_) => Ok(self.builder.insert_make_array(im::Vector::new(), typ.clone())),
+            // Like the branches of an `if`, the skipped and executed code can't be merged
+            // if they result in a reference or a function
+            Type::Reference(_) => {
+                let call_stack = self.builder.get_call_stack();
+                Err(RuntimeError::ReturnedReferenceFromDynamicIf { call_stack })
+            }
+            Type::Function => {
+                let call_stack = self.builder.get_call_stack();
+                Err(RuntimeError::ReturnedFunctionFromDynamicIf { call_stack })
+            }
+        }
+    }
 }
 
 /// True if the given operator cannot be encoded directly and needs
@@ -9,7 +9,7 @@ use noirc_frontend::hir_def::expr::Constructor;
 use noirc_frontend::token::FmtStrFragment;
 pub use program::Ssa;
 
-use context::{Loop, SharedContext};
+use context::{JumpFlags, Loop, SharedContext};
 use iter_extended::{try_vecmap, vecmap};
 use noirc_errors::Location;
 use noirc_frontend::ast::UnaryOp;
@@ -302,7 +302,8 @@ impl FunctionContext<'_> {
         self.codegen_array(elements, typ)
     }
 
-    // Codegen an array but make sure that we do not have a nested slice
+    /// Codegen an array but make sure that we do not have a nested slice in ACIR,
+    /// which only supports slices of flat elements.
     ///
     /// The bool aspect of each array element indicates whether the element is an array constant
     /// or not. If it is, we avoid incrementing the reference count because we consider the
@@ -312,7 +313,7 @@ impl FunctionContext<'_> {
         elements: Vec<Values>,
         typ: Type,
     ) -> Result<Values, RuntimeError> {
-        if typ.is_nested_slice() {
+        if self.builder.current_function.runtime().is_acir() && typ.is_nested_slice() {
             return Err(RuntimeError::NestedSlice { call_stack: self.builder.get_call_stack() });
         }
         Ok(self.codegen_array(elements, typ))
@@ -346,8 +347,16 @@ impl FunctionContext<'_> {
 
     fn codegen_block(&mut self, block: &[Expression]) -> Result<Values, RuntimeError> {
         let mut result = Self::unit_value();
-        for expr in block {
+        for (i, expr) in block.iter().enumerate() {
+            let jumps_before = self.jumps_lowered();
             result = self.codegen_expression(expr)?;
+
+            // In a constrained loop, the rest of the block must be skipped if the
+            // expression executed a `break` or `continue`
+            let rest = &block[i + 1..];
+            if self.jumps_lowered() != jumps_before && !rest.is_empty() {
+                return self.codegen_unless_skipped(|this| this.codegen_block(rest));
+            }
         }
         Ok(result)
     }
@@ -408,6 +417,20 @@ impl FunctionContext<'_> {
                 let tuple = self.codegen_reference(tuple)?;
                 Ok(Self::get_field(tuple, *index))
             }
+            // Refer to `(*reference).field` through the reference rather than to a copy of it
+            Expression::Unary(ast::Unary {
+                operator: UnaryOp::Dereference { .. },
+                rhs,
+                result_type,
+                ..
+            }) => {
+                let references = self.codegen_expression(rhs)?;
+                let element_types = Self::convert_type(result_type);
+                Ok(references.map_both(element_types, |reference, element_type| {
+                    let reference = reference.eval(self);
+                    value::Value::Mutable(reference, element_type).into()
+                }))
+            }
             other => self.codegen_expression(other),
         }
     }
@@ -540,6 +563,12 @@ impl FunctionContext<'_> {
     /// loop_end():
     ///   ... This is the current insert point after codegen_for finishes ...
     /// ```
+    ///
+    /// In constrained code the loop is fully unrolled, so a `break` or `continue` can't jump
+    /// out of the loop body. Instead they set flags which skip the code they would have jumped
+    /// over: the body is codegen'd as `if !broken { skipped = false; body }` and any code
+    /// following a `break` or `continue` as `if !skipped { ... }`. Once flattened, these
+    /// become side-effect predicates disabling the rest of the loop.
     fn codegen_for(&mut self, for_expr: &ast::For) -> Result<Values, RuntimeError> {
         self.builder.set_location(for_expr.start_range_location);
         let start_index = self.codegen_non_tuple_expression(&for_expr.start_range)?;
@@ -558,6 +587,8 @@ impl FunctionContext<'_> {
             }
         }
 
+        let jump_flags = self.allocate_jump_flags(&for_expr.block);
+
         let loop_entry = self.builder.insert_block();
         let loop_body = self.builder.insert_block();
         let loop_end = self.builder.insert_block();
@@ -568,7 +599,7 @@ impl FunctionContext<'_> {
 
         // Remember the blocks and variable used in case there are break/continue instructions
         // within the loop which need to jump to them.
-        self.enter_loop(Loop { loop_entry, loop_index: Some(loop_index), loop_end });
+        self.enter_loop(Loop { loop_entry, loop_index: Some(loop_index), loop_end, jump_flags });
 
         // Set the location of the initial jmp instruction to the start range. This is the location
         // used to issue an error if the start range cannot be determined at compile-time.
@@ -589,8 +620,11 @@ impl FunctionContext<'_> {
         self.builder.switch_to_block(loop_body);
         self.define(for_expr.index_variable, loop_index.into());
 
-        let result = self.codegen_expression(&for_expr.block);
-        self.codegen_unless_break_or_continue(result, |this, _| {
+        let result = match jump_flags {
+            Some(flags) => self.codegen_for_body_unless_broken(flags, &for_expr.block),
+            None => self.codegen_expression(&for_expr.block),
+        };
+        self.codegen_unless_break_or_continue(result, &ast::Type::Unit, |this, _| {
             let new_loop_index = this.make_offset(loop_index, 1);
             this.builder.terminate_with_jmp(loop_entry, vec![new_loop_index]);
         })?;
@@ -601,6 +635,79 @@ impl FunctionContext<'_> {
         Ok(Self::unit_value())
     }
 
+    /// Codegens the body of a constrained `for` loop containing a `break` or `continue`
+    /// as `if !broken { skipped = false; body }`.
+    fn codegen_for_body_unless_broken(
+        &mut self,
+        flags: JumpFlags,
+        body: &Expression,
+    ) -> Result<Values, RuntimeError> {
+        let broken = self.builder.insert_load(flags.broken, Type::bool());
+        let not_broken = self.builder.insert_not(broken);
+
+        let body_block = self.builder.insert_block();
+        let end_block = self.builder.insert_block();
+        self.builder.terminate_with_jmpif(not_broken, body_block, end_block);
+
+        self.builder.switch_to_block(body_block);
+        let false_value = self.builder.numeric_constant(0u128, NumericType::bool());
+        self.builder.insert_store(flags.skipped, false_value);
+
+        let result = self.codegen_expression(body);
+        self.codegen_unless_break_or_continue(result, &ast::Type::Unit, |this, _| {
+            this.builder.terminate_with_jmp(end_block, Vec::new());
+        })?;
+
+        self.builder.switch_to_block(end_block);
+        Ok(Self::unit_value())
+    }
+
+    /// Codegens the code following a `break` or `continue` in a constrained loop
+    /// as `if !skipped { f() }`, using zeroed values as its result if it was skipped.
+    fn codegen_unless_skipped(
+        &mut self,
+        f: impl FnOnce(&mut Self) -> Result<Values, RuntimeError>,
+    ) -> Result<Values, RuntimeError> {
+        let flags = self.current_loop().jump_flags.expect("Expected a loop with jump flags");
+        let skipped = self.builder.insert_load(flags.skipped, Type::bool());
+        let not_skipped = self.builder.insert_not(skipped);
+
+        let then_block = self.builder.insert_block();
+        let else_block = self.builder.insert_block();
+        let end_block = self.builder.insert_block();
+        self.builder.terminate_with_jmpif(not_skipped, then_block, else_block);
+
+        self.builder.switch_to_block(then_block);
+        let values = match f(self) {
+            Ok(values) => values.map(|value| value.eval(self).into()),
+            Err(RuntimeError::BreakOrContinue { call_stack }) => {
+                // The code always jumped so there is no value to merge
+                self.builder.terminate_with_jmp(end_block, Vec::new());
+                self.builder.switch_to_block(else_block);
+                self.builder.terminate_with_jmp(end_block, Vec::new());
+                self.builder.switch_to_block(end_block);
+                return Err(RuntimeError::BreakOrContinue { call_stack });
+            }
+            Err(err) => return Err(err),
+        };
+
+        let then_values = values.clone().into_value_list(self);
+        self.builder.terminate_with_jmp(end_block, then_values.clone());
+
+        self.builder.switch_to_block(else_block);
+        let else_values = try_vecmap(then_values, |value| {
+            let typ = self.builder.type_of_value(value);
+            self.zeroed_value(&typ)
+        })?;
+        self.builder.terminate_with_jmp(end_block, else_values);
+
+        self.builder.switch_to_block(end_block);
+        Ok(values.map(|value| {
+            let typ = self.builder.type_of_value(value.eval(self));
+            self.builder.add_block_parameter(end_block, typ).into()
+        }))
+    }
+
     /// Codegens a loop, creating two new blocks in the process.
     /// The return value of a loop is always a unit literal.
     ///
@@ -618,14 +725,19 @@ impl FunctionContext<'_> {
         let loop_body = self.builder.insert_block();
         let loop_end = self.builder.insert_block();
 
-        self.enter_loop(Loop { loop_entry: loop_body, loop_index: None, loop_end });
+        self.enter_loop(Loop {
+            loop_entry: loop_body,
+            loop_index: None,
+            loop_end,
+            jump_flags: None,
+        });
 
         self.builder.terminate_with_jmp(loop_body, vec![]);
 
         // Compile the loop body
         self.builder.switch_to_block(loop_body);
         let result = self.codegen_expression(block);
-        self.codegen_unless_break_or_continue(result, |this, _| {
+        self.codegen_unless_break_or_continue(result, &ast::Type::Unit, |this, _| {
             this.builder.terminate_with_jmp(loop_body, vec![]);
         })?;
 
@@ -663,12 +775,17 @@ impl FunctionContext<'_> {
         let condition = self.codegen_non_tuple_expression(&while_.condition)?;
         self.builder.terminate_with_jmpif(condition, while_body, while_end);
 
-        self.enter_loop(Loop { loop_entry: while_entry, loop_index: None, loop_end: while_end });
+        self.enter_loop(Loop {
+            loop_entry: while_entry,
+            loop_index: None,
+            loop_end: while_end,
+            jump_flags: None,
+        });
 
         // Codegen the body
         self.builder.switch_to_block(while_body);
         let result = self.codegen_expression(&while_.body);
-        self.codegen_unless_break_or_continue(result, |this, _| {
+        self.codegen_unless_break_or_continue(result, &ast::Type::Unit, |this, _| {
             this.builder.terminate_with_jmp(while_entry, vec![]);
         })?;
 
@@ -725,17 +842,25 @@ impl FunctionContext<'_> {
         if let Some(alternative) = &if_expr.alternative {
             let end_block = self.builder.insert_block();
 
-            self.codegen_unless_break_or_continue(then_result, |this, then_value| {
-                let then_values = then_value.into_value_list(this);
-                this.builder.terminate_with_jmp(end_block, then_values);
-            })?;
+            self.codegen_unless_break_or_continue(
+                then_result,
+                &if_expr.typ,
+                |this, then_value| {
+                    let then_values = then_value.into_value_list(this);
+                    this.builder.terminate_with_jmp(end_block, then_values);
+                },
+            )?;
 
             self.builder.switch_to_block(else_block);
             let else_result = self.codegen_expression(alternative);
-            self.codegen_unless_break_or_continue(else_result, |this, else_value| {
-                let else_values = else_value.into_value_list(this);
-                this.builder.terminate_with_jmp(end_block, else_values);
-            })?;
+            self.codegen_unless_break_or_continue(
+                else_result,
+                &if_expr.typ,
+                |this, else_value| {
+                    let else_values = else_value.into_value_list(this);
+                    this.builder.terminate_with_jmp(end_block, else_values);
+                },
+            )?;
 
             // Create block arguments for the end block as needed to branch to
             // with our then and else value.
@@ -819,7 +944,7 @@ impl FunctionContext<'_> {
             self.builder.switch_to_block(case_block);
             self.bind_case_arguments(variable.clone(), case);
             let results = self.codegen_expression(&case.branch);
-            self.codegen_unless_break_or_continue(results, |this, results| {
+            self.codegen_unless_break_or_continue(results, &match_expr.typ, |this, results| {
                 let results = results.into_value_list(this);
 
                 // Each branch will jump to a different end block for now. We have to merge them all
@@ -837,7 +962,7 @@ impl FunctionContext<'_> {
 
         if let Some(branch) = &match_expr.default_case {
             let results = self.codegen_expression(branch);
-            self.codegen_unless_break_or_continue(results, |this, results| {
+            self.codegen_unless_break_or_continue(results, &match_expr.typ, |this, results| {
                 let results = results.into_value_list(this);
                 this.builder.terminate_with_jmp(last_local_end_block, results);
             })?;
@@ -847,7 +972,7 @@ impl FunctionContext<'_> {
             let case = match_expr.cases.last().unwrap();
             self.bind_case_arguments(variable, case);
             let results = self.codegen_expression(&case.branch);
-            self.codegen_unless_break_or_continue(results, |this, results| {
+            self.codegen_unless_break_or_continue(results, &match_expr.typ, |this, results| {
                 let results = results.into_value_list(this);
                 this.builder.terminate_with_jmp(last_local_end_block, results);
             })?;
@@ -1119,9 +1244,19 @@ impl FunctionContext<'_> {
     fn codegen_assign(&mut self, assign: &ast::Assign) -> Result<Values, RuntimeError> {
         // Evaluate the rhs first - when we load the expression in the lvalue we want that
         // to reflect any mutations from evaluating the rhs.
+        let jumps_before = self.jumps_lowered();
         let rhs = self.codegen_expression(&assign.expression)?;
-        let lhs = self.extract_current_value(&assign.lvalue)?;
 
+        // In a constrained loop, nothing is assigned if the rhs executed a `break` or `continue`
+        if self.jumps_lowered() != jumps_before {
+            return self.codegen_unless_skipped(|this| {
+                let lhs = this.extract_current_value(&assign.lvalue)?;
+                this.assign_new_value(lhs, rhs);
+                Ok(Self::unit_value())
+            });
+        }
+
+        let lhs = self.extract_current_value(&assign.lvalue)?;
         self.assign_new_value(lhs, rhs);
 
         Ok(Self::unit_value())
@@ -1133,8 +1268,13 @@ impl FunctionContext<'_> {
     }
 
     fn codegen_break(&mut self) -> Result<Values, RuntimeError> {
-        let loop_end = self.current_loop().loop_end;
-        self.builder.terminate_with_jmp(loop_end, Vec::new());
+        let loop_ = self.current_loop();
+
+        if loop_.jump_flags.is_some() {
+            self.set_jump_flags(true);
+        } else {
+            self.builder.terminate_with_jmp(loop_.loop_end, Vec::new());
+        }
 
         Err(RuntimeError::BreakOrContinue { call_stack: CallStack::default() })
     }
@@ -1143,7 +1283,9 @@ impl FunctionContext<'_> {
         let loop_ = self.current_loop();
 
         // Must remember to increment i before jumping
-        if let Some(loop_index) = loop_.loop_index {
+        if loop_.jump_flags.is_some() {
+            self.set_jump_flags(false);
+        } else if let Some(loop_index) = loop_.loop_index {
             let new_loop_index = self.make_offset(loop_index, 1);
             self.builder.terminate_with_jmp(loop_.loop_entry, vec![new_loop_index]);
         } else {
@@ -1175,21 +1317,33 @@ impl FunctionContext<'_> {
         Ok(Self::unit_value())
     }
 
+    /// Calls `f` with the result of an expression unless the expression jumped away
+    /// with a `break` or `continue`.
+    ///
+    /// In a constrained loop these only set flags, so the current block still needs to
+    /// continue to the next one and `f` is instead called with zeroed values of type `typ`.
     #[must_use = "do not forget to add `?` at the end of this function call"]
-    fn codegen_unless_break_or_continue<T, F>(
+    fn codegen_unless_break_or_continue<F>(
         &mut self,
-        result: Result<T, RuntimeError>,
+        result: Result<Values, RuntimeError>,
+        typ: &ast::Type,
         f: F,
     ) -> Result<(), RuntimeError>
     where
-        F: FnOnce(&mut Self, T),
+        F: FnOnce(&mut Self, Values),
     {
         match result {
             Ok(value) => {
                 f(self, value);
                 Ok(())
             }
-            Err(RuntimeError::BreakOrContinue { .. }) => Ok(()),
+            Err(RuntimeError::BreakOrContinue { .. }) => {
+                if self.current_loop().jump_flags.is_some() {
+                    let value = self.zeroed_values(typ)?;
+                    f(self, value);
+                }
+                Ok(())
+            }
             Err(err) => Err(err),
         }
     }
@@ -107,3 +107,149 @@ fn basic_loop() {
 
     assert_normalized_ssa_equals(ssa, expected);
 }
+
+#[named]
+#[test]
+fn break_in_constrained_loop() {
+    let src = "
+    fn main(sum_to_check: u32) {
+        let mut sum = 0;
+        for i in 0..4 {
+            if i == 2 {
+                break;
+            }
+            sum = sum + i;
+        }
+        assert(sum_to_check == sum);
+    }
+    ";
+
+    let ssa = get_initial_ssa(src, function_path!()).unwrap();
+
+    // The loop body is skipped once `broken` (v4) is set, and the code following the `break`
+    // once `skipped` (v5) is set
+    let expected = "
+    acir(inline) fn main f0 {
+      b0(v0: u32):
+        v2 = allocate -> &mut u32
+        store u32 0 at v2
+        v4 = allocate -> &mut u1
+        store u1 0 at v4
+        v5 = allocate -> &mut u1
+        store u1 0 at v5
+        jmp b1(u32 0)
+      b1(v6: u32):
+        v8 = lt v6, u32 4
+        jmpif v8 then: b2, else: b3
+      b2():
+        v9 = load v4 -> u1
+        v10 = not v9
+        jmpif v10 then: b4, else: b5
+      b3():
+        v20 = load v2 -> u32
+        v21 = eq v0, v20
+        constrain v0 == v20
+        return
+      b4():
+        store u1 0 at v5
+        v12 = eq v6, u32 2
+        jmpif v12 then: b6, else: b7
+      b5():
+        v19 = unchecked_add v6, u32 1
+        jmp b1(v19)
+      b6():
+        store u1 1 at v4
+        store u1 1 at v5
+        jmp b7()
+      b7():
+        v14 = load v5 -> u1
+        v15 = not v14
+        jmpif v15 then: b8, else: b9
+      b8():
+        v16 = load v2 -> u32
+        v17 = add v16, v6
+        store v17 at v2
+        jmp b10()
+      b9():
+        jmp b10()
+      b10():
+        jmp b5()
+    }
+    ";
+
+    assert_normalized_ssa_equals(ssa, expected);
+}
+
+#[named]
+#[test]
+fn mutable_reference_to_field_through_reference() {
+    let src = "
+    fn main(x: u32) -> pub u32 {
+        let mut pair = (x, x);
+        increment_first(&mut pair);
+        pair.0
+    }
+
+    fn increment_first(pair: &mut (u32, u32)) {
+        increment(&mut pair.0);
+    }
+
+    fn increment(value: &mut u32) {
+        *value += 1;
+    }
+    ";
+
+    let ssa = get_initial_ssa(src, function_path!()).unwrap();
+
+    // `increment` is given the reference to the first field rather than a copy of it
+    let expected = "
+    acir(inline) fn main f0 {
+      b0(v0: u32):
+        v1 = allocate -> &mut u32
+        store v0 at v1
+        v2 = allocate -> &mut u32
+        store v0 at v2
+        call f1(v1, v2)
+        v4 = load v1 -> u32
+        v5 = load v2 -> u32
+        return v4
+    }
+    acir(inline) fn increment_first f1 {
+      b0(v0: &mut u32, v1: &mut u32):
+        call f2(v0)
+        return
+    }
+    acir(inline) fn increment f2 {
+      b0(v0: &mut u32):
+        v1 = load v0 -> u32
+        v3 = add v1, u32 1
+        store v3 at v0
+        return
+    }
+    ";
+    assert_normalized_ssa_equals(ssa, expected);
+}
+
+#[named]
+#[test]
+fn nested_slice_in_unconstrained_function() {
+    let src = "
+    unconstrained fn main() {
+        let _strings: [[u8]] = &[&[1, 2], &[3]];
+    }
+    ";
+
+    let ssa = get_initial_ssa(src, function_path!()).unwrap();
+
+    // Each inner slice is stored in the outer slice as its length followed by its contents
+    let expected = "
+    brillig(inline) fn main f0 {
+      b0():
+        v2 = make_array [u8 1, u8 2] : [u8]
+        v4 = make_array [u8 3] : [u8]
+        v7 = make_array [u32 2, v2, u32 1, v4] : [(u32, [u8])]
+        return
+    }
+    ";
+    assert_normalized_ssa_equals(ssa, expected);
+}
@@ -0,0 +1,132 @@
+//! Translation validation of the SSA pipeline.
+//!
+//! The SSA is interpreted with the same inputs before the first pass and after every
+//! pass, and the first pass which changes the return values, the printed output or
+//! whether the execution fails is reported as an error. This helps bisecting
+//! miscompilations down to the pass which introduced them.
+use acvm::FieldElement;
+use iter_extended::vecmap;
+use noirc_errors::call_stack::CallStack;
+
+use crate::errors::RuntimeError;
+
+use super::{
+    Ssa,
+    interpreter::{InterpreterOptions, errors::InterpreterError, value::Value},
+};
+
+pub(crate) struct TranslationValidator {
+    /// The flattened fields of the ABI encoded inputs of `main`
+    inputs: Vec<FieldElement>,
+    /// How the program behaved after the last pass
+    expected: Behavior,
+}
+
+impl TranslationValidator {
+    /// Interprets the SSA before any pass, to have a behavior to compare the passes with.
+    pub(crate) fn new(ssa: &Ssa, inputs: Vec<FieldElement>) -> Result<Self, RuntimeError> {
+        let expected = Behavior::of(ssa, &inputs)?;
+        Ok(Self { inputs, expected })
+    }
+
+    /// Checks that the SSA produced by the given pass behaves as it did before the pass.
+    pub(crate) fn validate(&mut self, ssa: &Ssa, pass: &str) -> Result<(), RuntimeError> {
+        let behavior = Behavior::of(ssa, &self.inputs)?;
+        if let Some(difference) = behavior.difference_from(&self.expected) {
+            return Err(RuntimeError::SsaPassChangedBehavior {
+                pass: pass.to_string(),
+                difference,
+                call_stack: CallStack::default(),
+            });
+        }
+        // Keep the latest behavior, so that error messages come from the latest pass
+        self.expected = behavior;
+        Ok(())
+    }
+}
+
+struct Behavior {
+    result: Result<Vec<Value>, InterpreterError>,
+    output: String,
+}
+
+impl Behavior {
+    fn of(ssa: &Ssa, inputs: &[FieldElement]) -> Result<Self, RuntimeError> {
+        let args = ssa.main_arguments_from_fields(inputs).map_err(|reason| {
+            RuntimeError::InvalidSsaVerificationInputs { reason, call_stack: CallStack::default() }
+        })?;
+        let mut output = Vec::new();
+        let result = ssa.interpret_with_options(args, InterpreterOptions::default(), &mut output);
+        let output = String::from_utf8_lossy(&output).into_owned();
+        Ok(Self { result, output })
+    }
+
+    /// Describes how this behavior differs from the expected one, if it does.
+    ///
+    /// Failures are only compared by whether they happen, as passes are free to
+    /// change how they are reported.
+    fn difference_from(&self, expected: &Behavior) -> Option<String> {
+        let values = |values: &[Value]| vecmap(values, ToString::to_string).join(", ");
+        let difference = match (&self.result, &expected.result) {
+            (Ok(actual), Ok(expected)) if actual != expected => {
+                format!("it returned `{}` instead of `{}`", values(actual), values(expected))
+            }
+            (Ok(actual), Err(error)) => {
+                format!("it returned `{}` instead of failing with `{error}`", values(actual))
+            }
+            (Err(error), Ok(expected)) => {
+                format!("it failed with `{error}` instead of returning `{}`", values(expected))
+            }
+            _ if self.output != expected.output => {
+                format!("it printed {:?} instead of {:?}", self.output, expected.output)
+            }
+            _ => return None,
+        };
+        Some(difference)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{errors::RuntimeError, ssa::ssa_gen::Ssa};
+
+    use super::TranslationValidator;
+
+    const SRC: &str = "
+        acir(inline) fn main f0 {
+          b0(v0: u32):
+            v1 = add v0, u32 1
+            return v1
+        }
+    ";
+
+    #[test]
+    fn accepts_passes_preserving_behavior() {
+        let ssa = Ssa::from_str(SRC).unwrap();
+        let mut validator = TranslationValidator::new(&ssa, vec![2_u128.into()]).unwrap();
+
+        let ssa = ssa.fold_constants();
+        assert!(validator.validate(&ssa, "Constant Folding").is_ok());
+    }
+
+    #[test]
+    fn reports_passes_changing_behavior() {
+        let ssa = Ssa::from_str(SRC).unwrap();
+        let mut validator = TranslationValidator::new(&ssa, vec![2_u128.into()]).unwrap();
+
+        let miscompiled = Ssa::from_str(&SRC.replace("u32 1", "u32 2")).unwrap();
+        let error = validator.validate(&miscompiled, "Miscompilation").unwrap_err();
+        let RuntimeError::SsaPassChangedBehavior { pass, difference, .. } = error else {
+            panic!("unexpected error: {error}");
+        };
+        assert_eq!(pass, "Miscompilation");
+        assert_eq!(difference, "it returned `u32 4` instead of `u32 3`");
+    }
+
+    #[test]
+    fn rejects_inputs_not_matching_main() {
+        let ssa = Ssa::from_str(SRC).unwrap();
+        let result = TranslationValidator::new(&ssa, Vec::new());
+        assert!(matches!(result, Err(RuntimeError::InvalidSsaVerificationInputs { .. })));
+    }
+}
@@ -416,7 +416,11 @@ pub struct IfExpression {
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub struct MatchExpression {
     pub expression: Expression,
-    pub rules: Vec<(/*pattern*/ Expression, /*branch*/ Expression)>,
+    pub rules: Vec<(
+        /*pattern*/ Expression,
+        /*guard*/ Option<Expression>,
+        /*branch*/ Expression,
+    )>,
 }
 
 #[derive(Debug, PartialEq, Eq, Clone)]
@@ -771,8 +775,11 @@ impl Display for IfExpression {
 impl Display for MatchExpression {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         writeln!(f, "match {} {{", self.expression)?;
-        for (pattern, branch) in &self.rules {
-            writeln!(f, "    {pattern} -> {branch},")?;
+        for (pattern, guard, branch) in &self.rules {
+            match guard {
+                Some(guard) => writeln!(f, "    {pattern} if {guard} -> {branch},")?,
+                None => writeln!(f, "    {pattern} -> {branch},")?,
+            }
         }
         write!(f, "}}")
     }
@@ -48,6 +48,8 @@ pub enum StatementKind {
     While(WhileStatement),
     Break,
     Continue,
+    /// An early return from the current function, optionally with a value
+    Return(Option<Expression>),
     /// This statement should be executed at compile-time
     Comptime(Box<Statement>),
     // This is an expression with a trailing semi-colon
@@ -85,6 +87,7 @@ impl Statement {
             | StatementKind::While(..)
             | StatementKind::Break
             | StatementKind::Continue
+            | StatementKind::Return(..)
             | StatementKind::Semi(..)
             | StatementKind::Interned(..)
             | StatementKind::Error => self.location,
@@ -116,6 +119,7 @@ impl StatementKind {
             | StatementKind::Semi(_)
             | StatementKind::Break
             | StatementKind::Continue
+            | StatementKind::Return(_)
             | StatementKind::Error => {
                 // These statements can omit the semicolon if they are the last statement in a block
                 if !last_statement_in_block && semi.is_none() {
@@ -907,6 +911,8 @@ impl Display for StatementKind {
             }
             StatementKind::Break => write!(f, "break"),
             StatementKind::Continue => write!(f, "continue"),
+            StatementKind::Return(Some(expression)) => write!(f, "return {expression}"),
+            StatementKind::Return(None) => write!(f, "return"),
             StatementKind::Comptime(statement) => write!(f, "comptime {}", statement.kind),
             StatementKind::Semi(semi) => write!(f, "{semi};"),
             StatementKind::Interned(_) => write!(f, "(resolved);"),
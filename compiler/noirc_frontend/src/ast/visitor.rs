@@ -335,6 +335,10 @@ pub trait Visitor {
 
     fn visit_continue(&mut self) {}
 
+    fn visit_return_statement(&mut self, _: Option<&Expression>) -> bool {
+        true
+    }
+
     fn visit_interned_statement(&mut self, _: InternedStatementKind) {}
 
     fn visit_error_statement(&mut self) {}
@@ -1161,8 +1165,11 @@ impl MatchExpression {
 
     pub fn accept_children(&self, visitor: &mut impl Visitor) {
         self.expression.accept(visitor);
-        for (pattern, branch) in &self.rules {
+        for (pattern, guard, branch) in &self.rules {
             pattern.accept(visitor);
+            if let Some(guard) = guard {
+                guard.accept(visitor);
+            }
             branch.accept(visitor);
         }
     }
@@ -1250,6 +1257,13 @@ impl Statement {
             }
             StatementKind::Break => visitor.visit_break(),
             StatementKind::Continue => visitor.visit_continue(),
+            StatementKind::Return(expression) => {
+                if visitor.visit_return_statement(expression.as_ref()) {
+                    if let Some(expression) = expression {
+                        expression.accept(visitor);
+                    }
+                }
+            }
             StatementKind::Interned(id) => visitor.visit_interned_statement(*id),
             StatementKind::Error => visitor.visit_error_statement(),
         }
@@ -496,7 +496,7 @@ impl DebugInstrumenter {
             ast::StatementKind::Expression(expr) => {
                 self.walk_expr(expr);
             }
-            ast::StatementKind::Semi(expr) => {
+            ast::StatementKind::Semi(expr) | ast::StatementKind::Return(Some(expr)) => {
                 self.walk_expr(expr);
             }
             ast::StatementKind::For(for_stmt) => {
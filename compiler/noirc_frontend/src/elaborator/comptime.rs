@@ -654,6 +654,8 @@ impl<'context> Elaborator<'context> {
     /// push a new function context to resolve any trait constraints early.
     pub(super) fn elaborate_in_comptime_context<T>(&mut self, f: impl FnOnce(&mut Self) -> T) -> T {
         let old_comptime_value = std::mem::replace(&mut self.in_comptime_context, true);
+        // A `return` can't leave the comptime code to return from an enclosing function.
+        let old_return = self.current_return.take();
         // We have to push a new FunctionContext so that we can resolve any constraints
         // in this comptime block early before the function as a whole finishes elaborating.
         // Otherwise the interpreter below may find expressions for which the underlying trait
@@ -664,6 +666,7 @@ impl<'context> Elaborator<'context> {
 
         self.check_and_pop_function_context();
         self.in_comptime_context = old_comptime_value;
+        self.current_return = old_return;
         result
     }
 
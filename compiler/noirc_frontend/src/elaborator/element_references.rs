@@ -0,0 +1,369 @@
+//! Lowering of mutable references to array elements.
+//!
+//! Arrays are values, so there is no allocation holding one of their elements which a reference
+//! could point to. Instead, a mutable reference to an array element passed to a function points
+//! to a copy of the element, which is written back to the array once the call returns:
+//!
+//! ```text
+//!                                          {
+//!                                              let argument_1 = first();
+//! foo(first(), &mut array[index()].field)  =>      let i_2 = index();
+//!                                              let mut element_3 = array[i_2].field;
+//!                                              let result_4 = foo(argument_1, &mut element_3);
+//!                                              array[i_2].field = element_3;
+//!                                              result_4
+//!                                          }
+//! ```
+//!
+//! Arguments are still evaluated from left to right, and indices only once.
+//!
+//! This is not a general reference to an array element: since the element is only written back
+//! once the call returns, these references are only allowed as call arguments, the called
+//! function may not return a reference, and the call is rejected if the callee may reach the
+//! array through any other reference, be it another argument or one captured by a closure.
+//! In particular `let r = &mut array[i];`, storing such a reference in a struct, or passing
+//! references to two elements of the same array (`swap(&mut a[0], &mut a[1])`) are errors.
+use noirc_errors::Location;
+
+use crate::{
+    Type,
+    ast::{Expression, ExpressionKind, UnaryOp},
+    hir::type_check::TypeCheckError,
+    hir_def::{
+        expr::{
+            HirBlockExpression, HirCallExpression, HirExpression, HirIdent, HirIndexExpression,
+            HirMemberAccess, HirPrefixExpression,
+        },
+        stmt::{HirAssignStatement, HirLValue, HirLetStatement, HirPattern, HirStatement},
+    },
+    node_interner::{DefinitionId, DefinitionKind, ExprId, StmtId},
+};
+
+use super::Elaborator;
+
+impl Elaborator<'_> {
+    /// True if `expr` is an array element of a variable, possibly nested in struct fields,
+    /// tuples or other arrays: `a[i]`, `a.b[i].c`, `(*a)[i][j]`, ...
+    pub(super) fn is_array_element(&self, expr: ExprId) -> bool {
+        match self.interner.expression(&expr) {
+            HirExpression::Index(index) => self.is_place(index.collection),
+            HirExpression::MemberAccess(access) => self.is_array_element(access.lhs),
+            HirExpression::Prefix(HirPrefixExpression {
+                operator: UnaryOp::Dereference { .. },
+                rhs,
+                ..
+            }) => self.is_array_element(rhs),
+            _ => false,
+        }
+    }
+
+    /// True if `expr` can be assigned to, provided its variable is mutable.
+    fn is_place(&self, expr: ExprId) -> bool {
+        self.place_variable(expr).is_some()
+    }
+
+    /// The variable a place such as `a.b[i].c` or `(*a)[i]` belongs to.
+    fn place_variable(&self, expr: ExprId) -> Option<DefinitionId> {
+        match self.interner.expression(&expr) {
+            HirExpression::Ident(ident, _) => Some(ident.id),
+            HirExpression::Index(index) => self.place_variable(index.collection),
+            HirExpression::MemberAccess(access) => self.place_variable(access.lhs),
+            HirExpression::Prefix(HirPrefixExpression {
+                operator: UnaryOp::Dereference { .. },
+                rhs,
+                ..
+            }) => self.place_variable(rhs),
+            _ => None,
+        }
+    }
+
+    /// Returns the `&mut` expression if `argument` is a mutable reference to an array element.
+    fn element_reference(&self, argument: ExprId) -> Option<HirPrefixExpression> {
+        match self.interner.expression(&argument) {
+            HirExpression::Prefix(prefix)
+                if prefix.operator == (UnaryOp::Reference { mutable: true })
+                    && self.is_array_element(prefix.rhs) =>
+            {
+                Some(prefix)
+            }
+            _ => None,
+        }
+    }
+
+    /// The variable of a place which is reached without following any reference, such as `a`
+    /// for `a.b[i]` but not for `(*r)[i]`, or for `a[i]` if `a` holds a reference.
+    fn direct_place_variable(&self, expr: ExprId) -> Option<DefinitionId> {
+        match self.interner.expression(&expr) {
+            HirExpression::Ident(ident, _) => {
+                let typ = self.interner.id_type(expr);
+                (!contains_reference(&typ)).then_some(ident.id)
+            }
+            HirExpression::Index(index) => self.direct_place_variable(index.collection),
+            HirExpression::MemberAccess(access) => self.direct_place_variable(access.lhs),
+            _ => None,
+        }
+    }
+
+    /// The variable referenced by a call argument such as `&mut a.b[i]` or `&a`,
+    /// if the reference doesn't follow any other reference.
+    fn referenced_direct_place_variable(&self, argument: ExprId) -> Option<DefinitionId> {
+        match self.interner.expression(&argument) {
+            HirExpression::Prefix(HirPrefixExpression {
+                operator: UnaryOp::Reference { .. },
+                rhs,
+                ..
+            }) => self.direct_place_variable(rhs),
+            _ => None,
+        }
+    }
+
+    /// Returns the location of the first mutable reference to an array element whose array
+    /// the callee may also reach through another reference, either given as another argument
+    /// or captured by the callee. The write back would then overwrite any change made through
+    /// that other reference.
+    ///
+    /// A reference to a variable which doesn't hold any reference itself can only alias
+    /// the element if it refers to the same variable.
+    fn aliased_element_reference(
+        &self,
+        function: ExprId,
+        arguments: &[ExprId],
+    ) -> Option<Location> {
+        let captures_reference = contains_reference(&self.interner.id_type(function));
+
+        arguments.iter().enumerate().find_map(|(position, argument)| {
+            let element = self.element_reference(*argument)?;
+            let variable = self.direct_place_variable(element.rhs);
+            let is_aliased = captures_reference
+                || arguments.iter().enumerate().any(|(other_position, other)| {
+                    if other_position == position {
+                        return false;
+                    }
+                    match (variable, self.referenced_direct_place_variable(*other)) {
+                        (Some(variable), Some(other_variable)) => variable == other_variable,
+                        _ => contains_reference(&self.interner.id_type(other)),
+                    }
+                });
+            is_aliased.then(|| self.interner.expr_location(argument))
+        })
+    }
+
+    /// Wrap the given call in a block writing back any array element it was given a mutable
+    /// reference to, or return the call unchanged if there is none.
+    pub(super) fn write_back_element_references(
+        &mut self,
+        call: HirCallExpression,
+        typ: &Type,
+        location: Location,
+    ) -> HirExpression {
+        let Some(last_element_reference) =
+            call.arguments.iter().rposition(|argument| self.element_reference(*argument).is_some())
+        else {
+            return HirExpression::Call(call);
+        };
+
+        if let Some(location) = self.aliased_element_reference(call.func, &call.arguments) {
+            self.push_err(TypeCheckError::AliasedMutableReferenceToArrayElement { location });
+            return HirExpression::Call(call);
+        }
+
+        if matches!(typ.follow_bindings(), Type::Reference(..)) {
+            self.push_err(TypeCheckError::MutableReferenceToArrayElementReturned { location });
+        }
+
+        let mut statements = Vec::new();
+        let mut write_backs = Vec::new();
+        let mut arguments = Vec::with_capacity(call.arguments.len());
+
+        for (position, argument) in call.arguments.iter().enumerate() {
+            let argument_location = self.interner.expr_location(argument);
+            let argument_type = self.interner.id_type(argument);
+
+            let Some(prefix) = self.element_reference(*argument) else {
+                // Arguments before an element reference are evaluated before its indices
+                let trivial = matches!(
+                    self.interner.expression(argument),
+                    HirExpression::Ident(..) | HirExpression::Literal(..)
+                );
+                if position > last_element_reference || trivial {
+                    arguments.push(*argument);
+                } else {
+                    let (value, let_argument) = self.fresh_variable(
+                        "argument",
+                        false,
+                        *argument,
+                        argument_type.clone(),
+                        argument_location,
+                    );
+                    statements.push(let_argument);
+                    let value =
+                        self.interner.push_expr_full(value, argument_location, argument_type);
+                    arguments.push(value);
+                }
+                continue;
+            };
+
+            // The indices are evaluated once, before reading the element
+            let (lvalue, read) = self.element_place(prefix.rhs, &mut statements);
+            let element_type = self.interner.id_type(prefix.rhs);
+
+            let (element, let_element) =
+                self.fresh_variable("element", true, read, element_type.clone(), argument_location);
+            statements.push(let_element);
+
+            let rhs = self.interner.push_expr_full(
+                element.clone(),
+                argument_location,
+                element_type.clone(),
+            );
+            let reference = HirExpression::Prefix(HirPrefixExpression { rhs, ..prefix });
+            arguments.push(self.interner.push_expr_full(
+                reference,
+                argument_location,
+                argument_type,
+            ));
+            write_backs.push((lvalue, element, element_type, argument_location));
+        }
+
+        // Keep the call as it was written so that `nargo expand` can show it instead of the block
+        let original_call =
+            self.interner.push_expr_full(HirExpression::Call(call.clone()), location, typ.clone());
+
+        let call = HirCallExpression { arguments, ..call };
+        let call = self.interner.push_expr_full(HirExpression::Call(call), location, typ.clone());
+        let (result, let_result) =
+            self.fresh_variable("result", false, call, typ.clone(), location);
+        statements.push(let_result);
+
+        for (lvalue, element, element_type, location) in write_backs {
+            let expression = self.interner.push_expr_full(element, location, element_type);
+            let assign = HirStatement::Assign(HirAssignStatement { lvalue, expression });
+            statements.push(self.push_statement(assign, location));
+        }
+
+        let result = self.interner.push_expr_full(result, location, typ.clone());
+        let result = self.push_statement(HirStatement::Expression(result), location);
+        self.interner.push_element_reference_call(result, original_call);
+        statements.push(result);
+        HirExpression::Block(HirBlockExpression { statements })
+    }
+
+    /// Convert an array element expression into the equivalent lvalue along with an expression
+    /// reading it, hoisting each index into a fresh variable so that it is only evaluated once.
+    fn element_place(&mut self, expr: ExprId, statements: &mut Vec<StmtId>) -> (HirLValue, ExprId) {
+        let location = self.interner.expr_location(&expr);
+        let typ = self.interner.id_type(expr);
+
+        match self.interner.expression(&expr) {
+            HirExpression::Ident(ident, _) => (HirLValue::Ident(ident, typ), expr),
+            HirExpression::Index(HirIndexExpression { collection, mut index }) => {
+                let (array, collection) = self.element_place(collection, statements);
+                let index_type = self.interner.id_type(index);
+                let index_location = self.interner.expr_location(&index);
+                if let Some((index_definition, new_index)) =
+                    self.fresh_definition_for_lvalue_index(index, index_type, index_location)
+                {
+                    index = new_index;
+                    statements.push(index_definition);
+                }
+                let read = HirExpression::Index(HirIndexExpression { collection, index });
+                let read = self.interner.push_expr_full(read, location, typ.clone());
+                let array = Box::new(array);
+                (HirLValue::Index { array, index, typ, location }, read)
+            }
+            HirExpression::MemberAccess(access) => {
+                let (object, lhs) = self.element_place(access.lhs, statements);
+                let field_index = self.interner.get_field_index(expr);
+                let field_name = access.rhs.clone();
+                let read = HirExpression::MemberAccess(HirMemberAccess { lhs, ..access });
+                let read = self.interner.push_expr_full(read, location, typ.clone());
+                self.interner.set_field_index(read, field_index);
+                let object = Box::new(object);
+                let field_index = Some(field_index);
+                (HirLValue::MemberAccess { object, field_name, field_index, typ, location }, read)
+            }
+            HirExpression::Prefix(HirPrefixExpression {
+                operator: operator @ UnaryOp::Dereference { implicitly_added },
+                rhs,
+                trait_method_id,
+            }) => {
+                let (lvalue, rhs) = self.element_place(rhs, statements);
+                let read =
+                    HirExpression::Prefix(HirPrefixExpression { operator, rhs, trait_method_id });
+                let read = self.interner.push_expr_full(read, location, typ.clone());
+                let lvalue = Box::new(lvalue);
+                let element_type = typ;
+                (HirLValue::Dereference { lvalue, element_type, implicitly_added, location }, read)
+            }
+            other => unreachable!("ICE: expected an array element, found {other:?}"),
+        }
+    }
+
+    /// Create `let <name> = <expr>;` for a fresh variable, returning an expression referring
+    /// to the variable along with the let statement.
+    fn fresh_variable(
+        &mut self,
+        name: &str,
+        mutable: bool,
+        expr: ExprId,
+        typ: Type,
+        location: Location,
+    ) -> (HirExpression, StmtId) {
+        let name = format!("{name}_{}", self.interner.definition_count());
+        let id = self.interner.push_definition(
+            name,
+            mutable,
+            false,
+            DefinitionKind::Local(Some(expr)),
+            location,
+        );
+        self.interner.push_definition_type(id, typ.clone());
+
+        let ident = HirIdent::non_trait_method(id, location);
+        let mut pattern = HirPattern::Identifier(ident.clone());
+        if mutable {
+            pattern = HirPattern::Mutable(Box::new(pattern), location);
+        }
+
+        let let_ = HirStatement::Let(HirLetStatement::basic(pattern, typ, expr));
+        let let_ = self.push_statement(let_, location);
+        (HirExpression::Ident(ident, None), let_)
+    }
+
+    fn push_statement(&mut self, statement: HirStatement, location: Location) -> StmtId {
+        let id = self.interner.push_stmt(statement);
+        self.interner.push_stmt_location(id, location);
+        id
+    }
+}
+
+/// True if a value of this type may hold a reference, including one captured by a closure.
+fn contains_reference(typ: &Type) -> bool {
+    match typ.follow_bindings_shallow().as_ref() {
+        Type::Reference(..) => true,
+        Type::Array(_, element) | Type::Slice(element) => contains_reference(element),
+        Type::FmtString(_, environment) => contains_reference(environment),
+        Type::Tuple(fields) => fields.iter().any(contains_reference),
+        Type::DataType(datatype, generics) => {
+            let datatype = datatype.borrow();
+            if let Some(fields) = datatype.get_fields(generics) {
+                fields.iter().any(|(_, field, _)| contains_reference(field))
+            } else if let Some(variants) = datatype.get_variants(generics) {
+                variants.iter().flat_map(|(_, args)| args).any(contains_reference)
+            } else {
+                false
+            }
+        }
+        Type::Alias(alias, generics) => contains_reference(&alias.borrow().get_type(generics)),
+        Type::CheckedCast { to, .. } => contains_reference(to),
+        // Only the environment of a function holds references, not its parameters
+        Type::Function(_, _, environment, _) => contains_reference(environment),
+        Type::Forall(_, typ) => contains_reference(typ),
+        _ => false,
+    }
+}
+
+/// True if `expr` is a `&mut` expression.
+pub(super) fn is_mutable_reference(expr: &Expression) -> bool {
+    matches!(&expr.kind, ExpressionKind::Prefix(prefix) if prefix.operator == UnaryOp::Reference { mutable: true })
+}
@@ -348,19 +348,29 @@ impl Elaborator<'_> {
     pub(super) fn elaborate_match_rules(
         &mut self,
         variable_to_match: DefinitionId,
-        rules: Vec<(Expression, Expression)>,
+        rules: Vec<(Expression, Option<Expression>, Expression)>,
     ) -> (Vec<Row>, Type) {
         let result_type = self.interner.next_type_variable();
         let expected_pattern_type = self.interner.definition_type(variable_to_match);
 
-        let rows = vecmap(rules, |(pattern, branch)| {
+        let rows = vecmap(rules, |(pattern, guard, branch)| {
             self.push_scope();
             let pattern_location = pattern.location;
             let pattern =
                 self.expression_to_pattern(pattern, &expected_pattern_type, &mut Vec::new());
             let columns = vec![Column::new(variable_to_match, pattern)];
 
-            let guard = None;
+            let guard = guard.map(|guard| {
+                let guard_location = guard.type_location();
+                let (guard, guard_type) = self.elaborate_expression(guard);
+                self.unify(&guard_type, &Type::Bool, || TypeCheckError::TypeMismatch {
+                    expected_typ: Type::Bool.to_string(),
+                    expr_typ: guard_type.to_string(),
+                    expr_location: guard_location,
+                });
+                guard
+            });
+
             let body_location = branch.type_location();
             let (body, body_type) = self.elaborate_expression(branch);
 
@@ -893,6 +903,8 @@ impl<'elab, 'ctx> MatchCompiler<'elab, 'ctx> {
                     HirMatch::Success(row.body)
                 }
                 Some(cond) => {
+                    // A guarded row may not match, in which case the remaining rows are tried
+                    self.unreachable_cases.remove(&row.original_body);
                     let remaining = self.compile_rows(rows)?;
                     HirMatch::Guard { cond, body: row.body, otherwise: Box::new(remaining) }
                 }
@@ -1104,7 +1116,7 @@ impl<'elab, 'ctx> MatchCompiler<'elab, 'ctx> {
                         cols.push(Column::new(*var, pat));
                     }
 
-                    cases[idx].2.push(Row::new(cols, row.guard, row.body, row.location));
+                    cases[idx].2.push(Row { columns: cols, ..row });
                 }
             } else {
                 for (_, _, rows) in &mut cases {
@@ -1192,6 +1204,9 @@ impl<'elab, 'ctx> MatchCompiler<'elab, 'ctx> {
             row.columns.retain(|col| {
                 if let Pattern::Binding(variable) = col.pattern {
                     row.body = self.let_binding(variable, col.variable_to_match, row.body);
+                    row.guard = row
+                        .guard
+                        .map(|guard| self.let_binding(variable, col.variable_to_match, guard));
                     false
                 } else {
                     true
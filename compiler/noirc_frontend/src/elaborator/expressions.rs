@@ -40,7 +40,8 @@ use crate::{
 };
 
 use super::{
-    Elaborator, LambdaContext, UnsafeBlockStatus, UnstableFeature,
+    Elaborator, LambdaContext, ReturnContext, UnsafeBlockStatus, UnstableFeature,
+    element_references::is_mutable_reference,
     function_context::BindableTypeVariableKind,
     path_resolution::{TypedPath, TypedPathSegment},
 };
@@ -163,9 +164,9 @@ impl Elaborator<'_> {
         let statements_len = block.statements.len();
         let mut statements = Vec::with_capacity(statements_len);
 
-        // If we found a break or continue statement, this holds its location (only for the first one)
+        // If we found a break, continue or return statement, this holds its location (only for the first one)
         let mut break_or_continue_location = None;
-        // When encountering a statement after a break or continue we'll error saying it's unreachable,
+        // When encountering a statement after a break, continue or return we'll error saying it's unreachable,
         // but we only want to error for the first statement.
         let mut errored_unreachable = false;
 
@@ -190,6 +191,7 @@ impl Elaborator<'_> {
             }
 
             let is_break_or_continue = matches!(stmt, HirStatement::Break | HirStatement::Continue);
+            let is_return = matches!(stmt, HirStatement::Return(_));
 
             if let Some(break_or_continue_location) = break_or_continue_location {
                 if !errored_unreachable {
@@ -199,10 +201,11 @@ impl Elaborator<'_> {
                     });
                     errored_unreachable = true;
                 }
-            } else if is_break_or_continue {
+            } else if is_break_or_continue || is_return {
                 break_or_continue_location = Some(location);
             }
 
+            // A block ending in a `return` takes the type of the `return`, which can be anything
             if i + 1 == statements.len() {
                 block_type = if is_break_or_continue { Type::Unit } else { stmt_type };
             }
@@ -403,6 +406,7 @@ impl Elaborator<'_> {
 
     fn elaborate_prefix(&mut self, prefix: PrefixExpression, location: Location) -> (ExprId, Type) {
         let rhs_location = prefix.rhs.location;
+        let is_call_argument = std::mem::take(&mut self.in_call_argument_reference);
 
         let (rhs, rhs_type) = self.elaborate_expression(prefix.rhs);
         let trait_method_id = self.interner.get_prefix_operator_trait_method(&prefix.operator);
@@ -412,6 +416,13 @@ impl Elaborator<'_> {
         if let UnaryOp::Reference { mutable } = operator {
             if mutable {
                 self.check_can_mutate(rhs, rhs_location);
+
+                // The element is written back to the array once the call returns,
+                // see `write_back_element_references`
+                if !is_call_argument && self.is_array_element(rhs) {
+                    let location = rhs_location;
+                    self.push_err(TypeCheckError::MutableReferenceToArrayElement { location });
+                }
             } else {
                 self.use_unstable_feature(UnstableFeature::Ownership, location);
             }
@@ -450,8 +461,8 @@ impl Elaborator<'_> {
                     }
                 }
             }
-            HirExpression::Index(_) => {
-                self.push_err(TypeCheckError::MutableReferenceToArrayElement { location });
+            HirExpression::Index(index) => {
+                self.check_can_mutate(index.collection, location);
             }
             HirExpression::MemberAccess(member_access) => {
                 self.check_can_mutate(member_access.lhs, location);
@@ -537,6 +548,7 @@ impl Elaborator<'_> {
         let args = vecmap(call.arguments.into_iter().enumerate(), |(arg_index, arg)| {
             let location = arg.location;
             let expected_type = func_arg_types.and_then(|args| args.get(arg_index));
+            self.in_call_argument_reference = is_mutable_reference(&arg);
 
             let (arg, typ) = if call.is_macro_call {
                 self.elaborate_in_comptime_context(|this| {
@@ -576,7 +588,8 @@ impl Elaborator<'_> {
             }
         }
 
-        (HirExpression::Call(hir_call), typ)
+        let call = self.write_back_element_references(hir_call, &typ, location);
+        (call, typ)
     }
 
     fn elaborate_method_call(
@@ -660,6 +673,7 @@ impl Elaborator<'_> {
                 for (arg_index, arg) in method_call.arguments.into_iter().enumerate() {
                     let location = arg.location;
                     let expected_type = func_arg_types.and_then(|args| args.get(arg_index + 1));
+                    self.in_call_argument_reference = is_mutable_reference(&arg);
                     let (arg, typ) = self.elaborate_expression_with_type(arg, expected_type);
 
                     // Try to unify this argument type against the function's argument type
@@ -702,7 +716,8 @@ impl Elaborator<'_> {
                             .unwrap_or((HirExpression::Error, Type::Error));
                     }
                 }
-                (HirExpression::Call(function_call), typ)
+                let call = self.write_back_element_references(function_call, &typ, location);
+                (call, typ)
             }
             None => (HirExpression::Error, Type::Error),
         }
@@ -1287,7 +1302,12 @@ impl Elaborator<'_> {
 
         let return_type = self.resolve_inferred_type(lambda.return_type);
         let body_location = lambda.body.location;
+        let old_return = self.current_return.replace(ReturnContext {
+            return_type: return_type.clone(),
+            declared_return_type: None,
+        });
         let (body, body_type) = self.elaborate_expression(lambda.body);
+        self.current_return = old_return;
 
         let lambda_context = self.lambda_stack.pop().unwrap();
         self.pop_scope();
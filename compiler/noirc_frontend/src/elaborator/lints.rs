@@ -1,3 +1,5 @@
+use std::cell::Cell;
+
 use crate::{
     Type,
     ast::{Ident, NoirFunction},
@@ -107,6 +109,36 @@ pub(super) fn oracle_not_marked_unconstrained(
     }
 }
 
+/// Oracle functions may not return nested slices as foreign call results can't be read into them.
+pub(super) fn oracle_returns_nested_slice(
+    func: &FuncMeta,
+    modifiers: &FunctionModifiers,
+) -> Option<ResolverError> {
+    let attribute = modifiers.attributes.function()?;
+    if !matches!(attribute.kind, FunctionAttributeKind::Oracle(_)) {
+        return None;
+    }
+
+    if returns_nested_slice(func.return_type()) {
+        Some(ResolverError::OracleReturnsNestedSlice { location: func.return_type.location() })
+    } else {
+        None
+    }
+}
+
+/// Each tuple element and struct field of a foreign call result is returned separately,
+/// so only nested slices within them are unsupported.
+fn returns_nested_slice(typ: &Type) -> bool {
+    match typ.follow_bindings_shallow().as_ref() {
+        Type::Tuple(types) => types.iter().any(returns_nested_slice),
+        Type::DataType(def, generics) => def
+            .borrow()
+            .get_fields(generics)
+            .is_some_and(|fields| fields.iter().any(|(_, field, _)| returns_nested_slice(field))),
+        typ => typ.is_nested_slice(),
+    }
+}
+
 /// Oracle functions may not be called by constrained functions directly.
 ///
 /// In order for a constrained function to call an oracle it must first call through an unconstrained function.
@@ -266,7 +298,9 @@ pub(crate) fn unbounded_recursion<'a>(
     func_location: Location,
     body_id: ExprId,
 ) -> Option<ResolverError> {
-    if !can_return_without_recursing(interner, func_id, body_id) {
+    // Set if an early `return` can be reached without recursing.
+    let returns = Cell::new(false);
+    if !can_return_without_recursing(interner, func_id, body_id, &returns) && !returns.get() {
         Some(ResolverError::UnconditionalRecursion {
             name: func_name().to_string(),
             location: func_location,
@@ -277,8 +311,15 @@ pub(crate) fn unbounded_recursion<'a>(
 }
 
 /// Check if an expression will end up calling a specific function.
-fn can_return_without_recursing(interner: &NodeInterner, func_id: FuncId, expr_id: ExprId) -> bool {
-    let check = |e| can_return_without_recursing(interner, func_id, e);
+///
+/// `returns` is set when a `return` statement is reached without calling the function.
+fn can_return_without_recursing(
+    interner: &NodeInterner,
+    func_id: FuncId,
+    expr_id: ExprId,
+    returns: &Cell<bool>,
+) -> bool {
+    let check = |e| can_return_without_recursing(interner, func_id, e, returns);
 
     let check_block = |block: HirBlockExpression| {
         block.statements.iter().all(|stmt_id| match interner.statement(stmt_id) {
@@ -290,6 +331,13 @@ fn can_return_without_recursing(interner: &NodeInterner, func_id: FuncId, expr_i
             HirStatement::For(e) => check(e.start_range) && check(e.end_range),
             HirStatement::Loop(e) => check(e),
             HirStatement::While(condition, block) => check(condition) && check(block),
+            HirStatement::Return(e) => {
+                let can_return = e.is_none_or(check);
+                if can_return {
+                    returns.set(true);
+                }
+                can_return
+            }
             HirStatement::Comptime(_)
             | HirStatement::Break
             | HirStatement::Continue
@@ -316,7 +364,9 @@ fn can_return_without_recursing(interner: &NodeInterner, func_id: FuncId, expr_i
         HirExpression::If(e) => {
             check(e.condition) && (check(e.consequence) || e.alternative.map(check).unwrap_or(true))
         }
-        HirExpression::Match(e) => can_return_without_recursing_match(interner, func_id, &e),
+        HirExpression::Match(e) => {
+            can_return_without_recursing_match(interner, func_id, &e, returns)
+        }
         HirExpression::Tuple(e) => e.iter().cloned().all(check),
         HirExpression::Unsafe(b) => check_block(b),
         // Rust doesn't check the lambda body (it might not be called).
@@ -334,9 +384,10 @@ fn can_return_without_recursing_match(
     interner: &NodeInterner,
     func_id: FuncId,
     match_expr: &HirMatch,
+    returns: &Cell<bool>,
 ) -> bool {
-    let check_match = |e| can_return_without_recursing_match(interner, func_id, e);
-    let check = |e| can_return_without_recursing(interner, func_id, e);
+    let check_match = |e| can_return_without_recursing_match(interner, func_id, e, returns);
+    let check = |e| can_return_without_recursing(interner, func_id, e, returns);
 
     match match_expr {
         HirMatch::Success(expr) => check(*expr),
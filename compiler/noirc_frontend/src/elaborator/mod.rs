@@ -17,8 +17,8 @@ use crate::{
 use crate::{
     EnumVariant, Shared, Type, TypeVariable,
     ast::{
-        BlockExpression, FunctionKind, GenericTypeArgs, Ident, NoirFunction, NoirStruct, Param,
-        Path, Pattern, TraitBound, UnresolvedGeneric, UnresolvedGenerics,
+        BlockExpression, FunctionKind, FunctionReturnType, GenericTypeArgs, Ident, NoirFunction,
+        NoirStruct, Param, Path, Pattern, TraitBound, UnresolvedGeneric, UnresolvedGenerics,
         UnresolvedTraitConstraint, UnresolvedTypeData, UnsupportedNumericGenericType, Visitor,
     },
     graph::CrateId,
@@ -52,6 +52,7 @@ use crate::{
 };
 
 mod comptime;
+mod element_references;
 mod enums;
 mod expressions;
 mod function_context;
@@ -116,6 +117,15 @@ pub struct Loop {
     pub has_break: bool,
 }
 
+/// The function or lambda whose body is being elaborated, which `return` statements return from.
+#[derive(Clone)]
+pub struct ReturnContext {
+    pub return_type: Type,
+    /// The declared return type of a function, used when reporting a mismatch.
+    /// This is `None` for lambdas.
+    pub declared_return_type: Option<FunctionReturnType>,
+}
+
 pub struct Elaborator<'context> {
     scopes: ScopeForest,
 
@@ -129,6 +139,11 @@ pub struct Elaborator<'context> {
 
     unsafe_block_status: UnsafeBlockStatus,
     current_loop: Option<Loop>,
+    current_return: Option<ReturnContext>,
+
+    /// True while elaborating a `&mut` expression passed directly as a call argument,
+    /// the only place a mutable reference to an array element is allowed.
+    in_call_argument_reference: bool,
 
     /// Contains a mapping of the current struct or functions's generics to
     /// unique type variables if we're resolving a struct. Empty otherwise.
@@ -259,6 +274,8 @@ impl<'context> Elaborator<'context> {
             interpreter_output,
             unsafe_block_status: UnsafeBlockStatus::NotInUnsafeBlock,
             current_loop: None,
+            current_return: None,
+            in_call_argument_reference: false,
             generics: Vec::new(),
             lambda_stack: Vec::new(),
             self_type: None,
@@ -513,7 +530,12 @@ impl<'context> Elaborator<'context> {
             | FunctionKind::TraitFunctionWithoutBody => (HirFunction::empty(), Type::Error),
             FunctionKind::Normal => {
                 let return_type = func_meta.return_type();
+                let old_return = self.current_return.replace(ReturnContext {
+                    return_type: return_type.clone(),
+                    declared_return_type: Some(func_meta.return_type.clone()),
+                });
                 let (block, body_type) = self.elaborate_block(body, Some(return_type));
+                self.current_return = old_return;
                 let expr_id = self.intern_expr(block, body_location);
                 self.interner.push_expr_type(expr_id, body_type.clone());
                 (HirFunction::unchecked_from_expr(expr_id), body_type)
@@ -1131,6 +1153,7 @@ impl<'context> Elaborator<'context> {
             lints::unnecessary_pub_return(func, modifiers, pub_allowed).map(Into::into)
         });
         self.run_lint(|_| lints::oracle_not_marked_unconstrained(func, modifiers).map(Into::into));
+        self.run_lint(|_| lints::oracle_returns_nested_slice(func, modifiers).map(Into::into));
         self.run_lint(|elaborator| {
             lints::low_level_function_outside_stdlib(modifiers, elaborator.crate_id).map(Into::into)
         });
@@ -1881,10 +1904,6 @@ impl<'context> Elaborator<'context> {
     }
 
     fn collect_struct_definitions(&mut self, structs: &BTreeMap<TypeId, UnresolvedStruct>) {
-        // This is necessary to avoid cloning the entire struct map
-        // when adding checks after each struct field is resolved.
-        let struct_ids = structs.keys().copied().collect::<Vec<_>>();
-
         // Resolve each field in each struct.
         // Each struct should already be present in the NodeInterner after def collection.
         for (type_id, typ) in structs {
@@ -1926,25 +1945,6 @@ impl<'context> Elaborator<'context> {
                 struct_def.set_fields(fields);
             });
         }
-
-        // Check whether the struct fields have nested slices
-        // We need to check after all structs are resolved to
-        // make sure every struct's fields is accurately set.
-        for id in struct_ids {
-            let struct_type = self.interner.get_type(id);
-
-            // Only handle structs without generics as any generics args will be checked
-            // after monomorphization when performing SSA codegen
-            if struct_type.borrow().generics.is_empty() {
-                let fields = struct_type.borrow().get_fields(&[]).unwrap();
-                for (_, field_type, _) in fields.iter() {
-                    if field_type.is_nested_slice() {
-                        let location = struct_type.borrow().location;
-                        self.push_err(ResolverError::NestedSlices { location });
-                    }
-                }
-            }
-        }
     }
 
     pub fn resolve_struct_fields(
@@ -3,8 +3,9 @@ use noirc_errors::Location;
 use crate::{
     DataType, Type,
     ast::{
-        AssignStatement, Expression, ForLoopStatement, ForRange, Ident, IntegerBitSize,
-        ItemVisibility, LValue, LetStatement, Statement, StatementKind, WhileStatement,
+        AssignStatement, Expression, ExpressionKind, ForLoopStatement, ForRange, Ident,
+        IntegerBitSize, ItemVisibility, LValue, LetStatement, Literal, Statement, StatementKind,
+        WhileStatement,
     },
     hir::{
         def_collector::dc_crate::CompilationError,
@@ -45,6 +46,7 @@ impl Elaborator<'_> {
             StatementKind::While(while_) => self.elaborate_while(while_),
             StatementKind::Break => self.elaborate_jump(true, statement.location),
             StatementKind::Continue => self.elaborate_jump(false, statement.location),
+            StatementKind::Return(expr) => self.elaborate_return(expr, statement.location),
             StatementKind::Comptime(statement) => self.elaborate_comptime_statement(*statement),
             StatementKind::Expression(expr) => {
                 let (expr, typ) = self.elaborate_expression_with_target_type(expr, target_type);
@@ -330,12 +332,8 @@ impl Elaborator<'_> {
     }
 
     fn elaborate_jump(&mut self, is_break: bool, location: Location) -> (HirStatement, Type) {
-        let in_constrained_function = self.in_constrained_function();
-
-        if in_constrained_function {
-            self.push_err(ResolverError::JumpInConstrainedFn { is_break, location });
-        }
-
+        // `break` and `continue` are also allowed in constrained code: the only loops allowed
+        // there are `for` loops, which are unrolled with any code after the jump disabled.
         if let Some(current_loop) = &mut self.current_loop {
             if is_break {
                 current_loop.has_break = true;
@@ -348,6 +346,58 @@ impl Elaborator<'_> {
         (expr, self.interner.next_type_variable())
     }
 
+    fn elaborate_return(
+        &mut self,
+        expr: Option<Expression>,
+        location: Location,
+    ) -> (HirStatement, Type) {
+        let Some(context) = self.current_return.clone() else {
+            self.push_err(ResolverError::ReturnOutsideFunction { location });
+            // Keep the returned expression so comptime code can still be evaluated
+            let unit = || Expression::new(ExpressionKind::Literal(Literal::Unit), location);
+            let (expr, typ) = self.elaborate_expression(expr.unwrap_or_else(unit));
+            return (HirStatement::Expression(expr), typ);
+        };
+
+        let make_error = |actual: Type, expr_location: Location| match context.declared_return_type
+        {
+            Some(declared_return_type) => TypeCheckError::TypeMismatchWithSource {
+                expected: context.return_type.clone(),
+                actual,
+                location: expr_location,
+                source: Source::Return(declared_return_type, expr_location),
+            },
+            None => TypeCheckError::TypeMismatch {
+                expected_typ: context.return_type.to_string(),
+                expr_typ: actual.to_string(),
+                expr_location,
+            },
+        };
+
+        let expr = match expr {
+            Some(expr) => {
+                let expr_location = expr.type_location();
+                let (expr_id, typ) =
+                    self.elaborate_expression_with_target_type(expr, Some(&context.return_type));
+                self.unify_with_coercions(
+                    &typ,
+                    &context.return_type,
+                    expr_id,
+                    expr_location,
+                    || make_error(typ.clone(), expr_location).into(),
+                );
+                Some(expr_id)
+            }
+            None => {
+                self.unify(&Type::Unit, &context.return_type, || make_error(Type::Unit, location));
+                None
+            }
+        };
+
+        // Like `break` and `continue`, a `return` never produces a value.
+        (HirStatement::Return(expr), self.interner.next_type_variable())
+    }
+
     fn get_lvalue_error_info(&self, lvalue: &HirLValue) -> (DefinitionId, String, Location) {
         match lvalue {
             HirLValue::Ident(name, _) => {
@@ -547,7 +597,7 @@ impl Elaborator<'_> {
         }
     }
 
-    fn fresh_definition_for_lvalue_index(
+    pub(super) fn fresh_definition_for_lvalue_index(
         &mut self,
         expr: ExprId,
         typ: Type,
@@ -60,7 +60,7 @@ pub(super) enum TraitPathResolutionMethod {
 
 impl Elaborator<'_> {
     pub(crate) fn resolve_type(&mut self, typ: UnresolvedType) -> Type {
-        self.resolve_type_inner(typ, &Kind::Normal, PathResolutionMode::MarkAsReferenced)
+        self.resolve_type_with_kind_inner(typ, &Kind::Normal, PathResolutionMode::MarkAsReferenced)
     }
 
     pub(crate) fn use_type(&mut self, typ: UnresolvedType) -> Type {
@@ -68,22 +68,7 @@ impl Elaborator<'_> {
     }
 
     pub(crate) fn use_type_with_kind(&mut self, typ: UnresolvedType, kind: &Kind) -> Type {
-        self.resolve_type_inner(typ, kind, PathResolutionMode::MarkAsUsed)
-    }
-
-    /// Translates an UnresolvedType to a Type with a `TypeKind::Normal`
-    fn resolve_type_inner(
-        &mut self,
-        typ: UnresolvedType,
-        kind: &Kind,
-        mode: PathResolutionMode,
-    ) -> Type {
-        let location = typ.location;
-        let resolved_type = self.resolve_type_with_kind_inner(typ, kind, mode);
-        if resolved_type.is_nested_slice() {
-            self.push_err(ResolverError::NestedSlices { location });
-        }
-        resolved_type
+        self.resolve_type_with_kind_inner(typ, kind, PathResolutionMode::MarkAsUsed)
     }
 
     pub(crate) fn resolve_type_with_kind(&mut self, typ: UnresolvedType, kind: &Kind) -> Type {
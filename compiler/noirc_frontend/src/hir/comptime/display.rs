@@ -663,10 +663,11 @@ fn remove_interned_in_expression_kind(
         })),
         ExpressionKind::Match(match_expr) => ExpressionKind::Match(Box::new(MatchExpression {
             expression: remove_interned_in_expression(interner, match_expr.expression),
-            rules: vecmap(match_expr.rules, |(pattern, branch)| {
+            rules: vecmap(match_expr.rules, |(pattern, guard, branch)| {
                 let pattern = remove_interned_in_expression(interner, pattern);
+                let guard = guard.map(|guard| remove_interned_in_expression(interner, guard));
                 let branch = remove_interned_in_expression(interner, branch);
-                (pattern, branch)
+                (pattern, guard, branch)
             }),
         })),
         ExpressionKind::Variable(_) => expr,
@@ -826,6 +827,9 @@ fn remove_interned_in_statement_kind(
         StatementKind::Semi(expr) => {
             StatementKind::Semi(remove_interned_in_expression(interner, expr))
         }
+        StatementKind::Return(expr) => {
+            StatementKind::Return(expr.map(|expr| remove_interned_in_expression(interner, expr)))
+        }
         StatementKind::Interned(id) => {
             let statement = interner.get_statement_kind(id).clone();
             remove_interned_in_statement_kind(interner, statement)
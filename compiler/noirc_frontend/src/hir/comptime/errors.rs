@@ -5,6 +5,7 @@ use crate::{
     Type,
     ast::TraitBound,
     hir::{
+        comptime::Value,
         def_collector::dc_crate::CompilationError,
         type_check::{NoMatchingImplFoundError, TypeCheckError},
     },
@@ -266,9 +267,10 @@ pub enum InterpreterError {
     },
 
     // These cases are not errors, they are just used to prevent us from running more code
-    // until the loop can be resumed properly. These cases will never be displayed to users.
+    // until the loop or function can be resumed properly. These cases will never be displayed to users.
     Break,
     Continue,
+    Return(Value),
 }
 
 #[allow(unused)]
@@ -345,8 +347,8 @@ impl InterpreterError {
 
             InterpreterError::FailedToParseMacro { error, .. } => error.location(),
             InterpreterError::NoMatchingImplFound { error } => error.location,
-            InterpreterError::Break | InterpreterError::Continue => {
-                panic!("Tried to get the location of Break/Continue error!")
+            InterpreterError::Break | InterpreterError::Continue | InterpreterError::Return(_) => {
+                panic!("Tried to get the location of Break/Continue/Return error!")
             }
         }
     }
@@ -633,6 +635,7 @@ impl<'a> From<&'a InterpreterError> for CustomDiagnostic {
             InterpreterError::NoMatchingImplFound { error, .. } => error.into(),
             InterpreterError::Break => unreachable!("Uncaught InterpreterError::Break"),
             InterpreterError::Continue => unreachable!("Uncaught InterpreterError::Continue"),
+            InterpreterError::Return(_) => unreachable!("Uncaught InterpreterError::Return"),
             InterpreterError::TraitDefinitionMustBeAPath { location } => {
                 let msg = "Trait definition arguments must be a variable or path".to_string();
                 CustomDiagnostic::simple_error(msg, String::new(), *location)
@@ -58,6 +58,9 @@ impl HirStatement {
             }),
             HirStatement::Break => StatementKind::Break,
             HirStatement::Continue => StatementKind::Continue,
+            HirStatement::Return(expr) => {
+                StatementKind::Return(expr.map(|expr| expr.to_display_ast(interner)))
+            }
             HirStatement::Expression(expr) => {
                 StatementKind::Expression(expr.to_display_ast(interner))
             }
@@ -240,7 +243,7 @@ impl HirMatch {
                     let constructor = case.constructor.to_display_ast(args);
                     let constructor = Expression::new(constructor, location);
                     let branch = case.body.to_display_ast(interner, location);
-                    (constructor, Expression::new(branch, location))
+                    (constructor, None, Expression::new(branch, location))
                 });
 
                 if let Some(case) = default {
@@ -248,7 +251,7 @@ impl HirMatch {
                         ExpressionKind::Variable(Path::from_single("_".to_string(), location));
                     let pattern = Expression::new(kind, location);
                     let branch = Expression::new(case.to_display_ast(interner, location), location);
-                    rules.push((pattern, branch));
+                    rules.push((pattern, None, branch));
                 }
 
                 ExpressionKind::Match(Box::new(MatchExpression { expression, rules }))
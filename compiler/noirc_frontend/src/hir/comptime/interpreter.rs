@@ -4,7 +4,7 @@ use std::{collections::hash_map::Entry, rc::Rc};
 use acvm::AcirField;
 use acvm::blackbox_solver::BigIntSolverWithId;
 use im::Vector;
-use iter_extended::try_vecmap;
+use iter_extended::{try_vecmap, vecmap};
 use noirc_errors::Location;
 use rustc_hash::FxHashMap as HashMap;
 
@@ -27,10 +27,10 @@ use crate::{
     Shared, Type, TypeBindings,
     hir_def::{
         expr::{
-            HirArrayLiteral, HirBlockExpression, HirCallExpression, HirCastExpression,
+            Constructor, HirArrayLiteral, HirBlockExpression, HirCallExpression, HirCastExpression,
             HirConstrainExpression, HirConstructorExpression, HirEnumConstructorExpression,
             HirExpression, HirIdent, HirIfExpression, HirIndexExpression, HirInfixExpression,
-            HirLambda, HirLiteral, HirMemberAccess, HirPrefixExpression, ImplKind,
+            HirLambda, HirLiteral, HirMatch, HirMemberAccess, HirPrefixExpression, ImplKind,
         },
         function::FunctionBody,
         stmt::{
@@ -187,7 +187,7 @@ impl<'local, 'interner> Interpreter<'local, 'interner> {
         };
         let result = self.evaluate(function_body);
         self.exit_function(previous_state);
-        result
+        catch_return(result)
     }
 
     /// Try to retrieve a function's body.
@@ -321,7 +321,7 @@ impl<'local, 'interner> Interpreter<'local, 'interner> {
         let result = self.evaluate(closure.body);
 
         self.exit_function(previous_state);
-        result
+        catch_return(result)
     }
 
     /// Enters a function, pushing a new scope and resetting any required state.
@@ -549,7 +549,7 @@ impl<'local, 'interner> Interpreter<'local, 'interner> {
             HirExpression::Constrain(constrain) => self.evaluate_constrain(constrain),
             HirExpression::Cast(cast) => self.evaluate_cast(&cast, id),
             HirExpression::If(if_) => self.evaluate_if(if_, id),
-            HirExpression::Match(match_) => todo!("Evaluate match in comptime code"),
+            HirExpression::Match(match_) => self.evaluate_match(match_, id),
             HirExpression::Tuple(tuple) => self.evaluate_tuple(tuple),
             HirExpression::Lambda(lambda) => self.evaluate_lambda(lambda, id),
             HirExpression::Quote(tokens) => self.evaluate_quote(tokens),
@@ -990,12 +990,26 @@ impl<'local, 'interner> Interpreter<'local, 'interner> {
 
     fn evaluate_call(&mut self, call: HirCallExpression, id: ExprId) -> IResult<Value> {
         let function = self.evaluate(call.func)?;
+        let field_lvalues = vecmap(&call.arguments, |arg| self.field_reference_lvalue(*arg));
         let arguments = try_vecmap(call.arguments, |arg| {
             Ok((self.evaluate(arg)?, self.elaborator.interner.expr_location(&arg)))
         })?;
         let location = self.elaborator.interner.expr_location(&id);
 
-        match function {
+        // Fields are stored by value, so a mutable reference to a field points to a copy of it
+        // which must be written back once the call returns.
+        let write_backs: Vec<_> = field_lvalues
+            .into_iter()
+            .zip(&arguments)
+            .filter_map(|(lvalue, (argument, _))| match (lvalue, argument) {
+                (Some(lvalue), Value::Pointer(element, false, true)) => {
+                    Some((lvalue, element.clone()))
+                }
+                _ => None,
+            })
+            .collect();
+
+        let result = match function {
             Value::Function(function_id, _, bindings) => {
                 let bindings = unwrap_rc(bindings);
                 let mut result = self.call_function(function_id, arguments, bindings, location)?;
@@ -1030,6 +1044,54 @@ impl<'local, 'interner> Interpreter<'local, 'interner> {
                 let typ = value.get_type().into_owned();
                 Err(InterpreterError::NonFunctionCalled { typ, location })
             }
+        }?;
+
+        for (lvalue, element) in write_backs {
+            let value = element.borrow().clone();
+            self.store_lvalue(lvalue, value)?;
+        }
+        Ok(result)
+    }
+
+    /// If `expr` is a mutable reference to a struct or tuple field, `&mut foo.bar.baz`,
+    /// returns the lvalue of that field.
+    fn field_reference_lvalue(&self, expr: ExprId) -> Option<HirLValue> {
+        let HirExpression::Prefix(prefix) = self.elaborator.interner.expression(&expr) else {
+            return None;
+        };
+        let is_field = matches!(
+            self.elaborator.interner.expression(&prefix.rhs),
+            HirExpression::MemberAccess(_)
+        );
+        if prefix.operator != (UnaryOp::Reference { mutable: true }) || !is_field {
+            return None;
+        }
+        self.field_lvalue(prefix.rhs)
+    }
+
+    fn field_lvalue(&self, expr: ExprId) -> Option<HirLValue> {
+        let interner = &self.elaborator.interner;
+        let typ = interner.id_type(expr);
+        let location = interner.expr_location(&expr);
+
+        match interner.expression(&expr) {
+            HirExpression::Ident(ident, _) => Some(HirLValue::Ident(ident, typ)),
+            HirExpression::MemberAccess(access) => {
+                let object = Box::new(self.field_lvalue(access.lhs)?);
+                let field_index = Some(interner.get_field_index(expr));
+                let field_name = access.rhs;
+                Some(HirLValue::MemberAccess { object, field_name, field_index, typ, location })
+            }
+            HirExpression::Prefix(HirPrefixExpression {
+                operator: UnaryOp::Dereference { implicitly_added },
+                rhs,
+                ..
+            }) => {
+                let lvalue = Box::new(self.field_lvalue(rhs)?);
+                let element_type = typ;
+                Some(HirLValue::Dereference { lvalue, element_type, implicitly_added, location })
+            }
+            _ => None,
         }
     }
 
@@ -1083,6 +1145,79 @@ impl<'local, 'interner> Interpreter<'local, 'interner> {
         result
     }
 
+    fn evaluate_match(&mut self, match_: HirMatch, id: ExprId) -> IResult<Value> {
+        let location = self.elaborator.interner.expr_location(&id);
+        match match_ {
+            HirMatch::Success(body) => self.evaluate(body),
+            HirMatch::Failure { .. } => {
+                let message = Some("match failure".to_string());
+                let call_stack = self.elaborator.interpreter_call_stack.clone();
+                Err(InterpreterError::FailingConstraint { location, message, call_stack })
+            }
+            HirMatch::Guard { cond, body, otherwise } => match self.evaluate(cond)? {
+                Value::Bool(true) => self.evaluate(body),
+                Value::Bool(false) => self.evaluate_match(*otherwise, id),
+                value => {
+                    let location = self.elaborator.interner.expr_location(&cond);
+                    let typ = value.get_type().into_owned();
+                    Err(InterpreterError::NonBoolUsedInIf { typ, location })
+                }
+            },
+            HirMatch::Switch(variable, cases, default) => {
+                let value = self.lookup_id(variable, location)?;
+                for case in cases {
+                    let Some(arguments) = self.match_constructor(&case.constructor, &value) else {
+                        continue;
+                    };
+
+                    self.push_scope();
+                    for (argument, value) in case.arguments.into_iter().zip(arguments) {
+                        self.define(argument, value);
+                    }
+                    let result = self.evaluate_match(case.body, id);
+                    self.pop_scope();
+                    return result;
+                }
+
+                match default {
+                    Some(default) => self.evaluate_match(*default, id),
+                    None => self.evaluate_match(HirMatch::Failure { missing_case: false }, id),
+                }
+            }
+        }
+    }
+
+    /// Returns the values the constructor's arguments are bound to if `value` was built
+    /// with the given constructor, or `None` if it was not.
+    fn match_constructor(&self, constructor: &Constructor, value: &Value) -> Option<Vec<Value>> {
+        let matches = match (constructor, value) {
+            (Constructor::True, Value::Bool(value)) => *value,
+            (Constructor::False, Value::Bool(value)) => !*value,
+            (Constructor::Unit, Value::Unit) => true,
+            (Constructor::Int(expected), value) => value.to_signed_field() == Some(*expected),
+            (Constructor::Range(start, end), value) => {
+                value.to_signed_field().is_some_and(|value| *start <= value && value < *end)
+            }
+            (Constructor::Tuple(_), Value::Tuple(fields)) => return Some(fields.clone()),
+            (Constructor::Variant(_, index), Value::Enum(tag, arguments, _)) => {
+                return (index == tag).then(|| arguments.clone());
+            }
+            // Structs are matched as types with a single variant
+            (Constructor::Variant(_, _), Value::Struct(fields, typ)) => {
+                let Type::DataType(def, generics) = typ.follow_bindings() else {
+                    return None;
+                };
+                let field_names = def.borrow().get_fields(&generics)?;
+                return field_names
+                    .into_iter()
+                    .map(|(name, _, _)| fields.get(&name).cloned())
+                    .collect();
+            }
+            _ => false,
+        };
+        matches.then(Vec::new)
+    }
+
     fn evaluate_tuple(&mut self, tuple: Vec<ExprId>) -> IResult<Value> {
         let fields = try_vecmap(tuple, |field| self.evaluate(field))?;
         Ok(Value::Tuple(fields))
@@ -1114,6 +1249,7 @@ impl<'local, 'interner> Interpreter<'local, 'interner> {
             HirStatement::While(condition, block) => self.evaluate_while(condition, block),
             HirStatement::Break => self.evaluate_break(statement),
             HirStatement::Continue => self.evaluate_continue(statement),
+            HirStatement::Return(expression) => self.evaluate_return(expression),
             HirStatement::Expression(expression) => self.evaluate(expression),
             HirStatement::Comptime(statement) => self.evaluate_comptime(statement),
             HirStatement::Semi(expression) => {
@@ -1430,6 +1566,14 @@ impl<'local, 'interner> Interpreter<'local, 'interner> {
         }
     }
 
+    fn evaluate_return(&mut self, expression: Option<ExprId>) -> IResult<Value> {
+        let value = match expression {
+            Some(expression) => self.evaluate(expression)?,
+            None => Value::Unit,
+        };
+        Err(InterpreterError::Return(value))
+    }
+
     pub(super) fn evaluate_comptime(&mut self, statement: StmtId) -> IResult<Value> {
         self.evaluate_statement(statement)
     }
@@ -1561,6 +1705,14 @@ fn evaluate_integer(typ: Type, value: SignedField, location: Location) -> IResul
 
 /// Bounds check the given array and index pair.
 /// This will also ensure the given arguments are in fact an array and integer.
+/// Turn a `return` out of a function or closure body into the value of the call.
+fn catch_return(result: IResult<Value>) -> IResult<Value> {
+    match result {
+        Err(InterpreterError::Return(value)) => Ok(value),
+        result => result,
+    }
+}
+
 fn bounds_check(array: Value, index: Value, location: Location) -> IResult<(Vector<Value>, usize)> {
     let collection = match array {
         Value::Array(array, _) => array,
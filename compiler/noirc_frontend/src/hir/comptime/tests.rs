@@ -169,6 +169,61 @@ fn mutating_arrays() {
     assert_eq!(result, Value::U8(22));
 }
 
+#[test]
+fn mutating_array_elements_through_references() {
+    let program = "comptime fn main() -> pub u8 {
+        let mut a1 = [1, 2, 3, 4];
+        increment(&mut a1[1]);
+        a1[1]
+    }
+
+    comptime fn increment(x: &mut u8) {
+        *x += 20;
+    }";
+    let result = interpret(program);
+    assert_eq!(result, Value::U8(22));
+}
+
+#[test]
+fn evaluates_arguments_before_array_element_references() {
+    let program = "comptime fn main() -> pub u8 {
+        let mut counter = 0;
+        let mut a1 = [0, 0];
+        set(next(&mut counter), &mut a1[next(&mut counter)]);
+        a1[1]
+    }
+
+    comptime fn next(counter: &mut u32) -> u32 {
+        *counter += 1;
+        *counter - 1
+    }
+
+    comptime fn set(value: u32, x: &mut u8) {
+        *x = value as u8 + 20;
+    }";
+    let result = interpret(program);
+    assert_eq!(result, Value::U8(20));
+}
+
+#[test]
+fn mutating_struct_fields_through_references() {
+    let program = "struct Foo { bar: Bar }
+
+    struct Bar { x: u8 }
+
+    comptime fn main() -> pub u8 {
+        let mut foo = Foo { bar: Bar { x: 2 } };
+        increment(&mut foo.bar.x);
+        foo.bar.x
+    }
+
+    comptime fn increment(x: &mut u8) {
+        *x += 20;
+    }";
+    let result = interpret(program);
+    assert_eq!(result, Value::U8(22));
+}
+
 #[test]
 fn mutate_in_new_scope() {
     let program = "comptime fn main() -> pub u8 {
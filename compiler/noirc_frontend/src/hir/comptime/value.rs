@@ -680,6 +680,25 @@ impl Value {
         }
     }
 
+    /// Converts any integral `Value` into a `SignedField`.
+    /// Returns `None` for non-integral `Value`s.
+    pub(crate) fn to_signed_field(&self) -> Option<SignedField> {
+        match self {
+            Self::Field(value) => Some(*value),
+            Self::I8(value) => Some(SignedField::from_signed(*value)),
+            Self::I16(value) => Some(SignedField::from_signed(*value)),
+            Self::I32(value) => Some(SignedField::from_signed(*value)),
+            Self::I64(value) => Some(SignedField::from_signed(*value)),
+            Self::U1(value) => Some(SignedField::positive(*value)),
+            Self::U8(value) => Some(SignedField::positive(*value as u128)),
+            Self::U16(value) => Some(SignedField::positive(*value as u128)),
+            Self::U32(value) => Some(SignedField::positive(*value)),
+            Self::U64(value) => Some(SignedField::positive(*value)),
+            Self::U128(value) => Some(SignedField::positive(*value)),
+            _ => None,
+        }
+    }
+
     pub(crate) fn into_top_level_items(
         self,
         location: Location,
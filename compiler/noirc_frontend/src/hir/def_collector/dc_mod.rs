@@ -1074,7 +1074,7 @@ pub fn collect_function(
 
     interner.set_doc_comments(ReferenceId::Function(func_id), doc_comments);
 
-    if let Some((test_scope, location)) = test_attribute {
+    if let Some((test_scope, _, location)) = test_attribute {
         if function.def.parameters.is_empty()
             && matches!(test_scope, TestScope::OnlyFailWith { .. })
         {
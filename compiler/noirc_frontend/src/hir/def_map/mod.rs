@@ -5,7 +5,7 @@ use crate::hir::def_collector::dc_crate::{CompilationError, DefCollector};
 use crate::node_interner::{FuncId, NodeInterner};
 use crate::parse_program;
 use crate::parser::{ParsedModule, ParserError};
-use crate::token::{FunctionAttributeKind, FuzzingScope, TestScope};
+use crate::token::{FunctionAttributeKind, FuzzingScope, TestBudget, TestScope};
 use fm::{FileId, FileManager};
 use noirc_arena::{Arena, Index};
 use noirc_errors::Location;
@@ -209,10 +209,17 @@ impl CrateDefMap {
                     let has_arguments = !interner.function_meta(&func_id).parameters.is_empty();
                     let attributes = interner.function_attributes(&func_id);
                     match attributes.function().map(|attr| &attr.kind) {
-                        Some(FunctionAttributeKind::Test(scope)) => {
+                        Some(FunctionAttributeKind::Test(scope, budget)) => {
                             let location = interner.function_meta(&func_id).name.location;
                             let scope = scope.clone();
-                            Some(TestFunction { id: func_id, scope, location, has_arguments })
+                            let budget = *budget;
+                            Some(TestFunction {
+                                id: func_id,
+                                scope,
+                                budget,
+                                location,
+                                has_arguments,
+                            })
                         }
                         _ => None,
                     }
@@ -381,6 +388,7 @@ pub fn parse_file(fm: &FileManager, file_id: FileId) -> (ParsedModule, Vec<Parse
 pub struct TestFunction {
     pub id: FuncId,
     pub scope: TestScope,
+    pub budget: TestBudget,
     pub location: Location,
     pub has_arguments: bool,
 }
@@ -65,6 +65,21 @@ pub enum FunctionNameMatch {
     Contains(Vec<String>),
 }
 
+impl FunctionNameMatch {
+    /// Whether the fully qualified name of a function matches this pattern.
+    pub fn matches(&self, fully_qualified_name: &str) -> bool {
+        match self {
+            FunctionNameMatch::Anything => true,
+            FunctionNameMatch::Exact(patterns) => {
+                patterns.iter().any(|pattern| fully_qualified_name == pattern)
+            }
+            FunctionNameMatch::Contains(patterns) => {
+                patterns.iter().any(|pattern| fully_qualified_name.contains(pattern))
+            }
+        }
+    }
+}
+
 impl Context<'_, '_> {
     pub fn new(file_manager: FileManager, parsed_files: ParsedFiles) -> Context<'static, 'static> {
         Context {
@@ -187,17 +202,9 @@ impl Context<'_, '_> {
             .filter_map(|test_function| {
                 let fully_qualified_name =
                     self.fully_qualified_function_name(crate_id, &test_function.id);
-                match &pattern {
-                    FunctionNameMatch::Anything => Some((fully_qualified_name, test_function)),
-                    FunctionNameMatch::Exact(patterns) => patterns
-                        .iter()
-                        .any(|pattern| &fully_qualified_name == pattern)
-                        .then_some((fully_qualified_name, test_function)),
-                    FunctionNameMatch::Contains(patterns) => patterns
-                        .iter()
-                        .any(|pattern| fully_qualified_name.contains(pattern))
-                        .then_some((fully_qualified_name, test_function)),
-                }
+                pattern
+                    .matches(&fully_qualified_name)
+                    .then_some((fully_qualified_name, test_function))
             })
             .collect()
     }
@@ -62,8 +62,6 @@ pub enum ResolverError {
     ParserError(Box<ParserError>),
     #[error("Closure environment must be a tuple or unit type")]
     InvalidClosureEnvironment { typ: Type, location: Location },
-    #[error("Nested slices, i.e. slices within an array or slice, are not supported")]
-    NestedSlices { location: Location },
     #[error("#[abi(tag)] attribute is only allowed in contracts")]
     AbiAttributeOutsideContract { location: Location },
     #[error(
@@ -74,10 +72,10 @@ pub enum ResolverError {
     OracleMarkedAsConstrained { ident: Ident, location: Location },
     #[error("Oracle functions cannot be called directly from constrained functions")]
     UnconstrainedOracleReturnToConstrained { location: Location },
+    #[error("Oracle functions cannot return nested slices")]
+    OracleReturnsNestedSlice { location: Location },
     #[error("Dependency cycle found, '{item}' recursively depends on itself: {cycle} ")]
     DependencyCycle { location: Location, item: String, cycle: String },
-    #[error("break/continue are only allowed in unconstrained functions")]
-    JumpInConstrainedFn { is_break: bool, location: Location },
     #[error("`loop` is only allowed in unconstrained functions")]
     LoopInConstrainedFn { location: Location },
     #[error("`loop` must have at least one `break` in it")]
@@ -86,6 +84,8 @@ pub enum ResolverError {
     WhileInConstrainedFn { location: Location },
     #[error("break/continue are only allowed within loops")]
     JumpOutsideLoop { is_break: bool, location: Location },
+    #[error("`return` is only allowed within functions")]
+    ReturnOutsideFunction { location: Location },
     #[error("Only `comptime` globals can be mutable")]
     MutableGlobal { location: Location },
     #[error("Globals must have a specified type")]
@@ -202,15 +202,15 @@ impl ResolverError {
             | ResolverError::GenericsOnSelfType { location }
             | ResolverError::GenericsOnAssociatedType { location }
             | ResolverError::InvalidClosureEnvironment { location, .. }
-            | ResolverError::NestedSlices { location }
             | ResolverError::AbiAttributeOutsideContract { location }
             | ResolverError::UnconstrainedOracleReturnToConstrained { location }
+            | ResolverError::OracleReturnsNestedSlice { location }
             | ResolverError::DependencyCycle { location, .. }
-            | ResolverError::JumpInConstrainedFn { location, .. }
             | ResolverError::LoopInConstrainedFn { location }
             | ResolverError::LoopWithoutBreak { location }
             | ResolverError::WhileInConstrainedFn { location }
             | ResolverError::JumpOutsideLoop { location, .. }
+            | ResolverError::ReturnOutsideFunction { location }
             | ResolverError::MutableGlobal { location }
             | ResolverError::UnspecifiedGlobalType { pattern_location: location, .. }
             | ResolverError::UnevaluatedGlobalType { location }
@@ -425,11 +425,6 @@ impl<'a> From<&'a ResolverError> for Diagnostic {
             ResolverError::InvalidClosureEnvironment { location, typ } => Diagnostic::simple_error(
                 format!("{typ} is not a valid closure environment type"),
                 "Closure environment must be a tuple or unit type".to_string(), *location),
-            ResolverError::NestedSlices { location } => Diagnostic::simple_error(
-                "Nested slices, i.e. slices within an array or slice, are not supported".into(),
-                "Try to use a constant sized array or BoundedVec instead".into(),
-                *location,
-            ),
             ResolverError::AbiAttributeOutsideContract { location } => {
                 Diagnostic::simple_error(
                     "#[abi(tag)] attributes can only be used in contracts".to_string(),
@@ -456,6 +451,11 @@ impl<'a> From<&'a ResolverError> for Diagnostic {
                 "This oracle call must be wrapped in a call to another unconstrained function before being returned to a constrained runtime".into(),
                 *location,
             ),
+            ResolverError::OracleReturnsNestedSlice { location } => Diagnostic::simple_error(
+                error.to_string(),
+                "Foreign calls can only return slices of numeric values, arrays or structs without slices".into(),
+                *location,
+            ),
             ResolverError::DependencyCycle { location, item, cycle } => {
                 Diagnostic::simple_error(
                     "Dependency cycle found".into(),
@@ -463,14 +463,6 @@ impl<'a> From<&'a ResolverError> for Diagnostic {
                     *location,
                 )
             },
-            ResolverError::JumpInConstrainedFn { is_break, location } => {
-                let item = if *is_break { "break" } else { "continue" };
-                Diagnostic::simple_error(
-                    format!("{item} is only allowed in unconstrained functions"),
-                    "Constrained code must always have a known number of loop iterations".into(),
-                    *location,
-                )
-            },
             ResolverError::LoopInConstrainedFn { location } => {
                 Diagnostic::simple_error(
                     "`loop` is only allowed in unconstrained functions".into(),
@@ -500,6 +492,13 @@ impl<'a> From<&'a ResolverError> for Diagnostic {
                     *location,
                 )
             },
+            ResolverError::ReturnOutsideFunction { location } => {
+                Diagnostic::simple_error(
+                    "`return` is only allowed within functions".into(),
+                    "A `comptime` block or global can't return from the enclosing function".into(),
+                    *location,
+                )
+            },
             ResolverError::MutableGlobal { location } => {
                 Diagnostic::simple_error(
                     "Only `comptime` globals may be mutable".into(),
@@ -103,8 +103,16 @@ pub enum TypeCheckError {
     CannotMutateImmutableVariable { name: String, location: Location },
     #[error("Variable {name} captured in lambda must be a mutable reference")]
     MutableCaptureWithoutRef { name: String, location: Location },
-    #[error("Mutable references to array indices are unsupported")]
+    #[error("Mutable references to array elements are only supported as function call arguments")]
     MutableReferenceToArrayElement { location: Location },
+    #[error(
+        "Mutable references to array elements cannot be passed to a function returning a reference"
+    )]
+    MutableReferenceToArrayElementReturned { location: Location },
+    #[error(
+        "Mutable references to array elements cannot be passed to a call which may reach the same array through another reference"
+    )]
+    AliasedMutableReferenceToArrayElement { location: Location },
     #[error("No method named '{method_name}' found for type '{object_type}'")]
     UnresolvedMethodCall { method_name: String, object_type: Type, location: Location },
     #[error("Cannot invoke function field '{method_name}' on type '{object_type}' as a method")]
@@ -293,6 +301,8 @@ impl TypeCheckError {
             | TypeCheckError::CannotMutateImmutableVariable { location, .. }
             | TypeCheckError::MutableCaptureWithoutRef { location, .. }
             | TypeCheckError::MutableReferenceToArrayElement { location }
+            | TypeCheckError::MutableReferenceToArrayElementReturned { location }
+            | TypeCheckError::AliasedMutableReferenceToArrayElement { location }
             | TypeCheckError::UnresolvedMethodCall { location, .. }
             | TypeCheckError::CannotInvokeStructFieldFunctionType { location, .. }
             | TypeCheckError::IntegerSignedness { location, .. }
@@ -542,7 +552,13 @@ impl<'a> From<&'a TypeCheckError> for Diagnostic {
                 *location,
             ),
             TypeCheckError::MutableReferenceToArrayElement { location } => {
-                Diagnostic::simple_error("Mutable references to array elements are currently unsupported".into(), "Try storing the element in a fresh variable first".into(), *location)
+                Diagnostic::simple_error("Mutable references to array elements are only supported as function call arguments".into(), "Try copying the element into a mutable variable and assigning it back to the array".into(), *location)
+            },
+            TypeCheckError::MutableReferenceToArrayElementReturned { location } => {
+                Diagnostic::simple_error("Mutable references to array elements cannot be passed to a function returning a reference".into(), "The reference points to a copy of the element, written back to the array once the call returns".into(), *location)
+            },
+            TypeCheckError::AliasedMutableReferenceToArrayElement { location } => {
+                Diagnostic::simple_error("Mutable references to array elements cannot be passed to a call which may reach the same array through another reference".into(), "The reference points to a copy of the element, written back to the array once the call returns".into(), *location)
             },
             TypeCheckError::TypeAnnotationsNeededForMethodCall { location } => {
                 let mut error = Diagnostic::simple_error(
@@ -18,6 +18,7 @@ pub enum HirStatement {
     While(ExprId, ExprId),
     Break,
     Continue,
+    Return(Option<ExprId>),
     Expression(ExprId),
     Semi(ExprId),
     Comptime(StmtId),
@@ -1641,18 +1641,19 @@ impl Type {
         }
     }
 
+    /// True if this is a slice or an array containing slices, e.g. `[[Field]]` or `[[u8]; 2]`.
     pub(crate) fn is_nested_slice(&self) -> bool {
-        match self {
+        match self.follow_bindings_shallow().as_ref() {
             Type::Slice(elem) => elem.as_ref().contains_slice(),
             Type::Array(_, elem) => elem.as_ref().contains_slice(),
-            Type::Alias(alias, generics) => alias.borrow().get_type(generics).is_nested_slice(),
             _ => false,
         }
     }
 
     pub(crate) fn contains_slice(&self) -> bool {
-        match self {
+        match self.follow_bindings_shallow().as_ref() {
             Type::Slice(_) => true,
+            Type::Array(_, elem) => elem.contains_slice(),
             Type::DataType(typ, generics) => {
                 let typ = typ.borrow();
                 if let Some(fields) = typ.get_fields(generics) {
@@ -125,7 +125,7 @@ impl LexerErrorKind {
             ),
             LexerErrorKind::MalformedTestAttribute { location } => (
                 "Malformed test attribute".to_string(),
-                "The test attribute can be written in one of these forms: `#[test]`, `#[test(should_fail)]` or `#[test(should_fail_with = \"message\")]`".to_string(),
+                "The test attribute can be written in one of these forms: `#[test]`, `#[test(should_fail)]` or `#[test(should_fail_with = \"message\")]`, optionally followed by `timeout = <seconds>` and `max_steps = <steps>` arguments".to_string(),
                 *location,
             ),
             LexerErrorKind::MalformedFuzzAttribute { location } => (
@@ -1,5 +1,6 @@
 use acvm::FieldElement;
 use noirc_errors::{Located, Location, Position, Span, Spanned};
+use serde::{Deserialize, Serialize};
 use std::fmt::{self, Display};
 
 use crate::{
@@ -781,7 +782,7 @@ impl IntType {
 }
 
 /// TestScope is used to specify additional annotations for test functions
-#[derive(PartialEq, Eq, Hash, Debug, Clone, PartialOrd, Ord)]
+#[derive(PartialEq, Eq, Hash, Debug, Clone, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum TestScope {
     /// If a test has a scope of ShouldFailWith, then it can only pass
     /// if it fails with the specified reason. If the reason is None, then
@@ -809,6 +810,19 @@ impl fmt::Display for TestScope {
     }
 }
 
+/// TestBudget limits the resources a test function can use before it's reported as failed.
+/// It's specified with `timeout = <seconds>` and `max_steps = <steps>` arguments to the
+/// test attribute, which can be combined with each other and with a [TestScope].
+#[derive(
+    PartialEq, Eq, Hash, Debug, Clone, Copy, Default, PartialOrd, Ord, Serialize, Deserialize,
+)]
+pub struct TestBudget {
+    /// Maximum wall-clock time the test can run for, in seconds
+    pub timeout: Option<u64>,
+    /// Maximum number of ACIR opcodes and Brillig instructions the test can execute
+    pub max_steps: Option<u64>,
+}
+
 /// FuzzingScope is used to specify additional annotations for fuzzing harnesses
 #[derive(PartialEq, Eq, Hash, Debug, Clone, PartialOrd, Ord)]
 pub enum FuzzingScope {
@@ -876,10 +890,10 @@ impl Attributes {
         self.as_test_function().is_some()
     }
 
-    pub fn as_test_function(&self) -> Option<(&TestScope, Location)> {
+    pub fn as_test_function(&self) -> Option<(&TestScope, &TestBudget, Location)> {
         self.function().and_then(|attr| {
-            if let FunctionAttributeKind::Test(scope) = &attr.kind {
-                Some((scope, attr.location))
+            if let FunctionAttributeKind::Test(scope, budget) = &attr.kind {
+                Some((scope, budget, attr.location))
             } else {
                 None
             }
@@ -990,7 +1004,7 @@ pub enum FunctionAttributeKind {
     Foreign(String),
     Builtin(String),
     Oracle(String),
-    Test(TestScope),
+    Test(TestScope, TestBudget),
     Fold,
     NoPredicates,
     InlineAlways,
@@ -1068,7 +1082,31 @@ impl fmt::Display for FunctionAttribute {
 impl fmt::Display for FunctionAttributeKind {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            FunctionAttributeKind::Test(scope) => write!(f, "#[test{scope}]"),
+            FunctionAttributeKind::Test(scope, budget) => {
+                if *budget == TestBudget::default() {
+                    return write!(f, "#[test{scope}]");
+                }
+                let mut arguments = Vec::new();
+                match scope {
+                    TestScope::None => (),
+                    TestScope::ShouldFailWith { reason: None } => {
+                        arguments.push("should_fail".to_string());
+                    }
+                    TestScope::ShouldFailWith { reason: Some(reason) } => {
+                        arguments.push(format!("should_fail_with = {reason:?}"));
+                    }
+                    TestScope::OnlyFailWith { reason } => {
+                        arguments.push(format!("only_fail_with = {reason:?}"));
+                    }
+                }
+                if let Some(timeout) = budget.timeout {
+                    arguments.push(format!("timeout = {timeout}"));
+                }
+                if let Some(max_steps) = budget.max_steps {
+                    arguments.push(format!("max_steps = {max_steps}"));
+                }
+                write!(f, "#[test({})]", arguments.join(", "))
+            }
             FunctionAttributeKind::Foreign(k) => write!(f, "#[foreign({k})]"),
             FunctionAttributeKind::Builtin(k) => write!(f, "#[builtin({k})]"),
             FunctionAttributeKind::Oracle(k) => write!(f, "#[oracle({k})]"),
@@ -310,6 +310,110 @@ impl NodeInterner {
         }
     }
 
+    // Returns the location of the body of the given function, if it has one and it was elaborated.
+    fn function_body_location(&self, func_id: FuncId) -> Option<Location> {
+        let body = self.function(&func_id).try_as_expr()?;
+        Some(self.expr_location(&body))
+    }
+
+    // Returns the functions whose body references `func_id`, together with the locations
+    // of those references, sorted by the location of the caller.
+    // References are used instead of the dependency graph because method calls don't register
+    // a dependency between functions.
+    pub fn find_function_callers(&self, func_id: FuncId) -> Vec<(FuncId, Vec<Location>)> {
+        let Some(referenced_index) =
+            self.reference_graph_indices.get(&ReferenceId::Function(func_id))
+        else {
+            return Vec::new();
+        };
+
+        let references = self.find_all_references_for_index(*referenced_index, false, true);
+        let bodies: Vec<(FuncId, Location)> = self
+            .func_meta
+            .keys()
+            .filter_map(|func_id| Some((*func_id, self.function_body_location(*func_id)?)))
+            .collect();
+
+        let mut callers: Vec<(FuncId, Vec<Location>)> = Vec::new();
+        for reference in references {
+            // Pick the innermost function containing the reference (there might be several
+            // if, for example, the reference is inside a function nested in a comptime block).
+            let caller = bodies
+                .iter()
+                .filter(|(_, body)| body.contains(&reference))
+                .min_by_key(|(_, body)| body.span.end() - body.span.start());
+            let Some((caller, _)) = caller else {
+                continue;
+            };
+
+            match callers.iter_mut().find(|(func_id, _)| func_id == caller) {
+                Some((_, locations)) => locations.push(reference),
+                None => callers.push((*caller, vec![reference])),
+            }
+        }
+
+        callers.sort_by_key(|(func_id, _)| {
+            let location = self.function_meta(func_id).name.location;
+            (location.file, location.span.start())
+        });
+        for (_, locations) in &mut callers {
+            locations.sort_by_key(|location| location.span.start());
+        }
+        callers
+    }
+
+    // Returns the functions referenced from the body of `func_id`, together with the locations
+    // of those references, in the order they are first referenced.
+    pub fn find_function_callees(&self, func_id: FuncId) -> Vec<(FuncId, Vec<Location>)> {
+        let Some(body) = self.function_body_location(func_id) else {
+            return Vec::new();
+        };
+
+        let mut references: Vec<(Location, FuncId)> = self
+            .reference_graph
+            .node_indices()
+            .filter_map(|node_index| {
+                let ReferenceId::Reference(location, _) = self.reference_graph[node_index] else {
+                    return None;
+                };
+                if !body.contains(&location) {
+                    return None;
+                }
+
+                let referenced_index = self.referenced_index(node_index)?;
+                match self.reference_graph[referenced_index] {
+                    ReferenceId::Function(callee) => Some((location, callee)),
+                    _ => None,
+                }
+            })
+            .collect();
+        references.sort_by_key(|(location, _)| location.span.start());
+
+        let mut callees: Vec<(FuncId, Vec<Location>)> = Vec::new();
+        for (location, callee) in references {
+            match callees.iter_mut().find(|(func_id, _)| *func_id == callee) {
+                Some((_, locations)) => locations.push(location),
+                None => callees.push((callee, vec![location])),
+            }
+        }
+        callees
+    }
+
+    // Returns the traits which have `trait_id` as one of their parent traits.
+    pub fn find_child_traits(&self, trait_id: TraitId) -> Vec<TraitId> {
+        let mut children: Vec<TraitId> = self
+            .traits
+            .values()
+            .filter(|trait_| trait_.trait_bounds.iter().any(|bound| bound.trait_id == trait_id))
+            .map(|trait_| trait_.id)
+            .collect();
+        children.sort_by_key(|id| {
+            let location = self.get_trait(*id).location;
+            (location.file, location.span.start())
+        });
+        children
+    }
+
     // Given a referenced node index, find all references to it and return their locations, optionally together
     // with the reference node's location if `include_referenced` is true.
     // If `include_self_type_name` is true, references where "Self" is written are returned,
@@ -196,6 +196,69 @@ impl Expression {
             | Expression::Continue => false,
         }
     }
+
+    /// Check if the expression contains a `break` or `continue` jumping out of it,
+    /// which excludes those in the body of a loop nested in the expression.
+    pub fn contains_break_or_continue(&self) -> bool {
+        match self {
+            Expression::Break | Expression::Continue => true,
+            Expression::Ident(_) => false,
+            Expression::Literal(literal) => match literal {
+                Literal::Array(array) | Literal::Slice(array) => {
+                    array.contents.iter().any(Self::contains_break_or_continue)
+                }
+                Literal::FmtStr(_, _, captures) => captures.contains_break_or_continue(),
+                Literal::Integer(..) | Literal::Bool(_) | Literal::Unit | Literal::Str(_) => false,
+            },
+            Expression::Block(expressions) | Expression::Tuple(expressions) => {
+                expressions.iter().any(Self::contains_break_or_continue)
+            }
+            Expression::Unary(unary) => unary.rhs.contains_break_or_continue(),
+            Expression::Binary(binary) => {
+                binary.lhs.contains_break_or_continue() || binary.rhs.contains_break_or_continue()
+            }
+            Expression::Index(index) => {
+                index.collection.contains_break_or_continue()
+                    || index.index.contains_break_or_continue()
+            }
+            Expression::Cast(cast) => cast.lhs.contains_break_or_continue(),
+            // Only the parts of a loop evaluated before entering it can jump out of it
+            Expression::For(for_) => {
+                for_.start_range.contains_break_or_continue()
+                    || for_.end_range.contains_break_or_continue()
+            }
+            Expression::While(while_) => while_.condition.contains_break_or_continue(),
+            Expression::Loop(_) => false,
+            Expression::If(if_) => {
+                if_.condition.contains_break_or_continue()
+                    || if_.consequence.contains_break_or_continue()
+                    || if_.alternative.as_ref().is_some_and(|x| x.contains_break_or_continue())
+            }
+            Expression::Match(match_) => {
+                match_.cases.iter().any(|case| case.branch.contains_break_or_continue())
+                    || match_.default_case.as_ref().is_some_and(|x| x.contains_break_or_continue())
+            }
+            Expression::Call(call) => {
+                call.func.contains_break_or_continue()
+                    || call.arguments.iter().any(Self::contains_break_or_continue)
+            }
+            Expression::Let(let_) => let_.expression.contains_break_or_continue(),
+            Expression::Constrain(condition, _, message) => {
+                condition.contains_break_or_continue()
+                    || message
+                        .as_ref()
+                        .is_some_and(|message| message.0.contains_break_or_continue())
+            }
+            Expression::Assign(assign) => {
+                assign.lvalue.contains_break_or_continue()
+                    || assign.expression.contains_break_or_continue()
+            }
+            Expression::ExtractTupleField(x, _)
+            | Expression::Semi(x)
+            | Expression::Clone(x)
+            | Expression::Drop(x) => x.contains_break_or_continue(),
+        }
+    }
 }
 
 /// A definition is either a local (variable), function, or is a built-in
@@ -389,6 +452,20 @@ pub enum LValue {
     Dereference { reference: Box<LValue>, element_type: Type },
 }
 
+impl LValue {
+    /// Check if an index in the lvalue contains a `break` or `continue` jumping out of it.
+    pub fn contains_break_or_continue(&self) -> bool {
+        match self {
+            LValue::Ident(_) => false,
+            LValue::Index { array, index, .. } => {
+                array.contains_break_or_continue() || index.contains_break_or_continue()
+            }
+            LValue::MemberAccess { object, .. } => object.contains_break_or_continue(),
+            LValue::Dereference { reference, .. } => reference.contains_break_or_continue(),
+        }
+    }
+}
+
 pub type Parameters =
     Vec<(LocalId, /*mutable:*/ bool, /*name:*/ String, Type, Visibility)>;
 
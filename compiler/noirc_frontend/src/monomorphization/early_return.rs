@@ -0,0 +1,424 @@
+//! Lowering of early `return` statements.
+//!
+//! The monomorphized AST has no way to leave a function early, so a `return` is lowered
+//! into setting a "has returned" flag along with the returned value:
+//!
+//! ```text
+//! fn foo(x: u32) -> u32 {            fn foo(x: u32) -> u32 {
+//!     if x == 0 {                        let mut returned = false;
+//!         return 1;                      let mut return_value = 0;
+//!     }                                  let result = {
+//!     assert(x != 1);          =>            if x == 0 { return_value = 1; returned = true; };
+//!     x                                      if !returned { assert(x != 1); x } else { 0 }
+//! }                                      };
+//!                                        if returned { return_value } else { result }
+//!                                    }
+//! ```
+//!
+//! The statements following one containing a `return` only execute while the flag is unset,
+//! so in constrained code their side effects are guarded by the flag like those of any other
+//! `if` branch. Loops containing a `return` skip their remaining iterations once the flag is set.
+//!
+//! A `return` in tail position doesn't need the flag: a function body ending in `return value;`
+//! evaluates to `value`, and a guard clause followed by the rest of the body is turned into
+//! the equivalent nested `if`:
+//!
+//! ```text
+//! fn foo(x: u32) -> u32 {            fn foo(x: u32) -> u32 {
+//!     if x == 0 {                        if x == 0 {
+//!         return 1;                          1
+//!     }                        =>        } else {
+//!     assert(x != 1);                        assert(x != 1);
+//!     x                                      x
+//! }                                      }
+//!                                    }
+//! ```
+//!
+//! Functions without any `return` are left unchanged.
+use noirc_errors::Location;
+
+use crate::ast::UnaryOp;
+use crate::hir_def::expr::HirExpression;
+use crate::hir_def::stmt::HirStatement;
+use crate::node_interner::{ExprId, StmtId};
+use crate::{Type, TypeBinding};
+
+use super::ast::{self, Definition, LocalId};
+use super::{MonomorphizationError, Monomorphizer};
+
+/// The function or lambda being monomorphized, which `return` statements return from.
+pub(super) struct ReturnContext {
+    return_type: ast::Type,
+    location: Location,
+    /// The "has returned" flag, created when the first `return` is lowered.
+    returned: Option<LocalId>,
+    /// The returned value, created along with the flag unless the function returns unit.
+    value: Option<LocalId>,
+    /// The number of `return` statements lowered so far.
+    returns: usize,
+}
+
+impl Monomorphizer<'_> {
+    /// Start monomorphizing the body of a function or lambda.
+    pub(super) fn push_return_context(&mut self, return_type: ast::Type, location: Location) {
+        let context =
+            ReturnContext { return_type, location, returned: None, value: None, returns: 0 };
+        self.return_contexts.push(context);
+    }
+
+    /// Finish monomorphizing the body of a function or lambda, declaring the variables
+    /// used by any `return` in it and picking the returned value.
+    pub(super) fn pop_return_context(&mut self, body: ast::Expression) -> ast::Expression {
+        let context = self.return_contexts.pop().expect("ICE: expected a return context");
+        let Some(returned) = context.returned else {
+            return body;
+        };
+
+        let false_literal = ast::Expression::Literal(ast::Literal::Bool(false));
+        let mut statements = vec![let_local(returned, true, "returned", false_literal)];
+
+        let Some(value) = context.value else {
+            statements.push(body);
+            return ast::Expression::Block(statements);
+        };
+
+        let zeroed = self.zeroed_value_of_type(&context.return_type, context.location);
+        statements.push(let_local(value, true, "return_value", zeroed));
+
+        let result = self.next_local_id();
+        statements.push(let_local(result, false, "result", body));
+
+        let typ = context.return_type;
+        statements.push(ast::Expression::If(ast::If {
+            condition: Box::new(self.bool_local(returned, "returned")),
+            consequence: Box::new(self.local(value, "return_value", typ.clone())),
+            alternative: Some(Box::new(self.local(result, "result", typ.clone()))),
+            typ,
+        }));
+        ast::Expression::Block(statements)
+    }
+
+    /// Monomorphize the body of the current function or lambda, lowering the `return`s
+    /// in tail position without the "has returned" flag.
+    pub(super) fn function_body(
+        &mut self,
+        body: ExprId,
+    ) -> Result<ast::Expression, MonomorphizationError> {
+        let HirExpression::Block(block) = self.interner.expression(&body) else {
+            return self.expr(body);
+        };
+
+        // Constrained code can't pick a reference with an `if`
+        let typ = self.interner.id_type(body);
+        if !self.in_unconstrained_function && Self::contains_reference(&typ) {
+            return self.expr(body);
+        }
+
+        self.tail_block(block.statements)
+    }
+
+    /// Monomorphize the statements of a block whose value is returned by the function.
+    fn tail_block(
+        &mut self,
+        statement_ids: Vec<StmtId>,
+    ) -> Result<ast::Expression, MonomorphizationError> {
+        let mut statements = Vec::with_capacity(statement_ids.len());
+
+        for (i, statement_id) in statement_ids.iter().enumerate() {
+            let rest = &statement_ids[i + 1..];
+            match self.interner.statement(statement_id) {
+                HirStatement::Return(expr) => {
+                    // Any following statement is unreachable
+                    if let Some(expr) = expr {
+                        statements.push(self.expr(expr)?);
+                    }
+                    break;
+                }
+                HirStatement::Expression(expr) | HirStatement::Semi(expr) => {
+                    if let Some((condition, consequence)) = self.guard_clause(expr) {
+                        let condition = self.expr(condition)?;
+                        let consequence = self.tail_block(consequence)?;
+                        let alternative = self.tail_block(rest.to_vec())?;
+                        let (typ, _) = self.return_type();
+                        statements.push(ast::Expression::If(ast::If {
+                            condition: Box::new(condition),
+                            consequence: Box::new(consequence),
+                            alternative: Some(Box::new(alternative)),
+                            typ,
+                        }));
+                        break;
+                    }
+                }
+                _ => (),
+            }
+
+            let returns_before = self.returns_lowered();
+            statements.push(self.statement(*statement_id)?);
+
+            if self.returns_lowered() != returns_before && !rest.is_empty() {
+                let rest = self.tail_block(rest.to_vec())?;
+                let (typ, location) = self.return_type();
+                let rest = if typ == ast::Type::Unit {
+                    self.unless_returned(rest)
+                } else {
+                    let zeroed = self.zeroed_value_of_type(&typ, location);
+                    self.unless_returned_else(rest, zeroed, typ)
+                };
+                statements.push(rest);
+                break;
+            }
+        }
+
+        Ok(ast::Expression::Block(statements))
+    }
+
+    /// If `expr` is an `if` without an `else` whose block ends in a `return`,
+    /// returns its condition and the statements of its block.
+    fn guard_clause(&self, expr: ExprId) -> Option<(ExprId, Vec<StmtId>)> {
+        let HirExpression::If(if_expr) = self.interner.expression(&expr) else {
+            return None;
+        };
+        if if_expr.alternative.is_some() {
+            return None;
+        }
+        let HirExpression::Block(block) = self.interner.expression(&if_expr.consequence) else {
+            return None;
+        };
+        let last = block.statements.last()?;
+        let ends_in_return = matches!(self.interner.statement(last), HirStatement::Return(_));
+        ends_in_return.then_some((if_expr.condition, block.statements))
+    }
+
+    /// The return type of the current function or lambda, and its location.
+    fn return_type(&self) -> (ast::Type, Location) {
+        let context = self.return_contexts.last().expect("ICE: expected a return context");
+        (context.return_type.clone(), context.location)
+    }
+
+    /// The number of `return` statements lowered so far in the current function or lambda.
+    ///
+    /// Comparing it before and after monomorphizing an expression tells whether it may return.
+    pub(super) fn returns_lowered(&self) -> usize {
+        self.return_contexts.last().map_or(0, |context| context.returns)
+    }
+
+    /// Lower `return expr` into an assignment of the returned value and the flag.
+    pub(super) fn return_statement(
+        &mut self,
+        expr: Option<ExprId>,
+    ) -> Result<ast::Expression, MonomorphizationError> {
+        let returns_before = self.returns_lowered();
+        let value = expr.map(|expr| self.expr(expr)).transpose()?;
+        let nested_return = self.returns_lowered() != returns_before;
+
+        let context = self.return_contexts.last_mut().expect("ICE: `return` outside of a function");
+        context.returns += 1;
+        let return_type = context.return_type.clone();
+        let (returned, value_local) = self.return_locals();
+
+        let true_literal = ast::Expression::Literal(ast::Literal::Bool(true));
+        let assign_returned = self.assign(returned, "returned", ast::Type::Bool, true_literal);
+        let statements = match (value, value_local) {
+            // A `return` nested in the returned expression already set the value,
+            // so it must not be overwritten.
+            (Some(value), Some(value_local)) if nested_return => {
+                let result = self.next_local_id();
+                let let_result = let_local(result, false, "result", value);
+                let result = self.local(result, "result", return_type.clone());
+                let assign_value = self.assign(value_local, "return_value", return_type, result);
+                let assignments = ast::Expression::Block(vec![assign_value, assign_returned]);
+                vec![let_result, self.unless_returned(assignments)]
+            }
+            (Some(value), Some(value_local)) => {
+                let assign_value = self.assign(value_local, "return_value", return_type, value);
+                vec![assign_value, assign_returned]
+            }
+            // Returning unit, so the value is only evaluated for its side effects
+            (Some(value), None) => vec![ast::Expression::Semi(Box::new(value)), assign_returned],
+            (None, _) => vec![assign_returned],
+        };
+        Ok(ast::Expression::Block(statements))
+    }
+
+    /// Monomorphize the statements of a block, only executing the statements following
+    /// a `return` if the function hasn't returned yet.
+    ///
+    /// `typ` is the type of the block, used to give it a value if it returned.
+    pub(super) fn block(
+        &mut self,
+        statement_ids: Vec<StmtId>,
+        typ: &Type,
+        location: Location,
+    ) -> Result<ast::Expression, MonomorphizationError> {
+        let mut statements = Vec::with_capacity(statement_ids.len());
+
+        for (i, statement_id) in statement_ids.iter().enumerate() {
+            let returns_before = self.returns_lowered();
+            let is_return =
+                matches!(self.interner.statement(statement_id), HirStatement::Return(_));
+            statements.push(self.statement(*statement_id)?);
+
+            if is_return {
+                // Any following statement is unreachable
+                let block_type = Self::convert_block_type(typ, location)?;
+                if block_type != ast::Type::Unit {
+                    statements.push(self.zeroed_value_of_type(&block_type, location));
+                }
+                break;
+            }
+
+            let rest = &statement_ids[i + 1..];
+            if self.returns_lowered() != returns_before && !rest.is_empty() {
+                let block_type = Self::convert_block_type(typ, location)?;
+                let rest = self.block(rest.to_vec(), typ, location)?;
+                let rest = if block_type == ast::Type::Unit {
+                    self.unless_returned(rest)
+                } else {
+                    let zeroed = self.zeroed_value_of_type(&block_type, location);
+                    self.unless_returned_else(rest, zeroed, block_type)
+                };
+                statements.push(rest);
+                break;
+            }
+        }
+
+        Ok(ast::Expression::Block(statements))
+    }
+
+    /// Stop executing the body of a loop once it returned.
+    ///
+    /// Constrained code skips the body of the remaining iterations, while unconstrained
+    /// code can leave the loop directly.
+    pub(super) fn loop_body(
+        &mut self,
+        body: ast::Expression,
+        returns_before: usize,
+    ) -> ast::Expression {
+        if self.returns_lowered() == returns_before {
+            return body;
+        }
+
+        if self.in_unconstrained_function {
+            let (returned, _) = self.return_locals();
+            let break_if_returned = ast::Expression::If(ast::If {
+                condition: Box::new(self.bool_local(returned, "returned")),
+                consequence: Box::new(ast::Expression::Break),
+                alternative: None,
+                typ: ast::Type::Unit,
+            });
+            ast::Expression::Block(vec![body, break_if_returned])
+        } else {
+            self.unless_returned(body)
+        }
+    }
+
+    /// A block ending in a `return` may have a type which was never bound, in which
+    /// case its value is never used and it can be treated as unit.
+    fn convert_block_type(
+        typ: &Type,
+        location: Location,
+    ) -> Result<ast::Type, MonomorphizationError> {
+        if let Type::TypeVariable(binding) = typ.follow_bindings() {
+            if let TypeBinding::Unbound(..) = &*binding.borrow() {
+                return Ok(ast::Type::Unit);
+            }
+        }
+        Self::convert_type(typ, location)
+    }
+
+    /// The flag and value variables of the current function, creating them if needed.
+    fn return_locals(&mut self) -> (LocalId, Option<LocalId>) {
+        let context = self.return_contexts.last().expect("ICE: expected a return context");
+        if let Some(returned) = context.returned {
+            return (returned, context.value);
+        }
+
+        let returns_unit = context.return_type == ast::Type::Unit;
+        let returned = self.next_local_id();
+        let value = (!returns_unit).then(|| self.next_local_id());
+
+        let context = self.return_contexts.last_mut().expect("ICE: expected a return context");
+        context.returned = Some(returned);
+        context.value = value;
+        (returned, value)
+    }
+
+    /// `if !returned { expr }`
+    fn unless_returned(&mut self, expr: ast::Expression) -> ast::Expression {
+        let condition = self.not_returned();
+        ast::Expression::If(ast::If {
+            condition: Box::new(condition),
+            consequence: Box::new(expr),
+            alternative: None,
+            typ: ast::Type::Unit,
+        })
+    }
+
+    /// `if !returned { expr } else { value_if_returned }`
+    fn unless_returned_else(
+        &mut self,
+        expr: ast::Expression,
+        value_if_returned: ast::Expression,
+        typ: ast::Type,
+    ) -> ast::Expression {
+        let condition = self.not_returned();
+        ast::Expression::If(ast::If {
+            condition: Box::new(condition),
+            consequence: Box::new(expr),
+            alternative: Some(Box::new(value_if_returned)),
+            typ,
+        })
+    }
+
+    fn not_returned(&mut self) -> ast::Expression {
+        let (returned, _) = self.return_locals();
+        let location =
+            self.return_contexts.last().expect("ICE: expected a return context").location;
+        ast::Expression::Unary(ast::Unary {
+            operator: UnaryOp::Not,
+            rhs: Box::new(self.bool_local(returned, "returned")),
+            result_type: ast::Type::Bool,
+            location,
+        })
+    }
+
+    fn assign(
+        &mut self,
+        id: LocalId,
+        name: &str,
+        typ: ast::Type,
+        value: ast::Expression,
+    ) -> ast::Expression {
+        let ast::Expression::Ident(ident) = self.local(id, name, typ) else {
+            unreachable!("ICE: expected an identifier");
+        };
+        ast::Expression::Assign(ast::Assign {
+            lvalue: ast::LValue::Ident(ident),
+            expression: Box::new(value),
+        })
+    }
+
+    fn bool_local(&mut self, id: LocalId, name: &str) -> ast::Expression {
+        self.local(id, name, ast::Type::Bool)
+    }
+
+    fn local(&mut self, id: LocalId, name: &str, typ: ast::Type) -> ast::Expression {
+        ast::Expression::Ident(ast::Ident {
+            location: None,
+            definition: Definition::Local(id),
+            mutable: true,
+            name: name.to_string(),
+            typ,
+            id: self.next_ident_id(),
+        })
+    }
+}
+
+fn let_local(id: LocalId, mutable: bool, name: &str, value: ast::Expression) -> ast::Expression {
+    ast::Expression::Let(ast::Let {
+        id,
+        mutable,
+        name: name.to_string(),
+        expression: Box::new(value),
+    })
+}
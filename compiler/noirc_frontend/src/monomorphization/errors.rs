@@ -20,6 +20,7 @@ pub enum MonomorphizationError {
     CannotComputeAssociatedConstant { name: String, err: TypeCheckError, location: Location },
     ReferenceReturnedFromIfOrMatch { typ: String, location: Location },
     AssignedToVarContainingReference { typ: String, location: Location },
+    NestedSliceInConstrainedCode { typ: String, location: Location },
 }
 
 impl MonomorphizationError {
@@ -36,6 +37,7 @@ impl MonomorphizationError {
             | MonomorphizationError::NoDefaultType { location, .. }
             | MonomorphizationError::ReferenceReturnedFromIfOrMatch { location, .. }
             | MonomorphizationError::AssignedToVarContainingReference { location, .. }
+            | MonomorphizationError::NestedSliceInConstrainedCode { location, .. }
             | MonomorphizationError::CannotComputeAssociatedConstant { location, .. } => *location,
             MonomorphizationError::InterpreterError(error) => error.location(),
         }
@@ -108,6 +110,13 @@ impl From<MonomorphizationError> for CustomDiagnostic {
                 };
                 return CustomDiagnostic::simple_error(message, secondary, *location);
             }
+            MonomorphizationError::NestedSliceInConstrainedCode { typ, location } => {
+                let message = format!(
+                    "Nested slices such as `{typ}` are only supported in unconstrained functions"
+                );
+                let secondary = "Try to use a constant sized array or BoundedVec instead".into();
+                return CustomDiagnostic::simple_error(message, secondary, *location);
+            }
         };
 
         let location = error.location();
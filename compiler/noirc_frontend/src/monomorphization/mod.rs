@@ -42,12 +42,14 @@ use self::ast::InlineType;
 use self::debug_types::DebugTypeTracker;
 use self::{
     ast::{Definition, FuncId, Function, LocalId, Program},
+    early_return::ReturnContext,
     errors::MonomorphizationError,
 };
 
 pub mod ast;
 mod debug;
 pub mod debug_types;
+mod early_return;
 pub mod errors;
 pub mod printer;
 pub mod tests;
@@ -100,6 +102,9 @@ pub(super) struct Monomorphizer<'interner> {
 
     lambda_envs_stack: Vec<LambdaContext>,
 
+    /// The functions and lambdas being monomorphized, innermost last, used to lower `return`.
+    return_contexts: Vec<ReturnContext>,
+
     next_local_id: u32,
     next_global_id: u32,
     next_function_id: u32,
@@ -222,6 +227,7 @@ impl<'interner> Monomorphizer<'interner> {
             next_ident_id: 0,
             interner,
             lambda_envs_stack: Vec::new(),
+            return_contexts: Vec::new(),
             is_range_loop: false,
             return_location: None,
             debug_type_tracker,
@@ -401,7 +407,9 @@ impl<'interner> Monomorphizer<'interner> {
         let inline_type = InlineType::from(attributes);
 
         let parameters = self.parameters(&meta.parameters)?;
-        let body = self.expr(body_expr_id)?;
+        self.push_return_context(return_type.clone(), location);
+        let body = self.function_body(body_expr_id)?;
+        let body = self.pop_return_context(body);
         let function = ast::Function {
             id,
             name,
@@ -521,8 +529,11 @@ impl<'interner> Monomorphizer<'interner> {
                 }
             },
             HirExpression::Literal(HirLiteral::Unit) => ast::Expression::Block(vec![]),
-            HirExpression::Block(block) => self.block(block.statements)?,
-            HirExpression::Unsafe(block) => self.block(block.statements)?,
+            HirExpression::Block(block) | HirExpression::Unsafe(block) => {
+                let typ = self.interner.id_type(expr);
+                let location = self.interner.expr_location(&expr);
+                self.block(block.statements, &typ, location)?
+            }
 
             HirExpression::Prefix(prefix) => {
                 let rhs = self.expr(prefix.rhs)?;
@@ -706,7 +717,7 @@ impl<'interner> Monomorphizer<'interner> {
         is_slice: bool,
     ) -> Result<ast::Expression, MonomorphizationError> {
         let location = self.interner.expr_location(&array);
-        let typ = Self::convert_type(&self.interner.id_type(array), location)?;
+        let typ = self.array_type(array, location)?;
         let contents = try_vecmap(array_elements, |id| self.expr(id))?;
         if is_slice {
             Ok(ast::Expression::Literal(ast::Literal::Slice(ast::ArrayLiteral { contents, typ })))
@@ -715,6 +726,21 @@ impl<'interner> Monomorphizer<'interner> {
         }
     }
 
+    /// Convert the type of an array or slice literal, rejecting nested slices in constrained
+    /// code as they can't be represented in ACIR.
+    fn array_type(
+        &self,
+        array: node_interner::ExprId,
+        location: Location,
+    ) -> Result<ast::Type, MonomorphizationError> {
+        let typ = self.interner.id_type(array);
+        if !self.in_unconstrained_function && typ.is_nested_slice() {
+            let typ = typ.to_string();
+            return Err(MonomorphizationError::NestedSliceInConstrainedCode { typ, location });
+        }
+        Self::convert_type(&typ, location)
+    }
+
     fn repeated_array(
         &mut self,
         array: node_interner::ExprId,
@@ -723,7 +749,7 @@ impl<'interner> Monomorphizer<'interner> {
         is_slice: bool,
     ) -> Result<ast::Expression, MonomorphizationError> {
         let location = self.interner.expr_location(&array);
-        let typ = Self::convert_type(&self.interner.id_type(array), location)?;
+        let typ = self.array_type(array, location)?;
 
         let length = length.evaluate_to_u32(location).map_err(|err| {
             let location = self.interner.expr_location(&array);
@@ -764,7 +790,9 @@ impl<'interner> Monomorphizer<'interner> {
                 let index_variable = self.next_local_id();
                 self.define_local(for_loop.identifier.id, index_variable);
 
-                let block = Box::new(self.expr(for_loop.block)?);
+                let returns_before = self.returns_lowered();
+                let block = self.expr(for_loop.block)?;
+                let block = Box::new(self.loop_body(block, returns_before));
                 let index_location = for_loop.identifier.location;
                 let index_type = self.interner.id_type(for_loop.start_range);
                 let index_type = Self::convert_type(&index_type, index_location)?;
@@ -781,12 +809,16 @@ impl<'interner> Monomorphizer<'interner> {
                 }))
             }
             HirStatement::Loop(block) => {
-                let block = Box::new(self.expr(block)?);
+                let returns_before = self.returns_lowered();
+                let block = self.expr(block)?;
+                let block = Box::new(self.loop_body(block, returns_before));
                 Ok(ast::Expression::Loop(block))
             }
             HirStatement::While(condition, body) => {
                 let condition = Box::new(self.expr(condition)?);
-                let body = Box::new(self.expr(body)?);
+                let returns_before = self.returns_lowered();
+                let body = self.expr(body)?;
+                let body = Box::new(self.loop_body(body, returns_before));
                 Ok(ast::Expression::While(While { condition, body }))
             }
             HirStatement::Expression(expr) => self.expr(expr),
@@ -795,6 +827,7 @@ impl<'interner> Monomorphizer<'interner> {
             }
             HirStatement::Break => Ok(ast::Expression::Break),
             HirStatement::Continue => Ok(ast::Expression::Continue),
+            HirStatement::Return(expr) => self.return_statement(expr),
             HirStatement::Error => unreachable!(),
 
             // All `comptime` statements & expressions should be removed before runtime.
@@ -913,14 +946,6 @@ impl<'interner> Monomorphizer<'interner> {
         Ok(ast::Expression::Tuple(fields))
     }
 
-    fn block(
-        &mut self,
-        statement_ids: Vec<StmtId>,
-    ) -> Result<ast::Expression, MonomorphizationError> {
-        let stmts = try_vecmap(statement_ids, |id| self.statement(id));
-        stmts.map(ast::Expression::Block)
-    }
-
     fn unpack_pattern(
         &mut self,
         pattern: HirPattern,
@@ -1177,6 +1202,13 @@ impl<'interner> Monomorphizer<'interner> {
         typ: &HirType,
         location: Location,
     ) -> Result<ast::Expression, MonomorphizationError> {
+        // Globals are shared across runtimes, so a nested slice global may already have been
+        // monomorphized from unconstrained code
+        if !self.in_unconstrained_function && typ.is_nested_slice() {
+            let typ = typ.to_string();
+            return Err(MonomorphizationError::NestedSliceInConstrainedCode { typ, location });
+        }
+
         let global = self.interner.get_global(global_id);
         let id = global.id;
         let expr = if let Some(seen_global) = self.globals.get(&id) {
@@ -1966,7 +1998,9 @@ impl<'interner> Monomorphizer<'interner> {
             vecmap(lambda.parameters, |(pattern, typ)| (pattern, typ, Visibility::Private)).into();
 
         let parameters = self.parameters(&parameters)?;
-        let body = self.expr(lambda.body)?;
+        self.push_return_context(ret_type.clone(), location);
+        let body = self.function_body(lambda.body)?;
+        let body = self.pop_return_context(body);
         let id = self.next_function_id();
 
         let function = ast::Function {
@@ -2085,7 +2119,9 @@ impl<'interner> Monomorphizer<'interner> {
 
         self.lambda_envs_stack
             .push(LambdaContext { env_ident: env_ident.clone(), captures: lambda.captures });
-        let body = self.expr(lambda.body)?;
+        self.push_return_context(ret_type.clone(), self.interner.expr_location(&expr));
+        let body = self.function_body(lambda.body)?;
+        let body = self.pop_return_context(body);
         self.lambda_envs_stack.pop();
 
         let lambda_fn_typ: ast::Type = ast::Type::Function(
@@ -137,3 +137,72 @@ fn simple_closure_with_no_captured_variables() {
     }
     ");
 }
+
+#[named]
+#[test]
+fn early_return_in_tail_position_is_lowered_to_an_if() {
+    let src = r#"
+    fn main(x: u32) -> pub u32 {
+        if x == 0 {
+            return 1;
+        }
+        x + 1
+    }
+    "#;
+
+    let program = get_monomorphized!(src, Expect::Success).unwrap();
+    insta::assert_snapshot!(program, @r"
+    fn main$f0(x$l0: u32) -> pub u32 {
+        if (x$l0 == 0) {
+            1
+        } else {
+            (x$l0 + 1)
+        }
+    }
+    ");
+}
+
+#[named]
+#[test]
+fn early_return_in_loop_is_lowered_to_a_flag() {
+    let src = r#"
+    fn main(x: u32) -> pub u32 {
+        for i in 0..4 {
+            if i == x {
+                return i;
+            }
+        }
+        4
+    }
+    "#;
+
+    let program = get_monomorphized!(src, Expect::Success).unwrap();
+    insta::assert_snapshot!(program, @r"
+    fn main$f0(x$l0: u32) -> pub u32 {
+        let mut returned$l2 = false;
+        let mut return_value$l3 = 0;
+        let result$l4 = {
+            for i$l1 in 0 .. 4 {
+                if (!returned$l2) {
+                    if (i$l1 == x$l0) {
+                        {
+                            return_value$l3 = i$l1;
+                            returned$l2 = true
+                        }
+                    }
+                }
+            };
+            if (!returned$l2) {
+                4
+            } else {
+                0
+            }
+        };
+        if returned$l2 {
+            return_value$l3
+        } else {
+            result$l4
+        }
+    }
+    ");
+}
@@ -188,6 +188,11 @@ pub struct NodeInterner {
     /// checking.
     field_indices: HashMap<ExprId, usize>,
 
+    /// Calls given mutable references to array elements are lowered into a block writing the
+    /// elements back once the call returns. This maps the statement ending such a block to the
+    /// call as it was written.
+    element_reference_calls: HashMap<StmtId, ExprId>,
+
     // Maps GlobalId -> GlobalInfo
     // NOTE: currently only used for checking repeat globals and restricting their scope to a module
     globals: Vec<GlobalInfo>,
@@ -706,6 +711,7 @@ impl Default for NodeInterner {
             ordering_type: None,
             instantiation_bindings: HashMap::default(),
             field_indices: HashMap::default(),
+            element_reference_calls: HashMap::default(),
             next_type_variable_id: std::cell::Cell::new(0),
             globals: Vec::new(),
             global_attributes: HashMap::default(),
@@ -1435,6 +1441,14 @@ impl NodeInterner {
         self.field_indices.insert(expr_id, index);
     }
 
+    pub fn push_element_reference_call(&mut self, stmt_id: StmtId, call: ExprId) {
+        self.element_reference_calls.insert(stmt_id, call);
+    }
+
+    pub fn element_reference_call(&self, stmt_id: StmtId) -> Option<ExprId> {
+        self.element_reference_calls.get(&stmt_id).copied()
+    }
+
     pub fn function_definition_id(&self, function: FuncId) -> DefinitionId {
         self.function_definition_ids[&function]
     }
@@ -66,8 +66,6 @@ pub enum ParserErrorReason {
         "Invalid type expression: '{0}'. Only unsigned integer constants up to `u32`, globals, generics, +, -, *, /, and % may be used in this context."
     )]
     InvalidTypeExpression(Expression),
-    #[error("Early 'return' is unsupported")]
-    EarlyReturn,
     #[error("Visibility is ignored on a trait method")]
     TraitVisibilityIgnored,
     #[error("Visibility is ignored on a trait impl method")]
@@ -1,3 +1,4 @@
+use acvm::AcirField;
 use noirc_errors::Location;
 
 use crate::ast::{Expression, ExpressionKind, Ident, Literal, Path};
@@ -6,7 +7,7 @@ use crate::parser::ParserErrorReason;
 use crate::parser::labels::ParsingRuleLabel;
 use crate::token::{
     Attribute, FunctionAttribute, FunctionAttributeKind, FuzzingScope, MetaAttribute,
-    MetaAttributeName, SecondaryAttribute, SecondaryAttributeKind, TestScope, Token,
+    MetaAttributeName, SecondaryAttribute, SecondaryAttributeKind, TestBudget, TestScope, Token,
 };
 
 use super::Parser;
@@ -320,59 +321,98 @@ impl Parser<'_> {
         SecondaryAttributeKind::Deprecated(Some(message))
     }
 
+    /// TestAttribute = 'test' ( '(' TestArgument ( ',' TestArgument )* ','? ')' )?
+    ///
+    /// TestArgument = 'should_fail'
+    ///              | 'should_fail_with' '=' string
+    ///              | 'only_fail_with' '=' string
+    ///              | 'timeout' '=' integer
+    ///              | 'max_steps' '=' integer
     fn parse_test_attribute(&mut self, start_location: Location) -> Attribute {
-        let scope = if self.eat_left_paren() {
-            let scope = if let Some(ident) = self.eat_ident() {
-                match ident.as_str() {
-                    "should_fail" => Some(TestScope::ShouldFailWith { reason: None }),
-                    "should_fail_with" => {
+        let mut scope = TestScope::None;
+        let mut budget = TestBudget::default();
+        let mut malformed = false;
+
+        if self.eat_left_paren() {
+            loop {
+                let Some(ident) = self.eat_ident() else {
+                    malformed = true;
+                    break;
+                };
+                // Each argument can only be given once, and only one scope can be given
+                let has_scope = scope != TestScope::None;
+                let valid = match ident.as_str() {
+                    "should_fail" if !has_scope => {
+                        scope = TestScope::ShouldFailWith { reason: None };
+                        true
+                    }
+                    "should_fail_with" if !has_scope => {
                         self.eat_or_error(Token::Assign);
-                        if let Some(reason) = self.eat_str() {
-                            Some(TestScope::ShouldFailWith { reason: Some(reason) })
-                        } else {
-                            Some(TestScope::ShouldFailWith { reason: None })
-                        }
+                        scope = TestScope::ShouldFailWith { reason: self.eat_str() };
+                        true
                     }
-                    "only_fail_with" => {
+                    "only_fail_with" if !has_scope => {
                         self.eat_or_error(Token::Assign);
                         if let Some(reason) = self.eat_str() {
-                            Some(TestScope::OnlyFailWith { reason })
+                            scope = TestScope::OnlyFailWith { reason };
+                            true
                         } else {
                             self.expected_string();
-                            None
+                            false
                         }
                     }
-                    _ => None,
+                    "timeout" if budget.timeout.is_none() => {
+                        budget.timeout = self.parse_test_budget_value();
+                        budget.timeout.is_some()
+                    }
+                    "max_steps" if budget.max_steps.is_none() => {
+                        budget.max_steps = self.parse_test_budget_value();
+                        budget.max_steps.is_some()
+                    }
+                    _ => false,
+                };
+                if !valid {
+                    malformed = true;
+                    break;
                 }
-            } else {
-                None
-            };
-            self.eat_or_error(Token::RightParen);
-            scope
-        } else {
-            Some(TestScope::None)
-        };
+                if !self.eat_comma() || self.at(Token::RightParen) {
+                    break;
+                }
+            }
+            if !malformed {
+                self.eat_or_error(Token::RightParen);
+            }
+        }
 
         self.skip_until_right_bracket();
 
-        let scope = if let Some(scope) = scope {
-            scope
-        } else {
+        if malformed {
             self.errors.push(
                 LexerErrorKind::MalformedTestAttribute {
                     location: self.location_since(start_location),
                 }
                 .into(),
             );
-            TestScope::None
-        };
+            scope = TestScope::None;
+            budget = TestBudget::default();
+        }
 
         let location = self.location_since(start_location);
-        let kind = FunctionAttributeKind::Test(scope);
+        let kind = FunctionAttributeKind::Test(scope, budget);
         let attr = FunctionAttribute { kind, location };
         Attribute::Function(attr)
     }
 
+    /// Parses the `= integer` part of a `timeout` or `max_steps` test argument.
+    fn parse_test_budget_value(&mut self) -> Option<u64> {
+        self.eat_or_error(Token::Assign);
+        let value = self.eat_int().and_then(|(value, _)| value.try_to_u64());
+        if value.is_none() {
+            self.expected_label(ParsingRuleLabel::Integer);
+        }
+        value
+    }
+
     fn parse_fuzz_attribute(&mut self, start_location: Location) -> Attribute {
         let scope = if self.eat_left_paren() {
             let scope = if let Some(ident) = self.eat_ident() {
@@ -677,14 +717,17 @@ mod tests {
     #[test]
     fn parses_attribute_test_no_scope() {
         let src = "#[test]";
-        let expected = FunctionAttributeKind::Test(TestScope::None);
+        let expected = FunctionAttributeKind::Test(TestScope::None, TestBudget::default());
         parse_function_attribute_no_errors(src, expected);
     }
 
     #[test]
     fn parses_attribute_test_should_fail() {
         let src = "#[test(should_fail)]";
-        let expected = FunctionAttributeKind::Test(TestScope::ShouldFailWith { reason: None });
+        let expected = FunctionAttributeKind::Test(
+            TestScope::ShouldFailWith { reason: None },
+            TestBudget::default(),
+        );
         parse_function_attribute_no_errors(src, expected);
     }
 
@@ -692,7 +735,10 @@ mod tests {
     fn parses_attribute_test_should_fail_with() {
         let src = "#[test(should_fail_with = \"reason\")]";
         let reason = Some("reason".to_string());
-        let expected = FunctionAttributeKind::Test(TestScope::ShouldFailWith { reason });
+        let expected = FunctionAttributeKind::Test(
+            TestScope::ShouldFailWith { reason },
+            TestBudget::default(),
+        );
         parse_function_attribute_no_errors(src, expected);
     }
 
@@ -700,10 +746,47 @@ mod tests {
     fn parses_attribute_test_only_fail_with() {
         let src = "#[test(only_fail_with = \"reason\")]";
         let reason = "reason".to_string();
-        let expected = FunctionAttributeKind::Test(TestScope::OnlyFailWith { reason });
+        let expected =
+            FunctionAttributeKind::Test(TestScope::OnlyFailWith { reason }, TestBudget::default());
+        parse_function_attribute_no_errors(src, expected);
+    }
+
+    #[test]
+    fn parses_attribute_test_with_budget() {
+        let src = "#[test(timeout = 10, max_steps = 1000)]";
+        let expected = FunctionAttributeKind::Test(
+            TestScope::None,
+            TestBudget { timeout: Some(10), max_steps: Some(1000) },
+        );
         parse_function_attribute_no_errors(src, expected);
     }
 
+    #[test]
+    fn parses_attribute_test_should_fail_with_budget() {
+        let src = "#[test(should_fail_with = \"reason\", timeout = 5,)]";
+        let reason = Some("reason".to_string());
+        let expected = FunctionAttributeKind::Test(
+            TestScope::ShouldFailWith { reason },
+            TestBudget { timeout: Some(5), max_steps: None },
+        );
+        parse_function_attribute_no_errors(src, expected);
+    }
+
+    #[test]
+    fn errors_on_test_attribute_with_repeated_argument() {
+        let src = "#[test(timeout = 5, timeout = 10)]";
+        let mut parser = Parser::for_str_with_dummy_file(src);
+        let (attribute, _span) = parser.parse_attribute().unwrap();
+        assert!(!parser.errors.is_empty());
+        let Attribute::Function(attribute) = attribute else {
+            panic!("Expected function attribute");
+        };
+        assert_eq!(
+            attribute.kind,
+            FunctionAttributeKind::Test(TestScope::None, TestBudget::default())
+        );
+    }
+
     #[test]
     fn parses_meta_attribute_single_identifier_no_arguments() {
         let src = "#[foo]";
@@ -782,7 +865,7 @@ mod tests {
         let Attribute::Function(attr) = attr else {
             panic!("Expected function attribute");
         };
-        assert!(matches!(attr.kind, FunctionAttributeKind::Test(TestScope::None)));
+        assert!(matches!(attr.kind, FunctionAttributeKind::Test(TestScope::None, _)));
 
         let (attr, _) = attributes.remove(0);
         let Attribute::Secondary(attr) = attr else {
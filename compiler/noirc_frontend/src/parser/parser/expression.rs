@@ -31,7 +31,7 @@ enum ArrayLiteralOrError {
 
 impl Parser<'_> {
     #[inline(always)]
-    pub(crate) fn parse_expression_or_error(&mut self) -> Expression {
+    pub fn parse_expression_or_error(&mut self) -> Expression {
         self.parse_expression_or_error_impl(true) // allow constructors
     }
 
@@ -607,9 +607,16 @@ impl Parser<'_> {
         Some(ExpressionKind::Match(Box::new(MatchExpression { expression, rules })))
     }
 
-    /// MatchRule = Expression '->' (Block ','?) | (Expression ',')
-    fn parse_match_rule(&mut self) -> Option<(Expression, Expression)> {
+    /// MatchRule = Expression MatchGuard? '=>' (Block ','?) | (Expression ',')
+    ///
+    /// MatchGuard = 'if' Expression
+    fn parse_match_rule(&mut self) -> Option<(Expression, Option<Expression>, Expression)> {
         let pattern = self.parse_expression()?;
+        let guard = if self.eat_keyword(Keyword::If) {
+            Some(self.parse_expression_or_error())
+        } else {
+            None
+        };
         self.eat_or_error(Token::FatArrow);
 
         let start_location = self.current_token_location;
@@ -626,7 +633,7 @@ impl Parser<'_> {
                 branch
             }
         };
-        Some((pattern, branch))
+        Some((pattern, guard, branch))
     }
 
     /// ComptimeExpression = 'comptime' Block
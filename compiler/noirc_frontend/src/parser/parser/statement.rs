@@ -120,9 +120,11 @@ impl Parser<'_> {
         }
 
         if self.eat_keyword(Keyword::Return) {
-            self.parse_expression();
-            self.push_error(ParserErrorReason::EarlyReturn, self.location_since(start_location));
-            return Some(StatementKind::Error);
+            if self.at(Token::Semicolon) || self.at(Token::RightBrace) || self.at_eof() {
+                return Some(StatementKind::Return(None));
+            }
+            let expression = self.parse_expression_or_error();
+            return Some(StatementKind::Return(Some(expression)));
         }
 
         if self.at_keyword(Keyword::Let) {
@@ -471,11 +473,8 @@ mod tests {
     use crate::{
         ast::{ExpressionKind, ForRange, LValue, Statement, StatementKind, UnresolvedTypeData},
         parser::{
-            Parser, ParserErrorReason,
-            parser::tests::{
-                expect_no_errors, get_single_error, get_single_error_reason,
-                get_source_with_error_span,
-            },
+            Parser,
+            parser::tests::{expect_no_errors, get_single_error, get_source_with_error_span},
         },
     };
 
@@ -749,18 +748,28 @@ mod tests {
     }
 
     #[test]
-    fn errors_on_return_statement() {
+    fn parses_return_statement() {
         // This shouldn't be parsed as a call
-        let src = "
-        return 1
-        ^^^^^^^^
-        ";
-        let (src, span) = get_source_with_error_span(src);
-        let mut parser = Parser::for_str_with_dummy_file(&src);
-        let statement = parser.parse_statement_or_error();
-        assert!(matches!(statement.kind, StatementKind::Error));
-        let reason = get_single_error_reason(&parser.errors, span);
-        assert!(matches!(reason, ParserErrorReason::EarlyReturn));
+        let src = "return (1)";
+        let statement = parse_statement_no_errors(src);
+        let StatementKind::Return(Some(expression)) = statement.kind else {
+            panic!("Expected return");
+        };
+        assert_eq!(expression.to_string(), "(1)");
+    }
+
+    #[test]
+    fn parses_return_without_value() {
+        let src = "{ return; }";
+        let statement = parse_statement_no_errors(src);
+        let StatementKind::Expression(expr) = statement.kind else {
+            panic!("Expected expression statement");
+        };
+        let ExpressionKind::Block(block) = expr.kind else {
+            panic!("Expected block");
+        };
+        assert_eq!(block.statements.len(), 1);
+        assert!(matches!(block.statements[0].kind, StatementKind::Return(None)));
     }
 
     #[test]
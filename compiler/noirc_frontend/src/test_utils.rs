@@ -178,6 +178,7 @@ fn emit_compile_test(test_path: &str, src: &str, mut expect: Expect) {
         "imports_warns_on_use_of_private_exported_item",
         "metaprogramming_does_not_fail_to_parse_macro_on_parser_warning",
         "resolve_unused_var",
+        "statement_after_return_is_unreachable",
         "struct_array_len",
         "unused_items_errors_on_unused_private_import",
         "unused_items_errors_on_unused_pub_crate_import",
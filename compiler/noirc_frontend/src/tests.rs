@@ -959,6 +959,7 @@ fn find_lambda_captures(stmts: &[StmtId], interner: &NodeInterner, result: &mut
             HirStatement::Error => panic!("Invalid HirStatement!"),
             HirStatement::Break => panic!("Unexpected break"),
             HirStatement::Continue => panic!("Unexpected continue"),
+            HirStatement::Return(_) => panic!("Unexpected return"),
             HirStatement::Comptime(_) => panic!("Unexpected comptime"),
         };
         let expr = interner.expression(&expr_id);
@@ -1326,18 +1327,14 @@ fn break_and_continue_in_constrained_fn() {
             for i in 0 .. 10 {
                 if i == 2 {
                     continue;
-                    ^^^^^^^^^ continue is only allowed in unconstrained functions
-                    ~~~~~~~~~ Constrained code must always have a known number of loop iterations
                 }
                 if i == 5 {
                     break;
-                    ^^^^^^ break is only allowed in unconstrained functions
-                    ~~~~~~ Constrained code must always have a known number of loop iterations
                 }
             }
         }
     "#;
-    check_errors!(src);
+    assert_no_errors!(src);
 }
 
 #[named]
@@ -1356,6 +1353,81 @@ fn break_and_continue_outside_loop() {
     check_errors!(src);
 }
 
+#[named]
+#[test]
+fn early_return_in_constrained_fn() {
+    let src = r#"
+        fn main(x: u32) -> pub u32 {
+            let f = |y: u32| {
+                if y == 0 {
+                    return 1;
+                }
+                y
+            };
+            for i in 0..3 {
+                if i == x {
+                    return f(i);
+                }
+            }
+            check(x);
+            return x
+        }
+
+        fn check(x: u32) {
+            if x == 10 {
+                return;
+            }
+            assert(x != 20);
+        }
+    "#;
+    assert_no_errors!(src);
+}
+
+#[named]
+#[test]
+fn early_return_type_mismatch() {
+    let src = r#"
+        fn main() -> pub u32 {
+                         ^^^ expected u32 because of return type
+            if true {
+                return false;
+                       ~~~~~ bool returned here
+            }
+            1
+        }
+    "#;
+    check_errors!(src);
+}
+
+#[named]
+#[test]
+fn statement_after_return_is_unreachable() {
+    let src = r#"
+        fn main() {
+            return;
+            let _ = 1;
+            ^^^^^^^^^^ Unreachable statement
+            ~~~~~~~ Any code following this expression is unreachable
+        }
+    "#;
+    check_errors!(src);
+}
+
+#[named]
+#[test]
+fn return_outside_function() {
+    let src = r#"
+        fn main() {
+            comptime {
+                return;
+                ^^^^^^^ `return` is only allowed within functions
+                ~~~~~~~ A `comptime` block or global can't return from the enclosing function
+            }
+        }
+    "#;
+    check_errors!(src);
+}
+
 // Regression for #2540
 #[named]
 #[test]
@@ -4406,12 +4478,10 @@ fn mutable_reference_to_array_element_as_func_arg() {
     fn main() {
         let mut state: [u32; 4] = [1, 2, 3, 4];
         foo(&mut state[0]);
-                 ^^^^^^^^ Mutable references to array elements are currently unsupported
-                 ~~~~~~~~ Try storing the element in a fresh variable first
-        assert_eq(state[0], 2); // expect:2 got:1
+        assert_eq(state[0], 2);
     }
     "#;
-    check_errors!(src);
+    assert_no_errors!(src);
 }
 
 #[named]
@@ -4738,6 +4808,81 @@ fn cannot_determine_slice_type() {
     check_errors!(src);
 }
 
+#[named]
+#[test]
+fn nested_slices_in_unconstrained_function() {
+    let src = r#"
+    struct Bytes {
+        bytes: [u8],
+    }
+
+    unconstrained fn main() {
+        let strings: [[u8]] = &[&[1, 2], &[3]];
+        let arrays: [[u8]; 2] = [&[4], &[5, 6]];
+        let structs = &[Bytes { bytes: &[7] }];
+        let _ = (strings, arrays, structs);
+    }
+    "#;
+    check_monomorphization_error!(src);
+}
+
+#[named]
+#[test]
+fn nested_slice_in_constrained_function() {
+    let src = r#"
+    fn main() {
+        let _: [[u8]; 2] = [&[1, 2], &[3]];
+                           ^^^^^^^^^^^^^^^ Nested slices such as `[[u8]; 2]` are only supported in unconstrained functions
+                           ~~~~~~~~~~~~~~~ Try to use a constant sized array or BoundedVec instead
+    }
+    "#;
+    check_monomorphization_error!(src);
+}
+
+#[named]
+#[test]
+fn nested_slice_global_in_constrained_function() {
+    let src = r#"
+    global STRINGS: [[u8]] = &[&[1, 2], &[3]];
+
+    fn main() {
+        let _ = STRINGS;
+                ^^^^^^^ Nested slices such as `[[u8]]` are only supported in unconstrained functions
+                ~~~~~~~ Try to use a constant sized array or BoundedVec instead
+    }
+    "#;
+    check_monomorphization_error!(src);
+}
+
+#[named]
+#[test]
+fn cannot_return_array_of_slices_from_unconstrained_function() {
+    let src = r#"
+    fn main() {
+        // Safety: test
+        let _ = unsafe { foo() };
+                         ^^^^^ Slices cannot be returned from an unconstrained runtime to a constrained runtime
+    }
+
+    unconstrained fn foo() -> [[u8]; 1] {
+        [&[1]]
+    }
+    "#;
+    check_errors!(src);
+}
+
+#[named]
+#[test]
+fn oracle_cannot_return_nested_slice() {
+    let src = r#"
+    #[oracle(get_strings)]
+    pub unconstrained fn get_strings() -> [[u8]] {}
+                                          ^^^^^^ Oracle functions cannot return nested slices
+                                          ~~~~~~ Foreign calls can only return slices of numeric values, arrays or structs without slices
+    "#;
+    check_errors!(src);
+}
+
 #[named]
 #[test]
 fn overflowing_int_in_for_loop() {
@@ -390,3 +390,81 @@ fn match_on_empty_enum() {
         &features
     );
 }
+
+#[named]
+#[test]
+fn match_guard_does_not_cover_its_case() {
+    check_errors!(
+        "
+        fn main() {
+            match Opt::Some(3) {
+                  ^^^^^^^^^^^^ Missing case: `Some(_)`
+                Opt::Some(x) if x == 3 => (),
+                Opt::None => (),
+            }
+        }
+
+        enum Opt<T> {
+            None,
+            Some(T),
+        }
+    ",
+    );
+}
+
+#[named]
+#[test]
+fn match_guard_type_mismatch() {
+    check_errors!(
+        "
+        fn main() {
+            let x: u32 = 3;
+            match x {
+                y if y => (),
+                     ^ Expected type bool, found type u32
+                _ => (),
+            }
+        }
+    ",
+    );
+}
+
+#[named]
+#[test]
+fn match_guard_falls_through_to_later_cases() {
+    let src = r#"
+    fn main() {
+        let x: u32 = 3;
+        let _ = match x {
+            y if y > 10 => 1,
+            3 => 2,
+            _ => 3,
+        };
+    }
+    "#;
+    assert_no_errors!(src);
+}
+
+#[named]
+#[test]
+fn match_guard_in_comptime_code() {
+    let src = r#"
+    fn main() {
+        comptime {
+            let opt: Opt<u32> = Opt::Some(5);
+            let result = match opt {
+                Opt::Some(x) if x > 10 => 1,
+                Opt::Some(_) => 2,
+                Opt::None => 3,
+            };
+            assert_eq(result, 2);
+        }
+    }
+
+    enum Opt<T> {
+        None,
+        Some(T),
+    }
+    "#;
+    assert_no_errors!(src);
+}
@@ -1,4 +1,4 @@
-use crate::check_errors;
+use crate::{assert_no_errors, check_errors};
 
 #[named]
 #[test]
@@ -76,3 +76,185 @@ fn constrained_reference_to_unconstrained() {
     "#;
     check_errors!(src);
 }
+
+#[named]
+#[test]
+fn mutable_reference_to_nested_array_element() {
+    let src = r#"
+    struct Foo {
+        values: [Bar; 2],
+    }
+
+    struct Bar {
+        x: u32,
+    }
+
+    impl Bar {
+        fn increment(&mut self) {
+            self.x += 1;
+        }
+    }
+
+    fn increment(x: &mut u32) {
+        *x += 1;
+    }
+
+    fn main(i: u32) {
+        let mut foos = [Foo { values: [Bar { x: 1 }, Bar { x: 2 }] }];
+        increment(&mut foos[0].values[i].x);
+        foos[0].values[1].increment();
+        assert_eq(foos[0].values[1].x, 4);
+    }
+    "#;
+    assert_no_errors!(src);
+}
+
+#[named]
+#[test]
+fn cannot_mutate_immutable_array_element() {
+    let src = r#"
+    fn main() {
+        let array = [1];
+        mutate(&mut array[0]);
+                    ^^^^^^^^ Cannot mutate immutable variable `array`
+    }
+
+    fn mutate(foo: &mut Field) {
+        *foo = 1;
+    }
+    "#;
+    check_errors!(src);
+}
+
+#[named]
+#[test]
+fn mutable_reference_to_array_element_outside_of_call() {
+    let src = r#"
+    fn main() {
+        let mut array = [1, 2];
+        let element = &mut array[0];
+                           ^^^^^^^^ Mutable references to array elements are only supported as function call arguments
+                           ~~~~~~~~ Try copying the element into a mutable variable and assigning it back to the array
+        *element = 3;
+    }
+    "#;
+    check_errors!(src);
+}
+
+#[named]
+#[test]
+fn mutable_reference_to_array_element_passed_to_function_returning_reference() {
+    let src = r#"
+    fn main() {
+        let mut array = [1, 2];
+        let element = identity(&mut array[0]);
+                      ^^^^^^^^^^^^^^^^^^^^^^^ Mutable references to array elements cannot be passed to a function returning a reference
+                      ~~~~~~~~~~~~~~~~~~~~~~~ The reference points to a copy of the element, written back to the array once the call returns
+        *element = 3;
+    }
+
+    fn identity(x: &mut Field) -> &mut Field {
+        x
+    }
+    "#;
+    check_errors!(src);
+}
+
+#[named]
+#[test]
+fn mutable_reference_to_array_element_passed_along_with_reference_to_array() {
+    let src = r#"
+    fn main() {
+        let mut array = [1, 2];
+        set(&mut array[0], &mut array);
+            ^^^^^^^^^^^^^ Mutable references to array elements cannot be passed to a call which may reach the same array through another reference
+            ~~~~~~~~~~~~~ The reference points to a copy of the element, written back to the array once the call returns
+    }
+
+    fn set(element: &mut Field, array: &mut [Field; 2]) {
+        array[0] = 3;
+        *element = 4;
+    }
+    "#;
+    check_errors!(src);
+}
+
+#[named]
+#[test]
+fn mutable_reference_to_array_element_passed_along_with_reference_variable() {
+    let src = r#"
+    fn main() {
+        let mut array = [1, 2];
+        let reference = &mut array;
+        set(&mut array[0], reference);
+            ^^^^^^^^^^^^^ Mutable references to array elements cannot be passed to a call which may reach the same array through another reference
+            ~~~~~~~~~~~~~ The reference points to a copy of the element, written back to the array once the call returns
+    }
+
+    fn set(element: &mut Field, array: &mut [Field; 2]) {
+        array[0] = 3;
+        *element = 4;
+    }
+    "#;
+    check_errors!(src);
+}
+
+#[named]
+#[test]
+fn mutable_reference_to_array_element_passed_to_closure_capturing_reference() {
+    let src = r#"
+    fn main() {
+        let mut array = [1, 2];
+        let reference = &mut array;
+        let set = |element: &mut Field| {
+            (*reference)[0] = 3;
+            *element = 4;
+        };
+        set(&mut array[0]);
+            ^^^^^^^^^^^^^ Mutable references to array elements cannot be passed to a call which may reach the same array through another reference
+            ~~~~~~~~~~~~~ The reference points to a copy of the element, written back to the array once the call returns
+    }
+    "#;
+    check_errors!(src);
+}
+
+#[named]
+#[test]
+fn mutable_references_to_two_elements_of_the_same_array() {
+    let src = r#"
+    fn main() {
+        let mut array = [1, 2];
+        swap(&mut array[0], &mut array[1]);
+             ^^^^^^^^^^^^^ Mutable references to array elements cannot be passed to a call which may reach the same array through another reference
+             ~~~~~~~~~~~~~ The reference points to a copy of the element, written back to the array once the call returns
+    }
+
+    fn swap(x: &mut Field, y: &mut Field) {
+        let tmp = *x;
+        *x = *y;
+        *y = tmp;
+    }
+    "#;
+    check_errors!(src);
+}
+
+#[named]
+#[test]
+fn mutable_references_to_elements_of_two_arrays() {
+    let src = r#"
+    fn main() {
+        let mut a = [1, 2];
+        let mut b = [3, 4];
+        swap(&mut a[0], &mut b[1]);
+        assert_eq(a, [4, 2]);
+        assert_eq(b, [3, 1]);
+    }
+
+    fn swap(x: &mut Field, y: &mut Field) {
+        let tmp = *x;
+        *x = *y;
+        *y = tmp;
+    }
+    "#;
+    assert_no_errors!(src);
+}
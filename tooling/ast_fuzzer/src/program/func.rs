@@ -628,7 +628,8 @@ impl<'a> FunctionContext<'a> {
             return Ok(None);
         }
 
-        // Mutable references to array elements are currently unsupported.
+        // Mutable references to array elements are lowered by the frontend into a copy
+        // of the element which is written back after the call, so they can't appear here.
         if types::is_array_or_slice(src_type) {
             src_mutable = false;
         }
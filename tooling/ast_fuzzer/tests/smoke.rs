@@ -43,7 +43,16 @@ fn arb_program_can_be_executed() {
             enable_brillig_constraints_check_lookback: false,
             inliner_aggressiveness: 0,
             max_bytecode_increase_percent: None,
+            brillig_unroll_factor: 0,
+            brillig_unswitch_loops: false,
+            brillig_loop_size_limit: 0,
+            max_function_specializations: 0,
+            enable_value_range_analysis: false,
+            enable_brillig_global_value_numbering: false,
+            enable_acir_common_subexpression_elimination: false,
+            ssa_pipeline: None,
             skip_passes: Default::default(),
+            verify_passes_with_inputs: None,
         };
 
         // Print the AST if something goes wrong, then panic.
@@ -0,0 +1,309 @@
+//! Conditions, hit conditions and log messages attached to breakpoints, and
+//! data watchpoints.
+//!
+//! Conditions and the values interpolated in log messages are Noir expressions,
+//! evaluated with the same evaluator as the REPL `print` command, so they can
+//! refer to the variables of the current stack frame and to witnesses (`_3`).
+use std::str::FromStr;
+
+use acvm::AcirField;
+use acvm::acir::native_types::Witness;
+use noirc_printable_type::{PrintableType, PrintableValue};
+use thiserror::Error;
+
+use crate::eval::{self, EvaluationError, Value};
+
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub(super) enum BreakpointError {
+    #[error(transparent)]
+    Evaluation(#[from] EvaluationError),
+    #[error("Condition has type `{0}`, expected a boolean or a number")]
+    InvalidConditionType(String),
+    #[error("Invalid hit condition `{0}`, expected `N`, `== N`, `>= N`, `> N` or `% N`")]
+    InvalidHitCondition(String),
+    #[error("Unbalanced braces in log message `{0}`")]
+    UnbalancedBraces(String),
+    #[error("Invalid watchpoint `{0}`, expected `_N` for a witness or `mem[N]` for Brillig memory")]
+    InvalidWatchpoint(String),
+}
+
+/// A breakpoint condition, which holds when the expression evaluates to `true`
+/// or to a non-zero number.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct Condition {
+    expression: String,
+}
+
+impl Condition {
+    pub(super) fn evaluate(
+        &self,
+        evaluate: impl FnOnce(&str) -> Result<Value, EvaluationError>,
+    ) -> Result<bool, BreakpointError> {
+        match evaluate(&self.expression)? {
+            (
+                PrintableValue::Field(value),
+                PrintableType::Boolean
+                | PrintableType::Field
+                | PrintableType::UnsignedInteger { .. }
+                | PrintableType::SignedInteger { .. },
+            ) => Ok(!value.is_zero()),
+            (_, typ) => Err(BreakpointError::InvalidConditionType(eval::type_name(&typ))),
+        }
+    }
+}
+
+impl FromStr for Condition {
+    type Err = BreakpointError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        eval::parse(s)?;
+        Ok(Condition { expression: s.trim().to_string() })
+    }
+}
+
+/// Decides whether a breakpoint stops given how many times its location has
+/// been reached (with its condition holding, if any).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum HitCondition {
+    Equal(u64),
+    AtLeast(u64),
+    GreaterThan(u64),
+    Multiple(u64),
+}
+
+impl HitCondition {
+    pub(super) fn holds(self, hit_count: u64) -> bool {
+        match self {
+            HitCondition::Equal(count) => hit_count == count,
+            HitCondition::AtLeast(count) => hit_count >= count,
+            HitCondition::GreaterThan(count) => hit_count > count,
+            HitCondition::Multiple(count) => hit_count % count == 0,
+        }
+    }
+}
+
+impl FromStr for HitCondition {
+    type Err = BreakpointError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || BreakpointError::InvalidHitCondition(s.to_string());
+        let s = s.trim();
+        let parse_count = |count: &str| count.trim().parse::<u64>().or(Err(error()));
+        if let Some(count) = s.strip_prefix("==") {
+            parse_count(count).map(HitCondition::Equal)
+        } else if let Some(count) = s.strip_prefix(">=") {
+            parse_count(count).map(HitCondition::AtLeast)
+        } else if let Some(count) = s.strip_prefix('>') {
+            parse_count(count).map(HitCondition::GreaterThan)
+        } else if let Some(count) = s.strip_prefix('%') {
+            match parse_count(count)? {
+                0 => Err(error()),
+                count => Ok(HitCondition::Multiple(count)),
+            }
+        } else {
+            parse_count(s).map(HitCondition::AtLeast)
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum LogSegment {
+    Text(String),
+    Expression(String),
+}
+
+/// A logpoint message, where expressions between braces are replaced by their
+/// value when the logpoint is reached, eg. `x is {x}`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct LogMessage {
+    segments: Vec<LogSegment>,
+}
+
+impl LogMessage {
+    pub(super) fn render(
+        &self,
+        mut display: impl FnMut(&str) -> Result<String, EvaluationError>,
+    ) -> String {
+        self.segments
+            .iter()
+            .map(|segment| match segment {
+                LogSegment::Text(text) => text.clone(),
+                LogSegment::Expression(expression) => {
+                    display(expression).unwrap_or_else(|error| format!("<{error}>"))
+                }
+            })
+            .collect()
+    }
+}
+
+impl FromStr for LogMessage {
+    type Err = BreakpointError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || BreakpointError::UnbalancedBraces(s.to_string());
+        let mut segments = Vec::new();
+        let mut rest = s;
+        while let Some(start) = rest.find(['{', '}']) {
+            if rest[start..].starts_with('}') {
+                return Err(error());
+            }
+            let end = rest[start..].find('}').ok_or_else(error)? + start;
+            if start > 0 {
+                segments.push(LogSegment::Text(rest[..start].to_string()));
+            }
+            let expression = rest[start + 1..end].trim();
+            eval::parse(expression)?;
+            segments.push(LogSegment::Expression(expression.to_string()));
+            rest = &rest[end + 1..];
+        }
+        if !rest.is_empty() {
+            segments.push(LogSegment::Text(rest.to_string()));
+        }
+        Ok(LogMessage { segments })
+    }
+}
+
+/// The settings of a breakpoint besides its location. A breakpoint with a log
+/// message is a logpoint: it records the message instead of stopping execution.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(super) struct BreakpointOptions {
+    pub(super) condition: Option<Condition>,
+    pub(super) hit_condition: Option<HitCondition>,
+    pub(super) log_message: Option<LogMessage>,
+}
+
+impl BreakpointOptions {
+    /// Parses the optional condition, hit condition and log message, in the form
+    /// they're given in DAP's `SourceBreakpoint`.
+    pub(super) fn parse(
+        condition: Option<&str>,
+        hit_condition: Option<&str>,
+        log_message: Option<&str>,
+    ) -> Result<Self, BreakpointError> {
+        // Editors send empty strings when the user clears a condition
+        let non_empty = |value: Option<&str>| value.filter(|value| !value.trim().is_empty());
+        Ok(BreakpointOptions {
+            condition: non_empty(condition).map(str::parse).transpose()?,
+            hit_condition: non_empty(hit_condition).map(str::parse).transpose()?,
+            log_message: non_empty(log_message).map(str::parse).transpose()?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub(super) struct Breakpoint {
+    pub(super) options: BreakpointOptions,
+    /// Number of times the breakpoint location was reached with its condition holding
+    pub(super) hit_count: u64,
+}
+
+/// A value that stops execution when it changes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(super) enum Watchpoint {
+    Witness(Witness),
+    /// A slot of the memory of the Brillig VM currently executing
+    BrilligMemory(usize),
+}
+
+impl std::fmt::Display for Watchpoint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Watchpoint::Witness(witness) => write!(f, "_{}", witness.witness_index()),
+            Watchpoint::BrilligMemory(ptr) => write!(f, "mem[{ptr}]"),
+        }
+    }
+}
+
+impl FromStr for Watchpoint {
+    type Err = BreakpointError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || BreakpointError::InvalidWatchpoint(s.to_string());
+        let s = s.trim();
+        if let Some(index) = s.strip_prefix('_') {
+            index.parse().map(|index| Watchpoint::Witness(Witness(index))).or(Err(error()))
+        } else if let Some(ptr) = s.strip_prefix("mem[").and_then(|s| s.strip_suffix(']')) {
+            ptr.trim().parse().map(Watchpoint::BrilligMemory).or(Err(error()))
+        } else {
+            Err(error())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use acvm::FieldElement;
+
+    use super::*;
+
+    fn value(value: i128, typ: PrintableType) -> Value {
+        (PrintableValue::Field(FieldElement::from(value)), typ)
+    }
+
+    #[test]
+    fn parses_and_evaluates_conditions() {
+        let condition: Condition = " x >= 3 ".parse().unwrap();
+        let result = condition.evaluate(|expression| {
+            assert_eq!(expression, "x >= 3");
+            Ok(value(1, PrintableType::Boolean))
+        });
+        assert_eq!(result, Ok(true));
+
+        // Numbers hold when they're not zero
+        let condition: Condition = "x".parse().unwrap();
+        let unsigned = PrintableType::UnsignedInteger { width: 32 };
+        assert_eq!(condition.evaluate(|_| Ok(value(0, unsigned.clone()))), Ok(false));
+        assert_eq!(condition.evaluate(|_| Ok(value(2, unsigned.clone()))), Ok(true));
+
+        let string =
+            (PrintableValue::String("yes".to_string()), PrintableType::String { length: 3 });
+        assert_eq!(
+            condition.evaluate(|_| Ok(string)),
+            Err(BreakpointError::InvalidConditionType("str<3>".to_string()))
+        );
+
+        let error = EvaluationError::UnknownVariable("y".to_string());
+        assert_eq!(condition.evaluate(|_| Err(error.clone())), Err(error.into()));
+
+        assert!(matches!(
+            "x >=".parse::<Condition>(),
+            Err(BreakpointError::Evaluation(EvaluationError::Parse(_)))
+        ));
+    }
+
+    #[test]
+    fn parses_hit_conditions() {
+        assert_eq!("3".parse(), Ok(HitCondition::AtLeast(3)));
+        assert_eq!("== 3".parse(), Ok(HitCondition::Equal(3)));
+        assert_eq!(">3".parse(), Ok(HitCondition::GreaterThan(3)));
+        assert_eq!("% 2".parse(), Ok(HitCondition::Multiple(2)));
+        assert!("% 0".parse::<HitCondition>().is_err());
+        assert!("sometimes".parse::<HitCondition>().is_err());
+
+        let hits: Vec<u64> =
+            (1..=6).filter(|count| HitCondition::Multiple(2).holds(*count)).collect();
+        assert_eq!(hits, vec![2, 4, 6]);
+    }
+
+    #[test]
+    fn renders_log_messages() {
+        let message: LogMessage = "x = {x}, w = { _1 + 1 }!".parse().unwrap();
+        let rendered = message.render(|expression| match expression {
+            "x" => Ok(String::from("7")),
+            _ => Err(EvaluationError::UnknownVariable(expression.to_string())),
+        });
+        assert_eq!(rendered, "x = 7, w = <Unknown variable `_1 + 1`>!");
+
+        assert!("x = {x".parse::<LogMessage>().is_err());
+        assert!("x = }".parse::<LogMessage>().is_err());
+        assert!("x = {x +}".parse::<LogMessage>().is_err());
+    }
+
+    #[test]
+    fn parses_watchpoints() {
+        assert_eq!("_4".parse(), Ok(Watchpoint::Witness(Witness(4))));
+        assert_eq!("mem[12]".parse(), Ok(Watchpoint::BrilligMemory(12)));
+        assert_eq!(Watchpoint::BrilligMemory(12).to_string(), "mem[12]");
+        assert!("12".parse::<Watchpoint>().is_err());
+    }
+}
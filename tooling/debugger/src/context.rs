@@ -1,4 +1,7 @@
-use crate::foreign_calls::DebugForeignCallExecutor;
+use crate::breakpoints::{Breakpoint, BreakpointOptions, Condition, HitCondition, Watchpoint};
+use crate::eval::{self, EvaluationError};
+use crate::foreign_calls::{DebugForeignCall, DebugForeignCallExecutor};
+use crate::history::{ExecutionHistory, StateChange};
 use acvm::acir::brillig::BitSize;
 use acvm::acir::circuit::brillig::{BrilligBytecode, BrilligFunctionId};
 use acvm::acir::circuit::{Circuit, Opcode, OpcodeLocation};
@@ -19,11 +22,11 @@ use noirc_driver::{CompiledProgram, DebugFile};
 
 use noirc_errors::call_stack::CallStackId;
 use noirc_errors::debug_info::DebugInfo;
-use noirc_printable_type::{PrintableType, PrintableValue};
+use noirc_printable_type::{PrintableType, PrintableValue, PrintableValueDisplay};
 use thiserror::Error;
 
 use std::collections::BTreeMap;
-use std::collections::HashSet;
+use std::collections::HashMap;
 use std::path::PathBuf;
 
 /// A Noir program is composed by
@@ -237,6 +240,11 @@ pub(super) enum DebugCommandResult {
     Done,
     Ok,
     BreakpointReached(DebugLocation),
+    WatchpointTriggered {
+        watchpoint: Watchpoint,
+        old_value: Option<FieldElement>,
+        new_value: FieldElement,
+    },
     Error(NargoError<FieldElement>),
 }
 
@@ -263,6 +271,13 @@ impl<F: Clone> From<&StackFrame<'_, F>> for DebugStackFrame<F> {
     }
 }
 
+/// The state of the execution after replaying a step.
+struct ReplayedStep {
+    /// Whether a breakpoint or watchpoint would have stopped execution here
+    stopped: bool,
+    source_location: Option<Vec<Location>>,
+}
+
 pub struct ExecutionFrame<'a, B: BlackBoxFunctionSolver<FieldElement>> {
     circuit_id: u32,
     acvm: ACVM<'a, FieldElement, B>,
@@ -309,7 +324,15 @@ pub(super) struct DebugContext<'a, B: BlackBoxFunctionSolver<FieldElement>> {
     foreign_call_executor: Box<dyn DebugForeignCallExecutor + 'a>,
 
     debug_artifact: &'a DebugArtifact,
-    breakpoints: HashSet<DebugLocation>,
+    breakpoints: HashMap<DebugLocation, Breakpoint>,
+    /// Watched values, along with the value they had after the last step
+    watchpoints: Vec<(Watchpoint, Option<FieldElement>)>,
+    /// Messages produced by logpoints which haven't been displayed yet
+    log_messages: Vec<String>,
+    history: ExecutionHistory,
+    /// Set while replaying the execution to step backwards, so that logpoints
+    /// don't print their messages again
+    replaying: bool,
     source_to_locations: BTreeMap<FileId, Vec<(usize, DebugLocation)>>,
 
     circuits: &'a [Circuit<FieldElement>],
@@ -357,7 +380,11 @@ impl<'a, B: BlackBoxFunctionSolver<FieldElement>> DebugContext<'a, B> {
             backend: blackbox_solver,
             foreign_call_executor,
             debug_artifact,
-            breakpoints: HashSet::new(),
+            breakpoints: HashMap::new(),
+            watchpoints: Vec::new(),
+            log_messages: Vec::new(),
+            history: ExecutionHistory::default(),
+            replaying: false,
             source_to_locations: source_to_opcodes,
             circuits,
             unconstrained_functions,
@@ -389,6 +416,7 @@ impl<'a, B: BlackBoxFunctionSolver<FieldElement>> DebugContext<'a, B> {
         witness: Witness,
         value: FieldElement,
     ) -> Option<FieldElement> {
+        self.history.record_change(StateChange::Witness(witness, value));
         self.acvm.overwrite_witness(witness, value)
     }
 
@@ -653,7 +681,17 @@ impl<'a, B: BlackBoxFunctionSolver<FieldElement>> DebugContext<'a, B> {
         &mut self,
         foreign_call: ForeignCallWaitInfo<FieldElement>,
     ) -> DebugCommandResult {
-        let foreign_call_result = self.foreign_call_executor.execute(&foreign_call);
+        let foreign_call_result = if DebugForeignCall::lookup(&foreign_call.function).is_some() {
+            self.foreign_call_executor.execute(&foreign_call)
+        } else if let Some(result) = self.history.replay_foreign_call() {
+            Ok(result)
+        } else {
+            let result = self.foreign_call_executor.execute(&foreign_call);
+            if let Ok(result) = &result {
+                self.history.record_foreign_call(result.clone());
+            }
+            result
+        };
 
         match foreign_call_result {
             Ok(foreign_call_result) => {
@@ -754,17 +792,19 @@ impl<'a, B: BlackBoxFunctionSolver<FieldElement>> DebugContext<'a, B> {
     }
 
     pub(super) fn step_into_opcode(&mut self) -> DebugCommandResult {
-        if self.brillig_solver.is_some() {
-            return self.step_brillig_opcode();
-        }
-
-        match self.acvm.step_into_brillig() {
-            StepResult::IntoBrillig(solver) => {
-                self.brillig_solver = Some(solver);
-                self.step_brillig_opcode()
+        self.history.record_step();
+        let result = if self.brillig_solver.is_some() {
+            self.step_brillig_opcode()
+        } else {
+            match self.acvm.step_into_brillig() {
+                StepResult::IntoBrillig(solver) => {
+                    self.brillig_solver = Some(solver);
+                    self.step_brillig_opcode()
+                }
+                StepResult::Status(status) => self.handle_acvm_status(status),
             }
-            StepResult::Status(status) => self.handle_acvm_status(status),
-        }
+        };
+        self.check_watchpoints(result)
     }
 
     fn get_current_acir_index(&self) -> Option<usize> {
@@ -818,8 +858,8 @@ impl<'a, B: BlackBoxFunctionSolver<FieldElement>> DebugContext<'a, B> {
         if self.is_executing_brillig() {
             self.step_out_of_brillig_opcode()
         } else {
-            let status = self.acvm.solve_opcode();
-            self.handle_acvm_status(status)
+            // Outside of Brillig, stepping into the opcode just solves it
+            self.step_into_opcode()
         }
     }
 
@@ -879,6 +919,132 @@ impl<'a, B: BlackBoxFunctionSolver<FieldElement>> DebugContext<'a, B> {
         }
     }
 
+    /// Whether there is any executed step to go back from.
+    pub(super) fn can_step_back(&self) -> bool {
+        self.history.steps() > 0
+    }
+
+    /// Replays the execution from the start until the given number of steps
+    /// were executed. Returns the state after each step (including the initial
+    /// one), so that callers can choose where to go back to.
+    fn replay(&mut self, steps: usize) -> Vec<ReplayedStep> {
+        self.reset_execution();
+        self.history.rewind();
+        self.replaying = true;
+
+        let mut replayed = vec![ReplayedStep {
+            stopped: false,
+            source_location: self.get_current_source_location(),
+        }];
+        while self.history.steps() < steps {
+            self.apply_recorded_changes();
+            let result = self.step_into_opcode();
+            let stopped = matches!(
+                result,
+                DebugCommandResult::BreakpointReached(_)
+                    | DebugCommandResult::WatchpointTriggered { .. }
+            );
+            replayed.push(ReplayedStep {
+                stopped,
+                source_location: self.get_current_source_location(),
+            });
+            if matches!(result, DebugCommandResult::Done | DebugCommandResult::Error(_)) {
+                break;
+            }
+        }
+        self.apply_recorded_changes();
+
+        self.replaying = false;
+        replayed
+    }
+
+    fn apply_recorded_changes(&mut self) {
+        for change in self.history.changes_at(self.history.steps()) {
+            match change {
+                StateChange::Witness(witness, value) => {
+                    self.acvm.overwrite_witness(witness, value);
+                }
+                StateChange::BrilligMemory(ptr, value, bit_size) => {
+                    if let Some(solver) = self.brillig_solver.as_mut() {
+                        let value = MemoryValue::new_checked(value, bit_size)
+                            .expect("Invalid value for the given bit size");
+                        solver.write_memory_at(ptr, value);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns the result to report after going back: stopping at a breakpoint
+    /// is reported as such so that clients show it.
+    fn stepped_back_result(&self) -> DebugCommandResult {
+        match self.get_current_debug_location() {
+            Some(location) if self.is_breakpoint_set(&location) => {
+                DebugCommandResult::BreakpointReached(location)
+            }
+            _ => DebugCommandResult::Ok,
+        }
+    }
+
+    /// Steps debugging execution back to the previous opcode
+    pub(super) fn step_back_opcode(&mut self) -> DebugCommandResult {
+        let steps = self.history.steps();
+        if steps > 0 {
+            self.replay(steps - 1);
+        }
+        self.stepped_back_result()
+    }
+
+    /// Steps debugging execution back to the start of the previous source
+    /// location
+    pub(super) fn next_back(&mut self) -> DebugCommandResult {
+        let steps = self.history.steps();
+        if steps == 0 {
+            return DebugCommandResult::Ok;
+        }
+        let start_location = self.get_current_source_location();
+        let replayed = self.replay(steps - 1);
+
+        // Find the last step at a different source location, then move back
+        // to the first of the consecutive steps at that location
+        let mut target = None;
+        for (step, replayed_step) in replayed.iter().enumerate().rev() {
+            let location = &replayed_step.source_location;
+            if location.is_none() {
+                continue;
+            }
+            match target {
+                None if *location != start_location => target = Some(step),
+                Some(target_step) if *location == replayed[target_step].source_location => {
+                    target = Some(step);
+                }
+                Some(_) => break,
+                None => (),
+            }
+        }
+
+        let target = target.unwrap_or(0);
+        if target != steps - 1 {
+            self.replay(target);
+        }
+        self.stepped_back_result()
+    }
+
+    /// Runs debugging execution backwards until the last breakpoint or
+    /// watchpoint that stopped it, or the start of the program
+    pub(super) fn reverse_cont(&mut self) -> DebugCommandResult {
+        let steps = self.history.steps();
+        if steps == 0 {
+            return DebugCommandResult::Ok;
+        }
+        let replayed = self.replay(steps - 1);
+        let target = replayed.iter().rposition(|step| step.stopped).unwrap_or(0);
+        if target != steps - 1 {
+            self.replay(target);
+        }
+        self.stepped_back_result()
+    }
+
     pub(super) fn get_brillig_memory(&self) -> Option<&[MemoryValue<FieldElement>]> {
         self.brillig_solver.as_ref().map(|solver| solver.get_memory())
     }
@@ -890,6 +1056,7 @@ impl<'a, B: BlackBoxFunctionSolver<FieldElement>> DebugContext<'a, B> {
         bit_size: BitSize,
     ) {
         if let Some(solver) = self.brillig_solver.as_mut() {
+            self.history.record_change(StateChange::BrilligMemory(ptr, value, bit_size));
             solver.write_memory_at(
                 ptr,
                 MemoryValue::new_checked(value, bit_size)
@@ -906,14 +1073,143 @@ impl<'a, B: BlackBoxFunctionSolver<FieldElement>> DebugContext<'a, B> {
         self.foreign_call_executor.current_stack_frame()
     }
 
-    fn breakpoint_reached(&self) -> bool {
-        if let Some(location) = self.get_current_debug_location() {
-            self.breakpoints.contains(&location)
-        } else {
-            false
+    /// Checks whether execution should stop at a breakpoint in the current
+    /// location, evaluating its condition and hit condition. Logpoints record
+    /// their message and never stop execution.
+    fn breakpoint_reached(&mut self) -> bool {
+        let Some(location) = self.get_current_debug_location() else {
+            return false;
+        };
+        let Some(options) = self.breakpoints.get(&location).map(|bp| bp.options.clone()) else {
+            return false;
+        };
+
+        if let Some(condition) = &options.condition {
+            match condition.evaluate(|source| self.evaluate_expression(source)) {
+                Ok(true) => (),
+                Ok(false) => return false,
+                Err(_) if self.replaying => return true,
+                Err(error) => {
+                    // Stop so that the user gets a chance to fix the condition
+                    self.log_messages.push(format!(
+                        "Could not evaluate breakpoint condition at {location}: {error}"
+                    ));
+                    return true;
+                }
+            }
+        }
+
+        let breakpoint = self.breakpoints.get_mut(&location).expect("Breakpoint should be set");
+        breakpoint.hit_count += 1;
+        let hit_count = breakpoint.hit_count;
+        if !options.hit_condition.is_none_or(|hit_condition| hit_condition.holds(hit_count)) {
+            return false;
+        }
+
+        match &options.log_message {
+            Some(_) if self.replaying => false,
+            Some(log_message) => {
+                let message = log_message.render(|source| self.display_expression(source));
+                self.log_messages.push(message);
+                false
+            }
+            None => true,
         }
     }
 
+    /// Evaluates an expression of a log message and formats its value.
+    fn display_expression(&self, source: &str) -> Result<String, EvaluationError> {
+        let (value, typ) = self.evaluate_expression(source)?;
+        Ok(PrintableValueDisplay::Plain(value, typ).to_string())
+    }
+
+    /// Evaluates a Noir expression over the variables of the current stack
+    /// frame. Witnesses can be referred to as `_N`.
+    pub(super) fn evaluate_expression(
+        &self,
+        source: &str,
+    ) -> Result<(PrintableValue<FieldElement>, PrintableType), EvaluationError> {
+        let frame = self.current_stack_frame();
+        let witness_map = self.get_witness_map();
+        eval::evaluate(source, |name| {
+            // Later variables shadow earlier ones with the same name
+            let variable = frame.as_ref().and_then(|frame| {
+                frame.variables.iter().rev().find(|(variable_name, ..)| *variable_name == name)
+            });
+            if let Some((_, value, typ)) = variable {
+                return Some(((*value).clone(), (*typ).clone()));
+            }
+            let index = name.strip_prefix('_')?.parse().ok()?;
+            let value = witness_map.get(&Witness(index))?;
+            Some((PrintableValue::Field(*value), PrintableType::Field))
+        })
+    }
+
+    /// Compares the watched values against the ones they had before the last
+    /// step, stopping execution if any of them changed.
+    fn check_watchpoints(&mut self, result: DebugCommandResult) -> DebugCommandResult {
+        if !matches!(result, DebugCommandResult::Ok | DebugCommandResult::BreakpointReached(_)) {
+            return result;
+        }
+
+        let values: Vec<_> =
+            self.watchpoints.iter().map(|(watchpoint, _)| self.watched_value(watchpoint)).collect();
+        let mut triggered = None;
+        for ((watchpoint, old_value), new_value) in self.watchpoints.iter_mut().zip(values) {
+            let previous = std::mem::replace(old_value, new_value);
+            let Some(new_value) = new_value else {
+                continue;
+            };
+            // Brillig memory is only watched while the same VM is running, while
+            // a witness getting its first value is a change worth stopping at.
+            let changed = match watchpoint {
+                Watchpoint::Witness(_) => previous != Some(new_value),
+                Watchpoint::BrilligMemory(_) => previous.is_some_and(|value| value != new_value),
+            };
+            if changed && triggered.is_none() {
+                triggered = Some(DebugCommandResult::WatchpointTriggered {
+                    watchpoint: *watchpoint,
+                    old_value: previous,
+                    new_value,
+                });
+            }
+        }
+        triggered.unwrap_or(result)
+    }
+
+    fn watched_value(&self, watchpoint: &Watchpoint) -> Option<FieldElement> {
+        match watchpoint {
+            Watchpoint::Witness(witness) => self.get_witness_map().get(witness).copied(),
+            Watchpoint::BrilligMemory(ptr) => {
+                self.get_brillig_memory()?.get(*ptr).map(|value| value.to_field())
+            }
+        }
+    }
+
+    pub(super) fn add_watchpoint(&mut self, watchpoint: Watchpoint) -> bool {
+        if self.watchpoints.iter().any(|(watched, _)| *watched == watchpoint) {
+            return false;
+        }
+        let value = self.watched_value(&watchpoint);
+        self.watchpoints.push((watchpoint, value));
+        true
+    }
+
+    pub(super) fn delete_watchpoint(&mut self, watchpoint: &Watchpoint) -> bool {
+        let count = self.watchpoints.len();
+        self.watchpoints.retain(|(watched, _)| watched != watchpoint);
+        self.watchpoints.len() != count
+    }
+
+    pub(super) fn clear_watchpoints(&mut self) {
+        self.watchpoints.clear();
+    }
+
+    /// Returns the messages recorded by logpoints since the last call.
+    pub(super) fn take_log_messages(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.log_messages)
+    }
+
     pub(super) fn is_valid_debug_location(&self, location: &DebugLocation) -> bool {
         if location.circuit_id as usize >= self.circuits.len() {
             return false;
@@ -938,15 +1234,50 @@ impl<'a, B: BlackBoxFunctionSolver<FieldElement>> DebugContext<'a, B> {
     }
 
     pub(super) fn is_breakpoint_set(&self, location: &DebugLocation) -> bool {
-        self.breakpoints.contains(location)
+        self.breakpoints.contains_key(location)
     }
 
+    /// Adds an unconditional breakpoint, returning false if there was already
+    /// a breakpoint at the location.
     pub(super) fn add_breakpoint(&mut self, location: DebugLocation) -> bool {
-        self.breakpoints.insert(location)
+        if self.is_breakpoint_set(&location) {
+            return false;
+        }
+        self.set_breakpoint(location, BreakpointOptions::default());
+        true
+    }
+
+    /// Sets a breakpoint at the location, replacing any existing one there.
+    pub(super) fn set_breakpoint(&mut self, location: DebugLocation, options: BreakpointOptions) {
+        self.breakpoints.insert(location, Breakpoint { options, hit_count: 0 });
+    }
+
+    pub(super) fn set_breakpoint_condition(
+        &mut self,
+        location: &DebugLocation,
+        condition: Option<Condition>,
+    ) -> bool {
+        let Some(breakpoint) = self.breakpoints.get_mut(location) else {
+            return false;
+        };
+        breakpoint.options.condition = condition;
+        true
+    }
+
+    pub(super) fn set_breakpoint_hit_condition(
+        &mut self,
+        location: &DebugLocation,
+        hit_condition: Option<HitCondition>,
+    ) -> bool {
+        let Some(breakpoint) = self.breakpoints.get_mut(location) else {
+            return false;
+        };
+        breakpoint.options.hit_condition = hit_condition;
+        true
     }
 
     pub(super) fn delete_breakpoint(&mut self, location: &DebugLocation) -> bool {
-        self.breakpoints.remove(location)
+        self.breakpoints.remove(location).is_some()
     }
 
     pub(super) fn clear_breakpoints(&mut self) {
@@ -964,8 +1295,14 @@ impl<'a, B: BlackBoxFunctionSolver<FieldElement>> DebugContext<'a, B> {
     }
 
     pub(super) fn restart(&mut self) {
-        // restart everything that's progress related
-        // by assigning the initial values
+        self.reset_execution();
+        self.history.clear();
+        self.log_messages.clear();
+    }
+
+    /// Restarts everything that's progress related by assigning the initial
+    /// values, keeping the recorded history.
+    fn reset_execution(&mut self) {
         self.current_circuit_id = 0;
         self.brillig_solver = None;
         self.witness_stack = WitnessStack::default();
@@ -977,6 +1314,14 @@ impl<'a, B: BlackBoxFunctionSolver<FieldElement>> DebugContext<'a, B> {
             self.initial_witness.clone(),
             self.unconstrained_functions,
         );
+        for breakpoint in self.breakpoints.values_mut() {
+            breakpoint.hit_count = 0;
+        }
+        let values: Vec<_> =
+            self.watchpoints.iter().map(|(watchpoint, _)| self.watched_value(watchpoint)).collect();
+        for ((_, value), initial_value) in self.watchpoints.iter_mut().zip(values) {
+            *value = initial_value;
+        }
     }
 }
 
@@ -1377,6 +1722,289 @@ mod tests {
         assert_eq!(context.get_current_debug_location(), None);
     }
 
+    /// A circuit computing `z = x + y` in Brillig and then asserting it, for
+    /// witnesses `x = _1`, `y = _2` and `z = _3`.
+    fn brillig_add_circuit() -> (Circuit<FieldElement>, BrilligBytecode<FieldElement>) {
+        let fe_1 = FieldElement::one();
+        let w_x = Witness(1);
+        let w_y = Witness(2);
+        let w_z = Witness(3);
+
+        let zero_usize = MemoryAddress::direct(2);
+        let one_usize = MemoryAddress::direct(3);
+
+        // This Brillig block is equivalent to: z = x + y
+        let brillig_bytecode = BrilligBytecode {
+            bytecode: vec![
+                BrilligOpcode::Const {
+                    destination: MemoryAddress::direct(0),
+                    bit_size: BitSize::Integer(IntegerBitSize::U32),
+                    value: FieldElement::from(2u64),
+                },
+                BrilligOpcode::Const {
+                    destination: zero_usize,
+                    bit_size: BitSize::Integer(IntegerBitSize::U32),
+                    value: FieldElement::from(0u64),
+                },
+                BrilligOpcode::Const {
+                    destination: one_usize,
+                    bit_size: BitSize::Integer(IntegerBitSize::U32),
+                    value: FieldElement::from(1u64),
+                },
+                BrilligOpcode::CalldataCopy {
+                    destination_address: MemoryAddress::direct(0),
+                    size_address: MemoryAddress::direct(0),
+                    offset_address: zero_usize,
+                },
+                BrilligOpcode::BinaryFieldOp {
+                    destination: MemoryAddress::direct(0),
+                    op: BinaryFieldOp::Add,
+                    lhs: MemoryAddress::direct(0),
+                    rhs: MemoryAddress::direct(1),
+                },
+                BrilligOpcode::Stop {
+                    return_data: HeapVector { pointer: zero_usize, size: one_usize },
+                },
+            ],
+        };
+        let opcodes = vec![
+            // z = x + y
+            Opcode::BrilligCall {
+                id: BrilligFunctionId(0),
+                inputs: vec![
+                    BrilligInputs::Single(Expression {
+                        linear_combinations: vec![(fe_1, w_x)],
+                        ..Expression::default()
+                    }),
+                    BrilligInputs::Single(Expression {
+                        linear_combinations: vec![(fe_1, w_y)],
+                        ..Expression::default()
+                    }),
+                ],
+                outputs: vec![BrilligOutputs::Simple(w_z)],
+                predicate: None,
+            },
+            // x + y - z = 0
+            Opcode::AssertZero(Expression {
+                mul_terms: vec![],
+                linear_combinations: vec![(fe_1, w_x), (fe_1, w_y), (-fe_1, w_z)],
+                q_c: FieldElement::zero(),
+            }),
+        ];
+        let current_witness_index = 3;
+        let circuit = Circuit { current_witness_index, opcodes, ..Circuit::default() };
+        (circuit, brillig_bytecode)
+    }
+
+    #[test]
+    fn test_conditional_breakpoints_logpoints_and_watchpoints() {
+        let solver = StubbedBlackBoxSolver::default();
+        let fe_1 = FieldElement::one();
+        let w_x = Witness(1);
+        let w_y = Witness(2);
+        let w_z = Witness(3);
+        let (circuit, brillig_bytecode) = brillig_add_circuit();
+        let circuits = &[circuit];
+
+        let debug_symbols = vec![];
+        let file_map = BTreeMap::new();
+        let debug_artifact = &DebugArtifact { debug_symbols, file_map };
+
+        let initial_witness = BTreeMap::from([(w_x, fe_1), (w_y, fe_1)]).into();
+
+        let foreign_call_executor = Box::new(DefaultDebugForeignCallExecutor::from_artifact(
+            std::io::stdout(),
+            None,
+            debug_artifact,
+            None,
+            String::new(),
+        ));
+        let brillig_functions = &[brillig_bytecode];
+        let mut context = DebugContext::<StubbedBlackBoxSolver>::new(
+            &solver,
+            circuits,
+            debug_artifact,
+            initial_witness,
+            foreign_call_executor,
+            brillig_functions,
+        );
+
+        let add_location = DebugLocation {
+            circuit_id: 0,
+            opcode_location: OpcodeLocation::Brillig { acir_index: 0, brillig_index: 4 },
+            brillig_function_id: Some(BrilligFunctionId(0)),
+        };
+
+        // a breakpoint whose condition doesn't hold is skipped
+        let options = BreakpointOptions::parse(Some("_1 == 2"), None, None).unwrap();
+        context.set_breakpoint(add_location, options);
+        assert!(matches!(context.cont(), DebugCommandResult::Done));
+
+        // a breakpoint whose condition holds stops execution
+        context.restart();
+        assert!(context.set_breakpoint_condition(&add_location, Some("_2 >= 1".parse().unwrap())));
+        let result = context.cont();
+        assert!(
+            matches!(result, DebugCommandResult::BreakpointReached(location) if location == add_location)
+        );
+
+        // logpoints record their message without stopping
+        context.restart();
+        let options = BreakpointOptions::parse(None, None, Some("x + y = {_1} + {_2}")).unwrap();
+        context.set_breakpoint(add_location, options);
+        assert!(matches!(context.cont(), DebugCommandResult::Done));
+        assert_eq!(context.take_log_messages(), vec!["x + y = 0x01 + 0x01".to_string()]);
+        assert!(context.take_log_messages().is_empty());
+
+        // watched Brillig memory stops execution when written with a new value
+        context.restart();
+        context.clear_breakpoints();
+        assert!(context.add_watchpoint(Watchpoint::BrilligMemory(0)));
+        assert!(context.add_watchpoint(Watchpoint::Witness(w_z)));
+        assert!(!context.add_watchpoint(Watchpoint::Witness(w_z)));
+        let result = context.cont();
+        assert!(matches!(
+            result,
+            DebugCommandResult::WatchpointTriggered {
+                watchpoint: Watchpoint::BrilligMemory(0),
+                old_value: Some(old_value),
+                new_value,
+            } if old_value == FieldElement::from(2u64) && new_value == fe_1
+        ));
+
+        // while a witness stops execution when it's solved
+        assert!(context.delete_watchpoint(&Watchpoint::BrilligMemory(0)));
+        let result = context.cont();
+        assert!(matches!(
+            result,
+            DebugCommandResult::WatchpointTriggered {
+                watchpoint: Watchpoint::Witness(witness),
+                old_value: None,
+                new_value,
+            } if witness == w_z && new_value == FieldElement::from(2u64)
+        ));
+        assert!(matches!(context.cont(), DebugCommandResult::Done));
+    }
+
+    #[test]
+    fn test_evaluate_expression_over_witnesses() {
+        let solver = StubbedBlackBoxSolver::default();
+        let fe_1 = FieldElement::one();
+        let (circuit, brillig_bytecode) = brillig_add_circuit();
+        let circuits = &[circuit];
+
+        let debug_symbols = vec![];
+        let file_map = BTreeMap::new();
+        let debug_artifact = &DebugArtifact { debug_symbols, file_map };
+
+        let initial_witness = BTreeMap::from([(Witness(1), fe_1), (Witness(2), fe_1)]).into();
+
+        let foreign_call_executor = Box::new(DefaultDebugForeignCallExecutor::from_artifact(
+            std::io::stdout(),
+            None,
+            debug_artifact,
+            None,
+            String::new(),
+        ));
+        let brillig_functions = &[brillig_bytecode];
+        let context = DebugContext::<StubbedBlackBoxSolver>::new(
+            &solver,
+            circuits,
+            debug_artifact,
+            initial_witness,
+            foreign_call_executor,
+            brillig_functions,
+        );
+
+        assert_eq!(
+            context.evaluate_expression("(_1 + _2) * 3"),
+            Ok((PrintableValue::Field(FieldElement::from(6u128)), PrintableType::Field))
+        );
+        assert_eq!(
+            context.evaluate_expression("_3"),
+            Err(EvaluationError::UnknownVariable("_3".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_step_back_and_reverse_continue() {
+        let solver = StubbedBlackBoxSolver::default();
+        let fe_1 = FieldElement::one();
+        let (circuit, brillig_bytecode) = brillig_add_circuit();
+        let circuits = &[circuit];
+
+        let debug_symbols = vec![];
+        let file_map = BTreeMap::new();
+        let debug_artifact = &DebugArtifact { debug_symbols, file_map };
+
+        let initial_witness = BTreeMap::from([(Witness(1), fe_1), (Witness(2), fe_1)]).into();
+
+        let foreign_call_executor = Box::new(DefaultDebugForeignCallExecutor::from_artifact(
+            std::io::stdout(),
+            None,
+            debug_artifact,
+            None,
+            String::new(),
+        ));
+        let brillig_functions = &[brillig_bytecode];
+        let mut context = DebugContext::<StubbedBlackBoxSolver>::new(
+            &solver,
+            circuits,
+            debug_artifact,
+            initial_witness,
+            foreign_call_executor,
+            brillig_functions,
+        );
+        let brillig_location = |brillig_index| DebugLocation {
+            circuit_id: 0,
+            opcode_location: OpcodeLocation::Brillig { acir_index: 0, brillig_index },
+            brillig_function_id: Some(BrilligFunctionId(0)),
+        };
+        let memory_at_0 = |context: &DebugContext<StubbedBlackBoxSolver>| {
+            context.get_brillig_memory().map(|memory| memory[0].to_field())
+        };
+        assert!(!context.can_step_back());
+
+        // execute up to the addition, and change one of its operands
+        for _ in 0..4 {
+            assert!(matches!(context.step_into_opcode(), DebugCommandResult::Ok));
+        }
+        assert_eq!(context.get_current_debug_location(), Some(brillig_location(4)));
+        context.write_brillig_memory(0, FieldElement::from(5u64), BitSize::Field);
+
+        // stepping back over the addition restores the changed operand
+        assert!(matches!(context.step_into_opcode(), DebugCommandResult::Ok));
+        assert_eq!(memory_at_0(&context), Some(FieldElement::from(6u64)));
+        assert!(matches!(context.step_back_opcode(), DebugCommandResult::Ok));
+        assert_eq!(context.get_current_debug_location(), Some(brillig_location(4)));
+        assert_eq!(memory_at_0(&context), Some(FieldElement::from(5u64)));
+
+        // the changed operand makes the assertion fail
+        assert!(matches!(context.cont(), DebugCommandResult::Error(_)));
+
+        // so we go back to the last breakpoint before the failure
+        assert!(context.add_breakpoint(brillig_location(4)));
+        let result = context.reverse_cont();
+        assert!(matches!(
+            result,
+            DebugCommandResult::BreakpointReached(location) if location == brillig_location(4)
+        ));
+        assert_eq!(memory_at_0(&context), Some(FieldElement::from(5u64)));
+
+        // and without breakpoints, back to the start
+        context.clear_breakpoints();
+        assert!(matches!(context.reverse_cont(), DebugCommandResult::Ok));
+        assert!(!context.can_step_back());
+        assert_eq!(
+            context.get_current_debug_location(),
+            Some(DebugLocation {
+                circuit_id: 0,
+                opcode_location: OpcodeLocation::Acir(0),
+                brillig_function_id: None,
+            })
+        );
+    }
+
     #[test]
     fn test_address_debug_location_mapping() {
         let solver = StubbedBlackBoxSolver::default();
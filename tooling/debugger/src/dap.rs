@@ -6,25 +6,29 @@ use bn254_blackbox_solver::Bn254BlackBoxSolver;
 use nargo::NargoError;
 
 use crate::DebugProject;
+use crate::breakpoints::{BreakpointOptions, Watchpoint};
 use crate::context::{DebugCommandResult, DebugLocation, RunParams};
 use crate::context::{DebugContext, DebugExecutionResult};
+use crate::eval;
 use crate::foreign_calls::DefaultDebugForeignCallExecutor;
 
 use dap::errors::ServerError;
-use dap::events::StoppedEventBody;
+use dap::events::{OutputEventBody, StoppedEventBody};
 use dap::prelude::Event;
 use dap::requests::{Command, Request, SetBreakpointsArguments};
 use dap::responses::{
-    ContinueResponse, DisassembleResponse, ResponseBody, ScopesResponse, SetBreakpointsResponse,
+    ContinueResponse, DataBreakpointInfoResponse, DisassembleResponse, EvaluateResponse,
+    ResponseBody, ScopesResponse, SetBreakpointsResponse, SetDataBreakpointsResponse,
     SetExceptionBreakpointsResponse, SetInstructionBreakpointsResponse, StackTraceResponse,
     ThreadsResponse, VariablesResponse,
 };
 use dap::server::Server;
 use dap::types::{
-    Breakpoint, DisassembledInstruction, Scope, Source, StackFrame, SteppingGranularity,
-    StoppedEventReason, Thread, Variable,
+    Breakpoint, DisassembledInstruction, OutputEventCategory, Scope, Source, StackFrame,
+    SteppingGranularity, StoppedEventReason, Thread, Variable,
 };
 use noirc_artifacts::debug::DebugArtifact;
+use noirc_printable_type::PrintableValueDisplay;
 
 use fm::FileId;
 
@@ -36,8 +40,9 @@ pub struct DapSession<'a, R: Read, W: Write, B: BlackBoxFunctionSolver<FieldElem
     debug_artifact: &'a DebugArtifact,
     running: bool,
     next_breakpoint_id: BreakpointId,
-    instruction_breakpoints: Vec<(DebugLocation, BreakpointId)>,
-    source_breakpoints: BTreeMap<FileId, Vec<(DebugLocation, BreakpointId)>>,
+    instruction_breakpoints: Vec<(DebugLocation, BreakpointId, BreakpointOptions)>,
+    source_breakpoints: BTreeMap<FileId, Vec<(DebugLocation, BreakpointId, BreakpointOptions)>>,
+    data_breakpoints: Vec<(Watchpoint, BreakpointId)>,
     last_result: DebugCommandResult,
 }
 
@@ -87,6 +92,7 @@ impl<'a, R: Read, W: Write, B: BlackBoxFunctionSolver<FieldElement>> DapSession<
             next_breakpoint_id: 1,
             instruction_breakpoints: vec![],
             source_breakpoints: BTreeMap::new(),
+            data_breakpoints: vec![],
             last_result: DebugCommandResult::Ok,
         }
     }
@@ -141,6 +147,12 @@ impl<'a, R: Read, W: Write, B: BlackBoxFunctionSolver<FieldElement>> DapSession<
                 Command::SetInstructionBreakpoints(_) => {
                     self.handle_set_instruction_breakpoints(req)?;
                 }
+                Command::DataBreakpointInfo(_) => {
+                    self.handle_data_breakpoint_info(req)?;
+                }
+                Command::SetDataBreakpoints(_) => {
+                    self.handle_set_data_breakpoints(req)?;
+                }
                 Command::Threads => {
                     self.server.respond(req.success(ResponseBody::Threads(ThreadsResponse {
                         threads: vec![Thread { id: 0, name: "main".to_string() }],
@@ -179,12 +191,26 @@ impl<'a, R: Read, W: Write, B: BlackBoxFunctionSolver<FieldElement>> DapSession<
                 Command::Continue(_) => {
                     self.handle_continue(req)?;
                 }
+                Command::StepBack(ref args) => {
+                    let granularity =
+                        args.granularity.as_ref().unwrap_or(&SteppingGranularity::Statement);
+                    match granularity {
+                        SteppingGranularity::Instruction => self.handle_step_back(req)?,
+                        _ => self.handle_next_back(req)?,
+                    }
+                }
+                Command::ReverseContinue(_) => {
+                    self.handle_reverse_continue(req)?;
+                }
                 Command::Scopes(_) => {
                     self.handle_scopes(req)?;
                 }
                 Command::Variables(ref _args) => {
                     self.handle_variables(req)?;
                 }
+                Command::Evaluate(_) => {
+                    self.handle_evaluate(req)?;
+                }
                 _ => {
                     eprintln!("ERROR: unhandled command: {:?}", req.command);
                 }
@@ -317,6 +343,27 @@ impl<'a, R: Read, W: Write, B: BlackBoxFunctionSolver<FieldElement>> DapSession<
         self.handle_execution_result(result)
     }
 
+    fn handle_step_back(&mut self, req: Request) -> Result<(), ServerError> {
+        let result = self.context.step_back_opcode();
+        eprintln!("INFO: stepped back by instruction with result {result:?}");
+        self.server.respond(req.ack()?)?;
+        self.handle_execution_result(result)
+    }
+
+    fn handle_next_back(&mut self, req: Request) -> Result<(), ServerError> {
+        let result = self.context.next_back();
+        eprintln!("INFO: stepped back by statement with result {result:?}");
+        self.server.respond(req.ack()?)?;
+        self.handle_execution_result(result)
+    }
+
+    fn handle_reverse_continue(&mut self, req: Request) -> Result<(), ServerError> {
+        let result = self.context.reverse_cont();
+        eprintln!("INFO: reverse continue with result {result:?}");
+        self.server.respond(req.ack()?)?;
+        self.handle_execution_result(result)
+    }
+
     fn handle_continue(&mut self, req: Request) -> Result<(), ServerError> {
         let result = self.context.cont();
         eprintln!("INFO: continue with result {result:?}");
@@ -328,13 +375,13 @@ impl<'a, R: Read, W: Write, B: BlackBoxFunctionSolver<FieldElement>> DapSession<
 
     fn find_breakpoints_at_location(&self, debug_location: &DebugLocation) -> Vec<i64> {
         let mut result = vec![];
-        for (location, id) in &self.instruction_breakpoints {
+        for (location, id, _) in &self.instruction_breakpoints {
             if debug_location == location {
                 result.push(*id);
             }
         }
         for breakpoints in self.source_breakpoints.values() {
-            for (location, id) in breakpoints {
+            for (location, id, _) in breakpoints {
                 if debug_location == location {
                     result.push(*id);
                 }
@@ -344,6 +391,14 @@ impl<'a, R: Read, W: Write, B: BlackBoxFunctionSolver<FieldElement>> DapSession<
     }
 
     fn handle_execution_result(&mut self, result: DebugCommandResult) -> Result<(), ServerError> {
+        for message in self.context.take_log_messages() {
+            self.server.send_event(Event::Output(OutputEventBody {
+                category: Some(OutputEventCategory::Console),
+                output: format!("{message}\n"),
+                ..OutputEventBody::default()
+            }))?;
+        }
+
         self.last_result = result;
         match &self.last_result {
             DebugCommandResult::Done => {
@@ -372,6 +427,23 @@ impl<'a, R: Read, W: Write, B: BlackBoxFunctionSolver<FieldElement>> DapSession<
                     hit_breakpoint_ids: Some(breakpoint_ids),
                 }))?;
             }
+            DebugCommandResult::WatchpointTriggered { watchpoint, .. } => {
+                let breakpoint_ids = self
+                    .data_breakpoints
+                    .iter()
+                    .filter(|(watched, _)| watched == watchpoint)
+                    .map(|(_, id)| *id)
+                    .collect();
+                self.server.send_event(Event::Stopped(StoppedEventBody {
+                    reason: StoppedEventReason::Data,
+                    description: Some(format!("Paused on change of {watchpoint}")),
+                    thread_id: Some(0),
+                    preserve_focus_hint: Some(false),
+                    text: None,
+                    all_threads_stopped: Some(false),
+                    hit_breakpoint_ids: Some(breakpoint_ids),
+                }))?;
+            }
             DebugCommandResult::Error(_) => self.server.send_event(Event::Terminated(None))?,
         }
         Ok(())
@@ -385,12 +457,12 @@ impl<'a, R: Read, W: Write, B: BlackBoxFunctionSolver<FieldElement>> DapSession<
 
     fn reinstall_breakpoints(&mut self) {
         self.context.clear_breakpoints();
-        for (location, _) in &self.instruction_breakpoints {
-            self.context.add_breakpoint(*location);
+        for (location, _, options) in &self.instruction_breakpoints {
+            self.context.set_breakpoint(*location, options.clone());
         }
         for breakpoints in self.source_breakpoints.values() {
-            for (location, _) in breakpoints {
-                self.context.add_breakpoint(*location);
+            for (location, _, options) in breakpoints {
+                self.context.set_breakpoint(*location, options.clone());
             }
         }
     }
@@ -401,11 +473,25 @@ impl<'a, R: Read, W: Write, B: BlackBoxFunctionSolver<FieldElement>> DapSession<
         };
 
         // compute breakpoints to set and return
-        let mut breakpoints_to_set: Vec<(DebugLocation, i64)> = vec![];
+        let mut breakpoints_to_set: Vec<(DebugLocation, i64, BreakpointOptions)> = vec![];
         let breakpoints: Vec<Breakpoint> = args
             .breakpoints
             .iter()
             .map(|breakpoint| {
+                let options = match BreakpointOptions::parse(
+                    breakpoint.condition.as_deref(),
+                    breakpoint.hit_condition.as_deref(),
+                    None,
+                ) {
+                    Ok(options) => options,
+                    Err(error) => {
+                        return Breakpoint {
+                            verified: false,
+                            message: Some(error.to_string()),
+                            ..Breakpoint::default()
+                        };
+                    }
+                };
                 let offset = breakpoint.offset.unwrap_or(0);
                 let address = breakpoint.instruction_reference.parse::<i64>().unwrap_or(0) + offset;
                 let Ok(address): Result<usize, _> = address.try_into() else {
@@ -427,7 +513,7 @@ impl<'a, R: Read, W: Write, B: BlackBoxFunctionSolver<FieldElement>> DapSession<
                     };
                 };
                 let id = self.get_next_breakpoint_id();
-                breakpoints_to_set.push((location, id));
+                breakpoints_to_set.push((location, id, options));
                 Breakpoint {
                     id: Some(id),
                     verified: true,
@@ -469,11 +555,25 @@ impl<'a, R: Read, W: Write, B: BlackBoxFunctionSolver<FieldElement>> DapSession<
         let Some(breakpoints) = &args.breakpoints else {
             return vec![];
         };
-        let mut breakpoints_to_set: Vec<(DebugLocation, i64)> = vec![];
+        let mut breakpoints_to_set: Vec<(DebugLocation, i64, BreakpointOptions)> = vec![];
         let breakpoints = breakpoints
             .iter()
             .map(|breakpoint| {
                 let line = breakpoint.line;
+                let options = match BreakpointOptions::parse(
+                    breakpoint.condition.as_deref(),
+                    breakpoint.hit_condition.as_deref(),
+                    breakpoint.log_message.as_deref(),
+                ) {
+                    Ok(options) => options,
+                    Err(error) => {
+                        return Breakpoint {
+                            verified: false,
+                            message: Some(error.to_string()),
+                            ..Breakpoint::default()
+                        };
+                    }
+                };
                 let Some(location) = self.context.find_opcode_for_source_location(&file_id, line)
                 else {
                     return Breakpoint {
@@ -497,7 +597,7 @@ impl<'a, R: Read, W: Write, B: BlackBoxFunctionSolver<FieldElement>> DapSession<
                 let breakpoint_address = self.context.debug_location_to_address(&location);
                 let instruction_reference = format!("{}", breakpoint_address);
                 let breakpoint_id = self.get_next_breakpoint_id();
-                breakpoints_to_set.push((location, breakpoint_id));
+                breakpoints_to_set.push((location, breakpoint_id, options));
                 Breakpoint {
                     id: Some(breakpoint_id),
                     verified: true,
@@ -527,6 +627,65 @@ impl<'a, R: Read, W: Write, B: BlackBoxFunctionSolver<FieldElement>> DapSession<
         Ok(())
     }
 
+    /// Data breakpoints can be set on the witnesses shown in the witness map
+    /// scope, and on Brillig memory slots by using `mem[N]` as their name.
+    fn handle_data_breakpoint_info(&mut self, req: Request) -> Result<(), ServerError> {
+        let Command::DataBreakpointInfo(ref args) = req.command else {
+            unreachable!("handle_data_breakpoint_info called on a different request");
+        };
+        let in_scope = match args.variables_reference.map(ScopeReferences::from) {
+            None | Some(ScopeReferences::WitnessMap) => true,
+            Some(ScopeReferences::Locals | ScopeReferences::InvalidScope) => false,
+        };
+        let response = match args.name.parse::<Watchpoint>() {
+            Ok(watchpoint) if in_scope => DataBreakpointInfoResponse {
+                data_id: Some(watchpoint.to_string()),
+                description: format!("Changes of {watchpoint}"),
+                access_types: None,
+                can_persist: Some(false),
+            },
+            _ => DataBreakpointInfoResponse {
+                data_id: None,
+                description: String::from("Only witnesses and Brillig memory slots can be watched"),
+                access_types: None,
+                can_persist: None,
+            },
+        };
+        self.server.respond(req.success(ResponseBody::DataBreakpointInfo(response)))?;
+        Ok(())
+    }
+
+    fn handle_set_data_breakpoints(&mut self, req: Request) -> Result<(), ServerError> {
+        let Command::SetDataBreakpoints(ref args) = req.command else {
+            unreachable!("handle_set_data_breakpoints called on a different request");
+        };
+
+        self.context.clear_watchpoints();
+        self.data_breakpoints.clear();
+        let mut breakpoints = Vec::with_capacity(args.breakpoints.len());
+        for data_breakpoint in &args.breakpoints {
+            let breakpoint = match data_breakpoint.data_id.parse::<Watchpoint>() {
+                Ok(watchpoint) => {
+                    let id = self.get_next_breakpoint_id();
+                    self.context.add_watchpoint(watchpoint);
+                    self.data_breakpoints.push((watchpoint, id));
+                    Breakpoint { id: Some(id), verified: true, ..Breakpoint::default() }
+                }
+                Err(error) => Breakpoint {
+                    verified: false,
+                    message: Some(error.to_string()),
+                    ..Breakpoint::default()
+                },
+            };
+            breakpoints.push(breakpoint);
+        }
+
+        self.server.respond(req.success(ResponseBody::SetDataBreakpoints(
+            SetDataBreakpointsResponse { breakpoints },
+        )))?;
+        Ok(())
+    }
+
     fn handle_scopes(&mut self, req: Request) -> Result<(), ServerError> {
         self.server.respond(req.success(ResponseBody::Scopes(ScopesResponse {
             scopes: vec![
@@ -598,6 +757,30 @@ impl<'a, R: Read, W: Write, B: BlackBoxFunctionSolver<FieldElement>> DapSession<
         Ok(())
     }
 
+    /// Evaluates watch, hover and debug console expressions in the current stack frame.
+    fn handle_evaluate(&mut self, req: Request) -> Result<(), ServerError> {
+        let Command::Evaluate(ref args) = req.command else {
+            unreachable!("handle_evaluate called on a different request");
+        };
+        match self.context.evaluate_expression(&args.expression) {
+            Ok((value, typ)) => {
+                let type_field = Some(eval::type_name(&typ));
+                let result = PrintableValueDisplay::Plain(value, typ).to_string();
+                self.server.respond(req.success(ResponseBody::Evaluate(EvaluateResponse {
+                    result,
+                    type_field,
+                    presentation_hint: None,
+                    variables_reference: 0,
+                    named_variables: None,
+                    indexed_variables: None,
+                    memory_reference: None,
+                })))?;
+            }
+            Err(error) => self.server.respond(req.error(&error.to_string()))?,
+        }
+        Ok(())
+    }
+
     pub fn last_error(self) -> Option<NargoError<FieldElement>> {
         match self.last_result {
             DebugCommandResult::Error(error) => Some(error),
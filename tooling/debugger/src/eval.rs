@@ -0,0 +1,826 @@
+//! Evaluation of Noir expressions over the variables of the program being
+//! debugged, as used by the REPL `print` command and DAP `evaluate` requests.
+//!
+//! Expressions are parsed with the compiler's parser and then interpreted
+//! directly over the printable values tracked by the debug instrumentation.
+//! Only side-effect free expressions are supported: literals, variables, field
+//! and tuple member access, indexing, `len()`, casts and the unary and binary
+//! operators. Arithmetic follows Noir's semantics, so integer overflows and
+//! divisions by zero are reported as errors rather than wrapping around.
+use acvm::{AcirField, FieldElement};
+use noirc_frontend::ast::{
+    ArrayLiteral, BinaryOpKind, Expression, ExpressionKind, Literal, UnaryOp, UnresolvedTypeData,
+};
+use noirc_frontend::parser::Parser;
+use noirc_frontend::signed_field::SignedField;
+use noirc_frontend::token::IntegerTypeSuffix;
+use noirc_printable_type::{PrintableType, PrintableValue};
+use thiserror::Error;
+
+/// A value together with the type needed to display it.
+pub(super) type Value = (PrintableValue<FieldElement>, PrintableType);
+
+/// The longest array which can be built with a repeated array literal, e.g. `[0; 1000]`,
+/// so that a typo in the length doesn't exhaust the debugger's memory.
+const MAX_REPEATED_ARRAY_LENGTH: usize = 1 << 16;
+
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub(super) enum EvaluationError {
+    #[error("Could not parse expression: {0}")]
+    Parse(String),
+    #[error("Unsupported expression `{0}`: only side-effect free expressions can be evaluated")]
+    Unsupported(String),
+    #[error("Unknown variable `{0}`")]
+    UnknownVariable(String),
+    #[error("Type `{typ}` has no field `{field}`")]
+    UnknownField { typ: String, field: String },
+    #[error("Type `{typ}` has no method `{method}`")]
+    UnknownMethod { typ: String, method: String },
+    #[error("Type `{0}` cannot be indexed")]
+    NotIndexable(String),
+    #[error("Index out of bounds, the length is {length} but the index is {index}")]
+    IndexOutOfBounds { index: u128, length: usize },
+    #[error("Cannot apply `{operator}` to a value of type `{typ}`")]
+    InvalidOperand { operator: String, typ: String },
+    #[error("Types `{lhs}` and `{rhs}` don't match")]
+    TypeMismatch { lhs: String, rhs: String },
+    #[error("Cannot cast to `{0}`")]
+    InvalidCast(String),
+    #[error("Literal {value} does not fit in `{typ}`")]
+    LiteralOutOfRange { value: String, typ: String },
+    #[error("Attempt to {0} with overflow")]
+    Overflow(&'static str),
+    #[error("Attempt to divide by zero")]
+    DivisionByZero,
+    #[error(
+        "Array length {0} is too large, at most {MAX_REPEATED_ARRAY_LENGTH} elements are supported"
+    )]
+    ArrayTooLong(usize),
+}
+
+/// Parses `source` as an expression, without evaluating it.
+pub(super) fn parse(source: &str) -> Result<Expression, EvaluationError> {
+    let (expression, _warnings) = Parser::for_str_with_dummy_file(source)
+        .parse_result(Parser::parse_expression_or_error)
+        .map_err(|errors| {
+            let errors: Vec<_> = errors.iter().map(ToString::to_string).collect();
+            EvaluationError::Parse(errors.join(", "))
+        })?;
+    Ok(expression)
+}
+
+/// Parses and evaluates `source`, resolving identifiers with `lookup`.
+pub(super) fn evaluate(
+    source: &str,
+    lookup: impl Fn(&str) -> Option<Value>,
+) -> Result<Value, EvaluationError> {
+    let expression = parse(source)?;
+    let evaluated = Evaluator { lookup: &lookup }.evaluate(&expression)?;
+    // An integer literal not combined with anything typed defaults to a Field
+    Ok(evaluated.coerce(&PrintableType::Field)?.into_value())
+}
+
+/// Formats a type the way it's written in Noir source code.
+pub(super) fn type_name(typ: &PrintableType) -> String {
+    let join = |types: &[PrintableType]| types.iter().map(type_name).collect::<Vec<_>>().join(", ");
+    match typ {
+        PrintableType::Field => "Field".to_string(),
+        PrintableType::Array { length, typ } => format!("[{}; {length}]", type_name(typ)),
+        PrintableType::Slice { typ } => format!("[{}]", type_name(typ)),
+        PrintableType::Tuple { types } if types.len() == 1 => {
+            format!("({},)", type_name(&types[0]))
+        }
+        PrintableType::Tuple { types } => format!("({})", join(types)),
+        PrintableType::SignedInteger { width } => format!("i{width}"),
+        PrintableType::UnsignedInteger { width } => format!("u{width}"),
+        PrintableType::Boolean => "bool".to_string(),
+        PrintableType::Struct { name, .. } | PrintableType::Enum { name, .. } => name.clone(),
+        PrintableType::String { length } => format!("str<{length}>"),
+        PrintableType::Function { arguments, return_type, unconstrained, .. } => {
+            let unconstrained = if *unconstrained { "unconstrained " } else { "" };
+            format!("{unconstrained}fn({}) -> {}", join(arguments), type_name(return_type))
+        }
+        PrintableType::Reference { typ, mutable: true } => format!("&mut {}", type_name(typ)),
+        PrintableType::Reference { typ, mutable: false } => format!("&{}", type_name(typ)),
+        PrintableType::Unit => "()".to_string(),
+    }
+}
+
+/// The result of evaluating a sub-expression.
+struct Evaluated {
+    value: PrintableValue<FieldElement>,
+    typ: PrintableType,
+    /// Set for integer literals without a type suffix (and arithmetic on them),
+    /// whose type is inferred from the values they're combined with.
+    literal: Option<SignedField>,
+}
+
+impl Evaluated {
+    fn new(value: PrintableValue<FieldElement>, typ: PrintableType) -> Self {
+        Self { value, typ, literal: None }
+    }
+
+    fn field(value: FieldElement, typ: PrintableType) -> Self {
+        Self::new(PrintableValue::Field(value), typ)
+    }
+
+    fn bool(value: bool) -> Self {
+        Self::field(FieldElement::from(value), PrintableType::Boolean)
+    }
+
+    fn literal(value: SignedField) -> Self {
+        Self { literal: Some(value), ..Self::field(value.to_field_element(), PrintableType::Field) }
+    }
+
+    fn into_value(self) -> Value {
+        (self.value, self.typ)
+    }
+
+    fn type_name(&self) -> String {
+        if self.literal.is_some() { "integer literal".to_string() } else { type_name(&self.typ) }
+    }
+
+    fn as_field(&self) -> FieldElement {
+        match self.value {
+            PrintableValue::Field(value) => value,
+            _ => unreachable!("numeric values are always represented as fields"),
+        }
+    }
+
+    /// Gives an untyped integer literal the given type, checking that it fits in it.
+    fn coerce(self, typ: &PrintableType) -> Result<Self, EvaluationError> {
+        let Some(literal) = self.literal else {
+            return Ok(self);
+        };
+        let out_of_range = || EvaluationError::LiteralOutOfRange {
+            value: literal.to_string(),
+            typ: type_name(typ),
+        };
+        let value = match typ {
+            PrintableType::Field => literal.to_field_element(),
+            PrintableType::UnsignedInteger { width } => {
+                let value: u128 = literal.try_to_unsigned().ok_or_else(out_of_range)?;
+                if value > max_unsigned(*width) {
+                    return Err(out_of_range());
+                }
+                FieldElement::from(value)
+            }
+            PrintableType::SignedInteger { width } => {
+                let value: i128 = literal.try_to_signed().ok_or_else(out_of_range)?;
+                encode_signed(value, *width).ok_or_else(out_of_range)?
+            }
+            _ => {
+                return Err(EvaluationError::TypeMismatch {
+                    lhs: "integer literal".to_string(),
+                    rhs: type_name(typ),
+                });
+            }
+        };
+        Ok(Self::field(value, typ.clone()))
+    }
+}
+
+struct Evaluator<'a> {
+    lookup: &'a dyn Fn(&str) -> Option<Value>,
+}
+
+impl Evaluator<'_> {
+    fn evaluate(&self, expression: &Expression) -> Result<Evaluated, EvaluationError> {
+        let unsupported = || EvaluationError::Unsupported(expression.to_string());
+        match &expression.kind {
+            ExpressionKind::Literal(literal) => self.evaluate_literal(literal, expression),
+            ExpressionKind::Variable(path) => {
+                let name = path.as_ident().ok_or_else(unsupported)?.as_str();
+                let (value, typ) = (self.lookup)(name)
+                    .ok_or_else(|| EvaluationError::UnknownVariable(name.to_string()))?;
+                Ok(Evaluated::new(value, typ))
+            }
+            ExpressionKind::Parenthesized(expression) => self.evaluate(expression),
+            ExpressionKind::Tuple(elements) => {
+                let elements = self.evaluate_all(elements)?;
+                let types = elements.iter().map(|element| element.typ.clone()).collect();
+                let array_elements = elements.into_iter().map(|element| element.value).collect();
+                Ok(Evaluated::new(
+                    PrintableValue::Vec { array_elements, is_slice: false },
+                    PrintableType::Tuple { types },
+                ))
+            }
+            ExpressionKind::MemberAccess(access) => {
+                let lhs = self.evaluate(&access.lhs)?.coerce(&PrintableType::Field)?;
+                member_access(lhs, access.rhs.as_str())
+            }
+            ExpressionKind::Index(index) => {
+                let collection = self.evaluate(&index.collection)?.coerce(&PrintableType::Field)?;
+                let index = self.evaluate(&index.index)?;
+                self::index(collection, index)
+            }
+            ExpressionKind::MethodCall(call) => {
+                let object = self.evaluate(&call.object)?.coerce(&PrintableType::Field)?;
+                let unknown_method = || EvaluationError::UnknownMethod {
+                    typ: object.type_name(),
+                    method: call.method_name.to_string(),
+                };
+                if call.method_name.as_str() != "len"
+                    || !call.arguments.is_empty()
+                    || call.generics.is_some()
+                {
+                    return Err(unknown_method());
+                }
+                let length = match (&object.value, &object.typ) {
+                    (_, PrintableType::Array { length, .. })
+                    | (_, PrintableType::String { length }) => *length as usize,
+                    (PrintableValue::Vec { array_elements, .. }, PrintableType::Slice { .. }) => {
+                        array_elements.len()
+                    }
+                    _ => return Err(unknown_method()),
+                };
+                Ok(Evaluated::field(
+                    FieldElement::from(length as u128),
+                    PrintableType::UnsignedInteger { width: 32 },
+                ))
+            }
+            ExpressionKind::Prefix(prefix) => {
+                let rhs = self.evaluate(&prefix.rhs)?;
+                unary(prefix.operator, rhs)
+            }
+            ExpressionKind::Infix(infix) => {
+                let lhs = self.evaluate(&infix.lhs)?;
+                let rhs = self.evaluate(&infix.rhs)?;
+                binary(infix.operator.contents, lhs, rhs)
+            }
+            ExpressionKind::Cast(cast) => {
+                let lhs = self.evaluate(&cast.lhs)?;
+                let typ = match &cast.r#type.typ {
+                    UnresolvedTypeData::Named(path, generics, _) if generics.is_empty() => {
+                        path.as_ident().and_then(|ident| primitive_type(ident.as_str()))
+                    }
+                    _ => None,
+                };
+                let typ =
+                    typ.ok_or_else(|| EvaluationError::InvalidCast(cast.r#type.to_string()))?;
+                self::cast(lhs, typ)
+            }
+            _ => Err(unsupported()),
+        }
+    }
+
+    fn evaluate_all(&self, expressions: &[Expression]) -> Result<Vec<Evaluated>, EvaluationError> {
+        expressions
+            .iter()
+            .map(|expression| self.evaluate(expression)?.coerce(&PrintableType::Field))
+            .collect()
+    }
+
+    fn evaluate_literal(
+        &self,
+        literal: &Literal,
+        expression: &Expression,
+    ) -> Result<Evaluated, EvaluationError> {
+        match literal {
+            Literal::Bool(value) => Ok(Evaluated::bool(*value)),
+            Literal::Integer(value, None) => Ok(Evaluated::literal(*value)),
+            Literal::Integer(value, Some(suffix)) => {
+                Evaluated::literal(*value).coerce(&suffix_type(*suffix))
+            }
+            Literal::Str(string) | Literal::RawStr(string, _) => Ok(Evaluated::new(
+                PrintableValue::String(string.clone()),
+                PrintableType::String { length: string.len() as u32 },
+            )),
+            Literal::Unit => Ok(Evaluated::new(PrintableValue::Other, PrintableType::Unit)),
+            Literal::Array(array) | Literal::Slice(array) => {
+                let is_slice = matches!(literal, Literal::Slice(_));
+                let elements = match array {
+                    ArrayLiteral::Standard(elements) => self.evaluate_all(elements)?,
+                    ArrayLiteral::Repeated { repeated_element, length } => {
+                        let element =
+                            self.evaluate(repeated_element)?.coerce(&PrintableType::Field)?;
+                        let length = self.evaluate(length)?;
+                        let length = to_index(&length)
+                            .ok_or_else(|| EvaluationError::Unsupported(expression.to_string()))?;
+                        if length > MAX_REPEATED_ARRAY_LENGTH {
+                            return Err(EvaluationError::ArrayTooLong(length));
+                        }
+                        (0..length)
+                            .map(|_| Evaluated::new(element.value.clone(), element.typ.clone()))
+                            .collect()
+                    }
+                };
+                let element_type =
+                    elements.first().map_or(PrintableType::Field, |element| element.typ.clone());
+                if let Some(element) = elements.iter().find(|element| element.typ != element_type) {
+                    return Err(EvaluationError::TypeMismatch {
+                        lhs: type_name(&element_type),
+                        rhs: element.type_name(),
+                    });
+                }
+                let typ = Box::new(element_type);
+                let typ = if is_slice {
+                    PrintableType::Slice { typ }
+                } else {
+                    PrintableType::Array { length: elements.len() as u32, typ }
+                };
+                let array_elements = elements.into_iter().map(|element| element.value).collect();
+                Ok(Evaluated::new(PrintableValue::Vec { array_elements, is_slice }, typ))
+            }
+            Literal::FmtStr(..) => Err(EvaluationError::Unsupported(expression.to_string())),
+        }
+    }
+}
+
+fn member_access(lhs: Evaluated, member: &str) -> Result<Evaluated, EvaluationError> {
+    let unknown_field =
+        || EvaluationError::UnknownField { typ: lhs.type_name(), field: member.to_string() };
+    match (&lhs.value, &lhs.typ) {
+        (PrintableValue::Struct(values), PrintableType::Struct { fields, .. }) => {
+            let typ = fields.iter().find_map(|(name, typ)| (name == member).then_some(typ));
+            match (values.get(member), typ) {
+                (Some(value), Some(typ)) => Ok(Evaluated::new(value.clone(), typ.clone())),
+                _ => Err(unknown_field()),
+            }
+        }
+        (PrintableValue::Vec { array_elements, .. }, PrintableType::Tuple { types }) => {
+            let index: usize = member.parse().map_err(|_| unknown_field())?;
+            match (array_elements.get(index), types.get(index)) {
+                (Some(value), Some(typ)) => Ok(Evaluated::new(value.clone(), typ.clone())),
+                _ => Err(unknown_field()),
+            }
+        }
+        _ => Err(unknown_field()),
+    }
+}
+
+fn index(collection: Evaluated, index: Evaluated) -> Result<Evaluated, EvaluationError> {
+    let element_type = match &collection.typ {
+        PrintableType::Array { typ, .. } | PrintableType::Slice { typ } => typ,
+        _ => return Err(EvaluationError::NotIndexable(collection.type_name())),
+    };
+    let index = index.coerce(&PrintableType::UnsignedInteger { width: 32 })?;
+    let Some(position) = to_index(&index) else {
+        return Err(EvaluationError::InvalidOperand {
+            operator: "[]".to_string(),
+            typ: index.type_name(),
+        });
+    };
+    let PrintableValue::Vec { array_elements, .. } = &collection.value else {
+        return Err(EvaluationError::NotIndexable(collection.type_name()));
+    };
+    let element = array_elements.get(position).ok_or(EvaluationError::IndexOutOfBounds {
+        index: position as u128,
+        length: array_elements.len(),
+    })?;
+    Ok(Evaluated::new(element.clone(), (**element_type).clone()))
+}
+
+/// Reads an unsigned integer to be used as an index or length.
+fn to_index(value: &Evaluated) -> Option<usize> {
+    if let Some(literal) = value.literal {
+        return literal.try_to_unsigned();
+    }
+    match value.typ {
+        PrintableType::UnsignedInteger { .. } => value.as_field().to_u128().try_into().ok(),
+        _ => None,
+    }
+}
+
+fn unary(operator: UnaryOp, rhs: Evaluated) -> Result<Evaluated, EvaluationError> {
+    if let (UnaryOp::Minus, Some(literal)) = (operator, rhs.literal) {
+        return Ok(Evaluated::literal(-literal));
+    }
+    match (operator, &rhs.typ) {
+        (UnaryOp::Minus, PrintableType::Field) => Ok(Evaluated::field(-rhs.as_field(), rhs.typ)),
+        (UnaryOp::Minus, PrintableType::SignedInteger { width }) => {
+            let value = decode_signed(rhs.as_field(), *width);
+            let negated = value.checked_neg().and_then(|value| encode_signed(value, *width));
+            Ok(Evaluated::field(negated.ok_or(EvaluationError::Overflow("negate"))?, rhs.typ))
+        }
+        (UnaryOp::Not, PrintableType::Boolean) => Ok(Evaluated::bool(rhs.as_field().is_zero())),
+        (UnaryOp::Not, PrintableType::UnsignedInteger { width }) => {
+            let value = !rhs.as_field().to_u128() & max_unsigned(*width);
+            Ok(Evaluated::field(FieldElement::from(value), rhs.typ))
+        }
+        (UnaryOp::Not, PrintableType::SignedInteger { width }) => {
+            let value = !decode_signed(rhs.as_field(), *width);
+            let value = encode_signed(value, *width).expect("bitwise not stays in range");
+            Ok(Evaluated::field(value, rhs.typ))
+        }
+        _ => Err(EvaluationError::InvalidOperand {
+            operator: operator.to_string(),
+            typ: rhs.type_name(),
+        }),
+    }
+}
+
+fn binary(
+    operator: BinaryOpKind,
+    lhs: Evaluated,
+    rhs: Evaluated,
+) -> Result<Evaluated, EvaluationError> {
+    if let (Some(lhs), Some(rhs)) = (lhs.literal, rhs.literal) {
+        if let Some(result) = literal_binary(operator, lhs, rhs)? {
+            return Ok(result);
+        }
+    }
+
+    // The shift amount doesn't need to have the same type as the shifted value
+    let is_shift = matches!(operator, BinaryOpKind::ShiftLeft | BinaryOpKind::ShiftRight);
+    let (lhs, rhs) = if is_shift {
+        (
+            lhs.coerce(&PrintableType::Field)?,
+            rhs.coerce(&PrintableType::UnsignedInteger { width: 8 })?,
+        )
+    } else {
+        let lhs = lhs.coerce(&rhs.typ)?;
+        let rhs = rhs.coerce(&lhs.typ)?;
+        (lhs, rhs)
+    };
+    if !is_shift && lhs.typ != rhs.typ {
+        return Err(EvaluationError::TypeMismatch { lhs: lhs.type_name(), rhs: rhs.type_name() });
+    }
+
+    match operator {
+        BinaryOpKind::Equal => return Ok(Evaluated::bool(lhs.value == rhs.value)),
+        BinaryOpKind::NotEqual => return Ok(Evaluated::bool(lhs.value != rhs.value)),
+        _ => (),
+    }
+
+    let invalid_operand =
+        || EvaluationError::InvalidOperand { operator: operator.to_string(), typ: lhs.type_name() };
+    let result = match &lhs.typ {
+        PrintableType::Field => field_binary(operator, lhs.as_field(), rhs.as_field()),
+        PrintableType::Boolean => {
+            bool_binary(operator, !lhs.as_field().is_zero(), !rhs.as_field().is_zero())
+        }
+        PrintableType::UnsignedInteger { width } => {
+            if is_shift && !matches!(rhs.typ, PrintableType::UnsignedInteger { .. }) {
+                return Err(invalid_operand());
+            }
+            unsigned_binary(operator, lhs.as_field().to_u128(), rhs.as_field().to_u128(), *width)
+        }
+        PrintableType::SignedInteger { width } => {
+            let lhs_value = decode_signed(lhs.as_field(), *width);
+            if is_shift {
+                if !matches!(rhs.typ, PrintableType::UnsignedInteger { .. }) {
+                    return Err(invalid_operand());
+                }
+                signed_shift(operator, lhs_value, rhs.as_field().to_u128(), *width)
+            } else {
+                signed_binary(operator, lhs_value, decode_signed(rhs.as_field(), *width), *width)
+            }
+        }
+        _ => None,
+    };
+    let (value, is_comparison) = result.ok_or_else(invalid_operand)??;
+    let typ = if is_comparison { PrintableType::Boolean } else { lhs.typ };
+    Ok(Evaluated::field(value, typ))
+}
+
+/// The result of a binary operation on numbers: `None` if the operator doesn't
+/// apply to the operands' type, or else the resulting value and whether it's a
+/// boolean resulting from a comparison.
+type NumericResult = Option<Result<(FieldElement, bool), EvaluationError>>;
+
+fn comparison<T: Ord>(operator: BinaryOpKind, lhs: T, rhs: T) -> Option<bool> {
+    match operator {
+        BinaryOpKind::Less => Some(lhs < rhs),
+        BinaryOpKind::LessEqual => Some(lhs <= rhs),
+        BinaryOpKind::Greater => Some(lhs > rhs),
+        BinaryOpKind::GreaterEqual => Some(lhs >= rhs),
+        _ => None,
+    }
+}
+
+/// Arithmetic on two untyped integer literals, which keeps the result untyped.
+/// Returns `None` for operators that need a concrete type.
+fn literal_binary(
+    operator: BinaryOpKind,
+    lhs: SignedField,
+    rhs: SignedField,
+) -> Result<Option<Evaluated>, EvaluationError> {
+    let result = match operator {
+        BinaryOpKind::Add => lhs + rhs,
+        BinaryOpKind::Subtract => lhs - rhs,
+        BinaryOpKind::Multiply => lhs * rhs,
+        BinaryOpKind::Divide if rhs.absolute_value().is_zero() => {
+            return Err(EvaluationError::DivisionByZero);
+        }
+        BinaryOpKind::Divide => lhs / rhs,
+        BinaryOpKind::Equal => return Ok(Some(Evaluated::bool(lhs == rhs))),
+        BinaryOpKind::NotEqual => return Ok(Some(Evaluated::bool(lhs != rhs))),
+        _ => return Ok(comparison(operator, lhs, rhs).map(Evaluated::bool)),
+    };
+    Ok(Some(Evaluated::literal(result)))
+}
+
+fn field_binary(operator: BinaryOpKind, lhs: FieldElement, rhs: FieldElement) -> NumericResult {
+    let value = match operator {
+        BinaryOpKind::Add => lhs + rhs,
+        BinaryOpKind::Subtract => lhs - rhs,
+        BinaryOpKind::Multiply => lhs * rhs,
+        BinaryOpKind::Divide if rhs.is_zero() => return Some(Err(EvaluationError::DivisionByZero)),
+        BinaryOpKind::Divide => lhs / rhs,
+        _ => return comparison(operator, lhs, rhs).map(|result| Ok((result.into(), true))),
+    };
+    Some(Ok((value, false)))
+}
+
+fn bool_binary(operator: BinaryOpKind, lhs: bool, rhs: bool) -> NumericResult {
+    let value = match operator {
+        BinaryOpKind::And => lhs & rhs,
+        BinaryOpKind::Or => lhs | rhs,
+        BinaryOpKind::Xor => lhs ^ rhs,
+        _ => return comparison(operator, lhs, rhs).map(|result| Ok((result.into(), true))),
+    };
+    Some(Ok((value.into(), false)))
+}
+
+fn unsigned_binary(operator: BinaryOpKind, lhs: u128, rhs: u128, width: u32) -> NumericResult {
+    let value = match operator {
+        BinaryOpKind::Add => lhs.checked_add(rhs).ok_or("add"),
+        BinaryOpKind::Subtract => lhs.checked_sub(rhs).ok_or("subtract"),
+        BinaryOpKind::Multiply => lhs.checked_mul(rhs).ok_or("multiply"),
+        BinaryOpKind::Divide | BinaryOpKind::Modulo if rhs == 0 => {
+            return Some(Err(EvaluationError::DivisionByZero));
+        }
+        BinaryOpKind::Divide => Ok(lhs / rhs),
+        BinaryOpKind::Modulo => Ok(lhs % rhs),
+        BinaryOpKind::And => Ok(lhs & rhs),
+        BinaryOpKind::Or => Ok(lhs | rhs),
+        BinaryOpKind::Xor => Ok(lhs ^ rhs),
+        BinaryOpKind::ShiftLeft if rhs >= u128::from(width) => Err("shift left"),
+        BinaryOpKind::ShiftLeft => Ok((lhs << rhs) & max_unsigned(width)),
+        BinaryOpKind::ShiftRight => Ok(lhs.checked_shr(rhs as u32).unwrap_or_default()),
+        _ => return comparison(operator, lhs, rhs).map(|result| Ok((result.into(), true))),
+    };
+    let value = value.and_then(|value| {
+        if value > max_unsigned(width) { Err(operation_name(operator)) } else { Ok(value) }
+    });
+    Some(value.map(|value| (value.into(), false)).map_err(EvaluationError::Overflow))
+}
+
+fn signed_binary(operator: BinaryOpKind, lhs: i128, rhs: i128, width: u32) -> NumericResult {
+    let value = match operator {
+        BinaryOpKind::Add => lhs.checked_add(rhs),
+        BinaryOpKind::Subtract => lhs.checked_sub(rhs),
+        BinaryOpKind::Multiply => lhs.checked_mul(rhs),
+        BinaryOpKind::Divide | BinaryOpKind::Modulo if rhs == 0 => {
+            return Some(Err(EvaluationError::DivisionByZero));
+        }
+        BinaryOpKind::Divide => lhs.checked_div(rhs),
+        BinaryOpKind::Modulo => lhs.checked_rem(rhs),
+        BinaryOpKind::And => Some(lhs & rhs),
+        BinaryOpKind::Or => Some(lhs | rhs),
+        BinaryOpKind::Xor => Some(lhs ^ rhs),
+        _ => return comparison(operator, lhs, rhs).map(|result| Ok((result.into(), true))),
+    };
+    let value = value.and_then(|value| encode_signed(value, width));
+    Some(
+        value
+            .map(|value| (value, false))
+            .ok_or(EvaluationError::Overflow(operation_name(operator))),
+    )
+}
+
+fn signed_shift(operator: BinaryOpKind, lhs: i128, rhs: u128, width: u32) -> NumericResult {
+    if rhs >= u128::from(width) {
+        return match operator {
+            BinaryOpKind::ShiftLeft => Some(Err(EvaluationError::Overflow("shift left"))),
+            // Shifting right by the width or more keeps only the sign
+            _ => Some(Ok((encode_signed(lhs >> 127, width)?, false))),
+        };
+    }
+    let value = match operator {
+        BinaryOpKind::ShiftLeft => {
+            // Keep the lowest `width` bits and reinterpret them as signed
+            let bits = ((lhs << rhs) as u128) & max_unsigned(width);
+            decode_signed(bits.into(), width)
+        }
+        _ => lhs >> rhs,
+    };
+    Some(Ok((encode_signed(value, width)?, false)))
+}
+
+fn operation_name(operator: BinaryOpKind) -> &'static str {
+    match operator {
+        BinaryOpKind::Add => "add",
+        BinaryOpKind::Subtract => "subtract",
+        BinaryOpKind::Multiply => "multiply",
+        BinaryOpKind::Divide => "divide",
+        BinaryOpKind::Modulo => "calculate the remainder",
+        BinaryOpKind::ShiftLeft => "shift left",
+        _ => "evaluate",
+    }
+}
+
+fn cast(lhs: Evaluated, typ: PrintableType) -> Result<Evaluated, EvaluationError> {
+    let value = if let Some(literal) = lhs.literal {
+        literal.to_field_element()
+    } else {
+        match lhs.typ {
+            PrintableType::Field
+            | PrintableType::Boolean
+            | PrintableType::UnsignedInteger { .. }
+            | PrintableType::SignedInteger { .. } => lhs.as_field(),
+            _ => {
+                return Err(EvaluationError::InvalidOperand {
+                    operator: "as".to_string(),
+                    typ: lhs.type_name(),
+                });
+            }
+        }
+    };
+    // Integers are truncated to the target width, sign-extending signed values first
+    let bits = match (lhs.literal, &lhs.typ) {
+        (Some(literal), _) => {
+            let magnitude = low_bits(literal.absolute_value());
+            if literal.is_negative() { magnitude.wrapping_neg() } else { magnitude }
+        }
+        (None, PrintableType::SignedInteger { width }) => decode_signed(value, *width) as u128,
+        (None, _) => low_bits(value),
+    };
+    let value = match typ {
+        PrintableType::Field => value,
+        PrintableType::UnsignedInteger { width } | PrintableType::SignedInteger { width } => {
+            FieldElement::from(bits & max_unsigned(width))
+        }
+        _ => unreachable!("only numeric types are parsed as cast targets"),
+    };
+    Ok(Evaluated::field(value, typ))
+}
+
+fn low_bits(value: FieldElement) -> u128 {
+    let bytes = value.to_be_bytes();
+    u128::from_be_bytes(bytes[bytes.len() - 16..].try_into().expect("slice has 16 bytes"))
+}
+
+fn primitive_type(name: &str) -> Option<PrintableType> {
+    let width =
+        |width: &str| width.parse().ok().filter(|width| [1, 8, 16, 32, 64, 128].contains(width));
+    if name == "Field" {
+        Some(PrintableType::Field)
+    } else if let Some(width) = name.strip_prefix('u').and_then(width) {
+        Some(PrintableType::UnsignedInteger { width })
+    } else {
+        name.strip_prefix('i').and_then(width).map(|width| PrintableType::SignedInteger { width })
+    }
+}
+
+fn suffix_type(suffix: IntegerTypeSuffix) -> PrintableType {
+    let (signed, width) = match suffix {
+        IntegerTypeSuffix::Field => return PrintableType::Field,
+        IntegerTypeSuffix::I8 => (true, 8),
+        IntegerTypeSuffix::I16 => (true, 16),
+        IntegerTypeSuffix::I32 => (true, 32),
+        IntegerTypeSuffix::I64 => (true, 64),
+        IntegerTypeSuffix::U1 => (false, 1),
+        IntegerTypeSuffix::U8 => (false, 8),
+        IntegerTypeSuffix::U16 => (false, 16),
+        IntegerTypeSuffix::U32 => (false, 32),
+        IntegerTypeSuffix::U64 => (false, 64),
+        IntegerTypeSuffix::U128 => (false, 128),
+    };
+    if signed {
+        PrintableType::SignedInteger { width }
+    } else {
+        PrintableType::UnsignedInteger { width }
+    }
+}
+
+fn max_unsigned(width: u32) -> u128 {
+    if width >= 128 { u128::MAX } else { (1 << width) - 1 }
+}
+
+/// Reads a signed integer stored as its two's complement in `width` bits.
+fn decode_signed(value: FieldElement, width: u32) -> i128 {
+    let value = value.to_u128();
+    if width < 128 && (value >> (width - 1)) == 1 {
+        value as i128 - (1 << width)
+    } else {
+        value as i128
+    }
+}
+
+/// Stores a signed integer as its two's complement in `width` bits, if it fits.
+fn encode_signed(value: i128, width: u32) -> Option<FieldElement> {
+    if width < 128 {
+        let bound = 1i128 << (width - 1);
+        if value < -bound || value >= bound {
+            return None;
+        }
+    }
+    Some(FieldElement::from((value as u128) & max_unsigned(width)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    fn u32_value(value: u128) -> Value {
+        (PrintableValue::Field(value.into()), PrintableType::UnsignedInteger { width: 32 })
+    }
+
+    fn lookup(name: &str) -> Option<Value> {
+        let u32_type = PrintableType::UnsignedInteger { width: 32 };
+        let array = |values: &[u128]| PrintableValue::Vec {
+            array_elements: values
+                .iter()
+                .map(|value| PrintableValue::Field((*value).into()))
+                .collect(),
+            is_slice: false,
+        };
+        match name {
+            "x" => {
+                Some((array(&[1, 2]), PrintableType::Array { length: 2, typ: Box::new(u32_type) }))
+            }
+            "y" => Some((
+                array(&[10, 20, 30, 40]),
+                PrintableType::Array { length: 4, typ: Box::new(u32_type) },
+            )),
+            "z" => Some((
+                PrintableValue::Field(FieldElement::from(0xfeu128)),
+                PrintableType::SignedInteger { width: 8 },
+            )),
+            "point" => Some((
+                PrintableValue::Struct(BTreeMap::from([
+                    ("x".to_string(), PrintableValue::Field(3u128.into())),
+                    ("y".to_string(), PrintableValue::Field(4u128.into())),
+                ])),
+                PrintableType::Struct {
+                    name: "Point".to_string(),
+                    fields: vec![
+                        ("x".to_string(), PrintableType::Field),
+                        ("y".to_string(), PrintableType::Field),
+                    ],
+                },
+            )),
+            "max" => Some(u32_value(u32::MAX as u128)),
+            _ => None,
+        }
+    }
+
+    fn eval(source: &str) -> Result<Value, EvaluationError> {
+        evaluate(source, lookup)
+    }
+
+    #[test]
+    fn evaluates_arithmetic_over_variables() {
+        assert_eq!(eval("x.len() + y[3]"), Ok(u32_value(42)));
+        assert_eq!(eval("(y[1] - x[0]) * 2 / 3 % 5"), Ok(u32_value(2)));
+        assert_eq!(eval("y[x[0]] << 2"), Ok(u32_value(80)));
+        assert_eq!(
+            eval("point.x * point.x + point.y * point.y"),
+            Ok((PrintableValue::Field(25u128.into()), PrintableType::Field))
+        );
+        assert_eq!(
+            eval("(y[0] > 5) & (point.y == 4)"),
+            Ok((PrintableValue::Field(FieldElement::one()), PrintableType::Boolean))
+        );
+        assert_eq!(
+            eval("(x, 7).1"),
+            Ok((PrintableValue::Field(7u128.into()), PrintableType::Field))
+        );
+    }
+
+    #[test]
+    fn follows_integer_semantics() {
+        let i8_value = |value: i128| {
+            (
+                PrintableValue::Field(encode_signed(value, 8).unwrap()),
+                PrintableType::SignedInteger { width: 8 },
+            )
+        };
+        assert_eq!(eval("z"), Ok(i8_value(-2)));
+        assert_eq!(eval("z * 3 - 1"), Ok(i8_value(-7)));
+        assert_eq!(eval("-z >> 1"), Ok(i8_value(1)));
+        assert_eq!(
+            eval("z as u8"),
+            Ok((
+                PrintableValue::Field(254u128.into()),
+                PrintableType::UnsignedInteger { width: 8 }
+            ))
+        );
+        assert_eq!(
+            eval("-1 as u16"),
+            Ok((
+                PrintableValue::Field(65535u128.into()),
+                PrintableType::UnsignedInteger { width: 16 }
+            ))
+        );
+        assert_eq!(eval("max + 1"), Err(EvaluationError::Overflow("add")));
+        assert_eq!(eval("z * 100"), Err(EvaluationError::Overflow("multiply")));
+        assert_eq!(eval("y[0] / 0"), Err(EvaluationError::DivisionByZero));
+        assert!(matches!(eval("z + 200"), Err(EvaluationError::LiteralOutOfRange { .. })));
+        assert!(matches!(eval("z + y[0]"), Err(EvaluationError::TypeMismatch { .. })));
+    }
+
+    #[test]
+    fn reports_invalid_expressions() {
+        assert_eq!(eval("w + 1"), Err(EvaluationError::UnknownVariable("w".to_string())));
+        assert_eq!(eval("x[2]"), Err(EvaluationError::IndexOutOfBounds { index: 2, length: 2 }));
+        assert!(matches!(eval("point.z"), Err(EvaluationError::UnknownField { .. })));
+        assert!(matches!(eval("foo(1)"), Err(EvaluationError::Unsupported(_))));
+        assert!(matches!(eval("1 +"), Err(EvaluationError::Parse(_))));
+        assert_eq!(eval("[0; 4294967295]"), Err(EvaluationError::ArrayTooLong(4294967295)));
+        assert_eq!(eval("[0; 3].len()"), Ok(u32_value(3)));
+    }
+}
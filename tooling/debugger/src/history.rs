@@ -0,0 +1,119 @@
+//! Recording of a debugging session, so that it can be replayed up to an
+//! earlier point to step backwards.
+//!
+//! Execution is deterministic given the results of foreign calls and the
+//! changes made by the user to witnesses and Brillig memory, so that's all we
+//! record. Going back then means restarting and replaying the same number of
+//! steps, feeding the recorded foreign call results instead of executing the
+//! calls again (which could print their output twice, or get different results
+//! from mocks and oracles).
+use acvm::FieldElement;
+use acvm::acir::brillig::{BitSize, ForeignCallResult};
+use acvm::acir::native_types::Witness;
+
+/// A change to the execution state made by the user.
+#[derive(Debug, Clone, PartialEq)]
+pub(super) enum StateChange {
+    Witness(Witness, FieldElement),
+    BrilligMemory(usize, FieldElement, BitSize),
+}
+
+#[derive(Debug, Default)]
+pub(super) struct ExecutionHistory {
+    /// Number of opcodes stepped since the start of the execution
+    steps: usize,
+    /// Results of the foreign calls made so far, in execution order. Debug
+    /// instrumentation calls aren't recorded since they don't have side effects
+    /// outside of the debugger and need to be executed to track variables.
+    foreign_call_results: Vec<ForeignCallResult<FieldElement>>,
+    /// Index of the recorded result to use for the next foreign call. It's behind
+    /// the end of `foreign_call_results` while replaying.
+    next_foreign_call: usize,
+    /// Changes made by the user, with the number of steps executed when they were made
+    changes: Vec<(usize, StateChange)>,
+}
+
+impl ExecutionHistory {
+    pub(super) fn steps(&self) -> usize {
+        self.steps
+    }
+
+    pub(super) fn record_step(&mut self) {
+        self.steps += 1;
+    }
+
+    /// Returns the recorded result for the next foreign call, if execution
+    /// already went past it.
+    pub(super) fn replay_foreign_call(&mut self) -> Option<ForeignCallResult<FieldElement>> {
+        let result = self.foreign_call_results.get(self.next_foreign_call)?.clone();
+        self.next_foreign_call += 1;
+        Some(result)
+    }
+
+    pub(super) fn record_foreign_call(&mut self, result: ForeignCallResult<FieldElement>) {
+        self.foreign_call_results.push(result);
+        self.next_foreign_call += 1;
+    }
+
+    /// Records a change made at the current step. Since the execution may now
+    /// diverge from what was recorded, anything recorded past this point is dropped.
+    pub(super) fn record_change(&mut self, change: StateChange) {
+        self.foreign_call_results.truncate(self.next_foreign_call);
+        self.changes.retain(|(step, _)| *step <= self.steps);
+        self.changes.push((self.steps, change));
+    }
+
+    /// Changes to apply once the given number of steps has been replayed.
+    pub(super) fn changes_at(&self, step: usize) -> Vec<StateChange> {
+        self.changes
+            .iter()
+            .filter(|(change_step, _)| *change_step == step)
+            .map(|(_, change)| change.clone())
+            .collect()
+    }
+
+    /// Goes back to the start of the execution, keeping the recording to replay it.
+    pub(super) fn rewind(&mut self) {
+        self.steps = 0;
+        self.next_foreign_call = 0;
+    }
+
+    pub(super) fn clear(&mut self) {
+        *self = Self::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use acvm::AcirField;
+    use acvm::acir::brillig::ForeignCallParam;
+
+    fn result(value: u128) -> ForeignCallResult<FieldElement> {
+        ForeignCallResult { values: vec![ForeignCallParam::Single(FieldElement::from(value))] }
+    }
+
+    #[test]
+    fn replays_foreign_calls_and_drops_them_on_changes() {
+        let mut history = ExecutionHistory::default();
+        history.record_step();
+        history.record_foreign_call(result(1));
+        history.record_step();
+        history.record_foreign_call(result(2));
+        assert_eq!(history.replay_foreign_call(), None);
+
+        history.rewind();
+        assert_eq!(history.steps(), 0);
+        assert_eq!(history.replay_foreign_call(), Some(result(1)));
+
+        // changing a witness invalidates the results of later calls
+        history.record_step();
+        history.record_change(StateChange::Witness(Witness(1), FieldElement::one()));
+        assert_eq!(history.replay_foreign_call(), None);
+        assert_eq!(
+            history.changes_at(1),
+            vec![StateChange::Witness(Witness(1), FieldElement::one())]
+        );
+        assert!(history.changes_at(0).is_empty());
+    }
+}
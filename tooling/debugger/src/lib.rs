@@ -2,10 +2,13 @@
 #![cfg_attr(not(test), warn(unused_crate_dependencies, unused_extern_crates))]
 #![expect(unreachable_pub)] // This crate is full of issues related to this lint
 
+mod breakpoints;
 mod context;
 mod dap;
 pub mod errors;
+mod eval;
 mod foreign_calls;
+mod history;
 mod repl;
 mod source_code_printer;
 
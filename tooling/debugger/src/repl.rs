@@ -1,4 +1,5 @@
 use crate::DebugProject;
+use crate::breakpoints::{BreakpointOptions, Watchpoint};
 use crate::context::{
     DebugCommandResult, DebugContext, DebugExecutionResult, DebugLocation, DebugStackFrame,
     RunParams,
@@ -40,6 +41,12 @@ pub(super) enum DebugCommandAPI {
     AddBreakpoint(DebugLocation),
     AddBreakpointAtLine(i64),
     DeleteBreakpoint(DebugLocation),
+    SetBreakpointCondition(DebugLocation, String),
+    SetBreakpointHitCondition(DebugLocation, String),
+    AddLogpoint(DebugLocation, String),
+    AddLogpointAtLine(i64, String),
+    AddWatchpoint(Watchpoint),
+    DeleteWatchpoint(Watchpoint),
     Restart,
     StepAcirOpcode,
     StepIntoOpcode,
@@ -47,9 +54,12 @@ pub(super) enum DebugCommandAPI {
     NextOver,
     NextOut,
     Cont,
+    NextBack,
+    ReverseCont,
     UpdateWitness(u32, String),
     WriteBrilligMemory(usize, String, u32),
     ShowVariables,
+    Evaluate(String),
     ShowWitnessMap,
     ShowWitness(u32),
     ShowBrilligMemory,
@@ -140,6 +150,28 @@ impl<'a> AsyncReplDebugger<'a> {
                     DebugCommandAPI::DeleteBreakpoint(debug_location) => {
                         Self::delete_breakpoint_at(&mut context, debug_location);
                     }
+                    DebugCommandAPI::SetBreakpointCondition(debug_location, condition) => {
+                        Self::set_breakpoint_condition(&mut context, debug_location, condition);
+                    }
+                    DebugCommandAPI::SetBreakpointHitCondition(debug_location, hit_condition) => {
+                        Self::set_breakpoint_hit_condition(
+                            &mut context,
+                            debug_location,
+                            hit_condition,
+                        );
+                    }
+                    DebugCommandAPI::AddLogpoint(debug_location, message) => {
+                        Self::add_logpoint_at(&mut context, debug_location, message);
+                    }
+                    DebugCommandAPI::AddLogpointAtLine(line_number, message) => {
+                        Self::add_logpoint_at_line(&mut context, line_number, message);
+                    }
+                    DebugCommandAPI::AddWatchpoint(watchpoint) => {
+                        Self::add_watchpoint(&mut context, watchpoint);
+                    }
+                    DebugCommandAPI::DeleteWatchpoint(watchpoint) => {
+                        Self::delete_watchpoint(&mut context, watchpoint);
+                    }
                     DebugCommandAPI::Restart => {
                         self.restart_session(&mut context);
                     }
@@ -168,12 +200,24 @@ impl<'a> AsyncReplDebugger<'a> {
                         println!("(Continuing execution...)");
                         context.cont()
                     }),
+                    DebugCommandAPI::NextBack => {
+                        self.handle_step_back(&mut context, |context| context.next_back());
+                    }
+                    DebugCommandAPI::ReverseCont => {
+                        self.handle_step_back(&mut context, |context| {
+                            println!("(Continuing execution backwards...)");
+                            context.reverse_cont()
+                        });
+                    }
                     DebugCommandAPI::AddBreakpointAtLine(line_number) => {
                         Self::add_breakpoint_at_line(&mut context, line_number);
                     }
                     DebugCommandAPI::ShowVariables => {
                         Self::show_variables(&mut context);
                     }
+                    DebugCommandAPI::Evaluate(expression) => {
+                        Self::evaluate(&context, &expression);
+                    }
                     DebugCommandAPI::ShowWitnessMap => {
                         Self::show_witness_map(&mut context);
                     }
@@ -398,6 +442,89 @@ impl<'a> AsyncReplDebugger<'a> {
         }
     }
 
+    fn set_breakpoint_condition(
+        context: &mut Context<'_>,
+        location: DebugLocation,
+        condition: String,
+    ) {
+        let condition = if condition.trim().is_empty() {
+            None
+        } else {
+            match condition.parse() {
+                Ok(condition) => Some(condition),
+                Err(error) => {
+                    println!("{error}");
+                    return;
+                }
+            }
+        };
+        if !context.set_breakpoint_condition(&location, condition) {
+            println!("Breakpoint at {location} not set");
+        } else {
+            println!("Updated condition of breakpoint at {location}");
+        }
+    }
+
+    fn set_breakpoint_hit_condition(
+        context: &mut Context<'_>,
+        location: DebugLocation,
+        hit_condition: String,
+    ) {
+        let hit_condition = if hit_condition.trim().is_empty() {
+            None
+        } else {
+            match hit_condition.parse() {
+                Ok(hit_condition) => Some(hit_condition),
+                Err(error) => {
+                    println!("{error}");
+                    return;
+                }
+            }
+        };
+        if !context.set_breakpoint_hit_condition(&location, hit_condition) {
+            println!("Breakpoint at {location} not set");
+        } else {
+            println!("Updated hit condition of breakpoint at {location}");
+        }
+    }
+
+    fn add_logpoint_at(context: &mut Context<'_>, location: DebugLocation, message: String) {
+        if !context.is_valid_debug_location(&location) {
+            println!("Invalid location {location}");
+            return;
+        }
+        match BreakpointOptions::parse(None, None, Some(&message)) {
+            Ok(options) => {
+                context.set_breakpoint(location, options);
+                println!("Added logpoint at {location}");
+            }
+            Err(error) => println!("{error}"),
+        }
+    }
+
+    fn add_logpoint_at_line(context: &mut Context<'_>, line_number: i64, message: String) {
+        match context.find_opcode_at_current_file_line(line_number) {
+            Some(location) => Self::add_logpoint_at(context, location, message),
+            None => println!("No opcode at line {}", line_number),
+        }
+    }
+
+    fn add_watchpoint(context: &mut Context<'_>, watchpoint: Watchpoint) {
+        if context.add_watchpoint(watchpoint) {
+            println!("Watching {watchpoint}");
+        } else {
+            println!("Already watching {watchpoint}");
+        }
+    }
+
+    fn delete_watchpoint(context: &mut Context<'_>, watchpoint: Watchpoint) {
+        if context.delete_watchpoint(&watchpoint) {
+            println!("Stopped watching {watchpoint}");
+        } else {
+            println!("Not watching {watchpoint}");
+        }
+    }
+
     fn handle_result(&mut self, result: DebugCommandResult) {
         self.last_result = result;
         match &self.last_result {
@@ -408,6 +535,14 @@ impl<'a> AsyncReplDebugger<'a> {
             DebugCommandResult::BreakpointReached(location) => {
                 println!("Stopped at breakpoint in opcode {}", location);
             }
+            DebugCommandResult::WatchpointTriggered { watchpoint, old_value, new_value } => {
+                match old_value {
+                    Some(old_value) => {
+                        println!("Watchpoint {watchpoint} changed from {old_value} to {new_value}");
+                    }
+                    None => println!("Watchpoint {watchpoint} set to {new_value}"),
+                }
+            }
             DebugCommandResult::Error(error) => {
                 println!("ERROR: {}", error);
             }
@@ -419,7 +554,9 @@ impl<'a> AsyncReplDebugger<'a> {
         F: Fn(&mut Context) -> DebugCommandResult,
     {
         let should_execute = match self.last_result {
-            DebugCommandResult::Ok | DebugCommandResult::BreakpointReached(..) => true,
+            DebugCommandResult::Ok
+            | DebugCommandResult::BreakpointReached(..)
+            | DebugCommandResult::WatchpointTriggered { .. } => true,
             DebugCommandResult::Done => {
                 println!("Execution finished");
                 false
@@ -432,11 +569,29 @@ impl<'a> AsyncReplDebugger<'a> {
         };
         if should_execute {
             let result = step(context);
+            for message in context.take_log_messages() {
+                println!("{message}");
+            }
             self.show_current_vm_status(context);
             self.handle_result(result);
         }
     }
 
+    /// Going back is possible even after execution finished or failed, which
+    /// is when it's most useful.
+    fn handle_step_back<F>(&mut self, context: &mut Context<'_>, step_back: F)
+    where
+        F: Fn(&mut Context) -> DebugCommandResult,
+    {
+        if !context.can_step_back() {
+            println!("Already at the start of the execution");
+            return;
+        }
+        let result = step_back(context);
+        self.show_current_vm_status(context);
+        self.handle_result(result);
+    }
+
     fn restart_session(&mut self, context: &mut Context<'_>) {
         context.restart();
         self.last_result = DebugCommandResult::Ok;
@@ -524,6 +679,13 @@ impl<'a> AsyncReplDebugger<'a> {
         }
     }
 
+    fn evaluate(context: &Context<'_>, expression: &str) {
+        match context.evaluate_expression(expression) {
+            Ok((value, typ)) => println!("{}", PrintableValueDisplay::Plain(value, typ)),
+            Err(error) => println!("{error}"),
+        }
+    }
+
     fn terminate(self, context: Context<'_>) {
         let result = if context.is_solved() {
             let solved_witness_stack = context.finalize();
@@ -593,6 +755,12 @@ impl DebugController {
     pub fn next_out(&self) {
         self.call_debugger(DebugCommandAPI::NextOut);
     }
+    pub fn next_back(&self) {
+        self.call_debugger(DebugCommandAPI::NextBack);
+    }
+    pub fn reverse_cont(&self) {
+        self.call_debugger(DebugCommandAPI::ReverseCont);
+    }
     pub fn restart_session(&self) {
         self.call_debugger(DebugCommandAPI::Restart);
     }
@@ -605,6 +773,24 @@ impl DebugController {
     pub fn delete_breakpoint_at(&self, location: DebugLocation) {
         self.call_debugger(DebugCommandAPI::DeleteBreakpoint(location));
     }
+    pub fn set_breakpoint_condition(&self, location: DebugLocation, condition: String) {
+        self.call_debugger(DebugCommandAPI::SetBreakpointCondition(location, condition));
+    }
+    pub fn set_breakpoint_hit_condition(&self, location: DebugLocation, hit_condition: String) {
+        self.call_debugger(DebugCommandAPI::SetBreakpointHitCondition(location, hit_condition));
+    }
+    pub fn add_logpoint_at_line(&self, line_number: i64, message: String) {
+        self.call_debugger(DebugCommandAPI::AddLogpointAtLine(line_number, message));
+    }
+    pub fn add_logpoint_at(&self, location: DebugLocation, message: String) {
+        self.call_debugger(DebugCommandAPI::AddLogpoint(location, message));
+    }
+    pub fn add_watchpoint(&self, watchpoint: Watchpoint) {
+        self.call_debugger(DebugCommandAPI::AddWatchpoint(watchpoint));
+    }
+    pub fn delete_watchpoint(&self, watchpoint: Watchpoint) {
+        self.call_debugger(DebugCommandAPI::DeleteWatchpoint(watchpoint));
+    }
     pub fn update_witness(&self, index: u32, value: String) {
         self.call_debugger(DebugCommandAPI::UpdateWitness(index, value));
     }
@@ -614,6 +800,9 @@ impl DebugController {
     pub fn show_vars(&self) {
         self.call_debugger(DebugCommandAPI::ShowVariables);
     }
+    pub fn evaluate(&self, expression: String) {
+        self.call_debugger(DebugCommandAPI::Evaluate(expression));
+    }
     pub fn show_opcodes(&self) {
         self.call_debugger(DebugCommandAPI::ShowOpcodes);
     }
@@ -733,6 +922,26 @@ pub fn run(project: DebugProject, run_params: RunParams) -> DebugExecutionResult
                 }
             },
         )
+        .add(
+            "back",
+            command! {
+                "step back until the previous source location is reached",
+                () => || {
+                    ref_context.borrow_mut().next_back();
+                    Ok(CommandStatus::Done)
+                }
+            },
+        )
+        .add(
+            "reverse-continue",
+            command! {
+                "continue execution backwards until the previous breakpoint or the start of the program",
+                () => || {
+                    ref_context.borrow_mut().reverse_cont();
+                    Ok(CommandStatus::Done)
+                }
+            },
+        )
         .add(
             "restart",
             command! {
@@ -783,6 +992,66 @@ pub fn run(project: DebugProject, run_params: RunParams) -> DebugExecutionResult
                 }
             },
         )
+        .add(
+            "condition",
+            command! {
+                "set the condition of the breakpoint at an opcode location (eg. \"x > 3\"), or clear it with \"\"",
+                (LOCATION:DebugLocation, condition: String) => |location, condition| {
+                    ref_context.borrow_mut().set_breakpoint_condition(location, condition);
+                    Ok(CommandStatus::Done)
+                }
+            },
+        )
+        .add(
+            "hitcount",
+            command! {
+                "only stop at the breakpoint at an opcode location when its hit count matches (N, == N, > N or % N)",
+                (LOCATION:DebugLocation, hit_condition: String) => |location, hit_condition| {
+                    ref_context.borrow_mut().set_breakpoint_hit_condition(location, hit_condition);
+                    Ok(CommandStatus::Done)
+                }
+            },
+        )
+        .add(
+            "log",
+            command! {
+                "add a logpoint at a line of the current file, printing a message with {values} in braces",
+                (line_number: i64, message: String) => |line_number, message| {
+                    ref_context.borrow_mut().add_logpoint_at_line(line_number, message);
+                    Ok(CommandStatus::Done)
+                }
+            },
+        )
+        .add(
+            "log",
+            command! {
+                "add a logpoint at an opcode location, printing a message with {values} in braces",
+                (LOCATION:DebugLocation, message: String) => |location, message| {
+                    ref_context.borrow_mut().add_logpoint_at(location, message);
+                    Ok(CommandStatus::Done)
+                }
+            },
+        )
+        .add(
+            "watch",
+            command! {
+                "stop when a witness (_N) or Brillig memory slot (mem[N]) changes value",
+                (WATCHPOINT:Watchpoint) => |watchpoint| {
+                    ref_context.borrow_mut().add_watchpoint(watchpoint);
+                    Ok(CommandStatus::Done)
+                }
+            },
+        )
+        .add(
+            "unwatch",
+            command! {
+                "delete a watchpoint on a witness (_N) or Brillig memory slot (mem[N])",
+                (WATCHPOINT:Watchpoint) => |watchpoint| {
+                    ref_context.borrow_mut().delete_watchpoint(watchpoint);
+                    Ok(CommandStatus::Done)
+                }
+            },
+        )
         .add(
             "witness",
             command! {
@@ -853,6 +1122,26 @@ pub fn run(project: DebugProject, run_params: RunParams) -> DebugExecutionResult
                 }
             },
         )
+        .add(
+            "print",
+            command! {
+                "evaluate a Noir expression over the variables in scope (eg. \"x.len() + y[3]\")",
+                (expression: String) => |expression| {
+                    ref_context.borrow().evaluate(expression);
+                    Ok(CommandStatus::Done)
+                }
+            },
+        )
+        .add(
+            "eval",
+            command! {
+                "evaluate a Noir expression over the variables in scope, same as print",
+                (expression: String) => |expression| {
+                    ref_context.borrow().evaluate(expression);
+                    Ok(CommandStatus::Done)
+                }
+            },
+        )
         .build()
         .expect("Failed to initialize debugger repl");
 
@@ -15,8 +15,11 @@ use acvm::{BlackBoxFunctionSolver, FieldElement};
 use async_lsp::lsp_types::{
     CodeLens,
     request::{
-        CodeActionRequest, Completion, DocumentSymbolRequest, HoverRequest, InlayHintRequest,
-        PrepareRenameRequest, References, Rename, SignatureHelpRequest, WorkspaceSymbolRequest,
+        CallHierarchyIncomingCalls, CallHierarchyOutgoingCalls, CallHierarchyPrepare,
+        CodeActionRequest, Completion, DocumentHighlightRequest, DocumentSymbolRequest,
+        FoldingRangeRequest, HoverRequest, InlayHintRequest, PrepareRenameRequest, References,
+        Rename, SemanticTokensFullRequest, SignatureHelpRequest, TypeHierarchyPrepare,
+        TypeHierarchySubtypes, TypeHierarchySupertypes, WorkspaceSymbolRequest,
     },
 };
 use async_lsp::{
@@ -51,10 +54,14 @@ use notifications::{
 };
 use requests::{
     LspInitializationOptions, WorkspaceSymbolCache, on_code_action_request, on_code_lens_request,
-    on_completion_request, on_document_symbol_request, on_formatting, on_goto_declaration_request,
-    on_goto_definition_request, on_goto_type_definition_request, on_hover_request, on_initialize,
-    on_inlay_hint_request, on_prepare_rename_request, on_references_request, on_rename_request,
-    on_shutdown, on_signature_help_request, on_test_run_request, on_tests_request,
+    on_completion_request, on_document_highlight_request, on_document_symbol_request,
+    on_folding_range_request, on_formatting, on_goto_declaration_request,
+    on_goto_definition_request, on_goto_type_definition_request, on_hover_request,
+    on_incoming_calls_request, on_initialize, on_inlay_hint_request, on_outgoing_calls_request,
+    on_prepare_call_hierarchy_request, on_prepare_rename_request,
+    on_prepare_type_hierarchy_request, on_references_request, on_rename_request,
+    on_semantic_tokens_full_request, on_shutdown, on_signature_help_request, on_test_run_request,
+    on_tests_request, on_type_hierarchy_subtypes_request, on_type_hierarchy_supertypes_request,
     on_workspace_symbol_request,
 };
 use serde_json::Value as JsonValue;
@@ -173,6 +180,15 @@ impl NargoLspService {
             .request::<CodeActionRequest, _>(on_code_action_request)
             .request::<WorkspaceSymbolRequest, _>(on_workspace_symbol_request)
             .request::<NargoExpand, _>(on_expand_request)
+            .request::<SemanticTokensFullRequest, _>(on_semantic_tokens_full_request)
+            .request::<FoldingRangeRequest, _>(on_folding_range_request)
+            .request::<DocumentHighlightRequest, _>(on_document_highlight_request)
+            .request::<CallHierarchyPrepare, _>(on_prepare_call_hierarchy_request)
+            .request::<CallHierarchyIncomingCalls, _>(on_incoming_calls_request)
+            .request::<CallHierarchyOutgoingCalls, _>(on_outgoing_calls_request)
+            .request::<TypeHierarchyPrepare, _>(on_prepare_type_hierarchy_request)
+            .request::<TypeHierarchySupertypes, _>(on_type_hierarchy_supertypes_request)
+            .request::<TypeHierarchySubtypes, _>(on_type_hierarchy_subtypes_request)
             .notification::<notification::Initialized>(on_initialized)
             .notification::<notification::DidChangeConfiguration>(on_did_change_configuration)
             .notification::<notification::DidOpenTextDocument>(on_did_open_text_document)
@@ -0,0 +1,274 @@
+use std::future::{self, Future};
+
+use async_lsp::ResponseError;
+use async_lsp::lsp_types::{
+    CallHierarchyIncomingCall, CallHierarchyIncomingCallsParams, CallHierarchyItem,
+    CallHierarchyOutgoingCall, CallHierarchyOutgoingCallsParams, CallHierarchyPrepareParams,
+    SymbolKind, TextDocumentIdentifier, TextDocumentPositionParams,
+};
+use fm::FileMap;
+use noirc_frontend::node_interner::{FuncId, NodeInterner, ReferenceId};
+
+use crate::LspState;
+
+use super::{process_request, to_lsp_location};
+
+pub(crate) fn on_prepare_call_hierarchy_request(
+    state: &mut LspState,
+    params: CallHierarchyPrepareParams,
+) -> impl Future<Output = Result<Option<Vec<CallHierarchyItem>>, ResponseError>> + use<> {
+    let result = process_request(state, params.text_document_position_params, |args| {
+        let ReferenceId::Function(func_id) = args.interner.find_referenced(args.location)? else {
+            return None;
+        };
+        let item = call_hierarchy_item(func_id, args.interner, args.files)?;
+        Some(vec![item])
+    });
+    future::ready(result)
+}
+
+pub(crate) fn on_incoming_calls_request(
+    state: &mut LspState,
+    params: CallHierarchyIncomingCallsParams,
+) -> impl Future<Output = Result<Option<Vec<CallHierarchyIncomingCall>>, ResponseError>> + use<> {
+    let result = process_request(state, item_position(&params.item), |args| {
+        let location = args.location;
+
+        // Callers might live in any package of the workspace, each one with its own interner
+        let interners = std::iter::once(args.interner)
+            .chain(args.package_cache.values().map(|cache_data| &cache_data.node_interner));
+
+        let mut calls: Vec<CallHierarchyIncomingCall> = Vec::new();
+        for interner in interners {
+            let Some(ReferenceId::Function(func_id)) = interner.find_referenced(location) else {
+                continue;
+            };
+
+            for (caller, locations) in interner.find_function_callers(func_id) {
+                let Some(from) = call_hierarchy_item(caller, interner, args.files) else {
+                    continue;
+                };
+                if calls
+                    .iter()
+                    .any(|call| call.from.uri == from.uri && call.from.range == from.range)
+                {
+                    continue;
+                }
+
+                let from_ranges = locations
+                    .iter()
+                    .filter_map(|location| {
+                        to_lsp_location(args.files, location.file, location.span)
+                    })
+                    .map(|location| location.range)
+                    .collect();
+                calls.push(CallHierarchyIncomingCall { from, from_ranges });
+            }
+        }
+
+        Some(calls)
+    });
+    future::ready(result)
+}
+
+pub(crate) fn on_outgoing_calls_request(
+    state: &mut LspState,
+    params: CallHierarchyOutgoingCallsParams,
+) -> impl Future<Output = Result<Option<Vec<CallHierarchyOutgoingCall>>, ResponseError>> + use<> {
+    let result = process_request(state, item_position(&params.item), |args| {
+        let ReferenceId::Function(func_id) = args.interner.find_referenced(args.location)? else {
+            return None;
+        };
+
+        let calls = args
+            .interner
+            .find_function_callees(func_id)
+            .into_iter()
+            .filter_map(|(callee, locations)| {
+                let to = call_hierarchy_item(callee, args.interner, args.files)?;
+                let from_ranges = locations
+                    .iter()
+                    .filter_map(|location| {
+                        to_lsp_location(args.files, location.file, location.span)
+                    })
+                    .map(|location| location.range)
+                    .collect();
+                Some(CallHierarchyOutgoingCall { to, from_ranges })
+            })
+            .collect();
+        Some(calls)
+    });
+    future::ready(result)
+}
+
+/// The position of the name of an item, used to find the function it refers to.
+fn item_position(item: &CallHierarchyItem) -> TextDocumentPositionParams {
+    TextDocumentPositionParams {
+        text_document: TextDocumentIdentifier { uri: item.uri.clone() },
+        position: item.selection_range.start,
+    }
+}
+
+fn call_hierarchy_item(
+    func_id: FuncId,
+    interner: &NodeInterner,
+    files: &FileMap,
+) -> Option<CallHierarchyItem> {
+    let func_meta = interner.function_meta(&func_id);
+    let name_location = func_meta.name.location;
+
+    // The range covers the whole function, from its name to the end of its body
+    let mut location = name_location;
+    if let Some(body) = interner.function(&func_id).try_as_expr() {
+        location = location.merge(interner.expr_location(&body));
+    }
+
+    let selection_range = to_lsp_location(files, name_location.file, name_location.span)?;
+    let range = to_lsp_location(files, location.file, location.span)?;
+
+    let is_method = func_meta.type_id.is_some()
+        || func_meta.trait_id.is_some()
+        || func_meta.trait_impl.is_some();
+    let kind = if is_method { SymbolKind::METHOD } else { SymbolKind::FUNCTION };
+
+    let detail = if let Some(type_id) = func_meta.type_id {
+        Some(interner.get_type(type_id).borrow().name.to_string())
+    } else {
+        func_meta.trait_id.map(|trait_id| interner.get_trait(trait_id).name.to_string())
+    };
+
+    Some(CallHierarchyItem {
+        name: interner.function_name(&func_id).to_string(),
+        kind,
+        tags: None,
+        detail,
+        uri: selection_range.uri,
+        range: range.range,
+        selection_range: selection_range.range,
+        data: None,
+    })
+}
+
+#[cfg(test)]
+mod call_hierarchy_tests {
+    use crate::{
+        notifications::on_did_open_text_document, test_utils, utils::get_cursor_line_and_column,
+    };
+
+    use super::*;
+    use async_lsp::lsp_types::{
+        DidOpenTextDocumentParams, PartialResultParams, Position, TextDocumentItem,
+        WorkDoneProgressParams,
+    };
+    use tokio::test;
+
+    async fn prepare(src: &str) -> (LspState, CallHierarchyItem) {
+        let (mut state, noir_text_document) = test_utils::init_lsp_server("document_symbol").await;
+
+        let (line, column, src) = get_cursor_line_and_column(src);
+
+        let _ = on_did_open_text_document(
+            &mut state,
+            DidOpenTextDocumentParams {
+                text_document: TextDocumentItem {
+                    uri: noir_text_document.clone(),
+                    language_id: "noir".to_string(),
+                    version: 0,
+                    text: src.to_string(),
+                },
+            },
+        );
+
+        let items = on_prepare_call_hierarchy_request(
+            &mut state,
+            CallHierarchyPrepareParams {
+                text_document_position_params: TextDocumentPositionParams {
+                    text_document: TextDocumentIdentifier { uri: noir_text_document },
+                    position: Position { line: line as u32, character: column as u32 },
+                },
+                work_done_progress_params: WorkDoneProgressParams { work_done_token: None },
+            },
+        )
+        .await
+        .expect("Could not execute on_prepare_call_hierarchy_request")
+        .expect("Expected a call hierarchy item");
+        assert_eq!(items.len(), 1);
+
+        (state, items.into_iter().next().unwrap())
+    }
+
+    const SRC: &str = r#"fn main() {
+    let foo = Foo { x: 1 };
+    assert(foo.double() == two());
+}
+
+fn two() -> Field {
+    let one = 1;
+    one + one
+}
+
+struct Foo { x: Field }
+
+impl Foo {
+    fn double(self) -> Field {
+        self.x * two()
+    }
+}
+"#;
+
+    #[test]
+    async fn test_incoming_calls() {
+        let src = SRC.replacen("fn two", "fn t>|<wo", 1);
+        let (mut state, item) = prepare(&src).await;
+        assert_eq!(item.name, "two");
+        assert_eq!(item.kind, SymbolKind::FUNCTION);
+
+        let calls = on_incoming_calls_request(
+            &mut state,
+            CallHierarchyIncomingCallsParams {
+                item,
+                work_done_progress_params: WorkDoneProgressParams { work_done_token: None },
+                partial_result_params: PartialResultParams { partial_result_token: None },
+            },
+        )
+        .await
+        .expect("Could not execute on_incoming_calls_request")
+        .unwrap();
+
+        let callers: Vec<_> = calls
+            .iter()
+            .map(|call| {
+                let lines: Vec<u32> =
+                    call.from_ranges.iter().map(|range| range.start.line).collect();
+                (call.from.name.as_str(), call.from.kind, lines)
+            })
+            .collect();
+        assert_eq!(
+            callers,
+            vec![("main", SymbolKind::FUNCTION, vec![2]), ("double", SymbolKind::METHOD, vec![14])]
+        );
+    }
+
+    #[test]
+    async fn test_outgoing_calls() {
+        let src = SRC.replacen("fn main", "fn m>|<ain", 1);
+        let (mut state, item) = prepare(&src).await;
+        assert_eq!(item.name, "main");
+
+        let calls = on_outgoing_calls_request(
+            &mut state,
+            CallHierarchyOutgoingCallsParams {
+                item,
+                work_done_progress_params: WorkDoneProgressParams { work_done_token: None },
+                partial_result_params: PartialResultParams { partial_result_token: None },
+            },
+        )
+        .await
+        .expect("Could not execute on_outgoing_calls_request")
+        .unwrap();
+
+        let callees: Vec<_> = calls.iter().map(|call| call.to.name.as_str()).collect();
+        assert_eq!(callees, vec!["double", "two"]);
+        assert_eq!(calls[0].to.detail, Some("Foo".to_string()));
+    }
+}
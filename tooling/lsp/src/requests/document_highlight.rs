@@ -0,0 +1,129 @@
+use std::future::{self, Future};
+
+use async_lsp::ResponseError;
+use async_lsp::lsp_types::{DocumentHighlight, DocumentHighlightKind, DocumentHighlightParams};
+
+use crate::LspState;
+
+use super::{find_all_references_in_workspace, process_request, to_lsp_location};
+
+pub(crate) fn on_document_highlight_request(
+    state: &mut LspState,
+    params: DocumentHighlightParams,
+) -> impl Future<Output = Result<Option<Vec<DocumentHighlight>>, ResponseError>> + use<> {
+    let uri = params.text_document_position_params.text_document.uri.clone();
+    let result = process_request(state, params.text_document_position_params, |args| {
+        let referenced = args.interner.find_referenced(args.location)?;
+        let referenced_location = args.interner.reference_location(referenced);
+        let declaration =
+            to_lsp_location(args.files, referenced_location.file, referenced_location.span);
+
+        let locations = find_all_references_in_workspace(
+            args.location,
+            args.interner,
+            args.package_cache,
+            args.files,
+            true,
+            true,
+        )?;
+
+        // Highlights are only shown in the current document, and the declaration
+        // is distinguished from the places where the symbol is used.
+        let highlights = locations
+            .into_iter()
+            .filter(|location| location.uri == uri)
+            .map(|location| {
+                let kind = if declaration.as_ref() == Some(&location) {
+                    DocumentHighlightKind::WRITE
+                } else {
+                    DocumentHighlightKind::READ
+                };
+                DocumentHighlight { range: location.range, kind: Some(kind) }
+            })
+            .collect::<Vec<_>>();
+
+        if highlights.is_empty() { None } else { Some(highlights) }
+    });
+    future::ready(result)
+}
+
+#[cfg(test)]
+mod document_highlight_tests {
+    use crate::{
+        notifications::on_did_open_text_document, test_utils, utils::get_cursor_line_and_column,
+    };
+
+    use super::*;
+    use async_lsp::lsp_types::{
+        DidOpenTextDocumentParams, PartialResultParams, Position, Range, TextDocumentIdentifier,
+        TextDocumentItem, TextDocumentPositionParams, WorkDoneProgressParams,
+    };
+    use tokio::test;
+
+    async fn get_highlights(src: &str) -> Vec<(Range, DocumentHighlightKind)> {
+        let (mut state, noir_text_document) = test_utils::init_lsp_server("document_symbol").await;
+
+        let (line, column, src) = get_cursor_line_and_column(src);
+
+        let _ = on_did_open_text_document(
+            &mut state,
+            DidOpenTextDocumentParams {
+                text_document: TextDocumentItem {
+                    uri: noir_text_document.clone(),
+                    language_id: "noir".to_string(),
+                    version: 0,
+                    text: src.to_string(),
+                },
+            },
+        );
+
+        let highlights = on_document_highlight_request(
+            &mut state,
+            DocumentHighlightParams {
+                text_document_position_params: TextDocumentPositionParams {
+                    text_document: TextDocumentIdentifier { uri: noir_text_document },
+                    position: Position { line: line as u32, character: column as u32 },
+                },
+                work_done_progress_params: WorkDoneProgressParams { work_done_token: None },
+                partial_result_params: PartialResultParams { partial_result_token: None },
+            },
+        )
+        .await
+        .expect("Could not execute on_document_highlight_request")
+        .unwrap_or_default();
+
+        highlights.into_iter().map(|highlight| (highlight.range, highlight.kind.unwrap())).collect()
+    }
+
+    fn range(line: u32, start: u32, end: u32) -> Range {
+        Range { start: Position { line, character: start }, end: Position { line, character: end } }
+    }
+
+    #[test]
+    async fn test_highlights_local_variable() {
+        let src = r#"fn main() {
+    let value = 1;
+    let other = v>|<alue + 2;
+    assert(value + other == 4);
+}"#;
+        let highlights = get_highlights(src).await;
+        assert_eq!(
+            highlights,
+            vec![
+                (range(1, 8, 13), DocumentHighlightKind::WRITE),
+                (range(2, 16, 21), DocumentHighlightKind::READ),
+                (range(3, 11, 16), DocumentHighlightKind::READ),
+            ]
+        );
+    }
+
+    #[test]
+    async fn test_no_highlights_outside_symbols() {
+        let src = r#"fn main() {
+    let value = 1;
+    >|<
+    assert(value == 1);
+}"#;
+        assert!(get_highlights(src).await.is_empty());
+    }
+}
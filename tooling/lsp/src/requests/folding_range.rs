@@ -0,0 +1,278 @@
+use std::future::{self, Future};
+
+use async_lsp::ResponseError;
+use async_lsp::lsp_types::{
+    FoldingRange, FoldingRangeKind, FoldingRangeParams, Position, TextDocumentPositionParams,
+};
+use fm::{FileId, FileMap, PathString};
+use noirc_errors::Span;
+use noirc_frontend::{
+    ast::{
+        ItemVisibility, NoirEnumeration, NoirFunction, NoirStruct, NoirTrait, NoirTraitImpl,
+        TypeImpl, UseTree, Visitor,
+    },
+    lexer::Lexer,
+    parser::ParsedSubModule,
+    token::{DocStyle, Token},
+};
+
+use crate::LspState;
+
+use super::process_request;
+
+pub(crate) fn on_folding_range_request(
+    state: &mut LspState,
+    params: FoldingRangeParams,
+) -> impl Future<Output = Result<Option<Vec<FoldingRange>>, ResponseError>> + use<> {
+    let Ok(file_path) = params.text_document.uri.to_file_path() else {
+        return future::ready(Ok(None));
+    };
+
+    let text_document_position_params = TextDocumentPositionParams {
+        text_document: params.text_document.clone(),
+        position: Position { line: 0, character: 0 },
+    };
+
+    let result = process_request(state, text_document_position_params, |args| {
+        args.files.get_file_id(&PathString::from_path(file_path)).map(|file_id| {
+            let source = args.files.get_file(file_id).unwrap().source();
+            collect_folding_ranges(args.files, file_id, source)
+        })
+    });
+
+    future::ready(result)
+}
+
+fn collect_folding_ranges(files: &FileMap, file_id: FileId, source: &str) -> Vec<FoldingRange> {
+    let (parsed_module, _errors) = noirc_frontend::parse_program(source, file_id);
+
+    let mut collector = FoldingRangeCollector { files, file_id, ranges: Vec::new(), imports: None };
+    parsed_module.accept(&mut collector);
+    collector.flush_imports();
+    collector.collect_comments(source);
+
+    let mut ranges = collector.ranges;
+    ranges.sort_by_key(|range| (range.start_line, range.end_line));
+    ranges
+}
+
+struct FoldingRangeCollector<'a> {
+    files: &'a FileMap,
+    file_id: FileId,
+    ranges: Vec<FoldingRange>,
+    /// The lines spanned by the group of consecutive `use` items being visited, if any.
+    imports: Option<(u32, u32)>,
+}
+
+impl FoldingRangeCollector<'_> {
+    fn lines(&self, span: Span) -> Option<(u32, u32)> {
+        let range = crate::byte_span_to_range(self.files, self.file_id, span.into())?;
+        Some((range.start.line, range.end.line))
+    }
+
+    fn push(&mut self, start_line: u32, end_line: u32, kind: Option<FoldingRangeKind>) {
+        if start_line >= end_line {
+            return;
+        }
+
+        self.ranges.push(FoldingRange {
+            start_line,
+            start_character: None,
+            end_line,
+            end_character: None,
+            kind,
+            collapsed_text: None,
+        });
+    }
+
+    fn push_span(&mut self, span: Span) {
+        // Any item other than an import ends the current group of imports
+        self.flush_imports();
+
+        if let Some((start_line, end_line)) = self.lines(span) {
+            self.push(start_line, end_line, None);
+        }
+    }
+
+    fn flush_imports(&mut self) {
+        if let Some((start_line, end_line)) = self.imports.take() {
+            self.push(start_line, end_line, Some(FoldingRangeKind::Imports));
+        }
+    }
+
+    /// Adds a folding range for each multi-line block comment and for each group of
+    /// consecutive line comments of the same style.
+    fn collect_comments(&mut self, source: &str) {
+        let lexer = Lexer::new(source, self.file_id).skip_comments(false);
+
+        let mut line_comments: Option<(u32, u32, Option<DocStyle>)> = None;
+        for token in lexer.flatten() {
+            let Some((start_line, end_line)) = self.lines(token.span()) else {
+                continue;
+            };
+
+            match token.token() {
+                Token::LineComment(_, style) => {
+                    match &mut line_comments {
+                        Some((_, last_line, last_style))
+                            if *last_line + 1 == start_line && last_style == style =>
+                        {
+                            *last_line = start_line;
+                        }
+                        _ => {
+                            if let Some((first, last, _)) = line_comments.take() {
+                                self.push(first, last, Some(FoldingRangeKind::Comment));
+                            }
+                            line_comments = Some((start_line, start_line, *style));
+                        }
+                    }
+                    continue;
+                }
+                Token::BlockComment(..) => {
+                    self.push(start_line, end_line, Some(FoldingRangeKind::Comment));
+                }
+                _ => (),
+            }
+
+            if let Some((first, last, _)) = line_comments.take() {
+                self.push(first, last, Some(FoldingRangeKind::Comment));
+            }
+        }
+
+        if let Some((first, last, _)) = line_comments {
+            self.push(first, last, Some(FoldingRangeKind::Comment));
+        }
+    }
+}
+
+impl Visitor for FoldingRangeCollector<'_> {
+    fn visit_import(&mut self, _: &UseTree, span: Span, _visibility: ItemVisibility) -> bool {
+        let Some((start_line, end_line)) = self.lines(span) else {
+            return false;
+        };
+
+        match &mut self.imports {
+            Some((_, last_line)) if *last_line + 1 >= start_line => *last_line = end_line,
+            _ => {
+                self.flush_imports();
+                self.imports = Some((start_line, end_line));
+            }
+        }
+        false
+    }
+
+    fn visit_parsed_submodule(&mut self, _: &ParsedSubModule, span: Span) -> bool {
+        self.push_span(span);
+        true
+    }
+
+    fn visit_noir_function(&mut self, _: &NoirFunction, span: Span) -> bool {
+        self.push_span(span);
+        false
+    }
+
+    fn visit_noir_trait_impl(&mut self, _: &NoirTraitImpl, span: Span) -> bool {
+        self.push_span(span);
+        true
+    }
+
+    fn visit_type_impl(&mut self, _: &TypeImpl, span: Span) -> bool {
+        self.push_span(span);
+        true
+    }
+
+    fn visit_noir_trait(&mut self, _: &NoirTrait, span: Span) -> bool {
+        self.push_span(span);
+        true
+    }
+
+    fn visit_noir_struct(&mut self, _: &NoirStruct, span: Span) -> bool {
+        self.push_span(span);
+        false
+    }
+
+    fn visit_noir_enum(&mut self, _: &NoirEnumeration, span: Span) -> bool {
+        self.push_span(span);
+        false
+    }
+}
+
+#[cfg(test)]
+mod folding_range_tests {
+    use crate::{notifications::on_did_open_text_document, test_utils};
+
+    use super::*;
+    use async_lsp::lsp_types::{
+        DidOpenTextDocumentParams, PartialResultParams, TextDocumentIdentifier, TextDocumentItem,
+        WorkDoneProgressParams,
+    };
+    use tokio::test;
+
+    async fn get_folding_ranges(src: &str) -> Vec<(u32, u32, Option<FoldingRangeKind>)> {
+        let (mut state, noir_text_document) = test_utils::init_lsp_server("document_symbol").await;
+
+        let _ = on_did_open_text_document(
+            &mut state,
+            DidOpenTextDocumentParams {
+                text_document: TextDocumentItem {
+                    uri: noir_text_document.clone(),
+                    language_id: "noir".to_string(),
+                    version: 0,
+                    text: src.to_string(),
+                },
+            },
+        );
+
+        let ranges = on_folding_range_request(
+            &mut state,
+            FoldingRangeParams {
+                text_document: TextDocumentIdentifier { uri: noir_text_document },
+                work_done_progress_params: WorkDoneProgressParams { work_done_token: None },
+                partial_result_params: PartialResultParams { partial_result_token: None },
+            },
+        )
+        .await
+        .expect("Could not execute on_folding_range_request")
+        .unwrap();
+
+        ranges.into_iter().map(|range| (range.start_line, range.end_line, range.kind)).collect()
+    }
+
+    #[test]
+    async fn test_folding_ranges() {
+        let src = r#"use std::hash::poseidon2;
+use std::hash::keccak256;
+
+/// Some docs
+/// on two lines
+mod foo {
+    struct Bar {
+        x: Field,
+    }
+
+    impl Bar {
+        fn one_line(self) -> Field { self.x }
+    }
+}
+
+/* A block
+   comment */
+fn main() {
+    // A single comment doesn't fold
+}
+"#;
+        let ranges = get_folding_ranges(src).await;
+        assert_eq!(
+            ranges,
+            vec![
+                (0, 1, Some(FoldingRangeKind::Imports)),
+                (3, 4, Some(FoldingRangeKind::Comment)),
+                (5, 13, None),
+                (6, 8, None),
+                (10, 12, None),
+                (15, 16, Some(FoldingRangeKind::Comment)),
+                (17, 19, None),
+            ]
+        );
+    }
+}
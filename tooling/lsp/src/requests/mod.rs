@@ -11,7 +11,9 @@ use async_lsp::{ErrorCode, ResponseError};
 use fm::FileId;
 use fm::{FileMap, PathString, codespan_files::Error};
 use lsp_types::{
-    CodeActionKind, DeclarationCapability, Location, Position, TextDocumentPositionParams,
+    CallHierarchyServerCapability, CodeActionKind, DeclarationCapability,
+    FoldingRangeProviderCapability, Location, Position, SemanticTokensFullOptions,
+    SemanticTokensOptions, SemanticTokensServerCapabilities, TextDocumentPositionParams,
     TextDocumentSyncCapability, TextDocumentSyncKind, TypeDefinitionProviderCapability, Url,
     WorkDoneProgressOptions,
 };
@@ -45,32 +47,40 @@ pub(crate) use workspace_symbol::WorkspaceSymbolCache;
 // They are not attached to the `NargoLspService` struct so they can be unit tested with only `LspState`
 // and params passed in.
 
+mod call_hierarchy;
 mod code_action;
 mod code_lens_request;
 mod completion;
+mod document_highlight;
 mod document_symbol;
 mod expand;
+mod folding_range;
 mod goto_declaration;
 mod goto_definition;
 mod hover;
 mod inlay_hint;
 mod references;
 mod rename;
+mod semantic_tokens;
 mod signature_help;
 mod test_run;
 mod tests;
+mod type_hierarchy;
 mod workspace_symbol;
 
 pub(crate) use {
-    code_action::on_code_action_request, code_lens_request::collect_lenses_for_package,
-    code_lens_request::on_code_lens_request, completion::on_completion_request,
+    call_hierarchy::on_incoming_calls_request, call_hierarchy::on_outgoing_calls_request,
+    call_hierarchy::on_prepare_call_hierarchy_request, code_action::on_code_action_request,
+    code_lens_request::collect_lenses_for_package, code_lens_request::on_code_lens_request,
+    completion::on_completion_request, document_highlight::on_document_highlight_request,
     document_symbol::on_document_symbol_request, expand::on_expand_request,
-    goto_declaration::on_goto_declaration_request, goto_definition::on_goto_definition_request,
-    goto_definition::on_goto_type_definition_request, hover::on_hover_request,
-    inlay_hint::on_inlay_hint_request, references::on_references_request,
+    folding_range::on_folding_range_request, goto_declaration::on_goto_declaration_request,
+    goto_definition::on_goto_definition_request, goto_definition::on_goto_type_definition_request,
+    hover::on_hover_request, inlay_hint::on_inlay_hint_request, references::on_references_request,
     rename::on_prepare_rename_request, rename::on_rename_request,
-    signature_help::on_signature_help_request, test_run::on_test_run_request,
-    tests::on_tests_request, workspace_symbol::on_workspace_symbol_request,
+    semantic_tokens::on_semantic_tokens_full_request, signature_help::on_signature_help_request,
+    test_run::on_test_run_request, tests::on_tests_request,
+    workspace_symbol::on_workspace_symbol_request,
 };
 
 /// LSP client will send initialization request after the server has started.
@@ -302,6 +312,28 @@ pub(crate) fn on_initialize(
                         resolve_provider: None,
                     },
                 )),
+                semantic_tokens_provider: Some(
+                    SemanticTokensServerCapabilities::SemanticTokensOptions(
+                        SemanticTokensOptions {
+                            work_done_progress_options: WorkDoneProgressOptions {
+                                work_done_progress: None,
+                            },
+                            legend: semantic_tokens::semantic_tokens_legend(),
+                            range: None,
+                            full: Some(SemanticTokensFullOptions::Bool(true)),
+                        },
+                    ),
+                ),
+                folding_range_provider: Some(FoldingRangeProviderCapability::Simple(true)),
+                document_highlight_provider: Some(lsp_types::OneOf::Right(
+                    lsp_types::DocumentHighlightOptions {
+                        work_done_progress_options: WorkDoneProgressOptions {
+                            work_done_progress: None,
+                        },
+                    },
+                )),
+                call_hierarchy_provider: Some(CallHierarchyServerCapability::Simple(true)),
+                type_hierarchy_provider: Some(true),
             },
             server_info: None,
         })
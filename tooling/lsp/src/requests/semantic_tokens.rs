@@ -0,0 +1,423 @@
+use std::future::{self, Future};
+
+use async_lsp::ResponseError;
+use async_lsp::lsp_types::{
+    Position, SemanticToken, SemanticTokenModifier, SemanticTokenType, SemanticTokens,
+    SemanticTokensLegend, SemanticTokensParams, SemanticTokensResult, TextDocumentPositionParams,
+};
+use fm::{FileId, FileMap, PathString};
+use noirc_errors::{Location, Span};
+use noirc_frontend::{
+    ast::{BlockExpression, FunctionKind, NoirFunction, Statement, Visitor},
+    node_interner::{DefinitionKind, NodeInterner, ReferenceId},
+    token::Token,
+};
+
+use crate::LspState;
+
+use super::process_request;
+
+/// The token types reported by the server, in the order they are declared in the legend.
+const TOKEN_TYPES: [SemanticTokenType; 11] = [
+    SemanticTokenType::NAMESPACE,
+    SemanticTokenType::TYPE,
+    SemanticTokenType::STRUCT,
+    SemanticTokenType::ENUM,
+    SemanticTokenType::ENUM_MEMBER,
+    SemanticTokenType::INTERFACE,
+    SemanticTokenType::TYPE_PARAMETER,
+    SemanticTokenType::VARIABLE,
+    SemanticTokenType::PROPERTY,
+    SemanticTokenType::FUNCTION,
+    SemanticTokenType::METHOD,
+];
+
+/// Modifiers reported by the server, each one corresponding to a bit in a token's modifiers bitset.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Modifier {
+    Declaration,
+    /// A mutable binding.
+    Mutable,
+    /// Code that runs at compile-time: comptime functions, blocks and bindings.
+    Comptime,
+    Unconstrained,
+    Oracle,
+    /// A method declared in, or implementing, a trait.
+    Trait,
+}
+
+const MODIFIERS: [Modifier; 6] = [
+    Modifier::Declaration,
+    Modifier::Mutable,
+    Modifier::Comptime,
+    Modifier::Unconstrained,
+    Modifier::Oracle,
+    Modifier::Trait,
+];
+
+impl Modifier {
+    fn lsp_modifier(self) -> SemanticTokenModifier {
+        match self {
+            Modifier::Declaration => SemanticTokenModifier::DECLARATION,
+            Modifier::Mutable => SemanticTokenModifier::new("mutable"),
+            Modifier::Comptime => SemanticTokenModifier::new("comptime"),
+            Modifier::Unconstrained => SemanticTokenModifier::new("unconstrained"),
+            Modifier::Oracle => SemanticTokenModifier::new("oracle"),
+            Modifier::Trait => SemanticTokenModifier::new("trait"),
+        }
+    }
+
+    fn bit(self) -> u32 {
+        1 << MODIFIERS.iter().position(|modifier| *modifier == self).unwrap()
+    }
+}
+
+pub(crate) fn semantic_tokens_legend() -> SemanticTokensLegend {
+    SemanticTokensLegend {
+        token_types: TOKEN_TYPES.to_vec(),
+        token_modifiers: MODIFIERS.iter().map(|modifier| modifier.lsp_modifier()).collect(),
+    }
+}
+
+pub(crate) fn on_semantic_tokens_full_request(
+    state: &mut LspState,
+    params: SemanticTokensParams,
+) -> impl Future<Output = Result<Option<SemanticTokensResult>, ResponseError>> + use<> {
+    let text_document_position_params = TextDocumentPositionParams {
+        text_document: params.text_document.clone(),
+        position: Position { line: 0, character: 0 },
+    };
+
+    let result = process_request(state, text_document_position_params, |args| {
+        let path = PathString::from_path(params.text_document.uri.to_file_path().unwrap());
+        args.files.get_file_id(&path).map(|file_id| {
+            let data = collect_semantic_tokens(args.files, file_id, args.interner);
+            SemanticTokensResult::Tokens(SemanticTokens { result_id: None, data })
+        })
+    });
+    future::ready(result)
+}
+
+/// Classifies every identifier in a file using the resolved information in the interner,
+/// returning the tokens in the relative encoding expected by LSP clients.
+fn collect_semantic_tokens(
+    files: &FileMap,
+    file_id: FileId,
+    interner: &NodeInterner,
+) -> Vec<SemanticToken> {
+    let source = files.get_file(file_id).unwrap().source();
+
+    let (parsed_module, _errors) = noirc_frontend::parse_program(source, file_id);
+    let mut comptime_collector = ComptimeSpanCollector::default();
+    parsed_module.accept(&mut comptime_collector);
+    let comptime_spans = comptime_collector.spans;
+
+    let mut data = Vec::new();
+    let mut previous = Position { line: 0, character: 0 };
+
+    let (tokens, _errors) = noirc_frontend::lexer::Lexer::lex(source, file_id);
+    for token in tokens.0 {
+        if !matches!(token.token(), Token::Ident(..)) {
+            continue;
+        }
+
+        let span = token.span();
+        let location = Location::new(span, file_id);
+        let Some(referenced) = interner.find_referenced(location) else {
+            continue;
+        };
+        let Some((token_type, mut modifiers)) = classify(referenced, interner) else {
+            continue;
+        };
+
+        let is_declaration = interner
+            .reference_at_location(location)
+            .is_some_and(|reference| !matches!(reference, ReferenceId::Reference(..)));
+        if is_declaration {
+            modifiers |= Modifier::Declaration.bit();
+        }
+        if comptime_spans.iter().any(|comptime_span| comptime_span.contains(&span)) {
+            modifiers |= Modifier::Comptime.bit();
+        }
+
+        let Some(range) = crate::byte_span_to_range(files, file_id, span.into()) else {
+            continue;
+        };
+        // Identifiers never span multiple lines
+        let start = range.start;
+        let delta_line = start.line - previous.line;
+        let delta_start =
+            if delta_line == 0 { start.character - previous.character } else { start.character };
+
+        data.push(SemanticToken {
+            delta_line,
+            delta_start,
+            length: range.end.character - start.character,
+            token_type: token_type_index(token_type),
+            token_modifiers_bitset: modifiers,
+        });
+        previous = start;
+    }
+
+    data
+}
+
+/// Returns the token type and modifiers for something referenced in the code.
+fn classify(referenced: ReferenceId, interner: &NodeInterner) -> Option<(SemanticTokenType, u32)> {
+    let classified = match referenced {
+        ReferenceId::Module(_) => (SemanticTokenType::NAMESPACE, 0),
+        ReferenceId::Type(type_id) => {
+            if interner.get_type(type_id).borrow().is_enum() {
+                (SemanticTokenType::ENUM, 0)
+            } else {
+                (SemanticTokenType::STRUCT, 0)
+            }
+        }
+        ReferenceId::StructMember(..) => (SemanticTokenType::PROPERTY, 0),
+        ReferenceId::EnumVariant(..) => (SemanticTokenType::ENUM_MEMBER, 0),
+        ReferenceId::Trait(_) => (SemanticTokenType::INTERFACE, 0),
+        ReferenceId::TraitAssociatedType(_) | ReferenceId::Alias(_) => (SemanticTokenType::TYPE, 0),
+        ReferenceId::Global(global_id) => {
+            let definition = interner.definition(interner.get_global(global_id).definition_id);
+            (
+                SemanticTokenType::VARIABLE,
+                binding_modifiers(definition.mutable, definition.comptime),
+            )
+        }
+        ReferenceId::Function(func_id) => {
+            let modifiers = interner.function_modifiers(&func_id);
+            let func_meta = interner.function_meta(&func_id);
+            let is_trait_method = func_meta.trait_id.is_some() || func_meta.trait_impl.is_some();
+
+            let mut bits = 0;
+            if modifiers.is_comptime {
+                bits |= Modifier::Comptime.bit();
+            }
+            if modifiers.is_unconstrained {
+                bits |= Modifier::Unconstrained.bit();
+            }
+            if func_meta.kind == FunctionKind::Oracle {
+                bits |= Modifier::Oracle.bit();
+            }
+            if is_trait_method {
+                bits |= Modifier::Trait.bit();
+            }
+
+            if is_trait_method || func_meta.type_id.is_some() {
+                (SemanticTokenType::METHOD, bits)
+            } else {
+                (SemanticTokenType::FUNCTION, bits)
+            }
+        }
+        ReferenceId::Local(definition_id) => {
+            let definition = interner.definition(definition_id);
+            let token_type = match definition.kind {
+                DefinitionKind::NumericGeneric(..) => SemanticTokenType::TYPE_PARAMETER,
+                DefinitionKind::Function(_) => SemanticTokenType::FUNCTION,
+                DefinitionKind::Global(_)
+                | DefinitionKind::Local(_)
+                | DefinitionKind::AssociatedConstant(..) => SemanticTokenType::VARIABLE,
+            };
+            (token_type, binding_modifiers(definition.mutable, definition.comptime))
+        }
+        ReferenceId::Reference(..) => return None,
+    };
+    Some(classified)
+}
+
+fn binding_modifiers(mutable: bool, comptime: bool) -> u32 {
+    let mut bits = 0;
+    if mutable {
+        bits |= Modifier::Mutable.bit();
+    }
+    if comptime {
+        bits |= Modifier::Comptime.bit();
+    }
+    bits
+}
+
+fn token_type_index(token_type: SemanticTokenType) -> u32 {
+    TOKEN_TYPES.iter().position(|typ| *typ == token_type).unwrap() as u32
+}
+
+/// Collects the spans of code that is evaluated at compile-time.
+#[derive(Default)]
+struct ComptimeSpanCollector {
+    spans: Vec<Span>,
+}
+
+impl Visitor for ComptimeSpanCollector {
+    fn visit_noir_function(&mut self, noir_function: &NoirFunction, span: Span) -> bool {
+        if noir_function.def.is_comptime {
+            self.spans.push(span);
+            return false;
+        }
+        true
+    }
+
+    fn visit_comptime_expression(&mut self, _: &BlockExpression, span: Span) -> bool {
+        self.spans.push(span);
+        false
+    }
+
+    fn visit_comptime_statement(&mut self, statement: &Statement) -> bool {
+        self.spans.push(statement.location.span);
+        false
+    }
+}
+
+#[cfg(test)]
+mod semantic_tokens_tests {
+    use crate::{notifications::on_did_open_text_document, test_utils};
+
+    use super::*;
+    use async_lsp::lsp_types::{
+        DidOpenTextDocumentParams, PartialResultParams, TextDocumentIdentifier, TextDocumentItem,
+        WorkDoneProgressParams,
+    };
+    use tokio::test;
+
+    /// Returns the text, token type and modifiers of every semantic token in `src`.
+    async fn get_semantic_tokens(src: &str) -> Vec<(String, SemanticTokenType, Vec<Modifier>)> {
+        let (mut state, noir_text_document) = test_utils::init_lsp_server("document_symbol").await;
+
+        let _ = on_did_open_text_document(
+            &mut state,
+            DidOpenTextDocumentParams {
+                text_document: TextDocumentItem {
+                    uri: noir_text_document.clone(),
+                    language_id: "noir".to_string(),
+                    version: 0,
+                    text: src.to_string(),
+                },
+            },
+        );
+
+        let response = on_semantic_tokens_full_request(
+            &mut state,
+            SemanticTokensParams {
+                text_document: TextDocumentIdentifier { uri: noir_text_document },
+                work_done_progress_params: WorkDoneProgressParams { work_done_token: None },
+                partial_result_params: PartialResultParams { partial_result_token: None },
+            },
+        )
+        .await
+        .expect("Could not execute on_semantic_tokens_full_request")
+        .unwrap();
+
+        let SemanticTokensResult::Tokens(tokens) = response else {
+            panic!("Expected full semantic tokens");
+        };
+
+        // Decode the relative positions back into text
+        let lines: Vec<&str> = src.lines().collect();
+        let mut line = 0;
+        let mut character = 0;
+        tokens
+            .data
+            .into_iter()
+            .map(|token| {
+                if token.delta_line > 0 {
+                    line += token.delta_line;
+                    character = token.delta_start;
+                } else {
+                    character += token.delta_start;
+                }
+                let start = character as usize;
+                let text = lines[line as usize][start..start + token.length as usize].to_string();
+                let token_type = TOKEN_TYPES[token.token_type as usize].clone();
+                let modifiers = MODIFIERS
+                    .into_iter()
+                    .filter(|modifier| token.token_modifiers_bitset & modifier.bit() != 0)
+                    .collect();
+                (text, token_type, modifiers)
+            })
+            .collect()
+    }
+
+    fn find<'a>(
+        tokens: &'a [(String, SemanticTokenType, Vec<Modifier>)],
+        text: &str,
+    ) -> Vec<&'a (String, SemanticTokenType, Vec<Modifier>)> {
+        tokens.iter().filter(|(token_text, ..)| token_text == text).collect()
+    }
+
+    #[test]
+    async fn test_semantic_tokens_for_functions() {
+        let src = r#"
+        unconstrained fn hint() -> Field { 1 }
+
+        #[oracle(get_value)]
+        unconstrained fn get_value() -> Field {}
+
+        comptime fn generate() -> Field { 2 }
+
+        fn main() {
+            let x = unsafe { hint() };
+            let _ = unsafe { get_value() };
+            let _ = comptime { generate() };
+            assert(x == 1);
+        }
+        "#;
+        let tokens = get_semantic_tokens(src).await;
+
+        let hint = find(&tokens, "hint");
+        assert_eq!(hint.len(), 2);
+        assert_eq!(hint[0].1, SemanticTokenType::FUNCTION);
+        assert_eq!(hint[0].2, vec![Modifier::Declaration, Modifier::Unconstrained]);
+        assert_eq!(hint[1].2, vec![Modifier::Unconstrained]);
+
+        let get_value = find(&tokens, "get_value");
+        assert_eq!(get_value[1].2, vec![Modifier::Unconstrained, Modifier::Oracle]);
+
+        let generate = find(&tokens, "generate");
+        assert_eq!(generate[0].2, vec![Modifier::Declaration, Modifier::Comptime]);
+        assert_eq!(generate[1].2, vec![Modifier::Comptime]);
+
+        let main = find(&tokens, "main");
+        assert_eq!(main[0].2, vec![Modifier::Declaration]);
+    }
+
+    #[test]
+    async fn test_semantic_tokens_for_bindings_and_types() {
+        let src = r#"
+        struct Foo { value: Field }
+
+        trait Bar { fn bar(self) -> Field; }
+
+        impl Bar for Foo {
+            fn bar(self) -> Field { self.value }
+        }
+
+        fn main() {
+            let mut counter = 0;
+            let foo = Foo { value: 1 };
+            counter += foo.bar();
+            assert(counter == 1);
+        }
+        "#;
+        let tokens = get_semantic_tokens(src).await;
+
+        let foo_type = find(&tokens, "Foo");
+        assert!(foo_type.iter().all(|(_, typ, _)| *typ == SemanticTokenType::STRUCT));
+
+        let bar_trait = find(&tokens, "Bar");
+        assert!(bar_trait.iter().all(|(_, typ, _)| *typ == SemanticTokenType::INTERFACE));
+
+        let (_, typ, modifiers) = find(&tokens, "bar").pop().unwrap();
+        assert_eq!(*typ, SemanticTokenType::METHOD);
+        assert!(modifiers.contains(&Modifier::Trait));
+
+        let value = find(&tokens, "value");
+        assert!(value.iter().all(|(_, typ, _)| *typ == SemanticTokenType::PROPERTY));
+
+        let counter = find(&tokens, "counter");
+        assert_eq!(counter.len(), 3);
+        assert_eq!(counter[0].2, vec![Modifier::Declaration, Modifier::Mutable]);
+        assert!(counter.iter().all(|(_, _, modifiers)| modifiers.contains(&Modifier::Mutable)));
+
+        let foo_local = find(&tokens, "foo");
+        assert!(foo_local.iter().all(|(_, typ, modifiers)| *typ == SemanticTokenType::VARIABLE
+            && !modifiers.contains(&Modifier::Mutable)));
+    }
+}
@@ -0,0 +1,350 @@
+use std::future::{self, Future};
+
+use async_lsp::ResponseError;
+use async_lsp::lsp_types::{
+    SymbolKind, TextDocumentIdentifier, TextDocumentPositionParams, TypeHierarchyItem,
+    TypeHierarchyPrepareParams, TypeHierarchySubtypesParams, TypeHierarchySupertypesParams,
+};
+use fm::FileMap;
+use noirc_errors::Location;
+use noirc_frontend::{
+    Type,
+    node_interner::{NodeInterner, ReferenceId, TraitId, TypeId},
+};
+
+use crate::LspState;
+
+use super::{process_request, to_lsp_location};
+
+pub(crate) fn on_prepare_type_hierarchy_request(
+    state: &mut LspState,
+    params: TypeHierarchyPrepareParams,
+) -> impl Future<Output = Result<Option<Vec<TypeHierarchyItem>>, ResponseError>> + use<> {
+    let result = process_request(state, params.text_document_position_params, |args| {
+        let item = match args.interner.find_referenced(args.location)? {
+            ReferenceId::Trait(trait_id) => trait_item(trait_id, args.interner, args.files),
+            ReferenceId::Type(type_id) => type_item(type_id, args.interner, args.files),
+            _ => None,
+        }?;
+        Some(vec![item])
+    });
+    future::ready(result)
+}
+
+/// The supertypes of a trait are its parent traits, while the supertypes of
+/// a struct or enum are the traits it implements.
+pub(crate) fn on_type_hierarchy_supertypes_request(
+    state: &mut LspState,
+    params: TypeHierarchySupertypesParams,
+) -> impl Future<Output = Result<Option<Vec<TypeHierarchyItem>>, ResponseError>> + use<> {
+    let result = process_request(state, item_position(&params.item), |args| {
+        let interner = args.interner;
+        let mut trait_ids: Vec<TraitId> = match interner.find_referenced(args.location)? {
+            ReferenceId::Trait(trait_id) => interner
+                .get_trait(trait_id)
+                .trait_bounds
+                .iter()
+                .map(|bound| bound.trait_id)
+                .collect(),
+            ReferenceId::Type(type_id) => interner
+                .trait_implementations()
+                .values()
+                .filter(|trait_impl| is_data_type(&trait_impl.borrow().typ, type_id))
+                .map(|trait_impl| trait_impl.borrow().trait_id)
+                .collect(),
+            _ => return None,
+        };
+        // Sort by ID first: traits with the same name could otherwise end up interleaved
+        trait_ids.sort();
+        trait_ids.dedup();
+        trait_ids.sort_by_key(|trait_id| interner.get_trait(*trait_id).name.to_string());
+
+        let items = trait_ids
+            .into_iter()
+            .filter_map(|trait_id| trait_item(trait_id, interner, args.files))
+            .collect();
+        Some(items)
+    });
+    future::ready(result)
+}
+
+/// The subtypes of a trait are the traits which have it as a parent, followed by
+/// the types implementing it. Structs and enums have no subtypes.
+pub(crate) fn on_type_hierarchy_subtypes_request(
+    state: &mut LspState,
+    params: TypeHierarchySubtypesParams,
+) -> impl Future<Output = Result<Option<Vec<TypeHierarchyItem>>, ResponseError>> + use<> {
+    let result = process_request(state, item_position(&params.item), |args| {
+        let interner = args.interner;
+        let ReferenceId::Trait(trait_id) = interner.find_referenced(args.location)? else {
+            return Some(Vec::new());
+        };
+
+        let mut items: Vec<TypeHierarchyItem> = interner
+            .find_child_traits(trait_id)
+            .into_iter()
+            .filter_map(|child| trait_item(child, interner, args.files))
+            .collect();
+
+        let mut trait_impls: Vec<_> = interner
+            .trait_implementations()
+            .values()
+            .filter(|trait_impl| trait_impl.borrow().trait_id == trait_id)
+            .collect();
+        trait_impls.sort_by_key(|trait_impl| {
+            let location = trait_impl.borrow().location;
+            (location.file, location.span.start())
+        });
+
+        let mut seen_types = Vec::new();
+        for trait_impl in trait_impls {
+            let trait_impl = trait_impl.borrow();
+            let item = if let Type::DataType(data_type, _) = &trait_impl.typ {
+                let type_id = data_type.borrow().id;
+                if seen_types.contains(&type_id) {
+                    continue;
+                }
+                seen_types.push(type_id);
+                type_item(type_id, interner, args.files)
+            } else {
+                // Traits can also be implemented for primitive and generic types,
+                // in which case we point to the type in the impl.
+                let name = trait_impl.typ.to_string();
+                hierarchy_item(
+                    name,
+                    SymbolKind::STRUCT,
+                    trait_impl.location,
+                    trait_impl.location,
+                    args.files,
+                )
+            };
+            items.extend(item);
+        }
+
+        Some(items)
+    });
+    future::ready(result)
+}
+
+/// The position of the name of an item, used to find the trait or type it refers to.
+fn item_position(item: &TypeHierarchyItem) -> TextDocumentPositionParams {
+    TextDocumentPositionParams {
+        text_document: TextDocumentIdentifier { uri: item.uri.clone() },
+        position: item.selection_range.start,
+    }
+}
+
+fn is_data_type(typ: &Type, type_id: TypeId) -> bool {
+    matches!(typ, Type::DataType(data_type, _) if data_type.borrow().id == type_id)
+}
+
+fn trait_item(
+    trait_id: TraitId,
+    interner: &NodeInterner,
+    files: &FileMap,
+) -> Option<TypeHierarchyItem> {
+    let trait_ = interner.get_trait(trait_id);
+    let name_location = trait_.name.location();
+    let location = trait_.location.merge(name_location);
+    hierarchy_item(trait_.name.to_string(), SymbolKind::INTERFACE, location, name_location, files)
+}
+
+fn type_item(
+    type_id: TypeId,
+    interner: &NodeInterner,
+    files: &FileMap,
+) -> Option<TypeHierarchyItem> {
+    let data_type = interner.get_type(type_id);
+    let data_type = data_type.borrow();
+    let name_location = data_type.name.location();
+    let location = data_type.location.merge(name_location);
+    let kind = if data_type.is_enum() { SymbolKind::ENUM } else { SymbolKind::STRUCT };
+    hierarchy_item(data_type.name.to_string(), kind, location, name_location, files)
+}
+
+fn hierarchy_item(
+    name: String,
+    kind: SymbolKind,
+    location: Location,
+    selection_location: Location,
+    files: &FileMap,
+) -> Option<TypeHierarchyItem> {
+    let range = to_lsp_location(files, location.file, location.span)?;
+    let selection_range = to_lsp_location(files, selection_location.file, selection_location.span)?;
+    Some(TypeHierarchyItem {
+        name,
+        kind,
+        tags: None,
+        detail: None,
+        uri: selection_range.uri,
+        range: range.range,
+        selection_range: selection_range.range,
+        data: None,
+    })
+}
+
+#[cfg(test)]
+mod type_hierarchy_tests {
+    use crate::{
+        notifications::on_did_open_text_document, test_utils, utils::get_cursor_line_and_column,
+    };
+
+    use super::*;
+    use async_lsp::lsp_types::{
+        DidOpenTextDocumentParams, PartialResultParams, Position, TextDocumentItem,
+        WorkDoneProgressParams,
+    };
+    use tokio::test;
+
+    const SRC: &str = r#"trait Shape {
+    fn area(self) -> Field;
+}
+
+trait Polygon: Shape {}
+
+struct Square { side: Field }
+
+impl Shape for Square {
+    fn area(self) -> Field { self.side * self.side }
+}
+
+impl Polygon for Square {}
+
+impl Shape for Field {
+    fn area(self) -> Field { self }
+}
+
+fn main() {}
+"#;
+
+    async fn prepare(src: &str) -> (LspState, TypeHierarchyItem) {
+        let (mut state, noir_text_document) = test_utils::init_lsp_server("document_symbol").await;
+
+        let (line, column, src) = get_cursor_line_and_column(src);
+
+        let _ = on_did_open_text_document(
+            &mut state,
+            DidOpenTextDocumentParams {
+                text_document: TextDocumentItem {
+                    uri: noir_text_document.clone(),
+                    language_id: "noir".to_string(),
+                    version: 0,
+                    text: src.to_string(),
+                },
+            },
+        );
+
+        let items = on_prepare_type_hierarchy_request(
+            &mut state,
+            TypeHierarchyPrepareParams {
+                text_document_position_params: TextDocumentPositionParams {
+                    text_document: TextDocumentIdentifier { uri: noir_text_document },
+                    position: Position { line: line as u32, character: column as u32 },
+                },
+                work_done_progress_params: WorkDoneProgressParams { work_done_token: None },
+            },
+        )
+        .await
+        .expect("Could not execute on_prepare_type_hierarchy_request")
+        .expect("Expected a type hierarchy item");
+        assert_eq!(items.len(), 1);
+
+        (state, items.into_iter().next().unwrap())
+    }
+
+    fn names(items: &[TypeHierarchyItem]) -> Vec<(&str, SymbolKind)> {
+        items.iter().map(|item| (item.name.as_str(), item.kind)).collect()
+    }
+
+    #[test]
+    async fn test_trait_subtypes() {
+        let src = SRC.replacen("trait Shape", "trait Sh>|<ape", 1);
+        let (mut state, item) = prepare(&src).await;
+        assert_eq!(item.name, "Shape");
+        assert_eq!(item.kind, SymbolKind::INTERFACE);
+
+        let subtypes = on_type_hierarchy_subtypes_request(
+            &mut state,
+            TypeHierarchySubtypesParams {
+                item,
+                work_done_progress_params: WorkDoneProgressParams { work_done_token: None },
+                partial_result_params: PartialResultParams { partial_result_token: None },
+            },
+        )
+        .await
+        .expect("Could not execute on_type_hierarchy_subtypes_request")
+        .unwrap();
+
+        assert_eq!(
+            names(&subtypes),
+            vec![
+                ("Polygon", SymbolKind::INTERFACE),
+                ("Square", SymbolKind::STRUCT),
+                ("Field", SymbolKind::STRUCT),
+            ]
+        );
+    }
+
+    #[test]
+    async fn test_struct_supertypes() {
+        let src = SRC.replacen("struct Square", "struct Sq>|<uare", 1);
+        let (mut state, item) = prepare(&src).await;
+        assert_eq!(item.name, "Square");
+
+        let supertypes = on_type_hierarchy_supertypes_request(
+            &mut state,
+            TypeHierarchySupertypesParams {
+                item,
+                work_done_progress_params: WorkDoneProgressParams { work_done_token: None },
+                partial_result_params: PartialResultParams { partial_result_token: None },
+            },
+        )
+        .await
+        .expect("Could not execute on_type_hierarchy_supertypes_request")
+        .unwrap();
+
+        assert_eq!(
+            names(&supertypes),
+            vec![("Polygon", SymbolKind::INTERFACE), ("Shape", SymbolKind::INTERFACE)]
+        );
+    }
+
+    #[test]
+    async fn test_struct_supertypes_with_same_trait_names() {
+        let src = r#"mod a {
+    pub trait Convert<T> {}
+}
+
+mod b {
+    pub trait Convert {}
+}
+
+struct Fo>|<o {}
+
+impl a::Convert<u8> for Foo {}
+impl b::Convert for Foo {}
+impl a::Convert<u16> for Foo {}
+
+fn main() {}
+"#;
+        let (mut state, item) = prepare(src).await;
+        assert_eq!(item.name, "Foo");
+
+        let supertypes = on_type_hierarchy_supertypes_request(
+            &mut state,
+            TypeHierarchySupertypesParams {
+                item,
+                work_done_progress_params: WorkDoneProgressParams { work_done_token: None },
+                partial_result_params: PartialResultParams { partial_result_token: None },
+            },
+        )
+        .await
+        .expect("Could not execute on_type_hierarchy_supertypes_request")
+        .unwrap();
+
+        assert_eq!(
+            names(&supertypes),
+            vec![("Convert", SymbolKind::INTERFACE), ("Convert", SymbolKind::INTERFACE)]
+        );
+    }
+}
@@ -1,8 +1,9 @@
 use async_lsp::lsp_types::{
-    CodeActionOptions, CompletionOptions, DeclarationCapability, DefinitionOptions,
-    DocumentSymbolOptions, HoverOptions, InlayHintOptions, OneOf, ReferencesOptions, RenameOptions,
-    SignatureHelpOptions, TextDocumentIdentifier, TypeDefinitionProviderCapability,
-    WorkspaceSymbolOptions,
+    CallHierarchyServerCapability, CodeActionOptions, CompletionOptions, DeclarationCapability,
+    DefinitionOptions, DocumentHighlightOptions, DocumentSymbolOptions,
+    FoldingRangeProviderCapability, HoverOptions, InlayHintOptions, OneOf, ReferencesOptions,
+    RenameOptions, SemanticTokensServerCapabilities, SignatureHelpOptions, TextDocumentIdentifier,
+    TypeDefinitionProviderCapability, WorkspaceSymbolOptions,
 };
 use noirc_frontend::graph::CrateName;
 use serde::{Deserialize, Serialize};
@@ -171,6 +172,26 @@ pub(crate) struct ServerCapabilities {
     /// The server provides workspace symbol support.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub(crate) workspace_symbol_provider: Option<OneOf<bool, WorkspaceSymbolOptions>>,
+
+    /// The server provides semantic tokens support.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) semantic_tokens_provider: Option<SemanticTokensServerCapabilities>,
+
+    /// The server provides folding ranges support.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) folding_range_provider: Option<FoldingRangeProviderCapability>,
+
+    /// The server provides document highlight support.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) document_highlight_provider: Option<OneOf<bool, DocumentHighlightOptions>>,
+
+    /// The server provides call hierarchy support.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) call_hierarchy_provider: Option<CallHierarchyServerCapability>,
+
+    /// The server provides type hierarchy support.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) type_hierarchy_provider: Option<bool>,
 }
 
 #[derive(Debug, PartialEq, Clone, Default, Deserialize, Serialize)]
@@ -740,8 +740,12 @@ fn lambda_with_file(lambda: Lambda, file: FileId) -> Lambda {
 fn match_expression_with_file(expr: MatchExpression, file: FileId) -> MatchExpression {
     MatchExpression {
         expression: expression_with_file(expr.expression, file),
-        rules: vecmap(expr.rules, |(condition, body)| {
-            (expression_with_file(condition, file), expression_with_file(body, file))
+        rules: vecmap(expr.rules, |(condition, guard, body)| {
+            (
+                expression_with_file(condition, file),
+                guard.map(|guard| expression_with_file(guard, file)),
+                expression_with_file(body, file),
+            )
         }),
     }
 }
@@ -907,6 +911,9 @@ fn statement_kind_with_file(kind: StatementKind, file: FileId) -> StatementKind
         StatementKind::Semi(expression) => {
             StatementKind::Semi(expression_with_file(expression, file))
         }
+        StatementKind::Return(expression) => StatementKind::Return(
+            expression.map(|expression| expression_with_file(expression, file)),
+        ),
         StatementKind::Interned(..)
         | StatementKind::Break
         | StatementKind::Continue
@@ -133,6 +133,14 @@ fn extract_locations_from_error<F: AcirField>(
         | ExecutionError::SolvingError(
             OpcodeResolutionError::UnsatisfiedConstrain { opcode_location: error_location, .. },
             acir_call_stack,
+        )
+        | ExecutionError::SolvingError(
+            OpcodeResolutionError::StepLimitExceeded { opcode_location: error_location, .. },
+            acir_call_stack,
+        )
+        | ExecutionError::SolvingError(
+            OpcodeResolutionError::ExecutionInterrupted { opcode_location: error_location },
+            acir_call_stack,
         ) => match error_location {
             ErrorLocation::Unresolved => {
                 unreachable!("Cannot resolve index for unsatisfied constraint")
@@ -273,9 +281,9 @@ pub fn execution_error_from<F: AcirField>(
                 OpcodeResolutionError::UnsatisfiedConstrain { .. }
                 | OpcodeResolutionError::IndexOutOfBounds { .. }
                 | OpcodeResolutionError::InvalidInputBitSize { .. }
-                | OpcodeResolutionError::BrilligFunctionFailed { .. } => {
-                    Some(call_stack.to_owned())
-                }
+                | OpcodeResolutionError::BrilligFunctionFailed { .. }
+                | OpcodeResolutionError::StepLimitExceeded { .. }
+                | OpcodeResolutionError::ExecutionInterrupted { .. } => Some(call_stack.to_owned()),
                 _ => None,
             };
             ExecutionError::SolvingError(error, call_stack)
@@ -20,7 +20,7 @@ use std::sync::mpsc;
 use std::thread;
 use std::{
     collections::{BTreeMap, HashMap, HashSet},
-    path::PathBuf,
+    path::{Path, PathBuf},
 };
 
 use fm::{FILE_EXTENSION, FileManager};
@@ -209,8 +209,16 @@ fn collect_all_files_under_path(
 
 const STACK_SIZE: usize = 8 * 1024 * 1024;
 
-#[cfg(any(target_arch = "wasm32", target_arch = "wasm64"))]
 pub fn parse_all(file_manager: &FileManager) -> ParsedFiles {
+    parse_files(file_manager, |_| true)
+}
+
+/// Parses the Noir files in the `file_manager` whose path satisfies `should_parse`.
+#[cfg(any(target_arch = "wasm32", target_arch = "wasm64"))]
+pub fn parse_files(
+    file_manager: &FileManager,
+    should_parse: impl Fn(&Path) -> bool + Sync,
+) -> ParsedFiles {
     use rayon::iter::ParallelBridge as _;
     use rayon::iter::ParallelIterator as _;
 
@@ -222,17 +230,22 @@ pub fn parse_all(file_manager: &FileManager) -> ParsedFiles {
             let file_path = file_manager.path(file_id).expect("expected file to exist");
             let file_extension =
                 file_path.extension().expect("expected all file paths to have an extension");
-            file_extension == "nr"
+            file_extension == "nr" && should_parse(file_path)
         })
         .map(|&file_id| (file_id, parse_file(file_manager, file_id)))
         .collect()
 }
 
+/// Parses the Noir files in the `file_manager` whose path satisfies `should_parse`.
 #[cfg(not(any(target_arch = "wasm32", target_arch = "wasm64")))]
-pub fn parse_all(file_manager: &FileManager) -> ParsedFiles {
+pub fn parse_files(
+    file_manager: &FileManager,
+    should_parse: impl Fn(&Path) -> bool + Sync,
+) -> ParsedFiles {
     let num_threads = rayon::current_num_threads();
     let (sender, receiver) = mpsc::channel();
     let iter = &Mutex::new(file_manager.as_file_map().all_file_ids());
+    let should_parse = &should_parse;
 
     thread::scope(|scope| {
         // Start worker threads
@@ -254,7 +267,7 @@ pub fn parse_all(file_manager: &FileManager) -> ParsedFiles {
                         let file_extension = file_path
                             .extension()
                             .expect("expected all file paths to have an extension");
-                        if file_extension != "nr" {
+                        if file_extension != "nr" || !should_parse(file_path) {
                             continue;
                         }
 
@@ -0,0 +1,522 @@
+use std::{
+    collections::BTreeSet,
+    hash::{Hash, Hasher},
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use fm::FileManager;
+use noirc_driver::{CompilationResult, CompileOptions, CrateName, NOIR_ARTIFACT_VERSION_STRING};
+use noirc_frontend::hir::ParsedFiles;
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+
+use crate::{
+    package::{Dependency, Package},
+    parse_all, parse_files,
+    workspace::Workspace,
+};
+
+/// Name of the directory under the workspace target directory holding the cache entries.
+const CACHE_DIR: &str = "cache";
+
+/// The kind of result stored in a cache entry.
+///
+/// Each kind is stored in a separate file, so that for example checking a package
+/// doesn't invalidate the cached compilation results of the same package.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CacheKind {
+    /// A [noirc_driver::CompiledProgram].
+    Program,
+    /// A [noirc_driver::CompiledContract].
+    Contract,
+    /// The paths of the final artifacts saved by `nargo compile`.
+    Artifacts,
+    /// The result of type checking a package.
+    Check,
+    /// The compiled tests of a package, run by `nargo test`.
+    Tests,
+}
+
+impl CacheKind {
+    fn extension(&self) -> &'static str {
+        match self {
+            CacheKind::Program => "program.json",
+            CacheKind::Contract => "contract.json",
+            CacheKind::Artifacts => "artifacts.json",
+            CacheKind::Check => "check.json",
+            CacheKind::Tests => "tests.json",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct CacheEntry<T> {
+    noir_version: String,
+    fingerprint: u64,
+    value: T,
+}
+
+/// A persistent cache of front-end results for the packages of a workspace.
+///
+/// Each entry is keyed on a fingerprint of the sources of a package and all of its
+/// dependencies, the compiler version and the [CompileOptions] it was compiled with.
+/// When the fingerprint matches, parsing, elaboration and monomorphization of
+/// the package can be skipped entirely.
+///
+/// Only results which did not produce any warnings are cached, so that reusing them
+/// never hides diagnostics from the user.
+pub struct FrontendCache {
+    directory: PathBuf,
+    options_fingerprint: u64,
+    reused: Mutex<BTreeSet<CrateName>>,
+}
+
+impl FrontendCache {
+    /// Creates a cache for the workspace, unless caching was disabled or the options
+    /// request output which is only produced when actually running the compiler.
+    pub fn new(workspace: &Workspace, compile_options: &CompileOptions) -> Option<Self> {
+        if !Self::is_enabled(compile_options) {
+            return None;
+        }
+
+        Some(Self {
+            directory: workspace.target_directory_path().join(CACHE_DIR),
+            options_fingerprint: options_fingerprint(compile_options),
+            reused: Mutex::default(),
+        })
+    }
+
+    fn is_enabled(options: &CompileOptions) -> bool {
+        let shows_compiler_output = options.show_ssa
+            || !options.show_ssa_pass.is_empty()
+            || options.show_brillig
+            || options.show_monomorphized
+            || options.show_contract_fn.is_some()
+            || options.print_acir
+            || options.emit_ssa
+            || options.verify_ssa_passes.is_some()
+            || options.ssa_pipeline_file.is_some()
+            || options.benchmark_codegen
+            || options.debug_comptime_in_file.is_some()
+            || options.instrument_debug
+            || options.debug_compile_stdin;
+
+        !options.no_cache && !options.force_compile && !shows_compiler_output
+    }
+
+    /// Computes the fingerprint of a package, covering the sources of the package and all of its
+    /// dependencies as they were loaded into the `file_manager`.
+    pub fn fingerprint(&self, file_manager: &FileManager, package: &Package) -> u64 {
+        let mut package_dirs = Vec::new();
+        collect_package_dirs(package, &mut package_dirs);
+
+        let mut sources: Vec<(&Path, &str)> = file_manager
+            .as_file_map()
+            .all_file_ids()
+            .filter_map(|file_id| {
+                let path = file_manager.path(*file_id)?;
+                if !package_dirs.iter().any(|dir| path.starts_with(dir)) {
+                    return None;
+                }
+                Some((path, file_manager.fetch_file(*file_id)?))
+            })
+            .collect();
+        sources.sort();
+
+        let package_settings = (
+            package.name.to_string(),
+            package.package_type.to_string(),
+            format!("{:?}", package.expression_width),
+        );
+
+        fxhash::hash64(&(
+            NOIR_ARTIFACT_VERSION_STRING,
+            self.options_fingerprint,
+            package_settings,
+            sources,
+        ))
+    }
+
+    /// Loads the cached value of the given kind for a package, if its fingerprint matches.
+    pub fn load<T: DeserializeOwned>(
+        &self,
+        package: &Package,
+        kind: CacheKind,
+        fingerprint: u64,
+    ) -> Option<T> {
+        let contents = std::fs::read(self.entry_path(package, kind)).ok()?;
+        let entry: CacheEntry<T> = serde_json::from_slice(&contents).ok()?;
+        if entry.noir_version != NOIR_ARTIFACT_VERSION_STRING || entry.fingerprint != fingerprint {
+            return None;
+        }
+        Some(entry.value)
+    }
+
+    /// Records that the cached result of a package was used instead of compiling it.
+    pub fn mark_reused(&self, package: &Package) {
+        self.reused.lock().unwrap().insert(package.name.clone());
+    }
+
+    /// Stores a value in the cache, if it was produced without any warnings.
+    ///
+    /// Failing to write the cache is not an error: the package will simply be recompiled next time.
+    pub fn store<T: Serialize, W>(
+        &self,
+        package: &Package,
+        kind: CacheKind,
+        fingerprint: u64,
+        value: &T,
+        warnings: &[W],
+    ) {
+        let path = self.entry_path(package, kind);
+        if !warnings.is_empty() {
+            // Make sure we don't keep reusing an older entry which isn't valid anymore.
+            let _ = std::fs::remove_file(path);
+            return;
+        }
+
+        let entry = CacheEntry {
+            noir_version: NOIR_ARTIFACT_VERSION_STRING.to_string(),
+            fingerprint,
+            value,
+        };
+        let Ok(contents) = serde_json::to_vec(&entry) else {
+            return;
+        };
+        if let Err(err) =
+            std::fs::create_dir_all(&self.directory).and_then(|_| std::fs::write(&path, contents))
+        {
+            tracing::warn!("failed to write cache entry {}: {err}", path.display());
+        }
+    }
+
+    /// The packages for which a cached result was reused.
+    pub fn reused_packages(&self) -> Vec<CrateName> {
+        self.reused.lock().unwrap().iter().cloned().collect()
+    }
+
+    fn entry_path(&self, package: &Package, kind: CacheKind) -> PathBuf {
+        self.directory.join(format!("{}.{}", package.name, kind.extension()))
+    }
+}
+
+/// Returns the cached result of the given kind for a package if its fingerprint matches,
+/// otherwise runs `compile` and caches its result.
+pub fn compile_cached<T: Serialize + DeserializeOwned>(
+    cache: Option<&FrontendCache>,
+    file_manager: &FileManager,
+    package: &Package,
+    kind: CacheKind,
+    compile: impl FnOnce() -> CompilationResult<T>,
+) -> CompilationResult<T> {
+    let Some(cache) = cache else {
+        return compile();
+    };
+
+    let fingerprint = cache.fingerprint(file_manager, package);
+    if let Some(value) = cache.load(package, kind, fingerprint) {
+        cache.mark_reused(package);
+        return Ok((value, Vec::new()));
+    }
+
+    let result = compile();
+    if let Ok((value, warnings)) = &result {
+        cache.store(package, kind, fingerprint, value, warnings);
+    }
+    result
+}
+
+/// Parses the files loaded into the `file_manager`, skipping the sources which are only used
+/// by packages whose results can be reused from the cache, according to `is_cached`.
+///
+/// The fingerprints only depend on the contents of the files, so this can be decided
+/// before anything is parsed. Without a cache all files are parsed.
+pub fn parse_uncached_files<'a>(
+    cache: Option<&FrontendCache>,
+    file_manager: &FileManager,
+    packages: impl IntoIterator<Item = &'a Package>,
+    is_cached: impl Fn(&FrontendCache, &Package, u64) -> bool,
+) -> ParsedFiles {
+    let Some(cache) = cache else {
+        return parse_all(file_manager);
+    };
+
+    let mut cached_dirs = Vec::new();
+    let mut uncached_dirs = Vec::new();
+    for package in packages {
+        let fingerprint = cache.fingerprint(file_manager, package);
+        if is_cached(cache, package, fingerprint) {
+            collect_package_dirs(package, &mut cached_dirs);
+        } else {
+            collect_package_dirs(package, &mut uncached_dirs);
+        }
+    }
+
+    // A directory can be shared between cached and uncached packages, e.g. a common dependency,
+    // in which case it still needs to be parsed. Files outside of any package, like the standard
+    // library, are always parsed.
+    parse_files(file_manager, |path| {
+        !cached_dirs.iter().any(|dir| path.starts_with(dir))
+            || uncached_dirs.iter().any(|dir| path.starts_with(dir))
+    })
+}
+
+/// Hashes the [CompileOptions] which can change the results stored in the cache.
+///
+/// Options which only affect what gets printed, or which disable the cache altogether,
+/// are left out so that they don't needlessly invalidate the cache entries.
+fn options_fingerprint(options: &CompileOptions) -> u64 {
+    // Destructure the options so that adding a new one requires deciding whether it matters here.
+    let CompileOptions {
+        expression_width,
+        bounded_codegen,
+        force_compile: _,
+        no_cache: _,
+        show_ssa: _,
+        show_ssa_pass: _,
+        no_ssa_locations: _,
+        show_contract_fn: _,
+        skip_ssa_pass,
+        verify_ssa_passes: _,
+        emit_ssa: _,
+        minimal_ssa,
+        show_brillig: _,
+        print_acir: _,
+        benchmark_codegen: _,
+        deny_warnings,
+        silence_warnings,
+        show_monomorphized: _,
+        instrument_debug: _,
+        force_brillig,
+        debug_comptime_in_file: _,
+        show_artifact_paths: _,
+        skip_underconstrained_check,
+        skip_brillig_constraints_check,
+        enable_brillig_debug_assertions,
+        count_array_copies,
+        enable_brillig_constraints_check_lookback,
+        inliner_aggressiveness,
+        max_bytecode_increase_percent,
+        brillig_unroll_factor,
+        brillig_unswitch_loops,
+        brillig_loop_size_limit,
+        max_function_specializations,
+        enable_value_range_analysis,
+        enable_brillig_global_value_numbering,
+        enable_brillig_interference_graph_allocation,
+        enable_brillig_peephole_optimizer,
+        enable_acir_common_subexpression_elimination,
+        ssa_pipeline,
+        ssa_pipeline_file: _,
+        pedantic_solving,
+        debug_compile_stdin: _,
+        unstable_features,
+        disable_comptime_printing,
+    } = options;
+
+    let mut hasher = fxhash::FxHasher64::default();
+    expression_width.hash(&mut hasher);
+    bounded_codegen.hash(&mut hasher);
+    skip_ssa_pass.hash(&mut hasher);
+    minimal_ssa.hash(&mut hasher);
+    deny_warnings.hash(&mut hasher);
+    silence_warnings.hash(&mut hasher);
+    force_brillig.hash(&mut hasher);
+    skip_underconstrained_check.hash(&mut hasher);
+    skip_brillig_constraints_check.hash(&mut hasher);
+    enable_brillig_debug_assertions.hash(&mut hasher);
+    count_array_copies.hash(&mut hasher);
+    enable_brillig_constraints_check_lookback.hash(&mut hasher);
+    inliner_aggressiveness.hash(&mut hasher);
+    max_bytecode_increase_percent.hash(&mut hasher);
+    brillig_unroll_factor.hash(&mut hasher);
+    brillig_unswitch_loops.hash(&mut hasher);
+    brillig_loop_size_limit.hash(&mut hasher);
+    max_function_specializations.hash(&mut hasher);
+    enable_value_range_analysis.hash(&mut hasher);
+    enable_brillig_global_value_numbering.hash(&mut hasher);
+    enable_brillig_interference_graph_allocation.hash(&mut hasher);
+    enable_brillig_peephole_optimizer.hash(&mut hasher);
+    enable_acir_common_subexpression_elimination.hash(&mut hasher);
+    ssa_pipeline.hash(&mut hasher);
+    pedantic_solving.hash(&mut hasher);
+    unstable_features.hash(&mut hasher);
+    disable_comptime_printing.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Collects the source directories of a package and all of its transitive dependencies.
+fn collect_package_dirs(package: &Package, dirs: &mut Vec<PathBuf>) {
+    let Some(dir) = package.entry_path.parent() else {
+        return;
+    };
+    if dirs.iter().any(|existing| existing == dir) {
+        return;
+    }
+    dirs.push(dir.to_path_buf());
+
+    for dependency in package.dependencies.values() {
+        match dependency {
+            Dependency::Local { package } | Dependency::Remote { package } => {
+                collect_package_dirs(package, dirs);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::BTreeMap, path::Path, str::FromStr};
+
+    use fm::FileManager;
+    use noirc_driver::{CompilationResult, CompileOptions, CrateName};
+
+    use super::{
+        CacheKind, FrontendCache, compile_cached, options_fingerprint, parse_uncached_files,
+    };
+    use crate::{
+        package::{Package, PackageType},
+        workspace::Workspace,
+    };
+
+    fn setup(root: &Path) -> (Workspace, Package) {
+        let package = Package {
+            version: None,
+            compiler_required_version: None,
+            root_dir: root.to_path_buf(),
+            package_type: PackageType::Binary,
+            entry_path: root.join("src").join("main.nr"),
+            name: CrateName::from_str("foo").unwrap(),
+            dependencies: BTreeMap::new(),
+            expression_width: None,
+        };
+        let workspace = Workspace {
+            root_dir: root.to_path_buf(),
+            target_dir: None,
+            members: vec![package.clone()],
+            selected_package_index: Some(0),
+            is_assumed: false,
+        };
+        (workspace, package)
+    }
+
+    fn fingerprint(cache: &FrontendCache, package: &Package, source: &str) -> u64 {
+        let mut file_manager = FileManager::new(&package.root_dir);
+        file_manager.add_file_with_source(&package.entry_path, source.to_string());
+        cache.fingerprint(&file_manager, package)
+    }
+
+    #[test]
+    fn reuses_entries_with_matching_fingerprint() {
+        let tmp = tempfile::tempdir().unwrap();
+        let (workspace, package) = setup(tmp.path());
+        let cache = FrontendCache::new(&workspace, &CompileOptions::default()).unwrap();
+
+        let original = fingerprint(&cache, &package, "fn main() {}");
+        let modified = fingerprint(&cache, &package, "fn main() { assert(true); }");
+        assert_ne!(original, modified);
+
+        let no_warnings: [(); 0] = [];
+        cache.store(&package, CacheKind::Check, original, &42u32, &no_warnings);
+        assert_eq!(cache.load::<u32>(&package, CacheKind::Check, modified), None);
+        assert_eq!(cache.load::<u32>(&package, CacheKind::Check, original), Some(42));
+        // Other kinds of results are stored separately
+        assert_eq!(cache.load::<u32>(&package, CacheKind::Program, original), None);
+
+        // Results with warnings invalidate the entry
+        cache.store(&package, CacheKind::Check, original, &42u32, &[()]);
+        assert_eq!(cache.load::<u32>(&package, CacheKind::Check, original), None);
+    }
+
+    #[test]
+    fn compile_cached_reuses_results() {
+        let tmp = tempfile::tempdir().unwrap();
+        let (workspace, package) = setup(tmp.path());
+        let cache = FrontendCache::new(&workspace, &CompileOptions::default()).unwrap();
+
+        let mut file_manager = FileManager::new(&package.root_dir);
+        file_manager.add_file_with_source(&package.entry_path, "fn main() {}".to_string());
+
+        let (value, _) =
+            compile_cached(Some(&cache), &file_manager, &package, CacheKind::Program, || {
+                Ok((1u32, Vec::new()))
+            })
+            .unwrap();
+        assert_eq!(value, 1);
+        assert!(cache.reused_packages().is_empty());
+
+        let (value, _) = compile_cached(
+            Some(&cache),
+            &file_manager,
+            &package,
+            CacheKind::Program,
+            || -> CompilationResult<u32> { panic!("result should have been reused") },
+        )
+        .unwrap();
+        assert_eq!(value, 1);
+        assert_eq!(cache.reused_packages(), vec![package.name.clone()]);
+    }
+
+    #[test]
+    fn only_parses_files_of_uncached_packages() {
+        let tmp = tempfile::tempdir().unwrap();
+        let (workspace, _) = setup(tmp.path());
+        let cache = FrontendCache::new(&workspace, &CompileOptions::default()).unwrap();
+
+        let package = |name: &str| {
+            let (_, mut package) = setup(&tmp.path().join(name));
+            package.name = CrateName::from_str(name).unwrap();
+            package
+        };
+        let cached = package("cached");
+        let uncached = package("uncached");
+
+        let mut file_manager = FileManager::new(tmp.path());
+        let mut add_file = |path: &Path| {
+            file_manager.add_file_with_source(path, "fn main() {}".to_string()).unwrap()
+        };
+        let cached_file = add_file(&cached.entry_path);
+        let uncached_file = add_file(&uncached.entry_path);
+        let std_file = add_file(&tmp.path().join("std").join("lib.nr"));
+
+        let parsed_files = parse_uncached_files(
+            Some(&cache),
+            &file_manager,
+            [&cached, &uncached],
+            |_, package, _| package.name == cached.name,
+        );
+        assert!(!parsed_files.contains_key(&cached_file));
+        assert!(parsed_files.contains_key(&uncached_file));
+        assert!(parsed_files.contains_key(&std_file));
+
+        let parsed_files =
+            parse_uncached_files(None, &file_manager, [&cached, &uncached], |_, _, _| true);
+        assert!(parsed_files.contains_key(&cached_file));
+    }
+
+    #[test]
+    fn cache_can_be_disabled() {
+        let tmp = tempfile::tempdir().unwrap();
+        let (workspace, _) = setup(tmp.path());
+
+        let options = CompileOptions { no_cache: true, ..Default::default() };
+        assert!(FrontendCache::new(&workspace, &options).is_none());
+
+        let options = CompileOptions { show_ssa: true, ..Default::default() };
+        assert!(FrontendCache::new(&workspace, &options).is_none());
+    }
+
+    #[test]
+    fn only_options_affecting_results_change_the_fingerprint() {
+        let default = options_fingerprint(&CompileOptions::default());
+
+        let options = CompileOptions { show_artifact_paths: true, ..Default::default() };
+        assert_eq!(options_fingerprint(&options), default);
+
+        let options = CompileOptions { force_brillig: true, ..Default::default() };
+        assert_ne!(options_fingerprint(&options), default);
+
+        let options = CompileOptions { inliner_aggressiveness: 1, ..Default::default() };
+        assert_ne!(options_fingerprint(&options), default);
+    }
+}
@@ -7,6 +7,7 @@ use noirc_frontend::debug::DebugInstrumenter;
 use noirc_frontend::hir::{Context, ParsedFiles};
 
 use crate::errors::CompileError;
+use crate::ops::cache::{CacheKind, FrontendCache, compile_cached};
 use crate::prepare_package;
 use crate::{package::Package, workspace::Workspace};
 
@@ -14,6 +15,9 @@ use rayon::prelude::*;
 
 /// Compiles workspace.
 ///
+/// If a `cache` is provided, the results of packages whose sources, dependencies and options
+/// didn't change since they were last compiled are reused instead of running the compiler again.
+///
 /// # Errors
 ///
 /// This function will return an error if there are any compilations errors reported.
@@ -22,6 +26,7 @@ pub fn compile_workspace(
     parsed_files: &ParsedFiles,
     workspace: &Workspace,
     compile_options: &CompileOptions,
+    cache: Option<&FrontendCache>,
 ) -> CompilationResult<(Vec<CompiledProgram>, Vec<CompiledContract>)> {
     let (binary_packages, contract_packages): (Vec<_>, Vec<_>) = workspace
         .into_iter()
@@ -33,12 +38,25 @@ pub fn compile_workspace(
     let program_results: Vec<CompilationResult<CompiledProgram>> = binary_packages
         .par_iter()
         .map(|package| {
-            compile_program(file_manager, parsed_files, workspace, package, compile_options, None)
+            compile_cached(cache, file_manager, package, CacheKind::Program, || {
+                compile_program(
+                    file_manager,
+                    parsed_files,
+                    workspace,
+                    package,
+                    compile_options,
+                    None,
+                )
+            })
         })
         .collect();
     let contract_results: Vec<CompilationResult<CompiledContract>> = contract_packages
         .par_iter()
-        .map(|package| compile_contract(file_manager, parsed_files, package, compile_options))
+        .map(|package| {
+            compile_cached(cache, file_manager, package, CacheKind::Contract, || {
+                compile_contract(file_manager, parsed_files, package, compile_options)
+            })
+        })
         .collect();
 
     // Collate any warnings/errors which were encountered during compilation.
@@ -0,0 +1,260 @@
+//! Code coverage of program executions, mapped back to source lines through debug info.
+//!
+//! While executing, we count how many times each ACIR and Brillig opcode runs.
+//! These counts are then attributed to the source lines in each opcode's call
+//! stack, so that a line calling a function is covered by the opcodes of the callee.
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+use acvm::acir::circuit::brillig::{BrilligBytecode, BrilligFunctionId};
+use acvm::acir::circuit::{OpcodeLocation, Program};
+use acvm::brillig_vm::brillig::Opcode as BrilligOpcode;
+use acvm::pwg::{ProfilingSample, ProfilingSamples};
+use fm::FileId;
+use noirc_driver::DebugFile;
+use noirc_errors::Location;
+use noirc_errors::debug_info::DebugInfo;
+
+/// Key of a Brillig opcode: the index of the ACIR function calling the Brillig
+/// function (as debug info is kept per ACIR function), the Brillig function and
+/// the index of the opcode in it.
+type BrilligOpcodeKey = (usize, BrilligFunctionId, usize);
+
+/// Number of times each opcode of a program was executed.
+#[derive(Debug, Default, Clone)]
+pub struct ExecutionCoverage {
+    /// Keyed by the index of the ACIR function and the index of the opcode in it
+    acir_opcodes: HashMap<(usize, usize), usize>,
+    brillig_opcodes: HashMap<BrilligOpcodeKey, usize>,
+    /// Number of times each Brillig conditional jump was taken and not taken
+    brillig_branches: HashMap<BrilligOpcodeKey, [usize; 2]>,
+}
+
+impl ExecutionCoverage {
+    /// Records an execution of an ACIR function that went through its first
+    /// `executed_opcodes` opcodes, with the profiling samples of the Brillig calls it made.
+    pub(crate) fn record_acir_execution<F>(
+        &mut self,
+        function_index: usize,
+        executed_opcodes: usize,
+        samples: &ProfilingSamples,
+        unconstrained_functions: &[BrilligBytecode<F>],
+    ) {
+        for opcode_index in 0..executed_opcodes {
+            *self.acir_opcodes.entry((function_index, opcode_index)).or_default() += 1;
+        }
+
+        let brillig_index = |sample: &ProfilingSample| match sample.call_stack.last() {
+            Some(OpcodeLocation::Brillig { brillig_index, .. }) => Some(*brillig_index),
+            _ => None,
+        };
+        for (position, sample) in samples.iter().enumerate() {
+            let (Some(function_id), Some(opcode_index)) =
+                (sample.brillig_function_id, brillig_index(sample))
+            else {
+                continue;
+            };
+            let key = (function_index, function_id, opcode_index);
+            *self.brillig_opcodes.entry(key).or_default() += 1;
+
+            let bytecode = &unconstrained_functions[function_id.as_usize()].bytecode;
+            if !is_conditional_jump(bytecode.get(opcode_index)) {
+                continue;
+            }
+            // The next opcode executed by the same call tells whether the jump was taken
+            let Some(next) = samples.get(position + 1) else {
+                continue;
+            };
+            if next.call_stack.len() != sample.call_stack.len()
+                || next.call_stack.first() != sample.call_stack.first()
+            {
+                continue;
+            }
+            let not_taken = brillig_index(next) == Some(opcode_index + 1);
+            self.brillig_branches.entry(key).or_default()[usize::from(not_taken)] += 1;
+        }
+    }
+}
+
+fn is_conditional_jump<F>(opcode: Option<&BrilligOpcode<F>>) -> bool {
+    matches!(opcode, Some(BrilligOpcode::JumpIf { .. } | BrilligOpcode::JumpIfNot { .. }))
+}
+
+/// Line and branch hit counts per source file, which can be merged across
+/// executions of different programs.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct SourceCoverage {
+    pub files: BTreeMap<PathBuf, FileCoverage>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct FileCoverage {
+    /// Number of executed opcodes attributed to each line, by line number.
+    /// Lines which have opcodes that were never executed have a count of zero.
+    pub lines: BTreeMap<usize, usize>,
+    /// Conditional branches, keyed by line number and byte offset in the file,
+    /// with the number of times they were taken and not taken.
+    pub branches: BTreeMap<(usize, u32), [usize; 2]>,
+}
+
+impl SourceCoverage {
+    /// Adds the coverage of an execution of a compiled program, given its debug info and files.
+    pub fn add_execution<F>(
+        &mut self,
+        program: &Program<F>,
+        debug: &[DebugInfo],
+        file_map: &BTreeMap<FileId, DebugFile>,
+        execution: &ExecutionCoverage,
+    ) {
+        let mut lines = LineResolver::new(file_map);
+        for (function_index, debug_info) in debug.iter().enumerate() {
+            for (opcode_location, call_stack_id) in &debug_info.acir_locations {
+                let key = (function_index, opcode_location.index());
+                let hits = execution.acir_opcodes.get(&key).copied().unwrap_or_default();
+                let call_stack = debug_info.location_tree.get_call_stack(*call_stack_id);
+                self.add_hits(&mut lines, &call_stack, hits);
+            }
+
+            for (function_id, locations) in &debug_info.brillig_locations {
+                let bytecode = &program.unconstrained_functions[function_id.as_usize()].bytecode;
+                for (opcode_location, call_stack_id) in locations {
+                    let key = (function_index, *function_id, opcode_location.0);
+                    let hits = execution.brillig_opcodes.get(&key).copied().unwrap_or_default();
+                    let call_stack = debug_info.location_tree.get_call_stack(*call_stack_id);
+                    self.add_hits(&mut lines, &call_stack, hits);
+
+                    if !is_conditional_jump(bytecode.get(opcode_location.0)) {
+                        continue;
+                    }
+                    let Some(location) = call_stack.last() else {
+                        continue;
+                    };
+                    let Some((path, line)) = lines.resolve(*location) else {
+                        continue;
+                    };
+                    let offset = location.span.start();
+                    let outcomes =
+                        execution.brillig_branches.get(&key).copied().unwrap_or_default();
+                    let file = self.files.entry(path.to_path_buf()).or_default();
+                    let branch = file.branches.entry((line, offset)).or_default();
+                    branch[0] += outcomes[0];
+                    branch[1] += outcomes[1];
+                }
+            }
+        }
+    }
+
+    /// Adds the hit counts of another coverage to this one.
+    pub fn merge(&mut self, other: SourceCoverage) {
+        for (path, other_file) in other.files {
+            let file = self.files.entry(path).or_default();
+            for (line, hits) in other_file.lines {
+                *file.lines.entry(line).or_default() += hits;
+            }
+            for (key, outcomes) in other_file.branches {
+                let branch = file.branches.entry(key).or_default();
+                branch[0] += outcomes[0];
+                branch[1] += outcomes[1];
+            }
+        }
+    }
+
+    /// Attributes the hits of an opcode to each line of its call stack, counting
+    /// lines which appear several times (e.g. in recursive calls) only once.
+    fn add_hits(&mut self, lines: &mut LineResolver, call_stack: &[Location], hits: usize) {
+        let mut counted = Vec::with_capacity(call_stack.len());
+        for location in call_stack {
+            let Some((path, line)) = lines.resolve(*location) else {
+                continue;
+            };
+            if counted.contains(&(path, line)) {
+                continue;
+            }
+            counted.push((path, line));
+            *self.files.entry(path.to_path_buf()).or_default().lines.entry(line).or_default() +=
+                hits;
+        }
+    }
+}
+
+/// Resolves locations into file paths and line numbers.
+struct LineResolver<'a> {
+    file_map: &'a BTreeMap<FileId, DebugFile>,
+    /// Byte offsets at which each line starts, per file
+    line_starts: HashMap<FileId, Vec<usize>>,
+}
+
+impl<'a> LineResolver<'a> {
+    fn new(file_map: &'a BTreeMap<FileId, DebugFile>) -> Self {
+        Self { file_map, line_starts: HashMap::new() }
+    }
+
+    /// Returns the path of the location's file and the number of the line it starts at.
+    fn resolve(&mut self, location: Location) -> Option<(&'a Path, usize)> {
+        let file = self.file_map.get(&location.file)?;
+        let line_starts = self.line_starts.entry(location.file).or_insert_with(|| {
+            let newlines = file.source.match_indices('\n').map(|(index, _)| index + 1);
+            std::iter::once(0).chain(newlines).collect()
+        });
+        let offset = location.span.start() as usize;
+        let line = line_starts.partition_point(|start| *start <= offset);
+        Some((file.path.as_path(), line))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use acvm::FieldElement;
+    use acvm::acir::brillig::MemoryAddress;
+
+    fn sample(acir_index: usize, brillig_call_stack: &[usize]) -> ProfilingSample {
+        let call_stack = std::iter::once(OpcodeLocation::Acir(acir_index))
+            .chain(brillig_call_stack.iter().map(|brillig_index| OpcodeLocation::Brillig {
+                acir_index,
+                brillig_index: *brillig_index,
+            }))
+            .collect();
+        ProfilingSample { call_stack, brillig_function_id: Some(BrilligFunctionId(0)) }
+    }
+
+    #[test]
+    fn records_opcodes_and_branch_outcomes() {
+        let condition = MemoryAddress::direct(0);
+        let bytecode = vec![
+            BrilligOpcode::JumpIf { condition, location: 2 },
+            BrilligOpcode::Stop { return_data: Default::default() },
+            BrilligOpcode::Stop { return_data: Default::default() },
+        ];
+        let unconstrained_functions = [BrilligBytecode::<FieldElement> { bytecode }];
+
+        // Two calls from different ACIR opcodes: the first jumps, the second falls through
+        let samples = vec![sample(0, &[0]), sample(0, &[2]), sample(1, &[0]), sample(1, &[1])];
+        let mut coverage = ExecutionCoverage::default();
+        coverage.record_acir_execution(0, 2, &samples, &unconstrained_functions);
+
+        assert_eq!(coverage.acir_opcodes.get(&(0, 1)), Some(&1));
+        assert_eq!(coverage.acir_opcodes.get(&(0, 2)), None);
+        let jump = (0, BrilligFunctionId(0), 0);
+        assert_eq!(coverage.brillig_opcodes.get(&jump), Some(&2));
+        assert_eq!(coverage.brillig_branches.get(&jump), Some(&[1, 1]));
+    }
+
+    #[test]
+    fn merges_source_coverage() {
+        let file = |lines: &[(usize, usize)]| FileCoverage {
+            lines: lines.iter().copied().collect(),
+            branches: BTreeMap::from([((1, 4), [1, 0])]),
+        };
+        let mut coverage =
+            SourceCoverage { files: BTreeMap::from([(PathBuf::from("a.nr"), file(&[(1, 2)]))]) };
+        let other = SourceCoverage {
+            files: BTreeMap::from([(PathBuf::from("a.nr"), file(&[(1, 1), (2, 0)]))]),
+        };
+        coverage.merge(other);
+
+        let merged = &coverage.files[Path::new("a.nr")];
+        assert_eq!(merged.lines, BTreeMap::from([(1, 3), (2, 0)]));
+        assert_eq!(merged.branches, BTreeMap::from([((1, 4), [2, 0])]));
+    }
+}
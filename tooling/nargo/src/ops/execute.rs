@@ -3,7 +3,8 @@ use acvm::acir::circuit::{OpcodeLocation, Program};
 use acvm::acir::native_types::WitnessStack;
 use acvm::brillig_vm::BranchToFeatureMap;
 use acvm::pwg::{
-    ACVM, ACVMStatus, ErrorLocation, OpcodeNotSolvable, OpcodeResolutionError, ProfilingSamples,
+    ACVM, ACVMStatus, ErrorLocation, ExecutionBudget, OpcodeNotSolvable, OpcodeResolutionError,
+    ProfilingSamples,
 };
 use acvm::{AcirField, BlackBoxFunctionSolver};
 type NargoErrorAndCoverage<F> = (NargoError<F>, Option<Vec<u32>>);
@@ -15,6 +16,8 @@ use crate::NargoError;
 use crate::errors::{ExecutionError, ResolvedOpcodeLocation, execution_error_from};
 use crate::foreign_calls::ForeignCallExecutor;
 
+use super::coverage::ExecutionCoverage;
+
 struct ProgramExecutor<'a, F: AcirField, B: BlackBoxFunctionSolver<F>, E: ForeignCallExecutor<F>> {
     functions: &'a [Circuit<F>],
 
@@ -54,6 +57,13 @@ struct ProgramExecutor<'a, F: AcirField, B: BlackBoxFunctionSolver<F>, E: Foreig
 
     // Partial witness on failure
     failing_partial_witness: Option<WitnessMap<F>>,
+
+    // Opcodes executed so far, if we want to report code coverage.
+    // Collecting it requires profiling the Brillig VM to know which opcodes it went through.
+    coverage: Option<ExecutionCoverage>,
+
+    // Limits on the number of executed opcodes, shared by all the functions of the program
+    budget: Option<&'a ExecutionBudget>,
 }
 
 impl<'a, F: AcirField, B: BlackBoxFunctionSolver<F>, E: ForeignCallExecutor<F>>
@@ -80,6 +90,8 @@ impl<'a, F: AcirField, B: BlackBoxFunctionSolver<F>, E: ForeignCallExecutor<F>>
             last_fuzzing_trace: None,
             return_witness_on_failure: false,
             failing_partial_witness: None,
+            coverage: None,
+            budget: None,
         }
     }
 
@@ -95,6 +107,27 @@ impl<'a, F: AcirField, B: BlackBoxFunctionSolver<F>, E: ForeignCallExecutor<F>>
         self.return_witness_on_failure = return_witness_on_failure;
     }
 
+    fn with_budget(&mut self, budget: Option<&'a ExecutionBudget>) {
+        self.budget = budget;
+    }
+
+    fn with_coverage(&mut self) {
+        self.coverage = Some(ExecutionCoverage::default());
+    }
+
+    /// Records the first `executed_opcodes` opcodes of the current function and the
+    /// Brillig opcodes in `samples` as executed, if we are collecting coverage.
+    fn record_coverage(&mut self, executed_opcodes: usize, samples: &ProfilingSamples) {
+        if let Some(coverage) = &mut self.coverage {
+            coverage.record_acir_execution(
+                self.current_function_index,
+                executed_opcodes,
+                samples,
+                self.unconstrained_functions,
+            );
+        }
+    }
+
     fn finalize(self) -> WitnessStack<F> {
         self.witness_stack
     }
@@ -105,6 +138,11 @@ impl<'a, F: AcirField, B: BlackBoxFunctionSolver<F>, E: ForeignCallExecutor<F>>
         initial_witness: WitnessMap<F>,
     ) -> Result<(WitnessMap<F>, ProfilingSamples), NargoError<F>> {
         let circuit = &self.functions[self.current_function_index];
+        // The opcode at the instruction pointer has been (at least partially) executed, unless
+        // the circuit has been solved in which case the instruction pointer is past the end.
+        let opcodes_count = circuit.opcodes.len();
+        let executed_opcodes =
+            |instruction_pointer: usize| (instruction_pointer + 1).min(opcodes_count);
         let mut acvm = ACVM::new(
             self.blackbox_solver,
             &circuit.opcodes,
@@ -112,8 +150,9 @@ impl<'a, F: AcirField, B: BlackBoxFunctionSolver<F>, E: ForeignCallExecutor<F>>
             self.unconstrained_functions,
             &circuit.assert_messages,
         );
-        acvm.with_profiler(self.profiling_active);
+        acvm.with_profiler(self.profiling_active || self.coverage.is_some());
         acvm.with_brillig_fuzzing(self.brillig_branch_to_feature_map);
+        acvm.with_budget(self.budget);
 
         loop {
             let solver_status = acvm.solve();
@@ -124,6 +163,8 @@ impl<'a, F: AcirField, B: BlackBoxFunctionSolver<F>, E: ForeignCallExecutor<F>>
                     unreachable!("Execution should not stop while in `InProgress` state.")
                 }
                 ACVMStatus::Failure(error) => {
+                    let samples = acvm.take_profiling_samples();
+                    self.record_coverage(executed_opcodes(acvm.instruction_pointer()), &samples);
                     self.last_fuzzing_trace = acvm.get_brillig_fuzzing_trace();
                     if self.return_witness_on_failure {
                         self.failing_partial_witness = Some(acvm.witness_map().clone());
@@ -140,6 +181,13 @@ impl<'a, F: AcirField, B: BlackBoxFunctionSolver<F>, E: ForeignCallExecutor<F>>
                         | OpcodeResolutionError::InvalidInputBitSize {
                             opcode_location: ErrorLocation::Resolved(opcode_location),
                             ..
+                        }
+                        | OpcodeResolutionError::StepLimitExceeded {
+                            opcode_location: ErrorLocation::Resolved(opcode_location),
+                            ..
+                        }
+                        | OpcodeResolutionError::ExecutionInterrupted {
+                            opcode_location: ErrorLocation::Resolved(opcode_location),
                         } => {
                             let resolved_location = ResolvedOpcodeLocation {
                                 acir_function_index: self.current_function_index,
@@ -164,7 +212,15 @@ impl<'a, F: AcirField, B: BlackBoxFunctionSolver<F>, E: ForeignCallExecutor<F>>
                     )));
                 }
                 ACVMStatus::RequiresForeignCall(foreign_call) => {
-                    let foreign_call_result = self.foreign_call_executor.execute(&foreign_call)?;
+                    let foreign_call_result = self.foreign_call_executor.execute(&foreign_call);
+                    if foreign_call_result.is_err() {
+                        let samples = acvm.take_profiling_samples();
+                        self.record_coverage(
+                            executed_opcodes(acvm.instruction_pointer()),
+                            &samples,
+                        );
+                    }
+                    let foreign_call_result = foreign_call_result?;
                     acvm.resolve_pending_foreign_call(foreign_call_result);
                 }
                 ACVMStatus::RequiresAcirCall(call_info) => {
@@ -182,10 +238,18 @@ impl<'a, F: AcirField, B: BlackBoxFunctionSolver<F>, E: ForeignCallExecutor<F>>
                     let acir_to_call = &self.functions[call_info.id.as_usize()];
                     let initial_witness = call_info.initial_witness;
                     // TODO: Profiling among multiple circuits is not supported
-                    let (call_solved_witness, _) = self.execute_circuit(initial_witness)?;
+                    let call_result = self.execute_circuit(initial_witness);
 
                     // Set tracking index back to the parent function after ACIR call execution
                     self.current_function_index = acir_function_caller;
+                    if call_result.is_err() {
+                        let samples = acvm.take_profiling_samples();
+                        self.record_coverage(
+                            executed_opcodes(acvm.instruction_pointer()),
+                            &samples,
+                        );
+                    }
+                    let (call_solved_witness, _) = call_result?;
 
                     let mut call_resolved_outputs = Vec::new();
                     for return_witness_index in acir_to_call.return_values.indices() {
@@ -194,6 +258,11 @@ impl<'a, F: AcirField, B: BlackBoxFunctionSolver<F>, E: ForeignCallExecutor<F>>
                         {
                             call_resolved_outputs.push(*return_value);
                         } else {
+                            let samples = acvm.take_profiling_samples();
+                            self.record_coverage(
+                                executed_opcodes(acvm.instruction_pointer()),
+                                &samples,
+                            );
                             return Err(ExecutionError::SolvingError(
                                 OpcodeNotSolvable::MissingAssignment(return_witness_index).into(),
                                 None, // Missing assignment errors do not supply user-facing diagnostics so we do not need to attach a call stack
@@ -212,6 +281,7 @@ impl<'a, F: AcirField, B: BlackBoxFunctionSolver<F>, E: ForeignCallExecutor<F>>
         self.call_stack.clear();
 
         let profiling_samples = acvm.take_profiling_samples();
+        self.record_coverage(executed_opcodes(acvm.instruction_pointer()), &profiling_samples);
         self.last_fuzzing_trace = acvm.get_brillig_fuzzing_trace();
         Ok((acvm.finalize(), profiling_samples))
     }
@@ -255,6 +325,65 @@ pub fn execute_program_with_profiling<
         profiling_active,
     )
 }
+
+/// Execute the program while counting the ACIR and Brillig opcodes it goes through.
+/// The coverage is returned whether the execution succeeds or fails.
+pub fn execute_program_with_coverage<
+    F: AcirField,
+    B: BlackBoxFunctionSolver<F>,
+    E: ForeignCallExecutor<F>,
+>(
+    program: &Program<F>,
+    initial_witness: WitnessMap<F>,
+    blackbox_solver: &B,
+    foreign_call_executor: &mut E,
+    budget: Option<&ExecutionBudget>,
+) -> (Result<WitnessStack<F>, NargoError<F>>, ExecutionCoverage) {
+    let mut executor = ProgramExecutor::new(
+        &program.functions,
+        &program.unconstrained_functions,
+        blackbox_solver,
+        foreign_call_executor,
+        false,
+    );
+    executor.with_budget(budget);
+    executor.with_coverage();
+    let result = executor.execute_circuit(initial_witness);
+    let coverage = executor.coverage.take().expect("coverage is being collected");
+    let result = result.map(|(main_witness, _)| {
+        executor.witness_stack.push(0, main_witness);
+        executor.finalize()
+    });
+    (result, coverage)
+}
+
+/// Execute the program, failing once it has executed more opcodes than the budget allows
+/// or the budget is interrupted.
+pub fn execute_program_with_budget<
+    F: AcirField,
+    B: BlackBoxFunctionSolver<F>,
+    E: ForeignCallExecutor<F>,
+>(
+    program: &Program<F>,
+    initial_witness: WitnessMap<F>,
+    blackbox_solver: &B,
+    foreign_call_executor: &mut E,
+    budget: &ExecutionBudget,
+) -> Result<WitnessStack<F>, NargoError<F>> {
+    let mut executor = ProgramExecutor::new(
+        &program.functions,
+        &program.unconstrained_functions,
+        blackbox_solver,
+        foreign_call_executor,
+        false,
+    );
+    executor.with_budget(Some(budget));
+    let (main_witness, _) = executor.execute_circuit(initial_witness)?;
+    executor.witness_stack.push(0, main_witness);
+
+    Ok(executor.finalize())
+}
+
 pub(crate) fn execute_program_with_brillig_fuzzing<
     F: AcirField,
     B: BlackBoxFunctionSolver<F>,
@@ -1,3 +1,4 @@
+pub use self::cache::{CacheKind, FrontendCache, compile_cached, parse_uncached_files};
 pub use self::check::check_program;
 pub use self::compile::{
     check_crate_and_report_errors, collect_errors, compile_contract, compile_program,
@@ -6,17 +7,24 @@ pub use self::compile::{
 pub use self::optimize::{optimize_contract, optimize_program};
 pub use self::transform::{transform_contract, transform_program};
 
-pub use self::execute::{execute_program, execute_program_with_profiling};
+pub use self::coverage::{ExecutionCoverage, FileCoverage, SourceCoverage};
+pub use self::execute::{
+    execute_program, execute_program_with_budget, execute_program_with_coverage,
+    execute_program_with_profiling,
+};
 pub use self::fuzz::{
     FuzzExecutionConfig, FuzzFolderConfig, FuzzingRunStatus, run_fuzzing_harness,
 };
 pub use self::test::{
-    FuzzConfig, TestStatus, check_expected_failure_message, fuzz_test, run_or_fuzz_test, run_test,
+    FuzzConfig, TestLimits, TestStatus, check_expected_failure_message, fuzz_test,
+    run_compiled_test, run_or_fuzz_test, run_test, run_test_with_limits,
     test_status_program_compile_fail, test_status_program_compile_pass,
 };
 
+mod cache;
 mod check;
 mod compile;
+mod coverage;
 pub mod debug;
 mod execute;
 mod fuzz;
@@ -28,7 +28,7 @@ fn optimize_program_internal(
         .into_iter()
         .enumerate()
         .map(|(i, function)| {
-            let (optimized_circuit, location_map) = acvm::compiler::optimize(function);
+            let (optimized_circuit, location_map) = acvm::compiler::optimize(function, false);
             debug[i].update_acir(location_map);
             optimized_circuit
         })
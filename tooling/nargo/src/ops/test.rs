@@ -1,4 +1,4 @@
-use std::{fs::OpenOptions, path::PathBuf};
+use std::{fs::OpenOptions, path::PathBuf, sync::mpsc, time::Duration};
 
 use acvm::{
     AcirField, BlackBoxFunctionSolver, FieldElement,
@@ -6,7 +6,7 @@ use acvm::{
         brillig::ForeignCallResult,
         native_types::{WitnessMap, WitnessStack},
     },
-    pwg::ForeignCallWaitInfo,
+    pwg::{ExecutionBudget, ForeignCallWaitInfo, OpcodeResolutionError},
 };
 use noirc_abi::{Abi, input_parser::json::serialize_to_json};
 use noirc_driver::{
@@ -23,14 +23,15 @@ use noirc_frontend::{
 
 use crate::{
     NargoError,
-    errors::try_to_diagnose_runtime_error,
+    errors::{ExecutionError, try_to_diagnose_runtime_error},
     foreign_calls::{
         ForeignCallError, ForeignCallExecutor, layers, transcript::LoggingForeignCallExecutor,
     },
 };
 
 use super::{
-    FuzzExecutionConfig, FuzzFolderConfig, FuzzingRunStatus, execute_program, run_fuzzing_harness,
+    FuzzExecutionConfig, FuzzFolderConfig, FuzzingRunStatus, SourceCoverage, execute_program,
+    execute_program_with_budget, execute_program_with_coverage, run_fuzzing_harness,
 };
 
 #[derive(Debug)]
@@ -47,6 +48,33 @@ impl TestStatus {
     }
 }
 
+/// Limits on the resources a test can use, beyond which it's reported as failed.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TestLimits {
+    /// Maximum time the execution of the test can take
+    pub timeout: Option<Duration>,
+    /// Maximum number of ACIR opcodes and Brillig instructions the test can execute
+    pub max_steps: Option<usize>,
+}
+
+impl TestLimits {
+    /// Returns these limits, overridden by the ones given in the test's attribute.
+    fn for_test(self, test_function: &TestFunction) -> Self {
+        let budget = test_function.budget;
+        TestLimits {
+            timeout: budget.timeout.map(Duration::from_secs).or(self.timeout),
+            max_steps: budget
+                .max_steps
+                .map(|max_steps| usize::try_from(max_steps).unwrap_or(usize::MAX))
+                .or(self.max_steps),
+        }
+    }
+
+    fn is_limited(&self) -> bool {
+        self.timeout.is_some() || self.max_steps.is_some()
+    }
+}
+
 pub struct FuzzConfig {
     pub folder_config: FuzzFolderConfig,
     pub execution_config: FuzzExecutionConfig,
@@ -107,25 +135,67 @@ where
     E: ForeignCallExecutor<FieldElement>,
 {
     match compile_no_check(context, config, test_function.id, None, false) {
-        Ok(compiled_program) => run_test_impl(
+        Ok(compiled_program) => run_compiled_test(
+            blackbox_solver,
+            compiled_program,
+            test_function,
+            output,
+            config,
+            build_foreign_call_executor,
+            TestLimits::default(),
+            None,
+        ),
+        Err(err) => test_status_program_compile_fail(err, test_function),
+    }
+}
+
+/// Runs a test function like [run_test], failing it if it exceeds the given limits
+/// (unless they are overridden by the test's attribute).
+/// If `coverage` is given, the lines and branches executed by the test are added to it.
+#[allow(clippy::too_many_arguments)]
+pub fn run_test_with_limits<'a, W, B, F, E>(
+    blackbox_solver: &B,
+    context: &mut Context,
+    test_function: &TestFunction,
+    output: W,
+    config: &CompileOptions,
+    build_foreign_call_executor: F,
+    limits: TestLimits,
+    coverage: Option<&mut SourceCoverage>,
+) -> TestStatus
+where
+    W: std::io::Write + 'a,
+    B: BlackBoxFunctionSolver<FieldElement>,
+    F: Fn(Box<dyn std::io::Write + 'a>, layers::Unhandled) -> E,
+    E: ForeignCallExecutor<FieldElement>,
+{
+    match compile_no_check(context, config, test_function.id, None, false) {
+        Ok(compiled_program) => run_compiled_test(
             blackbox_solver,
             compiled_program,
             test_function,
             output,
             config,
             build_foreign_call_executor,
+            limits,
+            coverage,
         ),
         Err(err) => test_status_program_compile_fail(err, test_function),
     }
 }
 
-fn run_test_impl<'a, W, B, F, E>(
+/// Runs a test function which was already compiled, failing it if it exceeds the given limits
+/// like [run_test_with_limits].
+#[allow(clippy::too_many_arguments)]
+pub fn run_compiled_test<'a, W, B, F, E>(
     blackbox_solver: &B,
     compiled_program: CompiledProgram,
     test_function: &TestFunction,
     output: W,
     config: &CompileOptions,
     build_foreign_call_executor: F,
+    limits: TestLimits,
+    coverage: Option<&mut SourceCoverage>,
 ) -> TestStatus
 where
     W: std::io::Write + 'a,
@@ -161,12 +231,53 @@ where
     let foreign_call_executor = TestForeignCallExecutor::new(foreign_call_executor);
     let mut foreign_call_executor = LoggingForeignCallExecutor::new(foreign_call_executor, writer);
 
-    let circuit_execution = execute_program(
-        &compiled_program.program,
-        WitnessMap::new(),
-        blackbox_solver,
-        &mut foreign_call_executor,
-    );
+    let limits = limits.for_test(test_function);
+    let budget = limits.is_limited().then(|| ExecutionBudget::new(limits.max_steps));
+    let circuit_execution = run_with_timeout(limits.timeout, budget.as_ref(), || match coverage {
+        Some(coverage) => {
+            let (circuit_execution, execution_coverage) = execute_program_with_coverage(
+                &compiled_program.program,
+                WitnessMap::new(),
+                blackbox_solver,
+                &mut foreign_call_executor,
+                budget.as_ref(),
+            );
+            coverage.add_execution(
+                &compiled_program.program,
+                &compiled_program.debug,
+                &compiled_program.file_map,
+                &execution_coverage,
+            );
+            circuit_execution
+        }
+        None => match &budget {
+            Some(budget) => execute_program_with_budget(
+                &compiled_program.program,
+                WitnessMap::new(),
+                blackbox_solver,
+                &mut foreign_call_executor,
+                budget,
+            ),
+            None => execute_program(
+                &compiled_program.program,
+                WitnessMap::new(),
+                blackbox_solver,
+                &mut foreign_call_executor,
+            ),
+        },
+    });
+
+    // A test exceeding its limits fails even if it's expected to fail, as it didn't run to completion
+    if let Err(error) = &circuit_execution {
+        if let Some(message) = limit_exceeded_message(error, &limits) {
+            let error_diagnostic = try_to_diagnose_runtime_error(
+                error,
+                &compiled_program.abi,
+                &compiled_program.debug,
+            );
+            return TestStatus::Fail { message, error_diagnostic };
+        }
+    }
 
     let status = test_status_program_compile_pass(
         test_function,
@@ -188,6 +299,46 @@ where
     }
 }
 
+/// Runs `execute`, interrupting the execution of the budget if it takes longer than `timeout`.
+fn run_with_timeout<T>(
+    timeout: Option<Duration>,
+    budget: Option<&ExecutionBudget>,
+    execute: impl FnOnce() -> T,
+) -> T {
+    let (Some(timeout), Some(budget)) = (timeout, budget) else {
+        return execute();
+    };
+
+    std::thread::scope(|scope| {
+        let (finished_sender, finished_receiver) = mpsc::channel::<()>();
+        scope.spawn(move || {
+            // The sender is dropped once the execution finishes, which wakes this thread up early
+            if finished_receiver.recv_timeout(timeout) == Err(mpsc::RecvTimeoutError::Timeout) {
+                budget.interrupt();
+            }
+        });
+        let result = execute();
+        drop(finished_sender);
+        result
+    })
+}
+
+/// Returns the failure message of a test whose execution failed by exceeding its limits.
+fn limit_exceeded_message(error: &NargoError<FieldElement>, limits: &TestLimits) -> Option<String> {
+    let NargoError::ExecutionError(ExecutionError::SolvingError(error, _)) = error else {
+        return None;
+    };
+    match error {
+        OpcodeResolutionError::StepLimitExceeded { step_limit, .. } => {
+            Some(format!("Test exceeded the limit of {step_limit} steps"))
+        }
+        OpcodeResolutionError::ExecutionInterrupted { .. } => {
+            limits.timeout.map(|timeout| format!("Test timed out after {timeout:?}"))
+        }
+        _ => None,
+    }
+}
+
 /// Runs the fuzzer on a test function. This assumes the function has arguments.
 pub fn fuzz_test<'a, B, F, E>(
     context: &mut Context,
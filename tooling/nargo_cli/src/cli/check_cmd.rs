@@ -4,8 +4,11 @@ use clap::Args;
 use fm::FileManager;
 use iter_extended::btree_map;
 use nargo::{
-    errors::CompileError, insert_all_files_for_workspace_into_file_manager,
-    ops::check_crate_and_report_errors, package::Package, parse_all, prepare_package,
+    errors::CompileError,
+    insert_all_files_for_workspace_into_file_manager,
+    ops::{CacheKind, FrontendCache, compile_cached, parse_uncached_files, report_errors},
+    package::Package,
+    prepare_package,
     workspace::Workspace,
 };
 use nargo_toml::PackageSelection;
@@ -14,7 +17,7 @@ use noirc_abi::{AbiParameter, AbiType, MAIN_RETURN_NAME};
 use noirc_driver::{CompileOptions, check_crate, compute_function_abi};
 use noirc_frontend::{hir::ParsedFiles, monomorphization::monomorphize};
 
-use super::{LockType, PackageOptions, WorkspaceCommand};
+use super::{LockType, PackageOptions, WorkspaceCommand, compile_cmd::report_reused_packages};
 
 /// Check a local package and all of its dependencies for errors
 #[derive(Debug, Clone, Args)]
@@ -49,7 +52,17 @@ impl WorkspaceCommand for CheckCommand {
 pub(crate) fn run(args: CheckCommand, workspace: Workspace) -> Result<(), CliError> {
     let mut workspace_file_manager = workspace.new_file_manager();
     insert_all_files_for_workspace_into_file_manager(&workspace, &mut workspace_file_manager);
-    let parsed_files = parse_all(&workspace_file_manager);
+    // Showing the program hashes needs every package to be elaborated, so nothing is reused.
+    let cache =
+        FrontendCache::new(&workspace, &args.compile_options).filter(|_| !args.show_program_hash);
+    let parsed_files = parse_uncached_files(
+        cache.as_ref(),
+        &workspace_file_manager,
+        &workspace,
+        |cache, package, fingerprint| {
+            cache.load::<Option<String>>(package, CacheKind::Check, fingerprint).is_some()
+        },
+    );
 
     for package in &workspace {
         if args.show_program_hash {
@@ -71,8 +84,11 @@ pub(crate) fn run(args: CheckCommand, workspace: Workspace) -> Result<(), CliErr
             package,
             &args.compile_options,
             args.allow_overwrite,
+            cache.as_ref(),
         )?;
     }
+
+    report_reused_packages(cache.as_ref());
     Ok(())
 }
 
@@ -84,23 +100,40 @@ fn check_package(
     package: &Package,
     compile_options: &CompileOptions,
     allow_overwrite: bool,
+    cache: Option<&FrontendCache>,
 ) -> Result<(), CompileError> {
-    let (mut context, crate_id) = prepare_package(file_manager, parsed_files, package);
-    check_crate_and_report_errors(&mut context, crate_id, compile_options)?;
+    // The result of checking a package is the `Prover.toml` template of its `main` function, if any.
+    let result = compile_cached(cache, file_manager, package, CacheKind::Check, || {
+        let (mut context, crate_id) = prepare_package(file_manager, parsed_files, package);
+        let ((), warnings) = check_crate(&mut context, crate_id, compile_options)?;
+
+        let prover_toml = if package.is_library() || package.is_contract() {
+            None
+        } else {
+            compute_function_abi(&context, &crate_id)
+                .map(|(parameters, _)| create_input_toml_template(parameters, None))
+        };
+        Ok((prover_toml, warnings))
+    });
+    let prover_toml = report_errors(
+        result,
+        file_manager,
+        compile_options.deny_warnings,
+        compile_options.silence_warnings,
+    )?;
 
     if package.is_library() || package.is_contract() {
         // Libraries do not have ABIs while contracts have many, so we cannot generate a `Prover.toml` file.
         Ok(())
     } else {
         // XXX: We can have a --overwrite flag to determine if you want to overwrite the Prover/Verifier.toml files
-        if let Some((parameters, _)) = compute_function_abi(&context, &crate_id) {
+        if let Some(prover_toml) = prover_toml {
             let path_to_prover_input = package.prover_input_path();
 
             // Before writing the file, check if it exists and whether overwrite is set
             let should_write_prover = !path_to_prover_input.exists() || allow_overwrite;
 
             if should_write_prover {
-                let prover_toml = create_input_toml_template(parameters.clone(), None);
                 write_to_file(prover_toml.as_bytes(), &path_to_prover_input)
                     .expect("failed to write template");
             } else {
@@ -1,10 +1,13 @@
 use std::io::{Read, Write};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 
 use acvm::acir::circuit::ExpressionWidth;
 use fm::FileManager;
-use nargo::ops::{collect_errors, compile_contract, compile_program, report_errors};
+use nargo::ops::{
+    CacheKind, FrontendCache, collect_errors, compile_contract, compile_program,
+    parse_uncached_files, report_errors,
+};
 use nargo::package::Package;
 use nargo::workspace::Workspace;
 use nargo::{insert_all_files_for_workspace_into_file_manager, parse_all};
@@ -14,7 +17,7 @@ use noir_artifact_cli::fs::artifact::{
 };
 use noirc_driver::DEFAULT_EXPRESSION_WIDTH;
 use noirc_driver::NOIR_ARTIFACT_VERSION_STRING;
-use noirc_driver::{CompilationResult, CompileOptions, CompiledContract};
+use noirc_driver::{CompilationResult, CompileOptions, CompiledContract, Warnings};
 
 use clap::Args;
 use noirc_frontend::hir::ParsedFiles;
@@ -115,6 +118,13 @@ pub fn parse_workspace(
     workspace: &Workspace,
     debug_compile_stdin: Option<String>,
 ) -> (FileManager, ParsedFiles) {
+    let file_manager = load_workspace_files(workspace, debug_compile_stdin);
+    let parsed_files = parse_all(&file_manager);
+    (file_manager, parsed_files)
+}
+
+/// Load all files in the workspace, without parsing them.
+fn load_workspace_files(workspace: &Workspace, debug_compile_stdin: Option<String>) -> FileManager {
     let mut file_manager = workspace.new_file_manager();
 
     if let Some(main_nr) = debug_compile_stdin {
@@ -123,8 +133,7 @@ pub fn parse_workspace(
         insert_all_files_for_workspace_into_file_manager(workspace, &mut file_manager);
     }
 
-    let parsed_files = parse_all(&file_manager);
-    (file_manager, parsed_files)
+    file_manager
 }
 
 /// Parse and compile the entire workspace, then report errors.
@@ -142,10 +151,26 @@ pub fn compile_workspace_full(
         stdin_handle.read_to_string(&mut main_nr).expect("reading from stdin to succeed");
         debug_compile_stdin = Some(main_nr);
     }
-    let (workspace_file_manager, parsed_files) = parse_workspace(workspace, debug_compile_stdin);
+    let workspace_file_manager = load_workspace_files(workspace, debug_compile_stdin);
+    let cache = FrontendCache::new(workspace, compile_options);
+
+    // Libraries aren't compiled on their own, so only their dependents need their sources.
+    let parsed_files = parse_uncached_files(
+        cache.as_ref(),
+        &workspace_file_manager,
+        workspace,
+        |cache, package, fingerprint| {
+            package.is_library() || artifacts_are_cached(cache, package, fingerprint)
+        },
+    );
 
-    let compiled_workspace =
-        compile_workspace(&workspace_file_manager, &parsed_files, workspace, compile_options);
+    let compiled_workspace = compile_workspace(
+        &workspace_file_manager,
+        &parsed_files,
+        workspace,
+        compile_options,
+        cache.as_ref(),
+    );
 
     report_errors(
         compiled_workspace,
@@ -154,9 +179,25 @@ pub fn compile_workspace_full(
         compile_options.silence_warnings,
     )?;
 
+    report_reused_packages(cache.as_ref());
+
     Ok(())
 }
 
+/// Print the names of the packages whose cached front-end results were reused.
+pub(crate) fn report_reused_packages(cache: Option<&FrontendCache>) {
+    let Some(cache) = cache else {
+        return;
+    };
+    let reused = cache.reused_packages();
+    if !reused.is_empty() {
+        let names = reused.iter().map(|name| name.to_string()).collect::<Vec<_>>().join(", ");
+        eprintln!(
+            "Reused cached results for unchanged packages: {names} (use --no-cache to disable)"
+        );
+    }
+}
+
 /// Compile binary and contract packages.
 /// Returns the merged warnings or errors.
 fn compile_workspace(
@@ -164,6 +205,7 @@ fn compile_workspace(
     parsed_files: &ParsedFiles,
     workspace: &Workspace,
     compile_options: &CompileOptions,
+    cache: Option<&FrontendCache>,
 ) -> CompilationResult<()> {
     let (binary_packages, contract_packages): (Vec<_>, Vec<_>) = workspace
         .into_iter()
@@ -172,8 +214,14 @@ fn compile_workspace(
         .partition(|package| package.is_binary());
 
     // Compile all of the packages in parallel.
-    let program_warnings_or_errors: CompilationResult<()> =
-        compile_programs(file_manager, parsed_files, workspace, &binary_packages, compile_options);
+    let program_warnings_or_errors: CompilationResult<()> = compile_programs(
+        file_manager,
+        parsed_files,
+        workspace,
+        &binary_packages,
+        compile_options,
+        cache,
+    );
 
     let contract_warnings_or_errors: CompilationResult<()> = compile_contracts(
         file_manager,
@@ -181,6 +229,7 @@ fn compile_workspace(
         &contract_packages,
         compile_options,
         &workspace.target_directory_path(),
+        cache,
     );
 
     match (program_warnings_or_errors, contract_warnings_or_errors) {
@@ -202,6 +251,7 @@ fn compile_programs(
     workspace: &Workspace,
     binary_packages: &[Package],
     compile_options: &CompileOptions,
+    cache: Option<&FrontendCache>,
 ) -> CompilationResult<()> {
     // Load any existing artifact for a given package, _iff_ it was compiled with the same nargo version.
     // The loaded circuit includes backend specific transformations, which might be different from the current target.
@@ -213,7 +263,22 @@ fn compile_programs(
             .map(|p| p.into())
     };
 
-    let compile_package = |package| {
+    let compile_package = |package: &Package| {
+        // Skip compilation entirely if the package and its dependencies haven't changed
+        // since the artifacts were last saved.
+        let fingerprint = cache.map(|cache| (cache, cache.fingerprint(file_manager, package)));
+        if let Some((cache, fingerprint)) = fingerprint {
+            if artifacts_are_cached(cache, package, fingerprint) {
+                cache.mark_reused(package);
+                return Ok(((), Vec::new()));
+            }
+        }
+        let store_artifacts = |paths: Vec<PathBuf>, warnings: &Warnings| {
+            if let Some((cache, fingerprint)) = fingerprint {
+                cache.store(package, CacheKind::Artifacts, fingerprint, &paths, warnings);
+            }
+        };
+
         let cached_program = load_cached_program(package);
 
         // Hash over the entire compiled program, including any post-compile transformations.
@@ -245,6 +310,7 @@ fn compile_programs(
                 .all(|circuit| circuit.expression_width == target_width);
 
             if width_matches {
+                store_artifacts(vec![workspace.package_build_path(package)], &warnings);
                 return Ok(((), warnings));
             }
         }
@@ -253,8 +319,13 @@ fn compile_programs(
         // Check solvability.
         nargo::ops::check_program(&program)?;
         // Overwrite the build artifacts with the final circuit, which includes the backend specific transformations.
-        save_program_to_file(&program.into(), &package.name, &workspace.target_directory_path())
-            .expect("failed to save program");
+        let artifact_path = save_program_to_file(
+            &program.into(),
+            &package.name,
+            &workspace.target_directory_path(),
+        )
+        .expect("failed to save program");
+        store_artifacts(vec![artifact_path], &warnings);
 
         Ok(((), warnings))
     };
@@ -276,16 +347,36 @@ fn compile_contracts(
     contract_packages: &[Package],
     compile_options: &CompileOptions,
     target_dir: &Path,
+    cache: Option<&FrontendCache>,
 ) -> CompilationResult<()> {
     let contract_results: Vec<CompilationResult<()>> = contract_packages
         .par_iter()
         .map(|package| {
+            let fingerprint = cache.map(|cache| (cache, cache.fingerprint(file_manager, package)));
+            if let Some((cache, fingerprint)) = fingerprint {
+                if artifacts_are_cached(cache, package, fingerprint) {
+                    cache.mark_reused(package);
+                    return Ok(((), Vec::new()));
+                }
+            }
+
             let (contract, warnings) =
                 compile_contract(file_manager, parsed_files, package, compile_options)?;
             let target_width =
                 get_target_width(package.expression_width, compile_options.expression_width);
             let contract = nargo::ops::transform_contract(contract, target_width);
-            save_contract(contract, package, target_dir, compile_options.show_artifact_paths);
+            let artifact_path =
+                save_contract(contract, package, target_dir, compile_options.show_artifact_paths);
+
+            if let Some((cache, fingerprint)) = fingerprint {
+                cache.store(
+                    package,
+                    CacheKind::Artifacts,
+                    fingerprint,
+                    &[artifact_path],
+                    &warnings,
+                );
+            }
             Ok(((), warnings))
         })
         .collect();
@@ -294,12 +385,19 @@ fn compile_contracts(
     collect_errors(contract_results).map(|(_, warnings)| ((), warnings))
 }
 
+/// Check whether the artifacts saved for a package are still up to date with its sources.
+fn artifacts_are_cached(cache: &FrontendCache, package: &Package, fingerprint: u64) -> bool {
+    cache
+        .load::<Vec<PathBuf>>(package, CacheKind::Artifacts, fingerprint)
+        .is_some_and(|paths| paths.iter().all(|path| path.exists()))
+}
+
 fn save_contract(
     contract: CompiledContract,
     package: &Package,
     target_dir: &Path,
     show_artifact_paths: bool,
-) {
+) -> PathBuf {
     let contract_name = contract.name.clone();
     let artifact_path = save_contract_to_file(
         &contract.into(),
@@ -310,6 +408,7 @@ fn save_contract(
     if show_artifact_paths {
         println!("Saved contract artifact to: {}", artifact_path.display());
     }
+    artifact_path
 }
 
 /// If a target width was not specified in the CLI we can safely override the default.
@@ -403,7 +502,7 @@ mod tests {
         let verbose = matches!(sel, PackageSelection::Selected(_));
 
         let test_workspaces = read_test_program_dirs(&test_programs_dir(), "execution_success")
-            .filter_map(|dir| read_workspace(&dir, sel.clone()).ok())
+            .filter_map(|dir| read_workspace(&dir, sel.clone(), false).ok())
             .collect::<Vec<_>>();
 
         assert!(!test_workspaces.is_empty(), "should find some test workspaces");
@@ -198,6 +198,12 @@ fn loop_uninitialized_dap<R: Read, W: Write>(
                     supports_disassemble_request: Some(true),
                     supports_instruction_breakpoints: Some(true),
                     supports_stepping_granularity: Some(true),
+                    supports_conditional_breakpoints: Some(true),
+                    supports_hit_conditional_breakpoints: Some(true),
+                    supports_log_points: Some(true),
+                    supports_data_breakpoints: Some(true),
+                    supports_step_back: Some(true),
+                    supports_evaluate_for_hovers: Some(true),
                     ..Default::default()
                 }));
                 server.respond(rsp)?;
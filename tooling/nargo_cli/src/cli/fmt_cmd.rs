@@ -35,6 +35,11 @@ impl WorkspaceCommand for FormatCommand {
         // Writes source files, but doesn't touch compilation artifacts.
         LockType::None
     }
+
+    fn resolves_dependencies(&self) -> bool {
+        // Only formats the sources of the workspace members.
+        false
+    }
 }
 
 pub(crate) fn run(args: FormatCommand, workspace: Workspace) -> Result<(), CliError> {
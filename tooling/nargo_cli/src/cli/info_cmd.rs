@@ -168,11 +168,16 @@ fn profile_brillig_execution(
         program_info.push(ProgramInfo {
             package_name: package.name.to_string(),
             expression_width: Some(expression_width),
-            functions: vec![FunctionInfo { name: "main".to_string(), opcodes: 0 }],
+            functions: vec![FunctionInfo {
+                name: "main".to_string(),
+                opcodes: 0,
+                stack_frame_size: None,
+            }],
             unconstrained_functions_opcodes: profiling_samples.len(),
             unconstrained_functions: vec![FunctionInfo {
                 name: "main".to_string(),
                 opcodes: profiling_samples.len(),
+                stack_frame_size: None,
             }],
         });
     }
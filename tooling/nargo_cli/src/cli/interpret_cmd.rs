@@ -3,9 +3,14 @@
 use std::collections::BTreeMap;
 use std::path::PathBuf;
 
+use acvm::FieldElement;
+use acvm::acir::brillig::ForeignCallResult;
+use acvm::pwg::ForeignCallWaitInfo;
 use fm::{FileId, FileManager};
 use iter_extended::vecmap;
 use nargo::constants::PROVER_INPUT_FILE;
+use nargo::foreign_calls::transcript::ReplayForeignCallExecutor;
+use nargo::foreign_calls::{DefaultForeignCallBuilder, ForeignCallExecutor, layers};
 use nargo::ops::report_errors;
 use nargo::package::Package;
 use nargo::workspace::Workspace;
@@ -15,8 +20,8 @@ use noirc_driver::{CompilationResult, CompileOptions, gen_abi};
 
 use clap::Args;
 use noirc_errors::CustomDiagnostic;
-use noirc_evaluator::ssa::interpreter::InterpreterOptions;
 use noirc_evaluator::ssa::interpreter::value::Value;
+use noirc_evaluator::ssa::interpreter::{self, InterpreterOptions};
 use noirc_evaluator::ssa::ir::types::{NumericType, Type};
 use noirc_evaluator::ssa::ssa_gen::{Ssa, generate_ssa};
 use noirc_evaluator::ssa::{SsaEvaluatorOptions, SsaLogging, primary_passes};
@@ -53,6 +58,14 @@ pub(super) struct InterpretCommand {
     /// If true, the interpreter will trace its execution.
     #[clap(long)]
     trace: bool,
+
+    /// JSON RPC url to solve oracle calls
+    #[clap(long, conflicts_with = "oracle_file")]
+    oracle_resolver: Option<String>,
+
+    /// Path to the oracle transcript.
+    #[clap(long, conflicts_with = "oracle_resolver")]
+    oracle_file: Option<PathBuf>,
 }
 
 impl WorkspaceCommand for InterpretCommand {
@@ -129,6 +142,12 @@ pub(crate) fn run(args: InterpretCommand, workspace: Workspace) -> Result<(), Cl
         });
 
         let interpreter_options = InterpreterOptions { trace: args.trace };
+        let foreign_calls = ForeignCalls {
+            resolver_url: args.oracle_resolver.clone(),
+            oracle_file: args.oracle_file.clone(),
+            root_path: workspace.root_dir.clone(),
+            package_name: package.name.to_string(),
+        };
 
         print_and_interpret_ssa(
             ssa_options,
@@ -138,6 +157,7 @@ pub(crate) fn run(args: InterpretCommand, workspace: Workspace) -> Result<(), Cl
             &ssa_args,
             &ssa_return,
             interpreter_options,
+            &foreign_calls,
             &file_manager,
         )?;
 
@@ -161,6 +181,7 @@ pub(crate) fn run(args: InterpretCommand, workspace: Workspace) -> Result<(), Cl
                 &ssa_args,
                 &ssa_return,
                 interpreter_options,
+                &foreign_calls,
                 &file_manager,
             )?;
         }
@@ -245,11 +266,18 @@ fn interpret_ssa(
     args: &[Value],
     return_value: &Option<Vec<Value>>,
     options: InterpreterOptions,
+    foreign_calls: &ForeignCalls,
 ) -> Result<(), CliError> {
     if passes_to_interpret.is_empty() || msg_matches(passes_to_interpret, msg) {
         // We need to give a fresh copy of arrays each time, because the shared structures are modified.
         let args = Value::snapshot_args(args);
-        let result = ssa.interpret_with_options(args, options, std::io::stdout());
+        let mut foreign_call_executor = foreign_calls.executor()?;
+        let result = ssa.interpret_with_foreign_calls(
+            args,
+            options,
+            std::io::stdout(),
+            &mut foreign_call_executor,
+        );
         match &result {
             Ok(value) => {
                 let value_as_string = vecmap(value, ToString::to_string).join(", ");
@@ -283,10 +311,69 @@ fn print_and_interpret_ssa(
     args: &[Value],
     return_value: &Option<Vec<Value>>,
     interpreter_options: InterpreterOptions,
+    foreign_calls: &ForeignCalls,
     fm: &FileManager,
 ) -> Result<(), CliError> {
     print_ssa(options, ssa, msg, fm);
-    interpret_ssa(passes_to_interpret, ssa, msg, args, return_value, interpreter_options)
+    interpret_ssa(
+        passes_to_interpret,
+        ssa,
+        msg,
+        args,
+        return_value,
+        interpreter_options,
+        foreign_calls,
+    )
+}
+
+/// Options to build the executor resolving the oracle calls of the interpreted program.
+///
+/// A new executor is built for each interpretation, so that for example a transcript
+/// is replayed from the start after every SSA pass.
+struct ForeignCalls {
+    resolver_url: Option<String>,
+    oracle_file: Option<PathBuf>,
+    root_path: PathBuf,
+    package_name: String,
+}
+
+impl ForeignCalls {
+    fn executor(&self) -> Result<impl interpreter::ForeignCallExecutor, CliError> {
+        // As in `nargo execute`, the transcript is the innermost layer so that any
+        // extra oracle calls are handled by the default executors.
+        let transcript_executor = match &self.oracle_file {
+            Some(path) => layers::Either::Left(
+                ReplayForeignCallExecutor::from_file(path)
+                    .map_err(noir_artifact_cli::errors::CliError::from)?,
+            ),
+            None => layers::Either::Right(layers::Empty),
+        };
+
+        let executor = DefaultForeignCallBuilder {
+            output: std::io::stdout(),
+            enable_mocks: false,
+            resolver_url: self.resolver_url.clone(),
+            root_path: Some(self.root_path.clone()),
+            package_name: Some(self.package_name.clone()),
+        }
+        .build_with_base::<_, FieldElement>(transcript_executor);
+
+        Ok(InterpreterForeignCallExecutor(executor))
+    }
+}
+
+/// Adapts a `nargo` foreign call executor to the SSA interpreter.
+struct InterpreterForeignCallExecutor<E>(E);
+
+impl<E: ForeignCallExecutor<FieldElement>> interpreter::ForeignCallExecutor
+    for InterpreterForeignCallExecutor<E>
+{
+    fn execute(
+        &mut self,
+        foreign_call: &ForeignCallWaitInfo<FieldElement>,
+    ) -> Result<ForeignCallResult<FieldElement>, String> {
+        self.0.execute(foreign_call).map_err(|error| error.to_string())
+    }
 }
 
 fn flatten_values(values: Vec<Value>) -> Vec<Value> {
@@ -4,6 +4,7 @@ use nargo::workspace::Workspace;
 use nargo_toml::{
     ManifestError, NargoToml, PackageConfig, PackageMetadata, PackageSelection,
     get_package_manifest, resolve_workspace_from_fixed_toml, resolve_workspace_from_toml,
+    resolve_workspace_from_toml_and_update_lockfile,
 };
 use noirc_driver::{CrateName, NOIR_ARTIFACT_VERSION_STRING};
 use std::{
@@ -124,6 +125,11 @@ trait WorkspaceCommand {
     fn package_selection(&self) -> PackageSelection;
     /// The kind of lock the command needs to take out on the selected packages.
     fn lock_type(&self) -> LockType;
+    /// Whether the command resolves the dependencies of the workspace to build it,
+    /// in which case `Nargo.lock` is created or updated.
+    fn resolves_dependencies(&self) -> bool {
+        true
+    }
 }
 
 /// What kind of lock to take out on the (selected) workspace members.
@@ -172,18 +178,20 @@ pub(crate) fn start_cli() -> eyre::Result<()> {
     Ok(())
 }
 
-/// Read a given program directory into a workspace.
+/// Read a given program directory into a workspace, optionally updating its lockfile.
 fn read_workspace(
     program_dir: &Path,
     selection: PackageSelection,
+    update_lockfile: bool,
 ) -> Result<Workspace, ManifestError> {
     let toml_path = get_package_manifest(program_dir)?;
+    let compiler_version = Some(NOIR_ARTIFACT_VERSION_STRING.to_owned());
 
-    let workspace = resolve_workspace_from_toml(
-        &toml_path,
-        selection,
-        Some(NOIR_ARTIFACT_VERSION_STRING.to_owned()),
-    )?;
+    let workspace = if update_lockfile {
+        resolve_workspace_from_toml_and_update_lockfile(&toml_path, selection, compiler_version)?
+    } else {
+        resolve_workspace_from_toml(&toml_path, selection, compiler_version)?
+    };
 
     Ok(workspace)
 }
@@ -236,14 +244,14 @@ where
     // or a specific package; if that's the case then parse the package name to select it in the workspace.
     let selection = match cmd.package_selection() {
         PackageSelection::DefaultOrAll if workspace_dir != package_dir => {
-            let package = read_workspace(&package_dir, PackageSelection::DefaultOrAll)?;
+            let package = read_workspace(&package_dir, PackageSelection::DefaultOrAll, false)?;
             let package = package.into_iter().next().expect("there should be exactly 1 package");
             PackageSelection::Selected(package.name.clone())
         }
         other => other,
     };
     // Parse the top level workspace with the member selected.
-    let mut workspace = read_workspace(&workspace_dir, selection)?;
+    let mut workspace = read_workspace(&workspace_dir, selection, cmd.resolves_dependencies())?;
     // Optionally override the target directory. It's only done here because most commands like the LSP and DAP
     // don't read or write artifacts, so they don't use the target directory.
     workspace.target_dir = config.target_dir.clone();
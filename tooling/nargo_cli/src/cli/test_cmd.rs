@@ -4,6 +4,7 @@ use std::{
     fmt::Display,
     panic::{UnwindSafe, catch_unwind},
     path::PathBuf,
+    str::FromStr,
     sync::{
         Mutex,
         mpsc::{self, Sender},
@@ -15,25 +16,39 @@ use std::{
 use acvm::{BlackBoxFunctionSolver, FieldElement};
 use bn254_blackbox_solver::Bn254BlackBoxSolver;
 use clap::Args;
+use coverage::CoverageFormat;
 use fm::FileManager;
-use formatters::{Formatter, JsonFormatter, PrettyFormatter, TerseFormatter};
+use formatters::{
+    Formatter, JsonFormatter, JunitFormatter, PrettyFormatter, TapFormatter, TerseFormatter,
+};
 use nargo::{
     FuzzExecutionConfig, FuzzFolderConfig,
-    foreign_calls::DefaultForeignCallBuilder,
+    foreign_calls::{DefaultForeignCallBuilder, ForeignCallExecutor, layers},
     insert_all_files_for_workspace_into_file_manager,
-    ops::{FuzzConfig, TestStatus, check_crate_and_report_errors},
+    ops::{
+        CacheKind, FrontendCache, FuzzConfig, SourceCoverage, TestLimits, TestStatus,
+        parse_uncached_files, report_errors, test_status_program_compile_fail,
+    },
     package::Package,
-    parse_all, prepare_package,
+    prepare_package,
     workspace::Workspace,
 };
 use nargo_toml::PackageSelection;
-use noirc_driver::{CompileOptions, check_crate};
-use noirc_frontend::hir::{FunctionNameMatch, ParsedFiles, def_map::TestFunction};
+use noir_artifact_cli::fs::artifact::write_to_file;
+use noirc_driver::{CompileOptions, CompiledProgram, Warnings, check_crate, compile_no_check};
+use noirc_errors::Location;
+use noirc_frontend::{
+    hir::{FunctionNameMatch, ParsedFiles, def_map::TestFunction},
+    node_interner::FuncId,
+    token::{TestBudget, TestScope},
+};
+use serde::{Deserialize, Serialize};
 
 use crate::errors::CliError;
 
-use super::{LockType, PackageOptions, WorkspaceCommand};
+use super::{LockType, PackageOptions, WorkspaceCommand, compile_cmd::report_reused_packages};
 
+mod coverage;
 pub(crate) mod formatters;
 
 /// Run the tests for this program
@@ -108,6 +123,34 @@ pub(crate) struct TestCommand {
     /// Show progress of fuzzing (default: false)
     #[arg(long)]
     fuzz_show_progress: bool,
+
+    /// Record which source lines and branches are executed by the tests (excluding fuzz tests)
+    /// and write a coverage report for the files in the workspace
+    #[arg(long)]
+    coverage: bool,
+
+    /// Format of the coverage report
+    #[arg(long, value_enum, default_value_t, requires = "coverage")]
+    coverage_format: CoverageFormat,
+
+    /// Path to write the coverage report to (default: `lcov.info` or `cobertura.xml` in the target directory)
+    #[arg(long, requires = "coverage")]
+    coverage_output: Option<PathBuf>,
+
+    /// Only run the tests in the given shard, e.g. `2/3` for the second of three shards.
+    /// Tests are assigned to shards based on their package and name, so they always run in the same shard
+    #[arg(long, value_name = "INDEX/COUNT")]
+    shard: Option<Shard>,
+
+    /// Maximum time in seconds the execution of a test (other than a fuzz test) can take before it fails.
+    /// Overridden by `#[test(timeout = <seconds>)]`
+    #[arg(long, value_name = "SECONDS")]
+    test_timeout: Option<u64>,
+
+    /// Maximum number of ACIR opcodes and Brillig instructions a test (other than a fuzz test) can execute before it fails.
+    /// Overridden by `#[test(max_steps = <steps>)]`
+    #[arg(long, value_name = "STEPS")]
+    test_max_steps: Option<usize>,
 }
 
 impl WorkspaceCommand for TestCommand {
@@ -128,6 +171,10 @@ enum Format {
     Terse,
     /// Output a JSON Lines document
     Json,
+    /// Output a JUnit XML document, with a testsuite per package
+    Junit,
+    /// Output a TAP (Test Anything Protocol) version 13 stream
+    Tap,
 }
 
 impl Format {
@@ -136,6 +183,8 @@ impl Format {
             Format::Pretty => Box::new(PrettyFormatter),
             Format::Terse => Box::new(TerseFormatter),
             Format::Json => Box::new(JsonFormatter),
+            Format::Junit => Box::new(JunitFormatter::default()),
+            Format::Tap => Box::new(TapFormatter::default()),
         }
     }
 }
@@ -146,10 +195,53 @@ impl Display for Format {
             Format::Pretty => write!(f, "pretty"),
             Format::Terse => write!(f, "terse"),
             Format::Json => write!(f, "json"),
+            Format::Junit => write!(f, "junit"),
+            Format::Tap => write!(f, "tap"),
         }
     }
 }
 
+/// One of several disjoint subsets of the tests, to run them across multiple machines
+#[derive(Debug, Copy, Clone, PartialEq)]
+struct Shard {
+    /// 1-based index of the shard
+    index: u64,
+    count: u64,
+}
+
+impl FromStr for Shard {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error =
+            || format!("Invalid shard '{s}', expected INDEX/COUNT with 1 <= INDEX <= COUNT");
+        let (index, count) = s.split_once('/').ok_or_else(error)?;
+        let index = index.trim().parse().map_err(|_| error())?;
+        let count = count.trim().parse().map_err(|_| error())?;
+        if index == 0 || index > count {
+            return Err(error());
+        }
+        Ok(Shard { index, count })
+    }
+}
+
+impl Shard {
+    /// Whether the given test belongs to this shard.
+    ///
+    /// This hashes the package and test names with FNV-1a, which unlike the standard library's
+    /// hasher is guaranteed to give the same result across platforms and compiler versions.
+    fn contains(&self, package_name: &str, test_name: &str) -> bool {
+        const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+        const FNV_PRIME: u64 = 0x100000001b3;
+
+        let key = format!("{package_name}::{test_name}");
+        let hash = key
+            .bytes()
+            .fold(FNV_OFFSET_BASIS, |hash, byte| (hash ^ u64::from(byte)).wrapping_mul(FNV_PRIME));
+        hash % self.count == self.index - 1
+    }
+}
+
 struct Test<'a> {
     name: String,
     package_name: String,
@@ -177,12 +269,88 @@ impl TestResult {
     }
 }
 
+/// A test compiled by an earlier run, which can be run again without parsing and
+/// elaborating its package.
+#[derive(Serialize, Deserialize)]
+struct CompiledTest {
+    /// Fully qualified name of the test function
+    name: String,
+    scope: TestScope,
+    budget: TestBudget,
+    location: Location,
+    program: CompiledProgram,
+}
+
+impl CompiledTest {
+    fn new(name: &str, test_function: &TestFunction, program: CompiledProgram) -> Self {
+        CompiledTest {
+            name: name.to_string(),
+            scope: test_function.scope.clone(),
+            budget: test_function.budget,
+            location: test_function.location,
+            program,
+        }
+    }
+
+    /// The test function this was compiled from.
+    ///
+    /// Its ID is only needed to compile the function, so a dummy one is used.
+    fn test_function(&self) -> TestFunction {
+        TestFunction {
+            id: FuncId::dummy_id(),
+            scope: self.scope.clone(),
+            budget: self.budget,
+            location: self.location,
+            has_arguments: false,
+        }
+    }
+}
+
+/// The tests of a package compiled during this run.
+///
+/// They are cached once every test in the package has been compiled.
+struct CompiledPackageTests {
+    fingerprint: u64,
+    /// Names of all the tests in the package, in the order they were found
+    names: Vec<String>,
+    /// Warnings found when checking the package, which prevent caching its tests
+    warnings: Warnings,
+    tests: HashMap<String, CompiledTest>,
+}
+
+/// Where the program of a test comes from.
+enum TestSource<'a> {
+    /// The test is compiled from its package.
+    Package(&'a Package),
+    /// The test was compiled by an earlier run.
+    Cached(&'a CompiledTest),
+}
+
 const STACK_SIZE: usize = 4 * 1024 * 1024;
 
 pub(crate) fn run(args: TestCommand, workspace: Workspace) -> Result<(), CliError> {
     let mut file_manager = workspace.new_file_manager();
     insert_all_files_for_workspace_into_file_manager(&workspace, &mut file_manager);
-    let parsed_files = parse_all(&file_manager);
+
+    // Packages whose tests were all compiled by an earlier run can run them directly,
+    // so their sources don't need to be parsed.
+    let cache = FrontendCache::new(&workspace, &args.compile_options);
+    let mut cached_tests = HashMap::new();
+    if let Some(cache) = &cache {
+        for package in &workspace {
+            let fingerprint = cache.fingerprint(&file_manager, package);
+            if let Some(tests) =
+                cache.load::<Vec<CompiledTest>>(package, CacheKind::Tests, fingerprint)
+            {
+                cache.mark_reused(package);
+                cached_tests.insert(package.name.to_string(), tests);
+            }
+        }
+    }
+    let parsed_files =
+        parse_uncached_files(cache.as_ref(), &file_manager, &workspace, |_, package, _| {
+            cached_tests.contains_key(&package.name.to_string())
+        });
 
     let pattern = if args.test_names.is_empty() {
         FunctionNameMatch::Anything
@@ -208,6 +376,10 @@ pub(crate) fn run(args: TestCommand, workspace: Workspace) -> Result<(), CliErro
         pattern,
         num_threads: args.test_threads,
         formatter,
+        coverage: args.coverage.then(|| Mutex::new(SourceCoverage::default())),
+        cache,
+        cached_tests,
+        compiled_tests: Mutex::default(),
     };
     runner.run()
 }
@@ -220,12 +392,35 @@ struct TestRunner<'a> {
     pattern: FunctionNameMatch,
     num_threads: usize,
     formatter: Box<dyn Formatter>,
+    /// Coverage of all the tests run so far, if requested
+    coverage: Option<Mutex<SourceCoverage>>,
+    cache: Option<FrontendCache>,
+    /// Tests of the packages which were compiled by an earlier run, by package name
+    cached_tests: HashMap<String, Vec<CompiledTest>>,
+    /// Tests of the packages which can be cached once they were all compiled, by package name
+    compiled_tests: Mutex<HashMap<String, CompiledPackageTests>>,
 }
 
 impl<'a> TestRunner<'a> {
     fn run(&self) -> Result<(), CliError> {
         // First compile all packages and collect their tests
-        let packages_tests = self.collect_packages_tests()?;
+        let mut packages_tests = match self.collect_packages_tests() {
+            Ok(packages_tests) => packages_tests,
+            Err(err) => {
+                if !self.args.list_tests {
+                    self.formatter.run_end().expect("Could not display run end");
+                }
+                return Err(err);
+            }
+        };
+
+        // Whether there are tests matching the pattern, regardless of the shard they're in
+        let found_tests = packages_tests.values().any(|tests| !tests.is_empty());
+        if let Some(shard) = self.args.shard {
+            for (package_name, package_tests) in packages_tests.iter_mut() {
+                package_tests.retain(|test| shard.contains(package_name, &test.name));
+            }
+        }
 
         if self.args.list_tests {
             for (package_name, package_tests) in packages_tests {
@@ -246,10 +441,17 @@ impl<'a> TestRunner<'a> {
         }
 
         // Now run all tests in parallel, but show output for each package sequentially
-        let tests_count = tests.len();
         let all_passed = self.run_all_tests(tests, &test_count_per_package);
+        self.formatter.run_end().expect("Could not display run end");
+        self.store_compiled_tests();
 
-        if tests_count == 0 {
+        if let Some(coverage) = &self.coverage {
+            self.write_coverage_report(&coverage.lock().unwrap())?;
+        }
+
+        report_reused_packages(self.cache.as_ref());
+
+        if !found_tests {
             match &self.pattern {
                 FunctionNameMatch::Exact(patterns) => {
                     if patterns.len() == 1 {
@@ -550,6 +752,34 @@ impl<'a> TestRunner<'a> {
         root_path: Option<PathBuf>,
         package_name: String,
     ) -> Result<Vec<Test<'a>>, CliError> {
+        if let Some(cached_tests) = self.cached_tests.get(&package_name) {
+            let tests = cached_tests
+                .iter()
+                .filter(|test| self.pattern.matches(&test.name))
+                .map(|test| {
+                    let root_path = root_path.clone();
+                    let package_name_clone = package_name.clone();
+                    let runner = Box::new(move || {
+                        self.run_test::<S>(
+                            TestSource::Cached(test),
+                            &test.name,
+                            false,
+                            foreign_call_resolver_url,
+                            root_path,
+                            package_name_clone,
+                        )
+                    });
+                    Test {
+                        name: test.name.clone(),
+                        package_name: package_name.clone(),
+                        runner,
+                        has_arguments: false,
+                    }
+                })
+                .collect();
+            return Ok(tests);
+        }
+
         let test_functions = self.get_tests_in_package(package)?;
 
         let tests: Vec<Test> = test_functions
@@ -561,7 +791,7 @@ impl<'a> TestRunner<'a> {
                 let package_name_clone2 = package_name.clone();
                 let runner = Box::new(move || {
                     self.run_test::<S>(
-                        package,
+                        TestSource::Package(package),
                         &test_name,
                         test_function.has_arguments,
                         foreign_call_resolver_url,
@@ -588,16 +818,39 @@ impl<'a> TestRunner<'a> {
     ) -> Result<Vec<(String, TestFunction)>, CliError> {
         let (mut context, crate_id) =
             prepare_package(self.file_manager, self.parsed_files, package);
-        check_crate_and_report_errors(&mut context, crate_id, &self.args.compile_options)?;
+        let result = check_crate(&mut context, crate_id, &self.args.compile_options);
+        let warnings = result.as_ref().map(|(_, warnings)| warnings.clone()).unwrap_or_default();
+        report_errors(
+            result,
+            self.file_manager,
+            self.args.compile_options.deny_warnings,
+            self.args.compile_options.silence_warnings,
+        )?;
+
+        let test_functions = context
+            .get_all_test_functions_in_crate_matching(&crate_id, &FunctionNameMatch::Anything);
+
+        // Fuzz tests need the elaborated package to run, so only packages without them are cached.
+        if let Some(cache) = &self.cache {
+            if test_functions.iter().all(|(_, test_function)| !test_function.has_arguments) {
+                let package_tests = CompiledPackageTests {
+                    fingerprint: cache.fingerprint(self.file_manager, package),
+                    names: test_functions.iter().map(|(name, _)| name.clone()).collect(),
+                    warnings,
+                    tests: HashMap::new(),
+                };
+                self.compiled_tests.lock().unwrap().insert(package.name.to_string(), package_tests);
+            }
+        }
 
-        Ok(context.get_all_test_functions_in_crate_matching(&crate_id, &self.pattern))
+        Ok(test_functions.into_iter().filter(|(name, _)| self.pattern.matches(name)).collect())
     }
 
     /// Runs a single test and returns its status together with whatever was printed to stdout
     /// during the test.
     fn run_test<S: BlackBoxFunctionSolver<FieldElement> + Default>(
         &'a self,
-        package: &Package,
+        source: TestSource<'a>,
         fn_name: &str,
         has_arguments: bool,
         foreign_call_resolver_url: Option<&str>,
@@ -608,6 +861,36 @@ impl<'a> TestRunner<'a> {
             return (TestStatus::Skipped, String::new());
         }
 
+        let blackbox_solver = S::default();
+        let mut output_buffer = Vec::new();
+
+        let build_foreign_call_executor = |output, base| {
+            DefaultForeignCallBuilder {
+                output,
+                enable_mocks: true,
+                resolver_url: foreign_call_resolver_url.map(|s| s.to_string()),
+                root_path: root_path.clone(),
+                package_name: Some(package_name.clone()),
+            }
+            .build_with_base(base)
+        };
+
+        let package = match source {
+            TestSource::Package(package) => package,
+            TestSource::Cached(test) => {
+                let test_status = self.run_compiled_test(
+                    &blackbox_solver,
+                    test.program.clone(),
+                    &test.test_function(),
+                    &mut output_buffer,
+                    build_foreign_call_executor,
+                );
+                let output_string = String::from_utf8(output_buffer)
+                    .expect("output buffer should contain valid utf8");
+                return (test_status, output_string);
+            }
+        };
+
         // This is really hacky but we can't share `Context` or `S` across threads.
         // We then need to construct a separate copy for each test.
 
@@ -620,9 +903,6 @@ impl<'a> TestRunner<'a> {
         let test_functions = context.get_all_test_functions_in_crate_matching(&crate_id, &pattern);
         let (_, test_function) = test_functions.first().expect("Test function should exist");
 
-        let blackbox_solver = S::default();
-        let mut output_buffer = Vec::new();
-
         let fuzz_config = FuzzConfig {
             folder_config: FuzzFolderConfig {
                 corpus_dir: self.args.corpus_dir.clone(),
@@ -637,25 +917,40 @@ impl<'a> TestRunner<'a> {
             },
         };
 
-        let test_status = nargo::ops::run_or_fuzz_test(
-            &blackbox_solver,
-            &mut context,
-            test_function,
-            &mut output_buffer,
-            package_name.clone(),
-            &self.args.compile_options,
-            fuzz_config,
-            |output, base| {
-                DefaultForeignCallBuilder {
-                    output,
-                    enable_mocks: true,
-                    resolver_url: foreign_call_resolver_url.map(|s| s.to_string()),
-                    root_path: root_path.clone(),
-                    package_name: Some(package_name.clone()),
+        let test_status = if !has_arguments {
+            match compile_no_check(
+                &mut context,
+                &self.args.compile_options,
+                test_function.id,
+                None,
+                false,
+            ) {
+                Ok(compiled_program) => {
+                    self.record_compiled_test(package, fn_name, test_function, &compiled_program);
+                    self.run_compiled_test(
+                        &blackbox_solver,
+                        compiled_program,
+                        test_function,
+                        &mut output_buffer,
+                        build_foreign_call_executor,
+                    )
                 }
-                .build_with_base(base)
-            },
-        );
+                Err(err) => test_status_program_compile_fail(err, test_function),
+            }
+        } else {
+            // Fuzz tests run many executions within their own time limit,
+            // so neither the limits nor coverage apply to them
+            nargo::ops::run_or_fuzz_test(
+                &blackbox_solver,
+                &mut context,
+                test_function,
+                &mut output_buffer,
+                package_name.clone(),
+                &self.args.compile_options,
+                fuzz_config,
+                build_foreign_call_executor,
+            )
+        };
 
         let output_string =
             String::from_utf8(output_buffer).expect("output buffer should contain valid utf8");
@@ -663,6 +958,99 @@ impl<'a> TestRunner<'a> {
         (test_status, output_string)
     }
 
+    /// Runs a compiled test within the limits given on the command line,
+    /// adding the lines and branches it executes to the coverage if requested.
+    fn run_compiled_test<'b, W, S, F, E>(
+        &self,
+        blackbox_solver: &S,
+        compiled_program: CompiledProgram,
+        test_function: &TestFunction,
+        output: W,
+        build_foreign_call_executor: F,
+    ) -> TestStatus
+    where
+        W: std::io::Write + 'b,
+        S: BlackBoxFunctionSolver<FieldElement>,
+        F: Fn(Box<dyn std::io::Write + 'b>, layers::Unhandled) -> E,
+        E: ForeignCallExecutor<FieldElement>,
+    {
+        let limits = TestLimits {
+            timeout: self.args.test_timeout.map(Duration::from_secs),
+            max_steps: self.args.test_max_steps,
+        };
+        let mut test_coverage = self.coverage.is_some().then(SourceCoverage::default);
+        let test_status = nargo::ops::run_compiled_test(
+            blackbox_solver,
+            compiled_program,
+            test_function,
+            output,
+            &self.args.compile_options,
+            build_foreign_call_executor,
+            limits,
+            test_coverage.as_mut(),
+        );
+        if let (Some(coverage), Some(test_coverage)) = (&self.coverage, test_coverage) {
+            coverage.lock().unwrap().merge(test_coverage);
+        }
+        test_status
+    }
+
+    /// Keeps the compiled program of a test, if the tests of its package can be cached.
+    fn record_compiled_test(
+        &self,
+        package: &Package,
+        name: &str,
+        test_function: &TestFunction,
+        compiled_program: &CompiledProgram,
+    ) {
+        let mut compiled_tests = self.compiled_tests.lock().unwrap();
+        if let Some(package_tests) = compiled_tests.get_mut(&package.name.to_string()) {
+            let test = CompiledTest::new(name, test_function, compiled_program.clone());
+            package_tests.tests.insert(name.to_string(), test);
+        }
+    }
+
+    /// Caches the compiled tests of the packages in which every test was compiled by this run.
+    fn store_compiled_tests(&self) {
+        let Some(cache) = &self.cache else {
+            return;
+        };
+        let mut compiled_tests = self.compiled_tests.lock().unwrap();
+        for package in &self.workspace {
+            let Some(mut package_tests) = compiled_tests.remove(&package.name.to_string()) else {
+                continue;
+            };
+            let tests: Option<Vec<CompiledTest>> =
+                package_tests.names.iter().map(|name| package_tests.tests.remove(name)).collect();
+            if let Some(tests) = tests {
+                cache.store(
+                    package,
+                    CacheKind::Tests,
+                    package_tests.fingerprint,
+                    &tests,
+                    &package_tests.warnings,
+                );
+            }
+        }
+    }
+
+    /// Writes the coverage report of the workspace files, merged across all packages.
+    fn write_coverage_report(&self, coverage: &SourceCoverage) -> Result<(), CliError> {
+        let format = self.args.coverage_format;
+        let path = self.args.coverage_output.clone().unwrap_or_else(|| {
+            self.workspace.target_directory_path().join(format.default_file_name())
+        });
+        let report = format.render(coverage, &self.workspace.root_dir);
+        write_to_file(report.as_bytes(), &path).map_err(|error| {
+            CliError::Generic(format!(
+                "Failed to write coverage report to {}: {error}",
+                path.display()
+            ))
+        })?;
+        println!("Coverage report written to {}", path.display());
+        Ok(())
+    }
+
     /// Display the status of a single test
     fn display_test_result(
         &'a self,
@@ -681,3 +1069,25 @@ impl<'a> TestRunner<'a> {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::Shard;
+
+    #[test]
+    fn parses_shard() {
+        assert_eq!("2/3".parse(), Ok(Shard { index: 2, count: 3 }));
+        assert!("0/3".parse::<Shard>().is_err());
+        assert!("4/3".parse::<Shard>().is_err());
+        assert!("3".parse::<Shard>().is_err());
+    }
+
+    #[test]
+    fn each_test_is_in_exactly_one_shard() {
+        let shards = (1..=3).map(|index| Shard { index, count: 3 }).collect::<Vec<_>>();
+        for test_name in ["test_add", "test_sub", "test_mul", "test_div"] {
+            let containing = shards.iter().filter(|shard| shard.contains("pkg", test_name));
+            assert_eq!(containing.count(), 1);
+        }
+    }
+}
@@ -0,0 +1,201 @@
+//! Writers for the coverage reports produced by `nargo test --coverage`.
+use std::{
+    fmt::Write,
+    path::{Path, PathBuf},
+};
+
+use nargo::ops::{FileCoverage, SourceCoverage};
+
+use super::formatters::xml_escape;
+
+#[derive(Debug, Copy, Clone, Default, clap::ValueEnum)]
+pub(crate) enum CoverageFormat {
+    /// LCOV tracefile, as read by `genhtml` and most editors and CI services
+    #[default]
+    Lcov,
+    /// Cobertura XML document
+    Cobertura,
+}
+
+impl CoverageFormat {
+    /// The file name the report is written to when no output path is given.
+    pub(crate) fn default_file_name(&self) -> &'static str {
+        match self {
+            CoverageFormat::Lcov => "lcov.info",
+            CoverageFormat::Cobertura => "cobertura.xml",
+        }
+    }
+
+    /// Renders the coverage of the files under `root_dir`.
+    pub(crate) fn render(&self, coverage: &SourceCoverage, root_dir: &Path) -> String {
+        let files = coverage.files.iter().filter(|(path, _)| path.starts_with(root_dir));
+        match self {
+            CoverageFormat::Lcov => render_lcov(files),
+            CoverageFormat::Cobertura => render_cobertura(files, root_dir),
+        }
+    }
+}
+
+fn render_lcov<'a>(files: impl Iterator<Item = (&'a PathBuf, &'a FileCoverage)>) -> String {
+    let mut lcov = String::new();
+    for (path, file) in files {
+        writeln!(lcov, "TN:").unwrap();
+        writeln!(lcov, "SF:{}", path.display()).unwrap();
+        for (line, hits) in &file.lines {
+            writeln!(lcov, "DA:{line},{hits}").unwrap();
+        }
+        // Branches are numbered within each line, with block 0 being the jump itself
+        let mut branch_number = 0;
+        let mut previous_line = None;
+        for ((line, _), [taken, not_taken]) in &file.branches {
+            if previous_line != Some(*line) {
+                branch_number = 0;
+                previous_line = Some(*line);
+            }
+            let executed = taken + not_taken > 0;
+            for hits in [taken, not_taken] {
+                let hits = if executed { hits.to_string() } else { "-".to_string() };
+                writeln!(lcov, "BRDA:{line},0,{branch_number},{hits}").unwrap();
+                branch_number += 1;
+            }
+        }
+        writeln!(lcov, "LF:{}", file.lines.len()).unwrap();
+        writeln!(lcov, "LH:{}", lines_hit(file)).unwrap();
+        writeln!(lcov, "BRF:{}", file.branches.len() * 2).unwrap();
+        writeln!(lcov, "BRH:{}", branches_hit(file)).unwrap();
+        writeln!(lcov, "end_of_record").unwrap();
+    }
+    lcov
+}
+
+fn render_cobertura<'a>(
+    files: impl Iterator<Item = (&'a PathBuf, &'a FileCoverage)>,
+    root_dir: &Path,
+) -> String {
+    let files: Vec<_> = files.collect();
+    let lines_valid: usize = files.iter().map(|(_, file)| file.lines.len()).sum();
+    let lines_covered: usize = files.iter().map(|(_, file)| lines_hit(file)).sum();
+    let branches_valid: usize = files.iter().map(|(_, file)| file.branches.len() * 2).sum();
+    let branches_covered: usize = files.iter().map(|(_, file)| branches_hit(file)).sum();
+    let timestamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default();
+
+    let mut xml = String::new();
+    writeln!(xml, r#"<?xml version="1.0" encoding="UTF-8"?>"#).unwrap();
+    writeln!(
+        xml,
+        r#"<coverage line-rate="{}" branch-rate="{}" lines-covered="{lines_covered}" lines-valid="{lines_valid}" branches-covered="{branches_covered}" branches-valid="{branches_valid}" complexity="0" version="1.9" timestamp="{timestamp}">"#,
+        rate(lines_covered, lines_valid),
+        rate(branches_covered, branches_valid),
+    )
+    .unwrap();
+    writeln!(xml, "  <sources>").unwrap();
+    writeln!(xml, "    <source>{}</source>", xml_escape(&root_dir.display().to_string())).unwrap();
+    writeln!(xml, "  </sources>").unwrap();
+    writeln!(xml, "  <packages>").unwrap();
+    writeln!(
+        xml,
+        r#"    <package name="" line-rate="{}" branch-rate="{}" complexity="0">"#,
+        rate(lines_covered, lines_valid),
+        rate(branches_covered, branches_valid),
+    )
+    .unwrap();
+    writeln!(xml, "      <classes>").unwrap();
+    for (path, file) in files {
+        let filename = path.strip_prefix(root_dir).unwrap_or(path).display().to_string();
+        writeln!(
+            xml,
+            r#"        <class name="{0}" filename="{0}" line-rate="{1}" branch-rate="{2}" complexity="0">"#,
+            xml_escape(&filename),
+            rate(lines_hit(file), file.lines.len()),
+            rate(branches_hit(file), file.branches.len() * 2),
+        )
+        .unwrap();
+        writeln!(xml, "          <methods/>").unwrap();
+        writeln!(xml, "          <lines>").unwrap();
+        for (line, hits) in &file.lines {
+            let branches: Vec<_> = file
+                .branches
+                .range((*line, 0)..=(*line, u32::MAX))
+                .map(|(_, outcomes)| outcomes)
+                .collect();
+            if branches.is_empty() {
+                writeln!(
+                    xml,
+                    r#"            <line number="{line}" hits="{hits}" branch="false"/>"#
+                )
+                .unwrap();
+            } else {
+                let total = branches.len() * 2;
+                let covered: usize = branches
+                    .iter()
+                    .map(|outcomes| outcomes.iter().filter(|hits| **hits > 0).count())
+                    .sum();
+                writeln!(
+                    xml,
+                    r#"            <line number="{line}" hits="{hits}" branch="true" condition-coverage="{}% ({covered}/{total})"/>"#,
+                    covered * 100 / total,
+                )
+                .unwrap();
+            }
+        }
+        writeln!(xml, "          </lines>").unwrap();
+        writeln!(xml, "        </class>").unwrap();
+    }
+    writeln!(xml, "      </classes>").unwrap();
+    writeln!(xml, "    </package>").unwrap();
+    writeln!(xml, "  </packages>").unwrap();
+    writeln!(xml, "</coverage>").unwrap();
+    xml
+}
+
+fn lines_hit(file: &FileCoverage) -> usize {
+    file.lines.values().filter(|hits| **hits > 0).count()
+}
+
+fn branches_hit(file: &FileCoverage) -> usize {
+    file.branches.values().flatten().filter(|hits| **hits > 0).count()
+}
+
+fn rate(covered: usize, valid: usize) -> String {
+    if valid == 0 { "1".to_string() } else { format!("{:.4}", covered as f64 / valid as f64) }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+
+    #[test]
+    fn renders_lcov_for_files_in_root_dir() {
+        let file = FileCoverage {
+            lines: BTreeMap::from([(1, 3), (2, 0)]),
+            branches: BTreeMap::from([((1, 10), [2, 0]), ((2, 20), [0, 0])]),
+        };
+        let coverage = SourceCoverage {
+            files: BTreeMap::from([
+                (PathBuf::from("/project/src/main.nr"), file.clone()),
+                (PathBuf::from("std/lib.nr"), file),
+            ]),
+        };
+        let lcov = CoverageFormat::Lcov.render(&coverage, Path::new("/project"));
+        let expected = "TN:
+SF:/project/src/main.nr
+DA:1,3
+DA:2,0
+BRDA:1,0,0,2
+BRDA:1,0,1,0
+BRDA:2,0,0,-
+BRDA:2,0,1,-
+LF:2
+LH:1
+BRF:4
+BRH:1
+end_of_record
+";
+        assert_eq!(lcov, expected);
+    }
+}
@@ -1,4 +1,4 @@
-use std::{io::Write, panic::RefUnwindSafe, time::Duration};
+use std::{io::Write, panic::RefUnwindSafe, sync::Mutex, time::Duration};
 
 use fm::FileManager;
 use nargo::ops::TestStatus;
@@ -19,6 +19,7 @@ use super::TestResult;
 ///     1. A `package_start_sync` event
 ///     2. One `test_end` event for each test
 ///     3. A `package_end` event
+/// 5. A single `run_end` event, also sent when compilation failed.
 ///
 /// The reason we have some `sync` and `async` events is that formatters that show output
 /// to humans rely on the `sync` events to show a more predictable output (package by package),
@@ -62,6 +63,10 @@ pub(crate) trait Formatter: Send + Sync + RefUnwindSafe {
         deny_warnings: bool,
         silence_warnings: bool,
     ) -> std::io::Result<()>;
+
+    fn run_end(&self) -> std::io::Result<()> {
+        Ok(())
+    }
 }
 
 pub(crate) struct PrettyFormatter;
@@ -508,6 +513,325 @@ impl Formatter for JsonFormatter {
     }
 }
 
+/// Outputs a JUnit XML document with one `<testsuite>` per package.
+///
+/// The document is opened by the first package to end, and closed when the run ends.
+#[derive(Default)]
+pub(super) struct JunitFormatter {
+    opened: Mutex<bool>,
+}
+
+impl JunitFormatter {
+    fn open(opened: &mut bool, writer: &mut impl Write) -> std::io::Result<()> {
+        if !*opened {
+            writeln!(writer, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+            writeln!(writer, "<testsuites>")?;
+            *opened = true;
+        }
+        Ok(())
+    }
+}
+
+impl Formatter for JunitFormatter {
+    fn package_start_async(&self, _package_name: &str, _test_count: usize) -> std::io::Result<()> {
+        Ok(())
+    }
+
+    fn package_start_sync(&self, _package_name: &str, _test_count: usize) -> std::io::Result<()> {
+        Ok(())
+    }
+
+    fn test_start_async(&self, _name: &str, _package_name: &str) -> std::io::Result<()> {
+        Ok(())
+    }
+
+    fn test_end_async(
+        &self,
+        _test_result: &TestResult,
+        _file_manager: &FileManager,
+        _show_output: bool,
+        _deny_warnings: bool,
+        _silence_warnings: bool,
+    ) -> std::io::Result<()> {
+        Ok(())
+    }
+
+    fn test_end_sync(
+        &self,
+        _test_result: &TestResult,
+        _current_test_count: usize,
+        _total_test_count: usize,
+        _file_manager: &FileManager,
+        _show_output: bool,
+        _deny_warnings: bool,
+        _silence_warnings: bool,
+    ) -> std::io::Result<()> {
+        Ok(())
+    }
+
+    fn package_end(
+        &self,
+        package_name: &str,
+        test_results: &[TestResult],
+        file_manager: &FileManager,
+        _show_output: bool,
+        _deny_warnings: bool,
+        silence_warnings: bool,
+    ) -> std::io::Result<()> {
+        let mut opened = self.opened.lock().unwrap();
+        let writer = std::io::stdout();
+        let mut writer = writer.lock();
+
+        Self::open(&mut opened, &mut writer)?;
+        let testsuite = junit_testsuite(package_name, test_results, file_manager, silence_warnings);
+        write!(writer, "{testsuite}")?;
+        writer.flush()
+    }
+
+    fn run_end(&self) -> std::io::Result<()> {
+        let mut opened = self.opened.lock().unwrap();
+        let writer = std::io::stdout();
+        let mut writer = writer.lock();
+
+        // Always produce a complete document, even if no package ended
+        Self::open(&mut opened, &mut writer)?;
+        writeln!(writer, "</testsuites>")?;
+        writer.flush()
+    }
+}
+
+/// Renders the results of a package as a JUnit `<testsuite>` element.
+fn junit_testsuite(
+    package_name: &str,
+    test_results: &[TestResult],
+    file_manager: &FileManager,
+    silence_warnings: bool,
+) -> String {
+    let count_failed = test_results
+        .iter()
+        .filter(|test_result| matches!(test_result.status, TestStatus::Fail { .. }))
+        .count();
+    let count_errors = test_results
+        .iter()
+        .filter(|test_result| matches!(test_result.status, TestStatus::CompileError(_)))
+        .count();
+    let count_skipped = test_results
+        .iter()
+        .filter(|test_result| matches!(test_result.status, TestStatus::Skipped))
+        .count();
+    let time: f64 =
+        test_results.iter().map(|test_result| test_result.time_to_run.as_secs_f64()).sum();
+
+    let mut xml = String::new();
+    xml.push_str(&format!(
+        "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{count_failed}\" errors=\"{count_errors}\" skipped=\"{count_skipped}\" time=\"{time:.3}\">\n",
+        xml_escape(package_name),
+        test_results.len(),
+    ));
+
+    for test_result in test_results {
+        xml.push_str(&format!(
+            "    <testcase name=\"{}\" classname=\"{}\" time=\"{:.3}\"",
+            xml_escape(&test_result.name),
+            xml_escape(package_name),
+            test_result.time_to_run.as_secs_f64(),
+        ));
+
+        let mut body = String::new();
+        match &test_result.status {
+            TestStatus::Pass => (),
+            TestStatus::Fail { message, error_diagnostic } => {
+                let mut details = message.trim().to_string();
+                if let Some(diagnostic) = error_diagnostic {
+                    if !(diagnostic.is_warning() && silence_warnings) {
+                        details.push('\n');
+                        details.push_str(&diagnostic_to_string(diagnostic, file_manager));
+                    }
+                }
+                body.push_str(&format!(
+                    "      <failure message=\"{}\">{}</failure>\n",
+                    xml_escape(message.trim()),
+                    xml_escape(&details),
+                ));
+            }
+            TestStatus::Skipped => body.push_str("      <skipped/>\n"),
+            TestStatus::CompileError(diagnostic) => {
+                body.push_str(&format!(
+                    "      <error message=\"{}\">{}</error>\n",
+                    xml_escape(diagnostic.message.trim()),
+                    xml_escape(&diagnostic_to_string(diagnostic, file_manager)),
+                ));
+            }
+        }
+        if !test_result.output.is_empty() {
+            body.push_str(&format!(
+                "      <system-out>{}</system-out>\n",
+                xml_escape(&test_result.output)
+            ));
+        }
+
+        if body.is_empty() {
+            xml.push_str("/>\n");
+        } else {
+            xml.push_str(">\n");
+            xml.push_str(&body);
+            xml.push_str("    </testcase>\n");
+        }
+    }
+
+    xml.push_str("  </testsuite>\n");
+    xml
+}
+
+pub(super) fn xml_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for char in text.chars() {
+        match char {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            // Control characters other than whitespace aren't allowed in XML 1.0
+            char if char.is_control() && !matches!(char, '\n' | '\r' | '\t') => (),
+            char => escaped.push(char),
+        }
+    }
+    escaped
+}
+
+/// Outputs a [TAP](https://testanything.org) version 13 stream.
+///
+/// Tests are numbered across all packages and the plan is printed at the end of the run.
+#[derive(Default)]
+pub(super) struct TapFormatter {
+    started: Mutex<bool>,
+    test_number: Mutex<usize>,
+}
+
+impl TapFormatter {
+    fn start(started: &mut bool, writer: &mut impl Write) -> std::io::Result<()> {
+        if !*started {
+            writeln!(writer, "TAP version 13")?;
+            *started = true;
+        }
+        Ok(())
+    }
+}
+
+impl Formatter for TapFormatter {
+    fn package_start_async(&self, _package_name: &str, _test_count: usize) -> std::io::Result<()> {
+        Ok(())
+    }
+
+    fn package_start_sync(&self, package_name: &str, test_count: usize) -> std::io::Result<()> {
+        let mut started = self.started.lock().unwrap();
+        let writer = std::io::stdout();
+        let mut writer = writer.lock();
+
+        Self::start(&mut started, &mut writer)?;
+        let plural = if test_count == 1 { "" } else { "s" };
+        writeln!(writer, "# [{package_name}] Running {test_count} test function{plural}")
+    }
+
+    fn test_start_async(&self, _name: &str, _package_name: &str) -> std::io::Result<()> {
+        Ok(())
+    }
+
+    fn test_end_async(
+        &self,
+        _test_result: &TestResult,
+        _file_manager: &FileManager,
+        _show_output: bool,
+        _deny_warnings: bool,
+        _silence_warnings: bool,
+    ) -> std::io::Result<()> {
+        Ok(())
+    }
+
+    fn test_end_sync(
+        &self,
+        test_result: &TestResult,
+        _current_test_count: usize,
+        _total_test_count: usize,
+        file_manager: &FileManager,
+        show_output: bool,
+        _deny_warnings: bool,
+        silence_warnings: bool,
+    ) -> std::io::Result<()> {
+        let mut test_number = self.test_number.lock().unwrap();
+        *test_number += 1;
+
+        let writer = std::io::stdout();
+        let mut writer = writer.lock();
+
+        let description = format!("{}::{}", test_result.package_name, test_result.name);
+        match &test_result.status {
+            TestStatus::Pass => writeln!(writer, "ok {test_number} - {description}")?,
+            TestStatus::Skipped => writeln!(writer, "ok {test_number} - {description} # SKIP")?,
+            TestStatus::Fail { message, error_diagnostic } => {
+                writeln!(writer, "not ok {test_number} - {description}")?;
+                let mut details = message.trim().to_string();
+                if let Some(diagnostic) = error_diagnostic {
+                    if !(diagnostic.is_warning() && silence_warnings) {
+                        details.push('\n');
+                        details.push_str(&diagnostic_to_string(diagnostic, file_manager));
+                    }
+                }
+                write_tap_diagnostic(&mut writer, &details, test_result.time_to_run)?;
+            }
+            TestStatus::CompileError(diagnostic) => {
+                writeln!(writer, "not ok {test_number} - {description}")?;
+                let details = diagnostic_to_string(diagnostic, file_manager);
+                write_tap_diagnostic(&mut writer, &details, test_result.time_to_run)?;
+            }
+        }
+
+        if show_output && !test_result.output.is_empty() {
+            for line in test_result.output.lines() {
+                writeln!(writer, "# {line}")?;
+            }
+        }
+        Ok(())
+    }
+
+    fn package_end(
+        &self,
+        _package_name: &str,
+        _test_results: &[TestResult],
+        _file_manager: &FileManager,
+        _show_output: bool,
+        _deny_warnings: bool,
+        _silence_warnings: bool,
+    ) -> std::io::Result<()> {
+        Ok(())
+    }
+
+    fn run_end(&self) -> std::io::Result<()> {
+        let mut started = self.started.lock().unwrap();
+        let writer = std::io::stdout();
+        let mut writer = writer.lock();
+
+        Self::start(&mut started, &mut writer)?;
+        writeln!(writer, "1..{}", *self.test_number.lock().unwrap())
+    }
+}
+
+/// Writes the details of a failed test as a TAP YAML block.
+fn write_tap_diagnostic(
+    writer: &mut impl Write,
+    message: &str,
+    time_to_run: Duration,
+) -> std::io::Result<()> {
+    writeln!(writer, "  ---")?;
+    writeln!(writer, "  message: |")?;
+    for line in message.lines() {
+        writeln!(writer, "    {line}")?;
+    }
+    writeln!(writer, "  duration_ms: {}", time_to_run.as_millis())?;
+    writeln!(writer, "  ...")
+}
+
 fn package_start(package_name: &str, test_count: usize) -> std::io::Result<()> {
     let plural = if test_count == 1 { "" } else { "s" };
     println!("[{package_name}] Running {test_count} test function{plural}");
@@ -544,3 +868,63 @@ pub(crate) fn diagnostic_to_string(
 fn stdout() -> StandardStream {
     StandardStream::stdout(ColorChoice::Always)
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{path::Path, time::Duration};
+
+    use fm::FileManager;
+    use nargo::ops::TestStatus;
+
+    use super::{TestResult, junit_testsuite, xml_escape};
+
+    #[test]
+    fn escapes_xml_special_characters() {
+        assert_eq!(
+            xml_escape(r#"a < b && "c" > 'd'"#),
+            "a &lt; b &amp;&amp; &quot;c&quot; &gt; &apos;d&apos;"
+        );
+        assert_eq!(xml_escape("line\nbreak\u{1b}"), "line\nbreak");
+    }
+
+    #[test]
+    fn renders_junit_testsuite() {
+        let file_manager = FileManager::new(Path::new(""));
+        let test_result = |name: &str, status, output: &str| {
+            TestResult::new(
+                name.to_string(),
+                "foo".to_string(),
+                status,
+                output.to_string(),
+                Duration::from_millis(1500),
+            )
+        };
+        let test_results = vec![
+            test_result("passes", TestStatus::Pass, "hello\n"),
+            test_result(
+                "fails",
+                TestStatus::Fail { message: "x < y".to_string(), error_diagnostic: None },
+                "",
+            ),
+            test_result("skipped", TestStatus::Skipped, ""),
+        ];
+
+        let xml = junit_testsuite("foo", &test_results, &file_manager, false);
+        assert_eq!(
+            xml,
+            r#"  <testsuite name="foo" tests="3" failures="1" errors="0" skipped="1" time="4.500">
+    <testcase name="passes" classname="foo" time="1.500">
+      <system-out>hello
+</system-out>
+    </testcase>
+    <testcase name="fails" classname="foo" time="1.500">
+      <failure message="x &lt; y">x &lt; y</failure>
+    </testcase>
+    <testcase name="skipped" classname="foo" time="1.500">
+      <skipped/>
+    </testcase>
+  </testsuite>
+"#
+        );
+    }
+}
@@ -61,6 +61,27 @@ impl ItemPrinter<'_, '_> {
         }
     }
 
+    fn show_hir_expression_id_inside_block(&mut self, expr_id: ExprId) {
+        let hir_expr = self.interner.expression(&expr_id);
+        let is_block = match &hir_expr {
+            HirExpression::Block(block) => self.element_reference_call(block).is_none(),
+            _ => false,
+        };
+        if is_block {
+            self.show_hir_expression(hir_expr, expr_id);
+            return;
+        }
+
+        self.push_str("{\n");
+        self.increase_indent();
+        self.write_indent();
+        self.show_hir_expression(hir_expr, expr_id);
+        self.push('\n');
+        self.decrease_indent();
+        self.write_indent();
+        self.push('}');
+    }
+
     pub(super) fn show_hir_expression(&mut self, hir_expr: HirExpression, expr_id: ExprId) {
         match hir_expr {
             HirExpression::Ident(hir_ident, generics) => {
@@ -74,7 +95,11 @@ impl ItemPrinter<'_, '_> {
                 self.show_hir_literal(hir_literal, expr_id);
             }
             HirExpression::Block(hir_block_expression) => {
-                self.show_hir_block_expression(hir_block_expression);
+                if let Some(call) = self.element_reference_call(&hir_block_expression) {
+                    self.show_hir_expression_id(call);
+                } else {
+                    self.show_hir_block_expression(hir_block_expression);
+                }
             }
             HirExpression::Prefix(hir_prefix_expression) => match hir_prefix_expression.operator {
                 UnaryOp::Minus => {
@@ -271,9 +296,21 @@ impl ItemPrinter<'_, '_> {
                 self.push_str("if ");
                 self.show_hir_expression_id(cond);
                 self.push(' ');
-                self.show_hir_expression_id(body);
+                self.show_hir_expression_id_inside_block(body);
                 self.push_str(" else ");
-                self.show_hir_match(*otherwise);
+                if matches!(*otherwise, HirMatch::Guard { .. }) {
+                    self.show_hir_match(*otherwise);
+                } else {
+                    // `else` must be followed by a block or another `if`
+                    self.push_str("{\n");
+                    self.increase_indent();
+                    self.write_indent();
+                    self.show_hir_match(*otherwise);
+                    self.push('\n');
+                    self.decrease_indent();
+                    self.write_indent();
+                    self.push('}');
+                }
             }
             HirMatch::Switch(variable, cases, default) => {
                 self.push_str("match ");
@@ -460,6 +497,13 @@ impl ItemPrinter<'_, '_> {
         true
     }
 
+    /// Calls given mutable references to array elements are lowered into a block writing the
+    /// elements back, in which case this returns the call as it was written.
+    fn element_reference_call(&self, block: &HirBlockExpression) -> Option<ExprId> {
+        let statement = block.statements.last()?;
+        self.interner.element_reference_call(*statement)
+    }
+
     fn show_hir_block_expression(&mut self, block: HirBlockExpression) {
         self.push_str("{\n");
         self.increase_indent();
@@ -545,6 +589,14 @@ impl ItemPrinter<'_, '_> {
             HirStatement::Continue => {
                 self.push_str("continue;");
             }
+            HirStatement::Return(expr_id) => {
+                self.push_str("return");
+                if let Some(expr_id) = expr_id {
+                    self.push(' ');
+                    self.show_hir_expression_id(expr_id);
+                }
+                self.push(';');
+            }
             HirStatement::Expression(expr_id) => {
                 self.show_hir_expression_id(expr_id);
             }
@@ -848,6 +900,9 @@ impl ItemPrinter<'_, '_> {
             }
             HirStatement::Break => false,
             HirStatement::Continue => false,
+            HirStatement::Return(expr_id) => {
+                expr_id.is_some_and(|expr_id| self.expression_id_has_unsafe(expr_id))
+            }
             HirStatement::Expression(expr_id) => self.expression_id_has_unsafe(*expr_id),
             HirStatement::Semi(expr_id) => self.expression_id_has_unsafe(*expr_id),
             HirStatement::Comptime(stmt_id) => self.statement_id_has_unsafe(*stmt_id),
@@ -882,10 +937,12 @@ impl ItemPrinter<'_, '_> {
                 | HirLiteral::Str(_)
                 | HirLiteral::Unit => false,
             },
-            HirExpression::Block(_) => {
+            HirExpression::Block(hir_block_expression) => {
                 // A block consists of statements so if any of those have `unsafe`, those
-                // should have the safety comment, not this wrapping statement
-                false
+                // should have the safety comment, not this wrapping statement, unless the
+                // block is shown as the call it was lowered from
+                self.element_reference_call(&hir_block_expression)
+                    .is_some_and(|call| self.expression_id_has_unsafe(call))
             }
             HirExpression::Prefix(hir_prefix_expression) => {
                 self.expression_id_has_unsafe(hir_prefix_expression.rhs)
@@ -1,6 +1,6 @@
 use noirc_frontend::token::{
     Attribute, Attributes, FunctionAttribute, FunctionAttributeKind, FuzzingScope, MetaAttribute,
-    MetaAttributeName, SecondaryAttribute, SecondaryAttributeKind, TestScope, Token,
+    MetaAttributeName, SecondaryAttribute, SecondaryAttributeKind, TestBudget, TestScope, Token,
 };
 
 use crate::chunks::ChunkGroup;
@@ -59,7 +59,9 @@ impl Formatter<'_> {
             FunctionAttributeKind::Foreign(_)
             | FunctionAttributeKind::Builtin(_)
             | FunctionAttributeKind::Oracle(_) => self.format_one_arg_attribute(),
-            FunctionAttributeKind::Test(test_scope) => self.format_test_attribute(test_scope),
+            FunctionAttributeKind::Test(test_scope, test_budget) => {
+                self.format_test_attribute(test_scope, test_budget);
+            }
             FunctionAttributeKind::FuzzingHarness(fuzz_scope) => {
                 self.format_fuzz_attribute(fuzz_scope);
             }
@@ -122,30 +124,40 @@ impl Formatter<'_> {
         self.write_right_bracket(); // ]
     }
 
-    fn format_test_attribute(&mut self, test_scope: TestScope) {
+    fn format_test_attribute(&mut self, test_scope: TestScope, test_budget: TestBudget) {
         self.write_current_token_and_bump(); // #[
         self.skip_comments_and_whitespace();
         self.write_current_token_and_bump(); // test
 
-        match test_scope {
-            TestScope::None => (),
-            TestScope::ShouldFailWith { reason: None } => {
-                self.write_left_paren(); // (
+        if test_scope != TestScope::None || test_budget != TestBudget::default() {
+            self.write_left_paren(); // (
+            // Each argument is either a name, like `should_fail`, or a name with a value,
+            // like `should_fail_with = "reason"` or `timeout = 10`.
+            loop {
                 self.skip_comments_and_whitespace();
-                self.write_current_token_and_bump(); // should_fail
-                self.write_right_paren(); // )
-            }
-            TestScope::ShouldFailWith { reason: Some(..) } | TestScope::OnlyFailWith { .. } => {
-                self.write_left_paren(); // (
+                self.write_current_token_and_bump(); // name
                 self.skip_comments_and_whitespace();
-                self.write_current_token_and_bump(); // should_fail_with | only_fail_with
-                self.write_space();
-                self.write_token(Token::Assign);
-                self.write_space();
+                if self.is_at(Token::Assign) {
+                    self.write_space();
+                    self.write_token(Token::Assign);
+                    self.write_space();
+                    self.skip_comments_and_whitespace();
+                    self.write_current_token_and_bump(); // value
+                    self.skip_comments_and_whitespace();
+                }
+
+                if !self.is_at(Token::Comma) {
+                    break;
+                }
+                self.bump(); // ,
                 self.skip_comments_and_whitespace();
-                self.write_current_token_and_bump(); // "reason"
-                self.write_right_paren(); // )
+                // Trailing commas are removed
+                if self.is_at(Token::RightParen) {
+                    break;
+                }
+                self.write(", ");
             }
+            self.write_right_paren(); // )
         }
 
         self.write_right_bracket(); // ]
@@ -400,6 +412,13 @@ mod tests {
         assert_format_attribute(src, expected);
     }
 
+    #[test]
+    fn format_test_attribute_with_budget() {
+        let src = "  #[ test ( should_fail , timeout=10,max_steps = 500, )] ";
+        let expected = "#[test(should_fail, timeout = 10, max_steps = 500)]";
+        assert_format_attribute(src, expected);
+    }
+
     #[test]
     fn format_fuzz_attribute() {
         let src = "  #[ fuzz ] ";
@@ -954,9 +954,17 @@ impl ChunkFormatter<'_, '_> {
         }));
 
         group.increase_indentation();
-        for (pattern, branch) in match_expression.rules {
+        for (pattern, guard, branch) in match_expression.rules {
             group.line();
             self.format_expression(pattern, &mut group);
+            if let Some(guard) = guard {
+                group.text(self.chunk(|formatter| {
+                    formatter.write_space();
+                    formatter.write_keyword(Keyword::If);
+                    formatter.write_space();
+                }));
+                self.format_expression(guard, &mut group);
+            }
             group.text(self.chunk(|formatter| {
                 formatter.write_space();
                 formatter.write_token(Token::FatArrow);
@@ -2499,6 +2507,18 @@ global y = 1;
         assert_format(src, expected);
     }
 
+    #[test]
+    fn format_match_with_guard() {
+        let src = "fn main() {  match  x  {  A  if  y>1=>B,_=>C  } }";
+        let expected = "fn main() {
+    match x {
+        A if y > 1 => B,
+        _ => C,
+    }
+}\n";
+        assert_format(src, expected);
+    }
+
     #[test]
     fn format_last_assignment_in_block_without_semicolon() {
         let src = "fn main() {
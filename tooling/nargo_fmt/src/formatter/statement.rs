@@ -96,6 +96,9 @@ impl ChunkFormatter<'_, '_> {
                     }
                 }));
             }
+            StatementKind::Return(expression) => {
+                group.group(self.format_return(expression));
+            }
             StatementKind::Comptime(statement) => {
                 group.group(self.format_comptime_statement(*statement));
             }
@@ -356,6 +359,29 @@ impl ChunkFormatter<'_, '_> {
         group
     }
 
+    fn format_return(&mut self, expression: Option<Expression>) -> ChunkGroup {
+        let mut group = ChunkGroup::new();
+
+        group.text(self.chunk(|formatter| {
+            formatter.write_keyword(Keyword::Return);
+        }));
+
+        if let Some(expression) = expression {
+            group.space(self);
+            self.format_expression(expression, &mut group);
+        }
+
+        group.text(self.chunk(|formatter| {
+            formatter.skip_comments_and_whitespace();
+        }));
+
+        if self.is_at(Token::Semicolon) {
+            group.semicolon(self);
+        }
+
+        group
+    }
+
     fn format_semi_statement(&mut self, expression: Expression) -> ChunkGroup {
         let mut group = ChunkGroup::new();
 
@@ -405,6 +431,19 @@ mod tests {
         assert_format(src, expected);
     }
 
+    #[test]
+    fn format_return_statement() {
+        let src = " fn foo() { if true { return  1 ; } return ; } ";
+        let expected = "fn foo() {
+    if true {
+        return 1;
+    }
+    return;
+}
+";
+        assert_format(src, expected);
+    }
+
     #[test]
     fn format_continue_statement() {
         let src = " fn foo() { continue  ; } ";
@@ -77,6 +77,29 @@ pub enum ManifestError {
 
     #[error("Failed to parse expression width with the following error: {0}")]
     ParseExpressionWidth(String),
+
+    #[error("Invalid version requirement `{version}` for dependency `{name}` in {toml}: {error}")]
+    InvalidVersionRequirement { toml: PathBuf, name: String, version: String, error: String },
+
+    /// Encountered error while resolving a dependency from a package index.
+    #[error("{0}")]
+    RegistryError(String),
+
+    #[error("Nargo.lock at {path} is badly formed, could not parse.\n\n {error}")]
+    MalformedLockfile { path: PathBuf, error: String },
+
+    #[error("Failed to write lockfile to {0}")]
+    WriteLockfileFailed(PathBuf),
+
+    #[error(
+        "Checksum mismatch for dependency `{name}` from {origin}: Nargo.lock expects {expected} but found {found}. The dependency sources were modified after they were locked"
+    )]
+    ChecksumMismatch { name: String, origin: String, expected: String, found: String },
+
+    #[error(
+        "Commit mismatch for dependency `{name}` from {origin}: Nargo.lock expects {expected} but found {found}. The git tag was moved after the dependency was locked"
+    )]
+    GitRevMismatch { name: String, origin: String, expected: String, found: String },
 }
 
 #[allow(clippy::enum_variant_names)]
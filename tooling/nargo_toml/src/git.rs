@@ -13,7 +13,7 @@ fn resolve_folder_name(base: &url::Url, tag: &str) -> String {
 }
 
 /// Path to the `nargo` directory under `$HOME`.
-fn nargo_crates() -> PathBuf {
+pub(crate) fn nargo_crates() -> PathBuf {
     dirs::home_dir().unwrap().join("nargo")
 }
 
@@ -64,6 +64,19 @@ pub(crate) fn clone_git_repo(url: &str, tag: &str) -> Result<PathBuf, String> {
     Ok(loc)
 }
 
+/// Returns the commit hash currently checked out in a cloned git dependency.
+pub(crate) fn git_rev(repo_dir: &std::path::Path) -> Option<String> {
+    use std::process::Command;
+
+    let output =
+        Command::new("git").arg("-C").arg(repo_dir).arg("rev-parse").arg("HEAD").output().ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+    String::from_utf8(output.stdout).ok().map(|rev| rev.trim().to_string())
+}
+
 #[cfg(test)]
 mod tests {
     use test_case::test_case;
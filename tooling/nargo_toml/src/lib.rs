@@ -19,10 +19,18 @@ use serde::Deserialize;
 mod errors;
 mod flock;
 mod git;
+mod lockfile;
+mod registry;
 mod semver;
 
 pub use errors::ManifestError;
-use git::{clone_git_repo, lock_git_deps};
+use git::{clone_git_repo, git_rev, lock_git_deps};
+use lockfile::LockfileResolver;
+pub use lockfile::{LOCKFILE_NAME, LockedPackage, Lockfile};
+use registry::{
+    DEFAULT_REGISTRY_SOURCE, RegistryIndex, default_registry_index, package_checksum,
+    registry_source,
+};
 
 /// Searches for a `Nargo.toml` file in the current directory and all parent directories.
 /// For example, if the current directory is `/workspace/package/src`, then this function
@@ -155,6 +163,7 @@ impl PackageConfig {
         &self,
         root_dir: &Path,
         processed: &mut Vec<String>,
+        lockfile: &mut LockfileResolver,
         assume_default_entry: bool, // assume that the 'default_entry_path' exists, e.g. src/main.nr
     ) -> Result<Package, ManifestError> {
         let name: CrateName = if let Some(name) = &self.package.name {
@@ -167,12 +176,13 @@ impl PackageConfig {
         };
 
         let mut dependencies: BTreeMap<CrateName, Dependency> = BTreeMap::new();
-        for (name, dep_config) in self.dependencies.iter() {
-            let name = name.parse().map_err(|_| ManifestError::InvalidDependencyName {
+        for (dep_name, dep_config) in self.dependencies.iter() {
+            let name = dep_name.parse().map_err(|_| ManifestError::InvalidDependencyName {
                 toml: root_dir.join("Nargo.toml"),
-                name: name.into(),
+                name: dep_name.into(),
             })?;
-            let resolved_dep = dep_config.resolve_to_dependency(root_dir, processed)?;
+            let resolved_dep =
+                dep_config.resolve_to_dependency(dep_name, root_dir, processed, lockfile)?;
 
             dependencies.insert(name, resolved_dep);
         }
@@ -326,15 +336,32 @@ pub struct PackageMetadata {
 /// Enum representing the different types of ways to
 /// supply a source for the dependency
 pub enum DependencyConfig {
-    Github { git: String, tag: String, directory: Option<String> },
-    Path { path: String },
+    Github {
+        git: String,
+        tag: String,
+        directory: Option<String>,
+    },
+    Path {
+        path: String,
+    },
+    /// A semver requirement resolved against a package index, e.g. `{ version = "^1.2" }`.
+    ///
+    /// `index` is a path relative to the package root; when omitted the default index is used.
+    Registry {
+        version: String,
+        index: Option<String>,
+    },
+    /// A semver requirement resolved against the default package index, e.g. `"^1.2"`.
+    Version(String),
 }
 
 impl DependencyConfig {
     fn resolve_to_dependency(
         &self,
+        name: &str,
         pkg_root: &Path,
         processed: &mut Vec<String>,
+        lockfile: &mut LockfileResolver,
     ) -> Result<Dependency, ManifestError> {
         let dep = match self {
             Self::Github { git, tag, directory } => {
@@ -352,15 +379,41 @@ impl DependencyConfig {
                     dir_path
                 };
                 let toml_path = project_path.join("Nargo.toml");
-                let package = resolve_package_from_toml(&toml_path, processed)?;
+                let package = resolve_package_from_toml(&toml_path, processed, lockfile)?;
+
+                let source = match directory {
+                    Some(directory) => format!("git+{git}?tag={tag}&directory={directory}"),
+                    None => format!("git+{git}?tag={tag}"),
+                };
+                let checksum = package_checksum(&project_path)
+                    .map_err(|err| ManifestError::GitError(err.to_string()))?;
+                lockfile.lock(LockedPackage {
+                    name: name.to_string(),
+                    version: None,
+                    source,
+                    rev: git_rev(&dir_path),
+                    checksum,
+                })?;
+
                 Dependency::Remote { package }
             }
             Self::Path { path } => {
                 let dir_path = pkg_root.join(path);
                 let toml_path = dir_path.join("Nargo.toml");
-                let package = resolve_package_from_toml(&toml_path, processed)?;
+                let package = resolve_package_from_toml(&toml_path, processed, lockfile)?;
                 Dependency::Local { package }
             }
+            Self::Registry { version, index: Some(index) } => {
+                let index = pkg_root.join(index).normalize();
+                let source = registry_source(&index, lockfile.workspace_root());
+                let index = RegistryIndex::new(index, source);
+                resolve_registry_dependency(name, version, index, pkg_root, processed, lockfile)?
+            }
+            Self::Registry { version, index: None } | Self::Version(version) => {
+                let source = DEFAULT_REGISTRY_SOURCE.to_string();
+                let index = RegistryIndex::new(default_registry_index(), source);
+                resolve_registry_dependency(name, version, index, pkg_root, processed, lockfile)?
+            }
         };
 
         // Cannot depend on a binary
@@ -373,18 +426,54 @@ impl DependencyConfig {
     }
 }
 
+/// Resolves a dependency on package `name` from the package index at `index`,
+/// picking the version to use according to the semver `version` requirement and the lockfile.
+fn resolve_registry_dependency(
+    name: &str,
+    version: &str,
+    index: RegistryIndex,
+    pkg_root: &Path,
+    processed: &mut Vec<String>,
+    lockfile: &mut LockfileResolver,
+) -> Result<Dependency, ManifestError> {
+    let requirement = ::semver::VersionReq::parse(version).map_err(|err| {
+        ManifestError::InvalidVersionRequirement {
+            toml: pkg_root.join("Nargo.toml"),
+            name: name.to_string(),
+            version: version.to_string(),
+            error: err.to_string(),
+        }
+    })?;
+
+    let (package_dir, locked_package) = index
+        .resolve(name, &requirement, lockfile.previous())
+        .map_err(ManifestError::RegistryError)?;
+
+    let toml_path = package_dir.join("Nargo.toml");
+    let package = resolve_package_from_toml(&toml_path, processed, lockfile)?;
+    lockfile.lock(locked_package)?;
+
+    Ok(Dependency::Remote { package })
+}
+
 fn toml_to_workspace(
     nargo_toml: NargoToml,
     package_selection: PackageSelection,
     assume_default_entry: bool, // assume that the 'default_entry_path' exists, e.g. src/main.nr
-) -> Result<Workspace, ManifestError> {
+) -> Result<(Workspace, Lockfile), ManifestError> {
     let mut resolved = Vec::new();
     let _lock = lock_git_deps().expect("Failed to lock git dependencies cache");
+
+    let lockfile_path = nargo_toml.root_dir.join(LOCKFILE_NAME);
+    let mut lockfile =
+        LockfileResolver::new(Lockfile::read(&lockfile_path)?, nargo_toml.root_dir.clone());
+
     let workspace = match nargo_toml.config {
         Config::Package { package_config } => {
             let member = package_config.resolve_to_package(
                 &nargo_toml.root_dir,
                 &mut resolved,
+                &mut lockfile,
                 assume_default_entry,
             )?;
             match &package_selection {
@@ -406,7 +495,8 @@ fn toml_to_workspace(
             for (index, member_path) in workspace_config.members.into_iter().enumerate() {
                 let package_root_dir = nargo_toml.root_dir.join(&member_path);
                 let package_toml_path = package_root_dir.join("Nargo.toml");
-                let member = resolve_package_from_toml(&package_toml_path, &mut resolved)?;
+                let member =
+                    resolve_package_from_toml(&package_toml_path, &mut resolved, &mut lockfile)?;
 
                 match &package_selection {
                     PackageSelection::Selected(selected_name) => {
@@ -454,7 +544,7 @@ fn toml_to_workspace(
         }
     };
 
-    Ok(workspace)
+    Ok((workspace, lockfile.into_resolved()))
 }
 
 /// Attempts to read the file at the provided `toml_path` as a `Nargo.toml`
@@ -482,6 +572,7 @@ pub fn read_toml(toml_path: &Path) -> Result<NargoToml, ManifestError> {
 fn resolve_package_from_toml(
     toml_path: &Path,
     processed: &mut Vec<String>,
+    lockfile: &mut LockfileResolver,
 ) -> Result<Package, ManifestError> {
     // Checks for cyclic dependencies
     let str_path = toml_path.to_str().expect("ICE - path is empty");
@@ -507,7 +598,12 @@ fn resolve_package_from_toml(
     let result = match nargo_toml.config {
         Config::Package { package_config } => {
             let assume_default_entry = false;
-            package_config.resolve_to_package(&nargo_toml.root_dir, processed, assume_default_entry)
+            package_config.resolve_to_package(
+                &nargo_toml.root_dir,
+                processed,
+                lockfile,
+                assume_default_entry,
+            )
         }
         Config::Workspace { .. } => {
             Err(ManifestError::UnexpectedWorkspace(toml_path.to_path_buf()))
@@ -546,12 +642,46 @@ pub fn resolve_workspace_from_fixed_toml(
     package_selection: PackageSelection,
     current_compiler_version: Option<String>,
 ) -> Result<Workspace, ManifestError> {
+    let (workspace, _) =
+        resolve_workspace_and_lockfile(nargo_toml, package_selection, current_compiler_version)?;
+    Ok(workspace)
+}
+
+/// Resolves a Nargo.toml file into a `Workspace` like [`resolve_workspace_from_toml`], then
+/// creates or updates the `Nargo.lock` file of the workspace with the resolved dependencies.
+///
+/// Only commands which resolve dependencies to build the workspace should use this, so that
+/// the LSP and other read-only tools never modify the lockfile.
+pub fn resolve_workspace_from_toml_and_update_lockfile(
+    toml_path: &Path,
+    package_selection: PackageSelection,
+    current_compiler_version: Option<String>,
+) -> Result<Workspace, ManifestError> {
+    let nargo_toml = read_toml(toml_path)?;
+    let (workspace, lockfile) =
+        resolve_workspace_and_lockfile(nargo_toml, package_selection, current_compiler_version)?;
+
+    // Only create a lockfile if there is something to lock, but keep an existing one up to date.
+    let lockfile_path = workspace.root_dir.join(LOCKFILE_NAME);
+    if !lockfile.is_empty() || lockfile_path.exists() {
+        lockfile.write(&lockfile_path)?;
+    }
+
+    Ok(workspace)
+}
+
+fn resolve_workspace_and_lockfile(
+    nargo_toml: NargoToml,
+    package_selection: PackageSelection,
+    current_compiler_version: Option<String>,
+) -> Result<(Workspace, Lockfile), ManifestError> {
     let assume_default_entry = true;
-    let workspace = toml_to_workspace(nargo_toml, package_selection, assume_default_entry)?;
+    let (workspace, lockfile) =
+        toml_to_workspace(nargo_toml, package_selection, assume_default_entry)?;
     if let Some(current_compiler_version) = current_compiler_version {
         semver::semver_check_workspace(&workspace, current_compiler_version)?;
     }
-    Ok(workspace)
+    Ok((workspace, lockfile))
 }
 
 #[cfg(test)]
@@ -563,7 +693,10 @@ mod tests {
 
     use test_case::test_matrix;
 
-    use crate::{Config, ManifestError, find_root};
+    use crate::{
+        Config, LOCKFILE_NAME, Lockfile, ManifestError, PackageSelection, find_root,
+        resolve_workspace_from_toml, resolve_workspace_from_toml_and_update_lockfile,
+    };
 
     #[test]
     fn parse_standard_toml() {
@@ -635,6 +768,85 @@ mod tests {
         assert!(Config::try_from(src).is_ok());
     }
 
+    #[test]
+    fn parse_registry_dependencies_toml() {
+        let src = r#"
+        [package]
+        name = "test"
+        type = "bin"
+
+        [dependencies]
+        foo = "^1.2"
+        bar = { version = "~0.3", index = "../index" }
+    "#;
+
+        let Config::Package { package_config } = Config::try_from(src).unwrap() else {
+            panic!("expected a package config");
+        };
+        assert!(matches!(
+            package_config.dependencies["foo"],
+            crate::DependencyConfig::Version(ref version) if version == "^1.2"
+        ));
+        assert!(matches!(
+            package_config.dependencies["bar"],
+            crate::DependencyConfig::Registry { ref version, index: Some(_) } if version == "~0.3"
+        ));
+    }
+
+    #[test]
+    fn registry_dependency_is_locked_and_verified() {
+        let tmp = tempfile::tempdir().unwrap();
+        let index = tmp.path().join("index");
+        for version in ["1.2.0", "1.3.0"] {
+            let package_dir = index.join("foo").join(version);
+            std::fs::create_dir_all(package_dir.join("src")).unwrap();
+            std::fs::write(
+                package_dir.join("Nargo.toml"),
+                format!("[package]\nname = \"foo\"\ntype = \"lib\"\nversion = \"{version}\"\n"),
+            )
+            .unwrap();
+            std::fs::write(package_dir.join("src").join("lib.nr"), "").unwrap();
+        }
+
+        let root = tmp.path().join("project");
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::write(root.join("src").join("main.nr"), "fn main() {}").unwrap();
+        std::fs::write(
+            root.join("Nargo.toml"),
+            format!(
+                "[package]\nname = \"project\"\ntype = \"bin\"\n\n[dependencies]\nfoo = {{ version = \"^1.2\", index = {:?} }}\n",
+                index.display().to_string()
+            ),
+        )
+        .unwrap();
+
+        let toml_path = root.join("Nargo.toml");
+        let resolve = || {
+            resolve_workspace_from_toml_and_update_lockfile(&toml_path, PackageSelection::All, None)
+        };
+
+        // Resolving the workspace without updating the lockfile doesn't create it
+        resolve_workspace_from_toml(&toml_path, PackageSelection::All, None)
+            .expect("should resolve the registry dependency");
+        assert!(!root.join(LOCKFILE_NAME).exists());
+
+        resolve().expect("should resolve the registry dependency");
+        let lockfile = Lockfile::read(&root.join(LOCKFILE_NAME)).unwrap();
+        let locked = lockfile.packages.iter().next().expect("dependency should be locked");
+        assert_eq!(locked.name, "foo");
+        assert_eq!(locked.version.as_deref(), Some("1.3.0"));
+        // The index is recorded relative to the workspace root
+        assert_eq!(locked.source, "registry+../index");
+
+        // Modifying the sources of a locked dependency is detected
+        std::fs::write(index.join("foo").join("1.3.0").join("src").join("lib.nr"), "// changed")
+            .unwrap();
+        let Err(error) = resolve() else {
+            panic!("expected a checksum mismatch");
+        };
+        assert!(matches!(error, ManifestError::ChecksumMismatch { .. }));
+    }
+
     /// Test that `find_root` handles all kinds of prefixes.
     /// (It dispatches based on `workspace` to methods which handle paths differently).
     #[test_matrix(
@@ -0,0 +1,229 @@
+use std::{
+    collections::BTreeSet,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::ManifestError;
+
+/// Name of the lockfile written next to the root `Nargo.toml` of a workspace.
+pub const LOCKFILE_NAME: &str = "Nargo.lock";
+
+const LOCKFILE_VERSION: u32 = 1;
+
+const LOCKFILE_HEADER: &str =
+    "# This file is automatically @generated by Nargo.\n# It is not intended for manual editing.\n";
+
+/// The contents of a `Nargo.lock` file.
+///
+/// It records the exact version and content hash of every dependency which was resolved from
+/// a registry or a git repository, so that subsequent builds use (and verify) the same sources.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Lockfile {
+    pub version: u32,
+    #[serde(default, rename = "package")]
+    pub packages: BTreeSet<LockedPackage>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct LockedPackage {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    pub source: String,
+    /// The git commit the dependency was resolved to, if it is a git dependency.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rev: Option<String>,
+    pub checksum: String,
+}
+
+impl Default for Lockfile {
+    fn default() -> Self {
+        Self { version: LOCKFILE_VERSION, packages: BTreeSet::new() }
+    }
+}
+
+impl Lockfile {
+    /// Reads the lockfile at `path`, returning an empty lockfile if it doesn't exist.
+    pub fn read(path: &Path) -> Result<Self, ManifestError> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let contents = std::fs::read_to_string(path)
+            .map_err(|_| ManifestError::ReadFailed(path.to_path_buf()))?;
+        let lockfile: Lockfile = toml::from_str(&contents).map_err(|err| {
+            ManifestError::MalformedLockfile { path: path.to_path_buf(), error: err.to_string() }
+        })?;
+
+        if lockfile.version != LOCKFILE_VERSION {
+            return Err(ManifestError::MalformedLockfile {
+                path: path.to_path_buf(),
+                error: format!("unsupported lockfile version {}", lockfile.version),
+            });
+        }
+
+        Ok(lockfile)
+    }
+
+    /// Writes the lockfile to `path`, unless the file already has the same contents.
+    pub fn write(&self, path: &Path) -> Result<(), ManifestError> {
+        let contents = format!(
+            "{LOCKFILE_HEADER}{}",
+            toml::to_string(self).expect("lockfile should always be serializable")
+        );
+
+        if std::fs::read_to_string(path).is_ok_and(|existing| existing == contents) {
+            return Ok(());
+        }
+
+        std::fs::write(path, contents)
+            .map_err(|_| ManifestError::WriteLockfileFailed(path.to_path_buf()))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.packages.is_empty()
+    }
+
+    /// Returns all the packages locked for the given name and source.
+    pub fn find<'a>(
+        &'a self,
+        name: &'a str,
+        source: &'a str,
+    ) -> impl Iterator<Item = &'a LockedPackage> + 'a {
+        self.packages.iter().filter(move |package| package.name == name && package.source == source)
+    }
+}
+
+/// Keeps track of the lockfile which existed before resolution and the lockfile being built
+/// while resolving the dependencies of a workspace.
+#[derive(Debug, Default)]
+pub(crate) struct LockfileResolver {
+    previous: Lockfile,
+    resolved: Lockfile,
+    /// The directory containing the lockfile, which paths recorded in it are relative to.
+    workspace_root: PathBuf,
+}
+
+impl LockfileResolver {
+    pub(crate) fn new(previous: Lockfile, workspace_root: PathBuf) -> Self {
+        Self { previous, resolved: Lockfile::default(), workspace_root }
+    }
+
+    pub(crate) fn previous(&self) -> &Lockfile {
+        &self.previous
+    }
+
+    pub(crate) fn workspace_root(&self) -> &Path {
+        &self.workspace_root
+    }
+
+    /// Records a resolved package, checking its git commit and checksum against the ones in the
+    /// previous lockfile.
+    pub(crate) fn lock(&mut self, package: LockedPackage) -> Result<(), ManifestError> {
+        let previous = self
+            .previous
+            .find(&package.name, &package.source)
+            .find(|previous| previous.version == package.version);
+        let expected_rev = previous.and_then(|previous| previous.rev.clone());
+        let expected_checksum = previous.map(|previous| previous.checksum.clone());
+
+        if let Some(expected) = expected_rev {
+            if package.rev.as_ref() != Some(&expected) {
+                return Err(ManifestError::GitRevMismatch {
+                    name: package.name,
+                    origin: package.source,
+                    expected,
+                    found: package.rev.unwrap_or_else(|| "an unknown commit".to_string()),
+                });
+            }
+        }
+
+        if let Some(expected) = expected_checksum {
+            if expected != package.checksum {
+                return Err(ManifestError::ChecksumMismatch {
+                    name: package.name,
+                    origin: package.source,
+                    expected,
+                    found: package.checksum,
+                });
+            }
+        }
+
+        self.resolved.packages.insert(package);
+        Ok(())
+    }
+
+    /// Returns the lockfile which should be written after resolution.
+    pub(crate) fn into_resolved(self) -> Lockfile {
+        self.resolved
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::{LockedPackage, Lockfile, LockfileResolver};
+    use crate::ManifestError;
+
+    fn locked_package(version: &str, checksum: &str) -> LockedPackage {
+        LockedPackage {
+            name: "foo".to_string(),
+            version: Some(version.to_string()),
+            source: "registry+../index".to_string(),
+            rev: None,
+            checksum: checksum.to_string(),
+        }
+    }
+
+    #[test]
+    fn lockfile_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("Nargo.lock");
+
+        let mut lockfile = Lockfile::default();
+        lockfile.packages.insert(locked_package("1.2.3", "abcd"));
+        lockfile.write(&path).unwrap();
+
+        assert_eq!(Lockfile::read(&path).unwrap(), lockfile);
+    }
+
+    #[test]
+    fn detects_checksum_mismatch() {
+        let mut previous = Lockfile::default();
+        previous.packages.insert(locked_package("1.2.3", "abcd"));
+
+        let mut resolver = LockfileResolver::new(previous.clone(), PathBuf::new());
+        resolver.lock(locked_package("1.2.3", "abcd")).unwrap();
+        // A different version doesn't need to match the old checksum
+        resolver.lock(locked_package("1.3.0", "ef01")).unwrap();
+
+        let mut resolver = LockfileResolver::new(previous, PathBuf::new());
+        let error = resolver.lock(locked_package("1.2.3", "ef01")).unwrap_err();
+        assert!(matches!(error, ManifestError::ChecksumMismatch { .. }));
+    }
+
+    #[test]
+    fn detects_git_rev_mismatch() {
+        let git_package = |rev: &str| LockedPackage {
+            name: "foo".to_string(),
+            version: None,
+            source: "git+https://github.com/noir-lang/foo?tag=v1.0.0".to_string(),
+            rev: Some(rev.to_string()),
+            checksum: "abcd".to_string(),
+        };
+
+        let mut previous = Lockfile::default();
+        previous.packages.insert(git_package("1234"));
+
+        let mut resolver = LockfileResolver::new(previous.clone(), PathBuf::new());
+        resolver.lock(git_package("1234")).unwrap();
+
+        // The tag was moved to another commit after the dependency was locked
+        let mut resolver = LockfileResolver::new(previous, PathBuf::new());
+        let error = resolver.lock(git_package("5678")).unwrap_err();
+        assert!(matches!(error, ManifestError::GitRevMismatch { .. }));
+    }
+}
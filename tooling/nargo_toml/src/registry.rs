@@ -0,0 +1,277 @@
+use std::path::{Path, PathBuf};
+
+use fm::NormalizePath;
+use semver::{Version, VersionReq};
+use sha2::{Digest, Sha256};
+
+use crate::{
+    git::nargo_crates,
+    lockfile::{LockedPackage, Lockfile},
+};
+
+/// Environment variable which can be used to point nargo at a package index
+/// other than the default one under `$HOME/nargo/registry`.
+pub(crate) const REGISTRY_INDEX_ENV: &str = "NARGO_REGISTRY_INDEX";
+
+/// Name of directories which are never taken into account when computing the checksum of a package.
+const IGNORED_DIRECTORIES: [&str; 2] = [".git", "target"];
+
+/// The default location of the package index, e.g. `$HOME/nargo/registry`
+pub(crate) fn default_registry_index() -> PathBuf {
+    match std::env::var_os(REGISTRY_INDEX_ENV) {
+        Some(index) => PathBuf::from(index),
+        None => nargo_crates().join("registry"),
+    }
+}
+
+/// The `source` recorded in `Nargo.lock` for packages from the default index.
+///
+/// It doesn't include the location of the index, which differs from one machine to another.
+pub(crate) const DEFAULT_REGISTRY_SOURCE: &str = "registry+default";
+
+/// The `source` recorded in `Nargo.lock` for packages from the index at `index`.
+///
+/// The index is recorded relative to the workspace root so that the lockfile stays valid
+/// in any checkout of the workspace.
+pub(crate) fn registry_source(index: &Path, workspace_root: &Path) -> String {
+    format!("registry+{}", relative_path(index, &workspace_root.normalize()))
+}
+
+/// Returns `path` relative to `base` using `/` as separator, e.g. `../index`.
+fn relative_path(path: &Path, base: &Path) -> String {
+    let path: Vec<_> = path.components().collect();
+    let base: Vec<_> = base.components().collect();
+    let common = path.iter().zip(&base).take_while(|(path, base)| path == base).count();
+
+    let parents = std::iter::repeat_n("..".to_string(), base.len() - common);
+    let children =
+        path[common..].iter().map(|component| component.as_os_str().to_string_lossy().into_owned());
+    let components: Vec<_> = parents.chain(children).collect();
+    if components.is_empty() { ".".to_string() } else { components.join("/") }
+}
+
+/// A file-based package index.
+///
+/// Every release of a package lives in its own directory laid out as `<index>/<name>/<version>`,
+/// which contains the sources of the package, including its `Nargo.toml`.
+pub(crate) struct RegistryIndex {
+    root: PathBuf,
+    /// The `source` recorded in `Nargo.lock` for the packages of this index.
+    source: String,
+}
+
+impl RegistryIndex {
+    pub(crate) fn new(root: PathBuf, source: String) -> Self {
+        Self { root, source }
+    }
+
+    /// Returns the path containing the sources of a specific version of a package.
+    fn package_dir(&self, name: &str, version: &Version) -> PathBuf {
+        self.root.join(name).join(version.to_string())
+    }
+
+    /// Returns all the versions of a package available in this index, sorted from lowest to highest.
+    ///
+    /// Directories which are not valid semver versions are ignored.
+    fn versions(&self, name: &str) -> Result<Vec<Version>, String> {
+        let package_dir = self.root.join(name);
+        let entries = std::fs::read_dir(&package_dir).map_err(|_| {
+            format!(
+                "Package `{name}` could not be found in the package index at {}",
+                self.root.display()
+            )
+        })?;
+
+        let mut versions: Vec<Version> = entries
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.path().is_dir())
+            .filter_map(|entry| Version::parse(entry.file_name().to_str()?).ok())
+            .collect();
+        versions.sort();
+        Ok(versions)
+    }
+
+    /// Selects the version of a package which should be used to satisfy `requirement`.
+    ///
+    /// A `locked` version is preferred as long as it still satisfies the requirement and is present
+    /// in the index, otherwise the highest matching version is used.
+    fn select_version(
+        &self,
+        name: &str,
+        requirement: &VersionReq,
+        locked: Option<&Version>,
+    ) -> Result<Version, String> {
+        let versions = self.versions(name)?;
+
+        if let Some(locked) = locked {
+            if requirement.matches(locked) && versions.contains(locked) {
+                return Ok(locked.clone());
+            }
+        }
+
+        versions.into_iter().rev().find(|version| requirement.matches(version)).ok_or_else(|| {
+            format!(
+                "No version of package `{name}` in the package index at {} matches the requirement `{requirement}`",
+                self.root.display()
+            )
+        })
+    }
+
+    /// Resolves `requirement` for the package `name`, preferring the highest version already
+    /// recorded for this index in `lockfile`.
+    ///
+    /// Returns the directory containing the sources of the selected package, along with the entry
+    /// which should be recorded for it in the lockfile.
+    pub(crate) fn resolve(
+        &self,
+        name: &str,
+        requirement: &VersionReq,
+        lockfile: &Lockfile,
+    ) -> Result<(PathBuf, LockedPackage), String> {
+        let source = self.source.clone();
+        let locked = lockfile
+            .find(name, &source)
+            .filter_map(|package| Version::parse(package.version.as_deref()?).ok())
+            .filter(|version| requirement.matches(version))
+            .max();
+
+        let version = self.select_version(name, requirement, locked.as_ref())?;
+        let package_dir = self.package_dir(name, &version);
+        let checksum = package_checksum(&package_dir).map_err(|err| {
+            format!("Could not read package `{name}` at {}: {err}", package_dir.display())
+        })?;
+
+        let locked_package = LockedPackage {
+            name: name.to_string(),
+            version: Some(version.to_string()),
+            source,
+            rev: None,
+            checksum,
+        };
+        Ok((package_dir, locked_package))
+    }
+}
+
+/// Computes a content hash of all the files in a package directory.
+///
+/// Files are visited in a deterministic order and both their relative paths and their contents
+/// are hashed, so renaming or editing any file changes the checksum.
+pub(crate) fn package_checksum(package_dir: &Path) -> std::io::Result<String> {
+    let mut files = Vec::new();
+    collect_files(package_dir, &mut files)?;
+    files.sort();
+
+    let mut hasher = Sha256::new();
+    for file in files {
+        let relative_path = file.strip_prefix(package_dir).expect("file is inside package dir");
+        let relative_path = relative_path
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        let contents = std::fs::read(&file)?;
+
+        hasher.update(relative_path.as_bytes());
+        hasher.update([0]);
+        hasher.update((contents.len() as u64).to_le_bytes());
+        hasher.update(&contents);
+    }
+
+    Ok(hex::encode(hasher.finalize()))
+}
+
+fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        if path.is_dir() {
+            let is_ignored =
+                entry.file_name().to_str().is_some_and(|name| IGNORED_DIRECTORIES.contains(&name));
+            if !is_ignored {
+                collect_files(&path, files)?;
+            }
+        } else {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use semver::{Version, VersionReq};
+
+    use std::path::Path;
+
+    use super::{DEFAULT_REGISTRY_SOURCE, RegistryIndex, package_checksum, relative_path};
+
+    fn create_index(versions: &[&str]) -> tempfile::TempDir {
+        let index = tempfile::tempdir().unwrap();
+        for version in versions {
+            let dir = index.path().join("foo").join(version).join("src");
+            std::fs::create_dir_all(&dir).unwrap();
+            std::fs::write(dir.join("lib.nr"), format!("// version {version}")).unwrap();
+        }
+        // Directories that aren't versions should be ignored
+        std::fs::create_dir_all(index.path().join("foo").join("not-a-version")).unwrap();
+        index
+    }
+
+    #[test]
+    fn selects_highest_matching_version() {
+        let index = create_index(&["1.1.0", "1.2.0", "1.3.5", "2.0.0"]);
+        let index =
+            RegistryIndex::new(index.path().to_path_buf(), DEFAULT_REGISTRY_SOURCE.to_string());
+
+        let requirement = VersionReq::parse("^1.2").unwrap();
+        let version = index.select_version("foo", &requirement, None).unwrap();
+        assert_eq!(version, Version::parse("1.3.5").unwrap());
+    }
+
+    #[test]
+    fn prefers_locked_version_if_it_still_matches() {
+        let index = create_index(&["1.2.0", "1.3.5"]);
+        let index =
+            RegistryIndex::new(index.path().to_path_buf(), DEFAULT_REGISTRY_SOURCE.to_string());
+
+        let requirement = VersionReq::parse("^1.2").unwrap();
+        let locked = Version::parse("1.2.0").unwrap();
+        let version = index.select_version("foo", &requirement, Some(&locked)).unwrap();
+        assert_eq!(version, locked);
+
+        let requirement = VersionReq::parse("^1.3").unwrap();
+        let version = index.select_version("foo", &requirement, Some(&locked)).unwrap();
+        assert_eq!(version, Version::parse("1.3.5").unwrap());
+    }
+
+    #[test]
+    fn errors_if_no_version_matches() {
+        let index = create_index(&["1.2.0"]);
+        let index =
+            RegistryIndex::new(index.path().to_path_buf(), DEFAULT_REGISTRY_SOURCE.to_string());
+
+        let requirement = VersionReq::parse("^2").unwrap();
+        assert!(index.select_version("foo", &requirement, None).is_err());
+        assert!(index.select_version("bar", &requirement, None).is_err());
+    }
+
+    #[test]
+    fn checksum_depends_on_contents() {
+        let index = create_index(&["1.2.0"]);
+        let package_dir = index.path().join("foo").join("1.2.0");
+
+        let checksum = package_checksum(&package_dir).unwrap();
+        assert_eq!(checksum, package_checksum(&package_dir).unwrap());
+
+        std::fs::write(package_dir.join("src").join("lib.nr"), "// modified").unwrap();
+        assert_ne!(checksum, package_checksum(&package_dir).unwrap());
+    }
+
+    #[test]
+    fn index_is_recorded_relative_to_workspace_root() {
+        let relative = |path: &str, base: &str| relative_path(Path::new(path), Path::new(base));
+        assert_eq!(relative("/work/index", "/work/project"), "../index");
+        assert_eq!(relative("/work/project/deps/index", "/work/project"), "deps/index");
+        assert_eq!(relative("/work/project", "/work/project"), ".");
+    }
+}
@@ -1,8 +1,13 @@
-use acvm::acir::circuit::ExpressionWidth;
+use acvm::acir::{
+    brillig::{BlackBoxOp, HeapVector, MemoryAddress, Opcode as BrilligOpcode, ValueOrArray},
+    circuit::ExpressionWidth,
+};
 use iter_extended::vecmap;
 use noirc_artifacts::program::ProgramArtifact;
 use prettytable::{Row, row, table};
-use rayon::iter::{IndexedParallelIterator, IntoParallelIterator, ParallelIterator};
+use rayon::iter::{
+    IndexedParallelIterator, IntoParallelIterator, IntoParallelRefIterator, ParallelIterator,
+};
 use serde::Serialize;
 
 #[derive(Debug, Default, Serialize)]
@@ -35,6 +40,7 @@ impl From<ProgramInfo> for Vec<Row> {
                 format!("{}", expression_width),
                 Fc->format!("{}", function.opcodes),
                 Fc->format!("{}", program_info.unconstrained_functions_opcodes),
+                Fc->format!("N/A"),
             ]
         });
         main.extend(vecmap(program_info.unconstrained_functions, |function| {
@@ -44,6 +50,7 @@ impl From<ProgramInfo> for Vec<Row> {
                 format!("N/A", ),
                 Fc->format!("N/A"),
                 Fc->format!("{}", function.opcodes),
+                Fc->format!("{}", function.stack_frame_size.map_or("N/A".to_string(), |size| size.to_string())),
             ]
         }));
         main
@@ -54,6 +61,9 @@ impl From<ProgramInfo> for Vec<Row> {
 pub struct FunctionInfo {
     pub name: String,
     pub opcodes: usize,
+    /// Size of the largest stack frame among the functions making up the bytecode of an unconstrained function.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stack_frame_size: Option<usize>,
 }
 
 pub fn count_opcodes_and_gates_in_program(
@@ -69,6 +79,7 @@ pub fn count_opcodes_and_gates_in_program(
         .map(|(i, function)| FunctionInfo {
             name: compiled_program.names[i].clone(),
             opcodes: function.opcodes.len(),
+            stack_frame_size: None,
         })
         .collect();
 
@@ -78,6 +89,12 @@ pub fn count_opcodes_and_gates_in_program(
         .iter()
         .map(|func| func.bytecode.len())
         .collect();
+    let stack_frame_sizes: Vec<usize> = compiled_program
+        .bytecode
+        .unconstrained_functions
+        .par_iter()
+        .map(|func| stack_frame_size(&func.bytecode))
+        .collect();
     let unconstrained_functions_opcodes = compiled_program
         .bytecode
         .unconstrained_functions
@@ -89,7 +106,12 @@ pub fn count_opcodes_and_gates_in_program(
         .clone()
         .iter()
         .zip(opcodes_len)
-        .map(|(name, len)| FunctionInfo { name: name.clone(), opcodes: len })
+        .zip(stack_frame_sizes)
+        .map(|((name, len), stack_frame_size)| FunctionInfo {
+            name: name.clone(),
+            opcodes: len,
+            stack_frame_size: Some(stack_frame_size),
+        })
         .collect();
 
     ProgramInfo {
@@ -101,6 +123,158 @@ pub fn count_opcodes_and_gates_in_program(
     }
 }
 
+/// Returns the number of stack slots a Brillig program needs for its largest stack frame,
+/// that is, one past the highest memory address it accesses relative to the stack pointer.
+fn stack_frame_size<F>(bytecode: &[BrilligOpcode<F>]) -> usize {
+    bytecode
+        .iter()
+        .flat_map(memory_addresses)
+        .filter_map(|address| match address {
+            MemoryAddress::Relative(offset) => Some(offset),
+            MemoryAddress::Direct(_) => None,
+        })
+        .max()
+        .map_or(0, |offset| offset + 1)
+}
+
+/// Returns the memory addresses an opcode reads from or writes to.
+fn memory_addresses<F>(opcode: &BrilligOpcode<F>) -> Vec<MemoryAddress> {
+    match opcode {
+        BrilligOpcode::BinaryFieldOp { destination, lhs, rhs, .. }
+        | BrilligOpcode::BinaryIntOp { destination, lhs, rhs, .. } => {
+            vec![*destination, *lhs, *rhs]
+        }
+        BrilligOpcode::Not { destination, source }
+        | BrilligOpcode::Cast { destination, source, .. }
+        | BrilligOpcode::Mov { destination, source } => vec![*destination, *source],
+        BrilligOpcode::JumpIfNot { condition, .. } | BrilligOpcode::JumpIf { condition, .. } => {
+            vec![*condition]
+        }
+        BrilligOpcode::CalldataCopy { destination_address, size_address, offset_address } => {
+            vec![*destination_address, *size_address, *offset_address]
+        }
+        BrilligOpcode::Const { destination, .. } => vec![*destination],
+        BrilligOpcode::IndirectConst { destination_pointer, .. } => vec![*destination_pointer],
+        BrilligOpcode::ForeignCall { destinations, inputs, .. } => {
+            destinations.iter().chain(inputs).flat_map(value_or_array_addresses).collect()
+        }
+        BrilligOpcode::ConditionalMov { destination, source_a, source_b, condition } => {
+            vec![*destination, *source_a, *source_b, *condition]
+        }
+        BrilligOpcode::Load { destination, source_pointer } => vec![*destination, *source_pointer],
+        BrilligOpcode::Store { destination_pointer, source } => {
+            vec![*destination_pointer, *source]
+        }
+        BrilligOpcode::BlackBox(op) => black_box_addresses(op),
+        BrilligOpcode::Trap { revert_data: vector }
+        | BrilligOpcode::Stop { return_data: vector } => heap_vector_addresses(vector),
+        BrilligOpcode::Jump { .. } | BrilligOpcode::Call { .. } | BrilligOpcode::Return => {
+            Vec::new()
+        }
+    }
+}
+
+fn black_box_addresses(op: &BlackBoxOp) -> Vec<MemoryAddress> {
+    match op {
+        BlackBoxOp::AES128Encrypt { inputs, iv, key, outputs } => {
+            let mut addresses = heap_vector_addresses(inputs);
+            addresses.extend([iv.pointer, key.pointer]);
+            addresses.extend(heap_vector_addresses(outputs));
+            addresses
+        }
+        BlackBoxOp::Blake2s { message, output } | BlackBoxOp::Blake3 { message, output } => {
+            let mut addresses = heap_vector_addresses(message);
+            addresses.push(output.pointer);
+            addresses
+        }
+        BlackBoxOp::Keccakf1600 { input, output } => vec![input.pointer, output.pointer],
+        BlackBoxOp::EcdsaSecp256k1 {
+            hashed_msg,
+            public_key_x,
+            public_key_y,
+            signature,
+            result,
+        }
+        | BlackBoxOp::EcdsaSecp256r1 {
+            hashed_msg,
+            public_key_x,
+            public_key_y,
+            signature,
+            result,
+        } => {
+            let mut addresses = heap_vector_addresses(hashed_msg);
+            addresses.extend([
+                public_key_x.pointer,
+                public_key_y.pointer,
+                signature.pointer,
+                *result,
+            ]);
+            addresses
+        }
+        BlackBoxOp::MultiScalarMul { points, scalars, outputs } => {
+            let mut addresses = heap_vector_addresses(points);
+            addresses.extend(heap_vector_addresses(scalars));
+            addresses.push(outputs.pointer);
+            addresses
+        }
+        BlackBoxOp::EmbeddedCurveAdd {
+            input1_x,
+            input1_y,
+            input1_infinite,
+            input2_x,
+            input2_y,
+            input2_infinite,
+            result,
+        } => vec![
+            *input1_x,
+            *input1_y,
+            *input1_infinite,
+            *input2_x,
+            *input2_y,
+            *input2_infinite,
+            result.pointer,
+        ],
+        BlackBoxOp::BigIntAdd { lhs, rhs, output }
+        | BlackBoxOp::BigIntSub { lhs, rhs, output }
+        | BlackBoxOp::BigIntMul { lhs, rhs, output }
+        | BlackBoxOp::BigIntDiv { lhs, rhs, output } => vec![*lhs, *rhs, *output],
+        BlackBoxOp::BigIntFromLeBytes { inputs, modulus, output } => {
+            let mut addresses = heap_vector_addresses(inputs);
+            addresses.extend(heap_vector_addresses(modulus));
+            addresses.push(*output);
+            addresses
+        }
+        BlackBoxOp::BigIntToLeBytes { input, output } => {
+            let mut addresses = vec![*input];
+            addresses.extend(heap_vector_addresses(output));
+            addresses
+        }
+        BlackBoxOp::Poseidon2Permutation { message, output, len } => {
+            let mut addresses = heap_vector_addresses(message);
+            addresses.extend([output.pointer, *len]);
+            addresses
+        }
+        BlackBoxOp::Sha256Compression { input, hash_values, output } => {
+            vec![input.pointer, hash_values.pointer, output.pointer]
+        }
+        BlackBoxOp::ToRadix { input, radix, output_pointer, num_limbs, output_bits } => {
+            vec![*input, *radix, *output_pointer, *num_limbs, *output_bits]
+        }
+    }
+}
+
+fn value_or_array_addresses(value: &ValueOrArray) -> Vec<MemoryAddress> {
+    match value {
+        ValueOrArray::MemoryAddress(address) => vec![*address],
+        ValueOrArray::HeapArray(array) => vec![array.pointer],
+        ValueOrArray::HeapVector(vector) => heap_vector_addresses(vector),
+    }
+}
+
+fn heap_vector_addresses(vector: &HeapVector) -> Vec<MemoryAddress> {
+    vec![vector.pointer, vector.size]
+}
+
 pub fn show_info_report(info_report: InfoReport, json: bool) {
     if json {
         // Expose machine-readable JSON data.
@@ -108,7 +282,7 @@ pub fn show_info_report(info_report: InfoReport, json: bool) {
     } else {
         // Otherwise print human-readable table.
         if !info_report.programs.is_empty() {
-            let mut program_table = table!([Fm->"Package", Fm->"Function", Fm->"Expression Width", Fm->"ACIR Opcodes", Fm->"Brillig Opcodes"]);
+            let mut program_table = table!([Fm->"Package", Fm->"Function", Fm->"Expression Width", Fm->"ACIR Opcodes", Fm->"Brillig Opcodes", Fm->"Brillig Stack Frame"]);
 
             for program_info in info_report.programs {
                 let program_rows: Vec<Row> = program_info.into();
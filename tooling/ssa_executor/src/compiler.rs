@@ -83,6 +83,7 @@ pub fn compile_from_artifacts(artifacts: ArtifactsAndWarnings) -> CompiledProgra
             BTreeMap::new(),
             BTreeMap::new(),
             BTreeMap::new(),
+            false,
         );
     let file_map = BTreeMap::new();
     CompiledProgram {
@@ -242,9 +242,18 @@ fn ssa_to_acir_program(ssa: Ssa) -> AcirProgram<FieldElement> {
         skip_brillig_constraints_check: true,
         inliner_aggressiveness: 0,
         max_bytecode_increase_percent: None,
+        brillig_unroll_factor: 0,
+        brillig_unswitch_loops: false,
+        brillig_loop_size_limit: 0,
+        max_function_specializations: 0,
+        enable_value_range_analysis: false,
+        enable_brillig_global_value_numbering: false,
+        enable_acir_common_subexpression_elimination: false,
+        ssa_pipeline: None,
         brillig_options: BrilligOptions::default(),
         enable_brillig_constraints_check_lookback: false,
         skip_passes: vec![],
+        verify_passes_with_inputs: None,
     };
     let (acir_functions, brillig, _, _) = match optimize_ssa_builder_into_acir(
         builder,